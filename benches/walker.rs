@@ -0,0 +1,28 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+use randomwalks_lib::dp::DynamicPrograms;
+use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+use randomwalks_lib::kernel::Kernel;
+use randomwalks_lib::walker::standard::StandardWalker;
+use randomwalks_lib::walker::Walker;
+
+fn standard_walker(c: &mut Criterion) {
+    let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+    let mut dp = DynamicProgramBuilder::new()
+        .simple()
+        .time_limit(200)
+        .kernel(kernel.clone())
+        .build()
+        .unwrap();
+
+    dp.compute();
+
+    let walker = StandardWalker::new(kernel);
+
+    c.bench_function("standard walker, 200 time steps", |b| {
+        b.iter(|| walker.generate_path(&dp, black_box(20), black_box(30), 200))
+    });
+}
+
+criterion_group!(benches, standard_walker);
+criterion_main!(benches);
@@ -15,31 +15,56 @@ class XYPoint:
     def __sub__(self, other) -> 'XYPoint': ...
     def __str__(self) -> str: ...
 
+class Transform:
+    scale: float
+    origin: tuple[float, float]
+
+    def __new__(cls, scale: float, origin: tuple[float, float] = (0.0, 0.0)) -> 'Transform': ...
+    def to_metric(self, point: 'XYPoint') -> tuple[float, float]: ...
+    def to_xy(self, point: tuple[float, float]) -> 'XYPoint': ...
+    def distance(self, from_point: 'XYPoint', to_point: 'XYPoint') -> float: ...
+    def __repr__(self) -> str: ...
+
 class Dataset:
     def __new__(cls, coordinate_type: 'CoordinateType') -> 'Dataset': ...
+    @staticmethod
+    def example(kind: 'ExampleKind') -> 'Dataset': ...
     def __len__(self) -> int: ...
     def is_empty(self) -> bool: ...
     def coordinate_type(self) -> 'CoordinateType': ...
     def push(self, datapoint: 'Datapoint'): ...
     def get(self, index: int) -> t.Optional['Datapoint']: ...
+    def insert(self, index: int, datapoint: 'Datapoint'): ...
+    def remove(self, index: int) -> 'Datapoint': ...
+    def update(self, index: int, f: t.Callable[['Datapoint'], 'Datapoint']): ...
+    def extend(self, datapoints: list['Datapoint']): ...
     def __iter__(self) -> t.Iterable['Datapoint']: ...
     def keep(self, from_idx: t.Optional[int] = None, to_idx: t.Optional[int] = None): ...
     def filter(self, filter: 'DatasetFilter') -> int: ...
+    def clip_to_bbox(self, min: 'Point', max: 'Point') -> list['Dataset']: ...
     def min_max(self, from_idx: t.Optional[int] = None,
                 to_idx: t.Optional[int] = None) -> t.Optional[tuple['Point', 'Point']]: ...
+    def find_gaps(self, min_duration: t.Optional[float] = None, min_distance: t.Optional[float] = None,
+                  metadata_key: t.Optional[str] = None) -> list[tuple[int, int]]: ...
     def convert_gcs_to_xy(self, scale: float): ...
     def convert_xy_to_gcs(self, scale: float): ...
-    def rw_between(self, dp: 'SimpleDynamicProgram' | 'MultiDynamicProgram',
-                   walker: 'StandardWalker' | 'CorrelatedWalker' | 'MultiStepWalker' | 'LevyWalker',
-                   from_idx: int, to_idx: int, time_steps: int, auto_scale: bool,
-                   extra_steps: int) -> 'Walk': ...
-    def generate_walks(self, dp: 'SimpleDynamicProgram' | 'MultiDynamicProgram',
-                       walker: 'StandardWalker' | 'CorrelatedWalker' | 'MultiStepWalker' | 'LevyWalker',
+    def rw_between(self, dp: 'DynamicProgram' | 'DynamicProgramPool',
+                   walker: 'StandardWalker' | 'CorrelatedWalker' | 'MultiStepWalker' | 'LevyWalker' | 'LandCoverWalker' | 'ScheduledWalker' | 'AttractionWalker' | 'BudgetWalker' | 'CustomWalker',
+                   from_idx: int, to_idx: int, time_steps: int, auto_scale: bool = False,
+                   extra_steps: int = 0, endpoint_tolerance: int = 0, max_retries: int = 0,
+                   retry_extra_steps: int = 0) -> 'Walk': ...
+    def generate_walks(self, dp: 'DynamicProgram' | 'DynamicProgramPool',
+                       walker: 'StandardWalker' | 'CorrelatedWalker' | 'MultiStepWalker' | 'LevyWalker' | 'LandCoverWalker' | 'ScheduledWalker' | 'AttractionWalker' | 'BudgetWalker' | 'CustomWalker',
                        count: int = 1, time_steps: t.Optional[int] = None,
                        by_time_diff: t.Optional[tuple[float, str]] = None,
                        by_dist: t.Optional[float] = None,
-                       auto_scale: t.Optional[bool] = False,
-                       extra_steps: t.Optional[int] = 0) -> list['Walk']: ...
+                       auto_scale: bool = False,
+                       extra_steps: int = 0,
+                       stride: t.Optional[int] = None,
+                       pairs: t.Optional[list[tuple[int, int]]] = None,
+                       endpoint_tolerance: int = 0,
+                       max_retries: int = 0,
+                       retry_extra_steps: int = 0) -> list['Walk']: ...
     def direct_between(self, from_idx: int, to_idx: int) -> 'Walk': ...
     def print(self, from_idx: t.Optional[int] = None, to_idx: t.Optional[int] = None): ...
     def plot(self, path: str, from_idx: t.Optional[int] = None, to_idx: t.Optional[int] = None,
@@ -61,6 +86,14 @@ class DatasetLoaderError(Enum):
     NoYColumnSpecified = 2
     MoreColumnsThanActions = 3
 
+class ExampleKind(Enum):
+    Line = 1
+    Loop = 2
+    TwoClusters = 3
+
+    def time_steps(self) -> int: ...
+    def dynamic_program(self) -> 'DynamicProgram': ...
+
 class CoordinateType(Enum):
     GCS = 1
     XY = 2
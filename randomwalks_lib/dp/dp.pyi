@@ -1,19 +1,141 @@
+import typing as t
+import numpy as np
+from enum import Enum
+
 class DynamicProgram:
     """
     A simple dynamic program.
     """
 
-    def __new__(cls, time_limit: int, kernel: 'Kernel', kernels: list['Kernel'], field_types: list[list[int]]=[]) -> 'DynamicProgram': ...
+    def __new__(cls, time_limit: int, kernel: 'Kernel', kernels: list['Kernel'], field_types: list[list[int]]=[],
+                kernel_schedule: list[tuple[int, int, 'Kernel']]=[],
+                single_precision: bool=False, sparse: bool=False, memory_mapped: t.Optional[str]=None,
+                log_space: bool=False, counting: bool=False, checkpoint: t.Optional[str]=None,
+                checkpoint_interval: int=100,
+                bounding_box: t.Optional[tuple[int, int, int, int]]=None,
+                absorbing: list[tuple[int, int]]=[], epsilon: float=0.0,
+                initial_distribution: list[tuple[int, int, float]]=[],
+                compressed: bool=False, threads: t.Optional[int]=None,
+                chunks: t.Optional[int]=None,
+                quantized: bool=False,
+                diffusivity: list[list[float]]=[]) -> 'DynamicProgram': ...
     def at(self, x: int, y: int, t: int) -> float: ...
     def at_or(self, x: int, y: int, t: int, default: float) -> float:
+    def log_at_or(self, x: int, y: int, t: int) -> float: ...
+    def contains(self, x: int, y: int) -> bool: ...
+    def bounding_box(self) -> tuple[int, int, int, int]: ...
+    @property
+    def log_space(self) -> bool: ...
+    @property
+    def epsilon(self) -> float: ...
+    @property
+    def initial_distribution(self) -> list[tuple[int, int, float]]: ...
+    def is_zero(self, x: int, y: int, t: int) -> bool: ...
+    def count_at(self, x: int, y: int, t: int) -> int | None: ...
+    def probability_at(self, x: int, y: int, t: int) -> float: ...
+    def absorbed_at(self, t: int) -> float: ...
+    def absorbed_mass(self) -> list[float]: ...
+    def slice(self, t: int) -> list[list[float]]: ...
+    def slice_numpy(self, t: int) -> np.ndarray: ...
+    def export_slice(self, t: int, format: 'ExportFormat', path: str): ...
+    def marginal_x(self, t: int) -> list[float]: ...
+    def marginal_y(self, t: int) -> list[float]: ...
+    def expected_displacement(self, t: int) -> tuple[float, float]: ...
+    def mass_outside(self, min: 'XYPoint', max: 'XYPoint', t: int) -> float: ...
+    def mass_outside_series(self, min: 'XYPoint', max: 'XYPoint') -> list[float]: ...
+    def first_passage_time(self, region: list['XYPoint']) -> list[float]: ...
+    def hitting_probability(self, region: list['XYPoint'], t: int) -> float: ...
+    def log_likelihood(self, walk: 'Walk') -> float: ...
+    def compute_multigrid(self, regions: list[tuple['XYPoint', 'XYPoint']]) -> 'ComputeStats': ...
+    def bridge_occupancy(self, from_point: 'XYPoint', to_point: 'XYPoint', time_steps: int) -> list[list[float]]: ...
+    def to_dataset(self, t: int, threshold: float = 0.0) -> 'Dataset': ...
+    @property
+    def counting(self) -> bool: ...
     def set(self, x: int, y: int, t: int, val: float): ...
     @staticmethod
     def load(filename: str) -> 'DynamicProgram': ...
     def limits(self) -> tuple[int, int]: ...
-    def compute(self): ...
+    def compute(self) -> 'ComputeStats': ...
+    def compute_gpu(self) -> 'ComputeStats': ...
     def field_types(self) -> list[list[int]]: ...
+    def model_card(self) -> 'ModelCard': ...
     def heatmap(self, path: str, t: int): ...
     def print(self, t: int): ...
     def save(self, filename: str): ...
     def __repr__(self) -> str: ...
     def __eq__(self, other) -> bool: ...
+
+class DynamicProgramPool:
+    @staticmethod
+    def single(dp: 'DynamicProgram') -> 'DynamicProgramPool': ...
+    @staticmethod
+    def multiple(dps: list['DynamicProgram']) -> 'DynamicProgramPool': ...
+    @staticmethod
+    def bridge(dp: 'DynamicProgram', to_x: int, to_y: int, time_steps: int) -> 'DynamicProgramPool': ...
+    def compute(self) -> 'ComputeStats': ...
+    def compute_parallel(self) -> 'ComputeStats': ...
+    def limits(self) -> tuple[int, int]: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, variant: int) -> 'DynamicProgram': ...
+    def print(self, t: int, variant: t.Optional[int] = None): ...
+    def save(self, filename: str): ...
+    @staticmethod
+    def load(filename: str) -> 'DynamicProgramPool': ...
+    def heatmap(self, path: str, t: int, variant: t.Optional[int] = None): ...
+
+class KernelCard:
+    name: str
+    short_name: str
+    probabilities: list[list[float]]
+
+class ScheduledKernelCard:
+    start: int
+    end: int
+    kernel: 'KernelCard'
+
+class ModelCard:
+    library_version: str
+    time_limit: int
+    bounding_box: tuple[int, int, int, int]
+    kernels: list['KernelCard']
+    kernel_schedule: list['ScheduledKernelCard']
+    field_type_counts: list[int]
+    boundary: str
+    barriers: int
+    absorbing: int
+    initial_distribution: list[tuple[int, int, float]]
+    storage: str
+    log_space: bool
+    epsilon: float
+    compute_duration: float | None
+
+    def to_json(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+class DpComparison:
+    kl_divergence: list[float]
+    l1_distance: list[float]
+    max_abs_difference: list[float]
+
+    @staticmethod
+    def compare(first: 'DynamicProgram', second: 'DynamicProgram') -> 'DpComparison': ...
+    def time_steps(self) -> int: ...
+    def is_close(self, tolerance: float) -> bool: ...
+    def to_markdown(self) -> str: ...
+    def to_json(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+class ComputeStats:
+    initialization: float
+    computation: float
+    finalization: float
+    time_steps: int
+    cells_computed: int
+    mass_lost: float
+
+    def total(self) -> float: ...
+    def __repr__(self) -> str: ...
+
+class ExportFormat(Enum):
+    Npy = 1
+    Parquet = 2
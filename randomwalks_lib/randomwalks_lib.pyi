@@ -1,4 +1,5 @@
 import typing as t
+import numpy as np
 from enum import Enum
 
 class Kernel:
@@ -16,7 +17,7 @@ class Kernel:
         """
         ...
     @staticmethod
-    def simple_rw() -> 'Kernel': ...
+    def simple_rw(stay_probability: float = 0.2) -> 'Kernel': ...
     @staticmethod
     def biased_rw(probability: float, direction: 'Direction') -> 'Kernel': ...
     @staticmethod
@@ -24,7 +25,24 @@ class Kernel:
     @staticmethod
     def biased_correlated_rw(probability: float, direction: 'Direction', persistence: float) -> list['Kernel']: ...
     @staticmethod
-    def normal_dist(diffusion: float, size: int) -> 'Kernel':
+    def normal_dist(diffusion: float, size: int) -> 'Kernel': ...
+    @staticmethod
+    def covariance(covariance: list[list[float]], size: int,
+                   drift: tuple[float, float] = (0.0, 0.0)) -> 'Kernel': ...
+    @staticmethod
+    def from_numpy(array: np.ndarray) -> 'Kernel': ...
+    def to_numpy(self) -> np.ndarray: ...
+    def mean_displacement(self) -> tuple[float, float]: ...
+    def displacement_covariance(self) -> list[list[float]]: ...
+    def mean_step_length(self) -> float: ...
+    def rms_distance(self, time_steps: int) -> float: ...
+    def expected_time_steps(self, distance: float) -> float:
+        """
+        Number of time steps after which the root-mean-square distance from the start point
+        equals `distance`. Divided by `distance`, it gives a typical multiplier for
+        `Dataset.generate_walks(by_dist=...)`.
+        """
+        ...
     def size(self) -> int: ...
     def set(self, x: int, y: int, val: float): ...
     def at(self, x: int, y: int) -> float: ...
@@ -48,13 +66,157 @@ class Direction(Enum):
 class Walk:
     def len(self) -> int: ...
     def is_empty(self) -> bool: ...
-    def __iter__(self) -> t.Iterable['Walk']: ...
+    def __iter__(self) -> t.Iterator['XYPoint']: ...
+    def __len__(self) -> int: ...
+    @t.overload
+    def __getitem__(self, index: int) -> 'XYPoint': ...
+    @t.overload
+    def __getitem__(self, index: slice) -> 'Walk': ...
+    def to_numpy(self) -> np.ndarray: ...
+    def push(self, point: 'XYPoint'): ...
+    def insert(self, index: int, point: 'XYPoint'): ...
+    def truncate(self, len: int): ...
+    def reverse(self): ...
+    def remove_range(self, start: int, end: int): ...
+    def retain(self, predicate: t.Callable[['XYPoint'], bool]): ...
     def frechet_distance(self, other: 'Walk') -> float: ...
     def directness_deviation(self) -> float: ...
+    def frechet_distance_approx(self, other: 'Walk', tolerance: float) -> float: ...
+    def directness_deviation_approx(self, tolerance: float) -> float: ...
+    def cumulative_distance(self, transform: t.Optional['Transform'] = None) -> list[float]: ...
+    def distance_at(self, t: int, transform: t.Optional['Transform'] = None) -> t.Optional[float]: ...
+    def resample(self, n_points: int) -> 'Walk': ...
+    def resample_by_distance(self, spacing: float, transform: t.Optional['Transform'] = None) -> 'Walk': ...
+    def step_lengths(self, transform: t.Optional['Transform'] = None) -> list[float]: ...
+    def turning_angles(self) -> list[float]: ...
+    def step_length_histogram(self, bins: int, max: t.Optional[float] = None,
+                              transform: t.Optional['Transform'] = None) -> list[int]: ...
+    def turning_angle_histogram(self, bins: int) -> list[int]: ...
+    def radius_of_gyration(self, transform: t.Optional['Transform'] = None) -> float: ...
+    def convex_hull(self) -> list['XYPoint']: ...
+    def hull_area(self, transform: t.Optional['Transform'] = None) -> float: ...
+    def msd(self, max_lag: t.Optional[int] = None, transform: t.Optional['Transform'] = None) -> list[float]: ...
     def translate(self, by: 'XYPoint') -> 'Walk': ...
     def scale(self, by: 'XYPoint') -> 'Walk': ...
     def rotate(self, degrees: float) -> 'Walk': ...
-    def plot(self, filename: str): ...
+    def bounding_box(self) -> t.Optional[tuple['XYPoint', 'XYPoint']]: ...
+    def intersects_rect(self, from_point: 'XYPoint', to_point: 'XYPoint') -> bool: ...
+    def passes_through(self, point: 'XYPoint', tolerance: float) -> bool: ...
+    def self_intersections(self) -> list[tuple[int, int]]: ...
+    def self_intersection_count(self) -> int: ...
+    def clip_to_bbox(self, min: 'XYPoint', max: 'XYPoint') -> list['Walk']: ...
+    def simplify(self, epsilon: float) -> 'Walk': ...
+    def smooth(self, window: int, transform: t.Optional['Transform'] = None) -> list[tuple[float, float]]: ...
+    def to_geojson(self, properties: t.Optional[dict[str, t.Union[bool, int, float, str]]] = None,
+                   transform: t.Optional['Transform'] = None) -> str: ...
     @staticmethod
-    def plot_multiple(walks: list['Walk'], filename: str): ...
+    def to_geojson_multiple(walks: list['Walk'],
+                            properties: t.Optional[dict[str, t.Union[bool, int, float, str]]] = None,
+                            transform: t.Optional['Transform'] = None) -> str: ...
+    def to_wkt(self) -> str: ...
+    @staticmethod
+    def to_wkt_multiple(walks: list['Walk']) -> str: ...
+    def to_csv(self, path: str): ...
+    @staticmethod
+    def to_csv_multiple(walks: list['Walk'], path: str): ...
+    def to_gpx(self, path: str, start_time: str, time_step_len: float,
+               transform: t.Optional['Transform'] = None): ...
+    def plot(self, filename: str, options: t.Optional['PlotOptions'] = None): ...
+    def animate(self, path: str, options: t.Optional['AnimationOptions'] = None,
+                dp: t.Optional['DynamicProgram'] = None): ...
+    @staticmethod
+    def plot_multiple(walks: list['Walk'], filename: str, options: t.Optional['PlotOptions'] = None): ...
+    def __repr__(self) -> str: ...
+
+class PlotBackend(Enum):
+    Bitmap = 1
+    Svg = 2
+
+class PlotOptions:
+    width: int
+    height: int
+    line_color: t.Optional[tuple[int, int, int]]
+    line_width: int
+    point_labels: bool
+    background: tuple[int, int, int]
+    x_range: t.Optional[tuple[int, int]]
+    y_range: t.Optional[tuple[int, int]]
+    backend: PlotBackend
+
+    def __new__(cls, width: int = 1000, height: int = 1000,
+                line_color: t.Optional[tuple[int, int, int]] = None, line_width: int = 1,
+                point_labels: bool = True, background: tuple[int, int, int] = (255, 255, 255),
+                x_range: t.Optional[tuple[int, int]] = None, y_range: t.Optional[tuple[int, int]] = None,
+                backend: PlotBackend = PlotBackend.Bitmap) -> 'PlotOptions': ...
+
+class AnimationOptions:
+    frame_delay: int
+    frames: bool
+    plot: PlotOptions
+
+    def __new__(cls, frame_delay: int = 100, frames: bool = False,
+                plot: PlotOptions = PlotOptions()) -> 'AnimationOptions': ...
+
+class WalkEnsemble:
+    def __new__(cls, walks: list['Walk']) -> 'WalkEnsemble': ...
+    def len(self) -> int: ...
+    def is_empty(self) -> bool: ...
+    @property
+    def walks(self) -> list['Walk']: ...
+    def sample_raster(self, grid: list[list[int]], transform: 'Transform') -> tuple[list[list[t.Optional[int]]], dict[int, float]]: ...
+    def bootstrap(self, metric: t.Callable[['Walk'], float], n_resamples: int, seed: int) -> 'ConfidenceInterval': ...
+    def msd(self, max_lag: t.Optional[int] = None, transform: t.Optional['Transform'] = None) -> list[float]: ...
+    def __len__(self) -> int: ...
     def __repr__(self) -> str: ...
+
+class ConfidenceInterval:
+    estimate: float
+    lower: float
+    upper: float
+    std_error: float
+    level: float
+
+    def __repr__(self) -> str: ...
+
+class Quantiles:
+    min: float
+    q25: float
+    median: float
+    q75: float
+    max: float
+    mean: float
+
+class ComparisonReport:
+    walks: int
+    frechet_distance: 'Quantiles'
+    endpoint_error: 'Quantiles'
+    corridor_coverage: float
+
+    @staticmethod
+    def compare(real: 'Walk', simulated: 'WalkEnsemble') -> 'ComparisonReport': ...
+    def to_markdown(self) -> str: ...
+    def to_json(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+class Workspace:
+    """
+    A directory collecting all plots and saved dynamic programs written while it is active.
+
+    Relative output paths are resolved inside the workspace directory. On exit, a
+    `manifest.json` listing all produced artifacts is written to the directory.
+    """
+
+    def __new__(cls, path: str) -> 'Workspace': ...
+    @property
+    def path(self) -> str: ...
+    def __enter__(self) -> 'Workspace': ...
+    def __exit__(self, exc_type: t.Any, exc_value: t.Any, traceback: t.Any) -> bool: ...
+    def __repr__(self) -> str: ...
+
+def workspace(path: str) -> 'Workspace':
+    """
+    Creates a workspace at the given path, to be used as a context manager.
+
+    :param path: Directory to write outputs to. It is created if it does not exist.
+    """
+    ...
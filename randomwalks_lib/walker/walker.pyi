@@ -1,3 +1,4 @@
+import typing as t
 from enum import Enum
 
 class WalkerError(Enum):
@@ -5,44 +6,257 @@ class WalkerError(Enum):
     RequiresMultipleDynamicPrograms = 2,
     NoPathExists = 3,
     InconsistentPath = 4,
-    RandomDistributionError = 5
+    RandomDistributionError = 5,
+    InvalidConfiguration = 6,
+    PythonException = 7
 
 class StandardWalker:
     def __new__(cls, kernel: 'Kernel') -> 'StandardWalker': ...
-    def generate_path(self, dp: 'DynamicProgram', to_x: int, to_y: int,
-                      time_steps: int) -> 'Walk': ...
-    def generate_paths(self, dp: 'DynamicProgram', qty: int,
-                       to_x: int, to_y: int, time_steps: int) -> list['Walk']: ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int,
+                             to_y: int, time_steps: int, seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def generate_path_through(self, dp: 'DynamicProgram | DynamicProgramPool', from_point: 'XYPoint',
+                              waypoints: list[tuple['XYPoint', int]], seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_path_between(self, dp: 'DynamicProgram | DynamicProgramPool', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
     def name(self, short: bool) -> str: ...
 
 class CorrelatedWalker:
     def __new__(cls) -> 'CorrelatedWalker': ...
-    def generate_path(self, dp: list['DynamicProgram'], to_x: int, to_y: int,
-                      time_steps: int) -> 'Walk': ...
-    def generate_paths(self, dp: list['DynamicProgram'], qty: int,
-                       to_x: int, to_y: int, time_steps: int) -> list['Walk']: ...
+    def generate_path(self, dp: 'DynamicProgramPool | list[DynamicProgram]', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgramPool | list[DynamicProgram]', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_between(self, dp: 'DynamicProgramPool | list[DynamicProgram]', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
     def name(self, short: bool) -> str: ...
 
 class MultiStepWalker:
-    def __new__(cls, max_step_size: int) -> 'MultiStepWalker': ...
-    def generate_path(self, dp: 'DynamicProgram', to_x: int, to_y: int,
-                      time_steps: int) -> 'Walk': ...
-    def generate_paths(self, dp: 'DynamicProgram', qty: int,
-                       to_x: int, to_y: int, time_steps: int) -> list['Walk']: ...
+    def __new__(cls, max_step_size: int, kernel: 'Kernel',
+                jump_penalty: t.Optional[t.Literal['exponential', 'power']]=None,
+                penalty_strength: float=1.0) -> 'MultiStepWalker': ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int, time_steps: int,
+                             seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def generate_path_through(self, dp: 'DynamicProgram | DynamicProgramPool', from_point: 'XYPoint',
+                              waypoints: list[tuple['XYPoint', int]], seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_path_between(self, dp: 'DynamicProgram | DynamicProgramPool', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
     def name(self, short: bool) -> str: ...
 
 class LandCoverWalker:
     def __new__(cls, max_step_sizes: dict[int, int], land_cover: list[list[int]]) -> 'LandCoverWalker': ...
-    def generate_path(self, dp: 'DynamicProgram', to_x: int, to_y: int,
-                      time_steps: int) -> 'Walk': ...
-    def generate_paths(self, dp: 'DynamicProgram', qty: int,
-                       to_x: int, to_y: int, time_steps: int) -> list['Walk']: ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int, time_steps: int,
+                             seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def generate_path_through(self, dp: 'DynamicProgram | DynamicProgramPool', from_point: 'XYPoint',
+                              waypoints: list[tuple['XYPoint', int]], seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_path_between(self, dp: 'DynamicProgram | DynamicProgramPool', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
     def name(self, short: bool) -> str: ...
 
 class LevyWalker:
     def __new__(cls, jump_probability: float, jump_distance: int, kernel: 'Kernel') -> 'LevyWalker': ...
-    def generate_path(self, dp: 'DynamicProgram', to_x: int, to_y: int,
-                      time_steps: int) -> 'Walk': ...
-    def generate_paths(self, dp: 'DynamicProgram', qty: int,
-                       to_x: int, to_y: int, time_steps: int) -> list['Walk']: ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int, time_steps: int,
+                             seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def generate_path_through(self, dp: 'DynamicProgram | DynamicProgramPool', from_point: 'XYPoint',
+                              waypoints: list[tuple['XYPoint', int]], seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_path_between(self, dp: 'DynamicProgram | DynamicProgramPool', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
     def name(self, short: bool) -> str: ...
+
+class ScheduledWalker:
+    def __new__(cls, kernel: 'Kernel',
+                schedule: list[tuple[int, int, 'Kernel']]=[]) -> 'ScheduledWalker': ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int, time_steps: int,
+                             seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def generate_path_through(self, dp: 'DynamicProgram | DynamicProgramPool', from_point: 'XYPoint',
+                              waypoints: list[tuple['XYPoint', int]], seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_path_between(self, dp: 'DynamicProgram | DynamicProgramPool', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def name(self, short: bool) -> str: ...
+
+class AttractionWalker:
+    def __new__(cls, kernel: 'Kernel', attractor: 'XYPoint', attraction: float,
+                range: float) -> 'AttractionWalker': ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int, time_steps: int,
+                             seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def generate_path_through(self, dp: 'DynamicProgram | DynamicProgramPool', from_point: 'XYPoint',
+                              waypoints: list[tuple['XYPoint', int]], seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_path_between(self, dp: 'DynamicProgram | DynamicProgramPool', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def name(self, short: bool) -> str: ...
+
+class BudgetWalker:
+    def __new__(cls, kernel: 'Kernel', budget: float) -> 'BudgetWalker': ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int, time_steps: int,
+                             seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def generate_path_through(self, dp: 'DynamicProgram | DynamicProgramPool', from_point: 'XYPoint',
+                              waypoints: list[tuple['XYPoint', int]], seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_path_between(self, dp: 'DynamicProgram | DynamicProgramPool', from_x: int, from_y: int, to_x: int,
+                              to_y: int, time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def name(self, short: bool) -> str: ...
+
+class SegmentedWalker:
+    def __new__(cls, walker: 'StandardWalker') -> 'SegmentedWalker': ...
+    def generate_path(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, dp: 'DynamicProgram | DynamicProgramPool', qty: int,
+                       to_x: int, to_y: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def generate_path_scored(self, dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int, time_steps: int,
+                             seed: t.Optional[int]=None) -> tuple['Walk', float]: ...
+    def name(self, short: bool) -> str: ...
+
+class CustomWalker(t.Protocol):
+    """
+    Any object providing these methods can be used as a walker in `Dataset.rw_between()` and
+    `Dataset.generate_walks()`.
+    """
+    def generate_path(self, dp: 'DynamicProgram | list[DynamicProgram]', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk': ...
+    def name(self, short: bool) -> str: ...
+
+class ForwardWalker:
+    def __new__(cls, kernel: 'Kernel') -> 'ForwardWalker': ...
+    def generate_path(self, from_point: 'XYPoint', time_steps: int,
+                      seed: t.Optional[int]=None) -> 'Walk': ...
+    def generate_paths(self, from_point: 'XYPoint', qty: int, time_steps: int,
+                       seed: t.Optional[int]=None) -> list['Walk']: ...
+    def name(self, short: bool) -> str: ...
+
+class FailureBucket:
+    min_distance: float
+    max_distance: float
+    targets: int
+    samples: int
+    inconsistent_path: int
+    no_path_exists: int
+    other: int
+
+    def failure_rate(self) -> float: ...
+    def inconsistent_path_rate(self) -> float: ...
+    def no_path_exists_rate(self) -> float: ...
+    def __repr__(self) -> str: ...
+
+class FailureReport:
+    time_steps: int
+    bucket_width: float
+    buckets: list['FailureBucket']
+
+    @staticmethod
+    def measure(dp: 'DynamicProgram', walker: 'StandardWalker', targets: list['XYPoint'],
+                n: int) -> 'FailureReport':
+        """
+        Generates `n` walks to each target and reports how often generation fails, grouped by
+        the distance of the targets from the origin.
+        """
+        ...
+    def failure_rate(self) -> float: ...
+    def to_json(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+class WalkResult:
+    walk: t.Optional['Walk']
+    error: t.Optional['WalkerError']
+    retries: int
+    duration: float
+
+    @staticmethod
+    def generate(walker: 'StandardWalker', dp: 'DynamicProgram', to_x: int, to_y: int,
+                 time_steps: int, max_retries: int = 0) -> 'WalkResult':
+        """
+        Generates a walk without raising on failure. Attempts failing by chance are retried up to
+        `max_retries` times.
+        """
+        ...
+    def is_ok(self) -> bool: ...
+    def error_message(self) -> t.Optional[str]: ...
+    def __repr__(self) -> str: ...
+
+class BatchResult:
+    results: list['WalkResult']
+    duration: float
+
+    @staticmethod
+    def generate(walker: 'StandardWalker', dp: 'DynamicProgram', qty: int, to_x: int, to_y: int,
+                 time_steps: int, max_retries: int = 0) -> 'BatchResult':
+        """
+        Generates `qty` walks without aborting on failed walks.
+        """
+        ...
+    def walks(self) -> list['Walk']: ...
+    def errors(self) -> list[tuple[int, 'WalkerError']]: ...
+    def success_rate(self) -> float: ...
+    def retries(self) -> int: ...
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+class StepTrace:
+    probabilities: list[float]
+    chosen: t.Optional[int]
+
+    def chosen_probability(self) -> t.Optional[float]: ...
+    def __repr__(self) -> str: ...
+
+class WalkTrace:
+    walk: t.Optional['Walk']
+    error: t.Optional['WalkerError']
+    steps: list['StepTrace']
+
+    @staticmethod
+    def generate(walker: 'StandardWalker', dp: 'DynamicProgram', to_x: int, to_y: int,
+                 time_steps: int, seed: t.Optional[int]=None) -> 'WalkTrace':
+        """
+        Generates a walk and records the probabilities of all candidates in each step together
+        with the chosen one.
+        """
+        ...
+    def failed_step(self) -> t.Optional[int]: ...
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+class RegionMask:
+    def __new__(cls, allowed: list[list[bool]]) -> 'RegionMask': ...
+    @staticmethod
+    def from_probabilities(probabilities: list[list[float]]) -> 'RegionMask': ...
+    def allows(self, x: int, y: int) -> bool: ...
+    def generate_path(self, walker: 'StandardWalker', dp: 'DynamicProgram | DynamicProgramPool', to_x: int, to_y: int,
+                      time_steps: int, seed: t.Optional[int]=None) -> 'Walk':
+        """
+        Generates a walk that never enters a cell forbidden by this mask.
+        """
+        ...
@@ -11,6 +11,14 @@
 //! - Add a line of points using [`line()`](DatasetBuilder::line)
 //! - Add points in a certain area using [`fill()`](DatasetBuilder::fill)
 //! - Add points to randomly generated locations using [`random()`](DatasetBuilder::random)
+//! - Add a regular grid of points using [`grid()`](DatasetBuilder::grid)
+//! - Add normally distributed points around a center using
+//! [`gaussian_cluster()`](DatasetBuilder::gaussian_cluster)
+//!
+//! Manually added points can additionally be displaced by random noise using
+//! [`jitter()`](DatasetBuilder::jitter) and given synthetic timestamps using
+//! [`timestamps()`](DatasetBuilder::timestamps). This allows creating synthetic benchmark datasets
+//! without any external data.
 //!
 //! [`ColumnAction`s](loader::ColumnAction) are used to define which column of the imported data
 //! (for CSV and Polars) contains which information, such as the X- and Y coordinates etc.
@@ -67,19 +75,45 @@
 //!     .unwrap();
 //! ```
 //!
+//! The last example creates a synthetic dataset consisting of a 10x10 grid of points spaced by
+//! 50 and a cluster of 20 points around `(250, 250)`. Each point is slightly jittered and a
+//! timestamp is stored under the key `time`, with consecutive points being 10 minutes apart.
+//! Generated points use the coordinate type of the dataset.
+//!
+//! ```
+//! use randomwalks_lib::dataset::builder::DatasetBuilder;
+//! use randomwalks_lib::dataset::loader::CoordinateType;
+//! use time::macros::datetime;
+//! use time::Duration;
+//!
+//! let dataset = DatasetBuilder::new()
+//!     .coordinate_type(CoordinateType::XY)
+//!     .grid(10, 10, 50.0)
+//!     .gaussian_cluster(20, (250.0, 250.0), 15.0)
+//!     .jitter(2.0)
+//!     .timestamps("time", datetime!(2023-01-01 0:00), Duration::minutes(10))
+//!     .build()
+//!     .unwrap();
+//! ```
+//!
 
 use crate::dataset::loader::csv::{CSVLoader, CSVLoaderOptions};
+#[cfg(feature = "polars_loading")]
 use crate::dataset::loader::polars::{PolarsLoader, PolarsLoaderOptions};
 use crate::dataset::loader::{ColumnAction, CoordinateType};
-use crate::dataset::point::{Point, XYPoint};
+use crate::dataset::point::{GCSPoint, Point, XYPoint};
 use crate::dataset::{Datapoint, Dataset};
 use crate::xy;
 use anyhow::bail;
 #[cfg(feature = "polars_loading")]
 use polars::prelude::DataFrame;
+use rand::distributions::Distribution;
 use rand::Rng;
+use statrs::distribution::Normal;
 use std::collections::HashMap;
 use thiserror::Error;
+use time::macros::format_description;
+use time::{Duration, PrimitiveDateTime};
 
 /// An error that can occur when using a [`DatasetBuilder`](DatasetBuilder).
 #[derive(Error, Debug)]
@@ -94,6 +128,27 @@ pub enum DatasetBuilderError {
     /// the [`coordinate_type()`](DatasetBuilder::coordinate_type) function.
     #[error("a coordinate type must be set")]
     NoCoordinateTypeSet,
+
+    /// This error occurs when a standard deviation used for generating points, e.g. in
+    /// [`gaussian_cluster()`](DatasetBuilder::gaussian_cluster) or
+    /// [`jitter()`](DatasetBuilder::jitter), is negative or not finite.
+    #[error("standard deviation must be non-negative and finite")]
+    InvalidSigma,
+}
+
+/// Points that are generated when building the dataset, as their coordinate type is only known
+/// then.
+enum Generator {
+    Grid {
+        nx: usize,
+        ny: usize,
+        spacing: f64,
+    },
+    GaussianCluster {
+        qty: usize,
+        center: (f64, f64),
+        sigma: f64,
+    },
 }
 
 #[derive(Default)]
@@ -117,6 +172,9 @@ pub struct DatasetBuilder {
     column_actions: Vec<ColumnAction<String>>,
     coordinate_type: Option<CoordinateType>,
     points: Vec<Point>,
+    generators: Vec<Generator>,
+    jitter: Option<f64>,
+    timestamps: Option<(String, PrimitiveDateTime, Duration)>,
 }
 
 impl DatasetBuilder {
@@ -258,6 +316,67 @@ impl DatasetBuilder {
         self
     }
 
+    /// Adds a regular grid of points to the dataset.
+    ///
+    /// This adds `nx * ny` points to the dataset, starting at `(0, 0)`. Points are placed in `nx`
+    /// columns and `ny` rows which are spaced by `spacing` in both directions. The points are
+    /// generated in the [`CoordinateType`](loader::CoordinateType) of the dataset when calling
+    /// [`build()`](DatasetBuilder::build), with XY coordinates being rounded to the nearest
+    /// integer.
+    pub fn grid(mut self, nx: usize, ny: usize, spacing: f64) -> Self {
+        self.source = DatasetSource::Manual;
+        self.generators.push(Generator::Grid { nx, ny, spacing });
+
+        self
+    }
+
+    /// Adds a cluster of normally distributed points to the dataset.
+    ///
+    /// This adds `qty` points whose X- and Y-coordinates are drawn independently from normal
+    /// distributions with mean `center` and standard deviation `sigma`. The points are generated
+    /// in the [`CoordinateType`](loader::CoordinateType) of the dataset when calling
+    /// [`build()`](DatasetBuilder::build), with XY coordinates being rounded to the nearest
+    /// integer.
+    pub fn gaussian_cluster(mut self, qty: usize, center: (f64, f64), sigma: f64) -> Self {
+        self.source = DatasetSource::Manual;
+        self.generators
+            .push(Generator::GaussianCluster { qty, center, sigma });
+
+        self
+    }
+
+    /// Displaces all manually added points by normally distributed noise.
+    ///
+    /// The X- and Y-coordinates of each point are displaced independently by values drawn from a
+    /// normal distribution with mean 0 and standard deviation `sigma`. XY coordinates are rounded
+    /// to the nearest integer after being displaced. Points loaded from CSV or Polars are not
+    /// affected.
+    pub fn jitter(mut self, sigma: f64) -> Self {
+        self.jitter = Some(sigma);
+
+        self
+    }
+
+    /// Adds synthetic timestamps to all manually added points.
+    ///
+    /// The first point gets the timestamp `start`, every following point is `interval` later
+    /// than the one before. The timestamps are stored in the metadata of each point under
+    /// `metadata_key` in the format `year-month-day hour:minute:second`, which is the default
+    /// format used by [`DatasetWalksBuilder`](crate::dataset::walks_builder::DatasetWalksBuilder).
+    pub fn timestamps<S>(
+        mut self,
+        metadata_key: S,
+        start: PrimitiveDateTime,
+        interval: Duration,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        self.timestamps = Some((metadata_key.into(), start, interval));
+
+        self
+    }
+
     /// Builds a dataset.
     ///
     /// This builds the dataset after all options have been specified. Returns a
@@ -296,15 +415,73 @@ impl DatasetBuilder {
             }
             DatasetSource::Manual => {
                 let mut dataset = Dataset::new(coordinate_type);
-
-                dataset.data = self
-                    .points
-                    .iter()
-                    .map(|p| Datapoint {
-                        point: p.clone(),
-                        metadata: HashMap::new(),
+                let mut rng = rand::thread_rng();
+                let mut points = self.points;
+
+                for generator in self.generators {
+                    match generator {
+                        Generator::Grid { nx, ny, spacing } => {
+                            for y in 0..ny {
+                                for x in 0..nx {
+                                    points.push(point(
+                                        coordinate_type,
+                                        x as f64 * spacing,
+                                        y as f64 * spacing,
+                                    ));
+                                }
+                            }
+                        }
+                        Generator::GaussianCluster { qty, center, sigma } => {
+                            let x_dist = normal(center.0, sigma)?;
+                            let y_dist = normal(center.1, sigma)?;
+
+                            for _ in 0..qty {
+                                let x = x_dist.map_or(center.0, |dist| dist.sample(&mut rng));
+                                let y = y_dist.map_or(center.1, |dist| dist.sample(&mut rng));
+
+                                points.push(point(coordinate_type, x, y));
+                            }
+                        }
+                    }
+                }
+
+                let jitter = match self.jitter {
+                    Some(sigma) => normal(0.0, sigma)?,
+                    None => None,
+                };
+
+                if let Some(dist) = jitter {
+                    for point in points.iter_mut() {
+                        match point {
+                            Point::GCS(p) => {
+                                p.x += dist.sample(&mut rng);
+                                p.y += dist.sample(&mut rng);
+                            }
+                            Point::XY(p) => {
+                                p.x += dist.sample(&mut rng).round() as i64;
+                                p.y += dist.sample(&mut rng).round() as i64;
+                            }
+                        }
+                    }
+                }
+
+                let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+                dataset.data = points
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, point)| {
+                        let mut metadata = HashMap::new();
+
+                        if let Some((key, start, interval)) = &self.timestamps {
+                            let time = *start + *interval * i as u32;
+
+                            metadata.insert(key.clone(), time.format(format)?);
+                        }
+
+                        Ok(Datapoint { point, metadata })
                     })
-                    .collect();
+                    .collect::<anyhow::Result<_>>()?;
 
                 Ok(dataset)
             }
@@ -313,6 +490,31 @@ impl DatasetBuilder {
     }
 }
 
+/// Creates a point at `(x, y)` in the given `coordinate_type`, rounding XY coordinates to the
+/// nearest integer.
+fn point(coordinate_type: CoordinateType, x: f64, y: f64) -> Point {
+    match coordinate_type {
+        CoordinateType::GCS => Point::GCS(GCSPoint::new(x, y)),
+        CoordinateType::XY => Point::XY(xy!(x.round() as i64, y.round() as i64)),
+    }
+}
+
+/// Creates a normal distribution, or returns `None` if the standard deviation is 0, i.e. if all
+/// samples equal the mean.
+fn normal(mean: f64, sigma: f64) -> Result<Option<Normal>, DatasetBuilderError> {
+    if !sigma.is_finite() || sigma < 0.0 {
+        return Err(DatasetBuilderError::InvalidSigma);
+    }
+
+    if sigma == 0.0 {
+        return Ok(None);
+    }
+
+    Normal::new(mean, sigma)
+        .map(Some)
+        .map_err(|_| DatasetBuilderError::InvalidSigma)
+}
+
 impl Default for DatasetBuilder {
     fn default() -> Self {
         Self {
@@ -322,6 +524,230 @@ impl Default for DatasetBuilder {
             column_actions: Vec::new(),
             coordinate_type: None,
             points: Vec::new(),
+            generators: Vec::new(),
+            jitter: None,
+            timestamps: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::builder::{DatasetBuilder, DatasetBuilderError};
+    use crate::dataset::loader::CoordinateType;
+    use crate::dataset::point::{GCSPoint, Point, XYPoint};
+    use crate::xy;
+    use time::macros::datetime;
+    use time::Duration;
+
+    fn points(builder: DatasetBuilder) -> Vec<Point> {
+        let dataset = builder.build().unwrap();
+
+        (0..dataset.len())
+            .map(|i| dataset.get(i).unwrap().point.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_builder_missing_coordinate_type() {
+        let dataset = DatasetBuilder::new().grid(2, 2, 1.0).build();
+
+        assert!(matches!(
+            dataset.err().unwrap().downcast_ref::<DatasetBuilderError>(),
+            Some(DatasetBuilderError::NoCoordinateTypeSet)
+        ));
+    }
+
+    #[test]
+    fn test_builder_missing_source() {
+        let dataset = DatasetBuilder::new()
+            .coordinate_type(CoordinateType::XY)
+            .build();
+
+        assert!(matches!(
+            dataset.err().unwrap().downcast_ref::<DatasetBuilderError>(),
+            Some(DatasetBuilderError::NoDatasetSourceSet)
+        ));
+    }
+
+    #[test]
+    fn test_builder_line_and_fill() {
+        let line = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::XY)
+                .line(3, xy!(1, 1), xy!(2, -1)),
+        );
+
+        assert_eq!(
+            line,
+            vec![
+                Point::XY(xy!(1, 1)),
+                Point::XY(xy!(3, 0)),
+                Point::XY(xy!(5, -1))
+            ]
+        );
+
+        let fill = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::XY)
+                .fill(xy!(0, 0), xy!(4, 2), xy!(2, 2)),
+        );
+
+        assert_eq!(fill, vec![Point::XY(xy!(0, 0)), Point::XY(xy!(2, 0))]);
+    }
+
+    #[test]
+    fn test_builder_random() {
+        let random = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::XY)
+                .random(50, xy!(-5, 10), xy!(5, 20)),
+        );
+
+        assert_eq!(random.len(), 50);
+        assert!(random.iter().all(|point| matches!(
+            point,
+            Point::XY(p) if (-5..5).contains(&p.x) && (10..20).contains(&p.y)
+        )));
+    }
+
+    #[test]
+    fn test_builder_grid() {
+        let grid = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::XY)
+                .grid(3, 2, 2.0),
+        );
+
+        assert_eq!(
+            grid,
+            vec![
+                Point::XY(xy!(0, 0)),
+                Point::XY(xy!(2, 0)),
+                Point::XY(xy!(4, 0)),
+                Point::XY(xy!(0, 2)),
+                Point::XY(xy!(2, 2)),
+                Point::XY(xy!(4, 2)),
+            ]
+        );
+
+        let grid = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::GCS)
+                .grid(2, 2, 0.25),
+        );
+
+        assert_eq!(
+            grid,
+            vec![
+                Point::GCS(GCSPoint::new(0.0, 0.0)),
+                Point::GCS(GCSPoint::new(0.25, 0.0)),
+                Point::GCS(GCSPoint::new(0.0, 0.25)),
+                Point::GCS(GCSPoint::new(0.25, 0.25)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builder_gaussian_cluster() {
+        let cluster = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::XY)
+                .gaussian_cluster(10, (3.4, -2.6), 0.0),
+        );
+
+        assert_eq!(cluster, vec![Point::XY(xy!(3, -3)); 10]);
+
+        let cluster = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::GCS)
+                .gaussian_cluster(3, (7.5, 51.5), 0.0),
+        );
+
+        assert_eq!(cluster, vec![Point::GCS(GCSPoint::new(7.5, 51.5)); 3]);
+
+        let cluster = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::GCS)
+                .gaussian_cluster(100, (7.5, 51.5), 0.01),
+        );
+
+        assert_eq!(cluster.len(), 100);
+        assert!(cluster.iter().all(|point| matches!(
+            point,
+            Point::GCS(p) if (p.x - 7.5).abs() < 0.1 && (p.y - 51.5).abs() < 0.1
+        )));
+        assert!(cluster
+            .iter()
+            .any(|point| matches!(point, Point::GCS(p) if p.x != 7.5)));
+
+        let dataset = DatasetBuilder::new()
+            .coordinate_type(CoordinateType::XY)
+            .gaussian_cluster(10, (0.0, 0.0), -1.0)
+            .build();
+
+        assert!(matches!(
+            dataset.err().unwrap().downcast_ref::<DatasetBuilderError>(),
+            Some(DatasetBuilderError::InvalidSigma)
+        ));
+    }
+
+    #[test]
+    fn test_builder_jitter() {
+        let jittered = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::XY)
+                .grid(5, 5, 100.0)
+                .jitter(2.0),
+        );
+
+        assert_eq!(jittered.len(), 25);
+
+        for (i, point) in jittered.iter().enumerate() {
+            let Point::XY(p) = point else {
+                panic!("expected XY point");
+            };
+
+            assert!((p.x - (i % 5) as i64 * 100).abs() <= 20);
+            assert!((p.y - (i / 5) as i64 * 100).abs() <= 20);
         }
+
+        let unchanged = points(
+            DatasetBuilder::new()
+                .coordinate_type(CoordinateType::GCS)
+                .grid(2, 1, 0.5)
+                .jitter(0.0),
+        );
+
+        assert_eq!(
+            unchanged,
+            vec![
+                Point::GCS(GCSPoint::new(0.0, 0.0)),
+                Point::GCS(GCSPoint::new(0.5, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builder_timestamps() {
+        let dataset = DatasetBuilder::new()
+            .coordinate_type(CoordinateType::XY)
+            .line(3, xy!(0, 0), xy!(1, 0))
+            .timestamps("time", datetime!(2023-01-01 23:50), Duration::minutes(10))
+            .build()
+            .unwrap();
+
+        let times: Vec<&str> = (0..dataset.len())
+            .map(|i| dataset.get(i).unwrap().metadata["time"].as_str())
+            .collect();
+
+        assert_eq!(
+            times,
+            vec![
+                "2023-01-01 23:50:00",
+                "2023-01-02 00:00:00",
+                "2023-01-02 00:10:00"
+            ]
+        );
     }
 }
@@ -0,0 +1,206 @@
+//! Provides small canonical datasets together with matching dynamic program configurations.
+//!
+//! Example datasets are generated deterministically and do not require any data files. They are
+//! meant for tutorials and integration tests exercising the whole pipeline, from computing a
+//! dynamic program to generating walks between the points of a dataset.
+//!
+//! ```
+//! # use randomwalks_lib::dataset::example::ExampleKind;
+//! # use randomwalks_lib::dataset::Dataset;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kind = ExampleKind::Line;
+//! let dataset = Dataset::example(kind);
+//! let mut dp = kind.dp_builder().build().unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let walker: Box<dyn Walker> = Box::new(StandardWalker::new(
+//!     Kernel::from_generator(SimpleRwGenerator::default()).unwrap(),
+//! ));
+//! let walk = dataset.rw_between(&dp, &walker, 0, 1, kind.time_steps(), false, 0).unwrap();
+//! ```
+
+use crate::dataset::loader::CoordinateType;
+use crate::dataset::point::XYPoint;
+use crate::dataset::{Datapoint, Dataset};
+use crate::dp::builder::DynamicProgramBuilder;
+use crate::dp::simple::DynamicProgram;
+use crate::dp::DynamicProgramPool;
+use crate::kernel;
+use crate::kernel::simple_rw::SimpleRwGenerator;
+use crate::kernel::Kernel;
+use crate::xy;
+use pyo3::{pyclass, pymethods};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Field type of the cells forming the barrier of [`ExampleKind::TwoClusters`].
+const BARRIER_FIELD_TYPE: usize = 1;
+
+/// The kind of an example dataset.
+#[pyclass]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExampleKind {
+    /// Ten points on a straight line along the x axis, ten units apart.
+    Line,
+
+    /// Twelve points on a circle of radius 20 around the origin, visited in order and returning
+    /// to the first point.
+    Loop,
+
+    /// Two clusters of five points each, 40 units apart. The dynamic program contains a wall
+    /// between the clusters, so that walks from one cluster to the other have to go around it.
+    TwoClusters,
+}
+
+#[pymethods]
+impl ExampleKind {
+    /// Returns the number of time steps to use for walks between consecutive points.
+    pub fn time_steps(&self) -> usize {
+        match self {
+            ExampleKind::Line => 20,
+            ExampleKind::Loop => 20,
+            ExampleKind::TwoClusters => 80,
+        }
+    }
+
+    /// Returns the matching dynamic program, which still has to be computed.
+    #[pyo3(name = "dynamic_program")]
+    pub fn py_dynamic_program(&self) -> DynamicProgram {
+        let Ok(DynamicProgramPool::Single(dp)) = self.dp_builder().build() else {
+            unreachable!("example configurations are valid");
+        };
+
+        dp
+    }
+}
+
+impl ExampleKind {
+    /// Returns the points of the example dataset, each with metadata describing it.
+    fn points(&self) -> Vec<(XYPoint, HashMap<String, String>)> {
+        let metadata = |entries: &[(&str, String)]| -> HashMap<String, String> {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect()
+        };
+
+        match self {
+            ExampleKind::Line => (0..10)
+                .map(|i| (xy!(10 * i, 0), metadata(&[("index", i.to_string())])))
+                .collect(),
+            ExampleKind::Loop => (0..=12)
+                .map(|i| {
+                    let angle = 2.0 * PI * (i % 12) as f64 / 12.0;
+                    let point = xy!(
+                        (20.0 * angle.cos()).round() as i64,
+                        (20.0 * angle.sin()).round() as i64
+                    );
+
+                    (point, metadata(&[("index", i.to_string())]))
+                })
+                .collect(),
+            ExampleKind::TwoClusters => [("a", -20), ("b", 20)]
+                .into_iter()
+                .flat_map(|(cluster, center)| {
+                    [(0, 0), (-3, 0), (0, -3), (3, 0), (0, 3)]
+                        .into_iter()
+                        .map(move |(x, y)| (cluster, xy!(center + x, y)))
+                })
+                .enumerate()
+                .map(|(i, (cluster, point))| {
+                    let entries = [("index", i.to_string()), ("cluster", cluster.to_string())];
+
+                    (point, metadata(&entries))
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns a builder for the matching dynamic program.
+    ///
+    /// All examples use a simple random walk kernel. For [`ExampleKind::TwoClusters`], cells
+    /// forming a wall of length 21 at a distance of 20 to the right of the origin use a kernel
+    /// of zeros, so that no probability mass can enter them.
+    pub fn dp_builder(&self) -> DynamicProgramBuilder {
+        let time_limit = self.time_steps();
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let builder = DynamicProgramBuilder::new().simple().time_limit(time_limit);
+
+        match self {
+            ExampleKind::Line | ExampleKind::Loop => builder.kernel(kernel),
+            ExampleKind::TwoClusters => {
+                let size = 2 * time_limit + 1;
+                let mut field_types = vec![vec![0; size]; size];
+
+                for y in time_limit - 10..=time_limit + 10 {
+                    field_types[time_limit + 20][y] = BARRIER_FIELD_TYPE;
+                }
+
+                builder
+                    .kernels(vec![
+                        (0, kernel),
+                        (
+                            BARRIER_FIELD_TYPE,
+                            kernel!(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+                        ),
+                    ])
+                    .field_types(field_types)
+            }
+        }
+    }
+}
+
+impl Dataset {
+    /// Creates a small example dataset in XY coordinates. See [`ExampleKind`].
+    pub fn example(kind: ExampleKind) -> Self {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for (point, metadata) in kind.points() {
+            dataset.push(Datapoint::new(point.into(), metadata));
+        }
+
+        dataset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::example::ExampleKind;
+    use crate::dataset::Dataset;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::Walker;
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(Dataset::example(ExampleKind::Line).len(), 10);
+        assert_eq!(Dataset::example(ExampleKind::Loop).len(), 13);
+
+        let kind = ExampleKind::TwoClusters;
+        let dataset = Dataset::example(kind);
+        let mut dp = kind.dp_builder().build().unwrap();
+
+        assert_eq!(dataset.len(), 10);
+        assert_eq!(dataset.get(7).unwrap().metadata["cluster"], "b");
+
+        dp.compute().unwrap();
+
+        let walker: Box<dyn Walker> = Box::new(StandardWalker::new(
+            Kernel::from_generator(SimpleRwGenerator::default()).unwrap(),
+        ));
+        let walk = dataset
+            .rw_between(&dp, &walker, 4, 5, kind.time_steps(), false, 0)
+            .unwrap();
+
+        // The walk starts at (-20, 3) and has to go around the wall 20 units to the right of it
+        assert!(walk.iter().all(|p| p.x != 0 || (p.y - 3).abs() > 10));
+    }
+}
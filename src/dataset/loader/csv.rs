@@ -78,6 +78,7 @@ impl CSVLoader {
         Ok(Dataset {
             data: datapoints,
             coordinate_type: self.coordinate_type(),
+            ..Default::default()
         })
     }
 
@@ -1,5 +1,5 @@
 pub mod csv;
-#[cfg(feature = "polars")]
+#[cfg(feature = "polars_loading")]
 pub mod polars;
 
 use crate::dataset::Datapoint;
@@ -86,7 +86,7 @@
 //! # let dp = DynamicProgramBuilder::new()
 //! #     .simple()
 //! #     .time_limit(400)
-//! #     .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+//! #     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
 //! #     .build()
 //! #     .unwrap();
 //! # let walker = Box::new(StandardWalker);
@@ -116,7 +116,7 @@
 //! # let dp = DynamicProgramBuilder::new()
 //! #     .simple()
 //! #     .time_limit(400)
-//! #     .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+//! #     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
 //! #     .build()
 //! #     .unwrap();
 //! # let walker = Box::new(StandardWalker);
@@ -137,31 +137,35 @@
 //! [`DatasetWalksBuilder`](DatasetWalksBuilder) for more information.
 
 pub mod builder;
+pub mod example;
 pub mod loader;
 pub mod point;
+#[cfg(feature = "projection")]
+pub(crate) mod projection;
+pub mod transform;
 pub mod walks_builder;
 
+use crate::dataset::example::ExampleKind;
 use crate::dataset::loader::{CoordinateType, DatasetLoader};
-use crate::dataset::walks_builder::DatasetWalksBuilder;
-use crate::dp::simple::DynamicProgram;
-use crate::dp::{DynamicProgramPool, DynamicPrograms, PyDynamicProgramPool};
+#[cfg(feature = "projection")]
+use crate::dataset::projection::{ProjCache, GCS_CRS, XY_CRS};
+use crate::dataset::walks_builder::{DatasetWalksBuilder, Pairing};
+use crate::dp::DynamicProgramPool;
 use crate::walk::Walk;
+use crate::walker::result::RetryPolicy;
 use crate::walker::{Walker, WalkerType};
 use crate::xy;
 use anyhow::{anyhow, bail, Context};
 use line_drawing::Bresenham;
+use log::warn;
 use pathfinding::prelude::{build_path, dijkstra_all};
-#[cfg(feature = "plotting")]
-use plotters::prelude::*;
 use point::{Coordinates, GCSPoint, Point, XYPoint};
-use proj::Proj;
-use pyo3::{pyclass, pymethods, Py, PyAny, PyCell, PyObject, PyRef, PyRefMut, PyResult};
-use rand::distributions::uniform::SampleBorrow;
-use rand::Rng;
+use pyo3::{pyclass, pymethods, Py, PyAny, PyCell, PyObject, PyRef, PyRefMut, PyResult, Python};
 use std::collections::HashMap;
-use thiserror::Error;
 use time::macros::format_description;
 use time::PrimitiveDateTime;
+#[cfg(feature = "plotting")]
+use {crate::workspace, plotters::prelude::*, rand::Rng};
 
 /// A filter that can be applied to a [`Dataset`] by calling [`Dataset::filter`].
 #[derive(Debug)]
@@ -175,6 +179,20 @@ pub enum DatasetFilter {
     ByCoordinates(Point, Point),
 }
 
+/// A criterion used by [`Dataset::find_gaps`] to decide whether two consecutive points are
+/// separated by a gap.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GapCriterion {
+    /// Two points are separated by a gap if the time between them is at least the given number
+    /// of seconds. Timestamps are read from the metadata entry with the given key and have to be
+    /// in the format `year-month-day hour:minute:second`.
+    Duration(f64, String),
+
+    /// Two points are separated by a gap if the euclidean distance between them is at least the
+    /// given distance. Requires a dataset in XY coordinates.
+    Distance(f64),
+}
+
 #[pyclass]
 #[pyo3(name = "DatasetFilter")]
 #[derive(Clone)]
@@ -262,6 +280,8 @@ impl DatasetIterator {
 pub struct Dataset {
     data: Vec<Datapoint>,
     coordinate_type: CoordinateType,
+    #[cfg(feature = "projection")]
+    proj_cache: ProjCache,
 }
 
 #[pymethods]
@@ -274,9 +294,17 @@ impl Dataset {
         Self {
             data: Vec::new(),
             coordinate_type,
+            #[cfg(feature = "projection")]
+            proj_cache: ProjCache::default(),
         }
     }
 
+    #[staticmethod]
+    #[pyo3(name = "example")]
+    pub fn py_example(kind: ExampleKind) -> Self {
+        Dataset::example(kind)
+    }
+
     pub fn __len__(&self) -> usize {
         self.len()
     }
@@ -301,6 +329,48 @@ impl Dataset {
         self.data.get(index).cloned()
     }
 
+    /// Insert a [`Datapoint`] at position `index`, shifting all later datapoints to the right.
+    ///
+    /// Returns an error if `index` is greater than the length of the dataset.
+    pub fn insert(&mut self, index: usize, datapoint: Datapoint) -> anyhow::Result<()> {
+        if index > self.data.len() {
+            bail!("index out of bounds.");
+        }
+
+        self.data.insert(index, datapoint);
+
+        Ok(())
+    }
+
+    /// Remove and return the [`Datapoint`] at position `index`, shifting all later datapoints
+    /// to the left.
+    ///
+    /// Returns an error if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> anyhow::Result<Datapoint> {
+        if index >= self.data.len() {
+            bail!("index out of bounds.");
+        }
+
+        Ok(self.data.remove(index))
+    }
+
+    /// Replace the [`Datapoint`] at position `index` with the result of calling `f` on it.
+    #[pyo3(name = "update")]
+    pub fn py_update(&mut self, py: Python<'_>, index: usize, f: PyObject) -> anyhow::Result<()> {
+        let datapoint = self.get(index).context("index out of bounds.")?.clone();
+        let updated: Datapoint = f.call1(py, (datapoint,))?.extract(py)?;
+
+        self.data[index] = updated;
+
+        Ok(())
+    }
+
+    /// Append all given [`Datapoint`]s to the dataset.
+    #[pyo3(name = "extend")]
+    pub fn py_extend(&mut self, datapoints: Vec<Datapoint>) {
+        self.extend(datapoints);
+    }
+
     pub fn __iter__(&self) -> DatasetIterator {
         DatasetIterator {
             inner: self.data.clone().into_iter(),
@@ -313,6 +383,11 @@ impl Dataset {
     /// If `from` is `None`, then the range starts at the beginning of the dataset. If `to` is
     /// `None`, then the range ends at the end of the dataset.
     #[pyo3(signature = (from_idx=None, to_idx=None))]
+    #[pyo3(name = "clip_to_bbox")]
+    pub fn py_clip_to_bbox(&self, min: Point, max: Point) -> anyhow::Result<Vec<Dataset>> {
+        self.clip_to_bbox(min, max)
+    }
+
     pub fn keep(&mut self, from_idx: Option<usize>, to_idx: Option<usize>) {
         let from = from_idx.unwrap_or(0);
         let to = to_idx.unwrap_or(self.data.len());
@@ -390,6 +465,31 @@ impl Dataset {
         Ok(filtered)
     }
 
+    /// Find gaps between consecutive points that exceed either `min_duration` seconds or a
+    /// distance of `min_distance`.
+    ///
+    /// Exactly one of both thresholds has to be set. When using `min_duration`, timestamps are
+    /// read from the metadata entry `metadata_key`. See [`Dataset::find_gaps`].
+    #[pyo3(name = "find_gaps")]
+    #[pyo3(signature = (min_duration=None, min_distance=None, metadata_key=None))]
+    pub fn py_find_gaps(
+        &self,
+        min_duration: Option<f64>,
+        min_distance: Option<f64>,
+        metadata_key: Option<String>,
+    ) -> anyhow::Result<Vec<(usize, usize)>> {
+        let criterion = match (min_duration, min_distance) {
+            (Some(duration), None) => GapCriterion::Duration(
+                duration,
+                metadata_key.context("metadata key must be set to find gaps by duration")?,
+            ),
+            (None, Some(distance)) => GapCriterion::Distance(distance),
+            _ => bail!("exactly one of min_duration and min_distance must be set"),
+        };
+
+        self.find_gaps(criterion)
+    }
+
     /// Find the minimum and maximum coordinates of the dataset.
     ///
     /// Returns None if the dataset is empty. Otherwise, returns the minimum and maximum coordinates
@@ -477,22 +577,27 @@ impl Dataset {
     }
 
     /// Convert all GCS points in the dataset to XY points and normalize them to the range [from, to].
+    ///
+    /// Requires the `projection` feature.
+    #[cfg(feature = "projection")]
     pub fn convert_gcs_to_xy(&mut self, scale: f64) -> anyhow::Result<()> {
         if self.coordinate_type != CoordinateType::GCS {
             bail!("dataset is not in GCS coordinates");
         }
 
-        let from = "EPSG:4326";
-        let to = "EPSG:3857";
-        let conv = Proj::new_known_crs(&from, &to, None).unwrap();
+        let mut points = Vec::with_capacity(self.data.len());
 
-        for datapoint in self.data.iter_mut() {
-            let Point::GCS(point) = datapoint.point.clone() else {
+        for datapoint in self.data.iter() {
+            let Point::GCS(point) = datapoint.point else {
                 bail!("point not in GCS coordinates");
             };
-            let new = conv
-                .convert((point.x, point.y))
-                .context("point conversion failed")?;
+
+            points.push((point.x, point.y));
+        }
+
+        self.proj_cache.convert(GCS_CRS, XY_CRS, &mut points)?;
+
+        for (datapoint, new) in self.data.iter_mut().zip(points) {
             let new = XYPoint::from(((new.0 * scale) as i64, (new.1 * scale) as i64));
 
             datapoint.point = Point::XY(new);
@@ -503,25 +608,29 @@ impl Dataset {
         Ok(())
     }
 
+    /// Convert all XY points in the dataset back to GCS points.
+    ///
+    /// Requires the `projection` feature.
+    #[cfg(feature = "projection")]
     pub fn convert_xy_to_gcs(&mut self, scale: f64) -> anyhow::Result<()> {
         if self.coordinate_type != CoordinateType::XY {
             bail!("dataset is not in XY coordinates");
         }
 
-        let from = "EPSG:3857";
-        let to = "EPSG:4326";
-        let conv = Proj::new_known_crs(&from, &to, None).unwrap();
+        let mut points = Vec::with_capacity(self.data.len());
 
-        for datapoint in self.data.iter_mut() {
-            let Point::XY(point) = datapoint.point.clone() else {
+        for datapoint in self.data.iter() {
+            let Point::XY(point) = datapoint.point else {
                 bail!("point not in XY coordinates");
             };
-            let new = GCSPoint::from(
-                conv.convert((point.x as f64 / scale, point.y as f64 / scale))
-                    .context("point conversion failed")?,
-            );
 
-            datapoint.point = Point::GCS(new);
+            points.push((point.x as f64 / scale, point.y as f64 / scale));
+        }
+
+        self.proj_cache.convert(XY_CRS, GCS_CRS, &mut points)?;
+
+        for (datapoint, new) in self.data.iter_mut().zip(points) {
+            datapoint.point = Point::GCS(GCSPoint::from(new));
         }
 
         self.coordinate_type = CoordinateType::GCS;
@@ -530,6 +639,7 @@ impl Dataset {
     }
 
     #[pyo3(name = "rw_between")]
+    #[pyo3(signature = (dp, walker, from_idx, to_idx, time_steps, auto_scale=false, extra_steps=0, endpoint_tolerance=0, max_retries=0, retry_extra_steps=0))]
     pub fn py_rw_between(
         slf: &PyCell<Self>,
         dp: PyObject,
@@ -539,31 +649,28 @@ impl Dataset {
         time_steps: usize,
         auto_scale: bool,
         extra_steps: usize,
+        endpoint_tolerance: usize,
+        max_retries: usize,
+        retry_extra_steps: usize,
     ) -> anyhow::Result<Walk> {
-        let dp: PyDynamicProgramPool = dp.extract(slf.py())?;
-        let dp: DynamicProgramPool = dp.into();
-        let walker: WalkerType = walker.extract(slf.py())?;
-
-        let walker: &Box<dyn Walker> = &match walker {
-            WalkerType::Standard(walker) => Box::new(walker),
-            WalkerType::Correlated(walker) => Box::new(walker),
-            WalkerType::MultiStep(walker) => Box::new(walker),
-            WalkerType::Levy(walker) => Box::new(walker),
-        };
+        let dp: DynamicProgramPool = dp.extract(slf.py())?;
+        let walker: Box<dyn Walker> = walker.extract::<WalkerType>(slf.py())?.into();
 
-        slf.borrow().rw_between(
+        slf.borrow().rw_between_or_nearest(
             &dp,
-            walker,
+            &walker,
             from_idx,
             to_idx,
             time_steps,
             auto_scale,
             extra_steps,
+            endpoint_tolerance,
+            RetryPolicy::new(max_retries, retry_extra_steps),
         )
     }
 
     #[pyo3(name = "generate_walks")]
-    #[pyo3(signature = (dp, walker, count=1, time_steps=None, by_time_diff=None, by_dist=None, auto_scale=false, extra_steps=0))]
+    #[pyo3(signature = (dp, walker, count=1, time_steps=None, by_time_diff=None, by_dist=None, auto_scale=false, extra_steps=0, stride=None, pairs=None, endpoint_tolerance=0, max_retries=0, retry_extra_steps=0))]
     pub fn py_generate_walks(
         slf: &PyCell<Self>,
         dp: PyObject,
@@ -574,53 +681,45 @@ impl Dataset {
         by_dist: Option<f64>,
         auto_scale: bool,
         extra_steps: usize,
+        stride: Option<usize>,
+        pairs: Option<Vec<(usize, usize)>>,
+        endpoint_tolerance: usize,
+        max_retries: usize,
+        retry_extra_steps: usize,
     ) -> anyhow::Result<Vec<Walk>> {
-        let dp: DynamicProgramPool =
-            DynamicProgramPool::Single(dp.extract::<DynamicProgram>(slf.py())?);
-        let walker: WalkerType = walker.extract(slf.py())?;
-
-        let walker: Box<dyn Walker> = match walker {
-            WalkerType::Standard(walker) => Box::new(walker),
-            WalkerType::Correlated(walker) => Box::new(walker),
-            WalkerType::MultiStep(walker) => Box::new(walker),
-            WalkerType::Levy(walker) => Box::new(walker),
-        };
+        let dp: DynamicProgramPool = dp.extract(slf.py())?;
+        let walker: Box<dyn Walker> = walker.extract::<WalkerType>(slf.py())?.into();
 
         let dataset = slf.borrow();
 
-        if let Some(time_steps) = time_steps {
-            DatasetWalksBuilder::new()
-                .dataset(&dataset)
-                .dp(&dp)
-                .walker(&walker)
-                .count(count)
-                .time_steps(time_steps)
-                .set_auto_scale(auto_scale)
-                .extra_steps(extra_steps)
-                .build()
+        let builder = DatasetWalksBuilder::new()
+            .dataset(&dataset)
+            .dp(&dp)
+            .walker(&walker)
+            .count(count)
+            .set_auto_scale(auto_scale)
+            .extra_steps(extra_steps)
+            .endpoint_tolerance(endpoint_tolerance)
+            .retry_policy(RetryPolicy::new(max_retries, retry_extra_steps));
+
+        let builder = if let Some(time_steps) = time_steps {
+            builder.time_steps(time_steps)
         } else if let Some((time_step_len, metadata_key)) = by_time_diff {
-            DatasetWalksBuilder::new()
-                .dataset(&dataset)
-                .dp(&dp)
-                .walker(&walker)
-                .count(count)
-                .time_steps_by_time(time_step_len, metadata_key)
-                .set_auto_scale(auto_scale)
-                .extra_steps(extra_steps)
-                .build()
+            builder.time_steps_by_time(time_step_len, metadata_key)
         } else if let Some(multiplier) = by_dist {
-            DatasetWalksBuilder::new()
-                .dataset(&dataset)
-                .dp(&dp)
-                .walker(&walker)
-                .count(count)
-                .time_steps_by_dist(multiplier)
-                .set_auto_scale(auto_scale)
-                .extra_steps(extra_steps)
-                .build()
+            builder.time_steps_by_dist(multiplier)
         } else {
             bail!("some time step computation method must be set")
-        }
+        };
+
+        let builder = match (stride, pairs) {
+            (Some(_), Some(_)) => bail!("only one of stride and pairs can be set"),
+            (Some(stride), None) => builder.pairing(Pairing::Stride(stride)),
+            (None, Some(pairs)) => builder.pairing(Pairing::Custom(pairs)),
+            (None, None) => builder,
+        };
+
+        builder.build()
     }
 
     pub fn direct_between(&self, from_idx: usize, to_idx: usize) -> anyhow::Result<Walk> {
@@ -797,6 +896,7 @@ impl Dataset {
 
         // Draw plot
 
+        let path = workspace::output_path(&path, "plot")?;
         let root = BitMapBackend::new(&path, (1000, 1000)).into_drawing_area();
         root.fill(&WHITE).unwrap();
         let root = root.margin(10, 10, 10, 10);
@@ -850,6 +950,8 @@ impl Dataset {
         Ok(Self {
             data,
             coordinate_type: loader.coordinate_type(),
+            #[cfg(feature = "projection")]
+            proj_cache: ProjCache::default(),
         })
     }
 
@@ -870,6 +972,37 @@ impl Dataset {
         self.data.get(index)
     }
 
+    /// Modify the [`Datapoint`] at position `index` in place using `f`.
+    ///
+    /// Returns an error if `index` is out of bounds.
+    ///
+    /// ```
+    /// # use randomwalks_lib::dataset::{Datapoint, Dataset};
+    /// # use randomwalks_lib::dataset::loader::CoordinateType;
+    /// # use randomwalks_lib::dataset::point::{Point, XYPoint};
+    /// # use randomwalks_lib::xy;
+    /// # use std::collections::HashMap;
+    /// #
+    /// let mut dataset = Dataset::new(CoordinateType::XY);
+    /// dataset.push(Datapoint::new(xy!(1, 2).into(), HashMap::new()));
+    ///
+    /// dataset
+    ///     .update(0, |datapoint| {
+    ///         datapoint.metadata.insert("cleaned".into(), "true".into());
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(dataset.get(0).unwrap().metadata["cleaned"], "true");
+    /// ```
+    pub fn update<F>(&mut self, index: usize, f: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&mut Datapoint),
+    {
+        f(self.data.get_mut(index).context("index out of bounds.")?);
+
+        Ok(())
+    }
+
     /// Remove all datapoints from the dataset, keeping only the datapoints that match
     /// the given [`DatasetFilter`]s.
     ///
@@ -889,40 +1022,12 @@ impl Dataset {
                             break;
                         }
                     }
-                    DatasetFilter::ByCoordinates(from, to) => match self.coordinate_type {
-                        CoordinateType::GCS => {
-                            let Point::GCS(from) = from else {
-                                return Err(anyhow!("Expected GCS coordinates in filter."));
-                            };
-                            let Point::GCS(to) = to else {
-                                return Err(anyhow!("Expected GCS coordinates in filter."));
-                            };
-
-                            let x: f64 = datapoint.point.x();
-                            let y: f64 = datapoint.point.y();
-
-                            if x < from.x || x > to.x || y < from.y || y > to.y {
-                                keep = false;
-                                break;
-                            }
-                        }
-                        CoordinateType::XY => {
-                            let Point::XY(from) = from else {
-                                return Err(anyhow!("Expected XY coordinates in filter."));
-                            };
-                            let Point::XY(to) = to else {
-                                return Err(anyhow!("Expected XY coordinates in filter."));
-                            };
-
-                            let x: i64 = datapoint.point.x();
-                            let y: i64 = datapoint.point.y();
-
-                            if x < from.x || x > to.x || y < from.y || y > to.y {
-                                keep = false;
-                                break;
-                            }
+                    DatasetFilter::ByCoordinates(from, to) => {
+                        if !self.in_bbox(&datapoint.point, from, to)? {
+                            keep = false;
+                            break;
                         }
-                    },
+                    }
                 }
             }
 
@@ -938,6 +1043,129 @@ impl Dataset {
         Ok(filtered)
     }
 
+    /// Clips the dataset to the rectangle from `min` to `max`, including its boundary.
+    ///
+    /// Like [`Walk::clip_to_bbox()`], the dataset is split each time it leaves the rectangle, so
+    /// that each returned dataset is a maximal sequence of consecutive datapoints inside the
+    /// rectangle. This way, no walks are generated between datapoints before and after leaving
+    /// the rectangle. `min` and `max` must use the coordinate type of the dataset.
+    pub fn clip_to_bbox(&self, min: Point, max: Point) -> anyhow::Result<Vec<Dataset>> {
+        let mut datasets = Vec::new();
+        let mut current = Dataset::new(self.coordinate_type);
+
+        for datapoint in self.data.iter() {
+            if self.in_bbox(&datapoint.point, &min, &max)? {
+                current.push(datapoint.clone());
+            } else if !current.is_empty() {
+                datasets.push(std::mem::replace(
+                    &mut current,
+                    Dataset::new(self.coordinate_type),
+                ));
+            }
+        }
+
+        if !current.is_empty() {
+            datasets.push(current);
+        }
+
+        Ok(datasets)
+    }
+
+    /// Returns whether `point` lies in the rectangle from `from` to `to`.
+    ///
+    /// Returns an error if `from` or `to` does not use the coordinate type of the dataset.
+    fn in_bbox(&self, point: &Point, from: &Point, to: &Point) -> anyhow::Result<bool> {
+        match self.coordinate_type {
+            CoordinateType::GCS => {
+                let Point::GCS(from) = from else {
+                    return Err(anyhow!("Expected GCS coordinates in filter."));
+                };
+                let Point::GCS(to) = to else {
+                    return Err(anyhow!("Expected GCS coordinates in filter."));
+                };
+
+                let x: f64 = point.x();
+                let y: f64 = point.y();
+
+                Ok(x >= from.x && x <= to.x && y >= from.y && y <= to.y)
+            }
+            CoordinateType::XY => {
+                let Point::XY(from) = from else {
+                    return Err(anyhow!("Expected XY coordinates in filter."));
+                };
+                let Point::XY(to) = to else {
+                    return Err(anyhow!("Expected XY coordinates in filter."));
+                };
+
+                let x: i64 = point.x();
+                let y: i64 = point.y();
+
+                Ok(x >= from.x && x <= to.x && y >= from.y && y <= to.y)
+            }
+        }
+    }
+
+    /// Find gaps between consecutive [`Datapoint`]s according to the given [`GapCriterion`].
+    ///
+    /// Returns the index pairs `(i, i + 1)` of all gaps, which can directly be used with
+    /// [`Pairing::Custom`] to only generate walks for the gaps.
+    ///
+    /// ```
+    /// # use randomwalks_lib::dataset::{Datapoint, Dataset, GapCriterion};
+    /// # use randomwalks_lib::dataset::loader::CoordinateType;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// # use std::collections::HashMap;
+    /// #
+    /// let mut dataset = Dataset::new(CoordinateType::XY);
+    ///
+    /// for point in [xy!(0, 0), xy!(1, 0), xy!(10, 0), xy!(11, 0)] {
+    ///     dataset.push(Datapoint::new(point.into(), HashMap::new()));
+    /// }
+    ///
+    /// let gaps = dataset.find_gaps(GapCriterion::Distance(5.0)).unwrap();
+    ///
+    /// assert_eq!(gaps, vec![(1, 2)]);
+    /// ```
+    pub fn find_gaps(&self, criterion: GapCriterion) -> anyhow::Result<Vec<(usize, usize)>> {
+        let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+        let mut gaps = Vec::new();
+
+        for (i, pair) in self.data.windows(2).enumerate() {
+            let is_gap = match &criterion {
+                GapCriterion::Duration(min_duration, metadata_key) => {
+                    let mut timestamps = pair.iter().map(|datapoint| {
+                        let timestamp = datapoint
+                            .metadata
+                            .get(metadata_key)
+                            .context("found datapoint without timestamp metadata key.")?;
+
+                        PrimitiveDateTime::parse(timestamp, &format)
+                            .context("could not parse timestamp.")
+                    });
+                    let from = timestamps.next().unwrap()?;
+                    let to = timestamps.next().unwrap()?;
+
+                    (to - from).as_seconds_f64().abs() >= *min_duration
+                }
+                GapCriterion::Distance(min_distance) => {
+                    let (Point::XY(from), Point::XY(to)) = (&pair[0].point, &pair[1].point) else {
+                        bail!("Points have to be in XY coordinates.");
+                    };
+
+                    (((to.x - from.x).pow(2) + (to.y - from.y).pow(2)) as f64).sqrt()
+                        >= *min_distance
+                }
+            };
+
+            if is_gap {
+                gaps.push((i, i + 1));
+            }
+        }
+
+        Ok(gaps)
+    }
+
     pub fn rw_between(
         &self,
         dp: &DynamicProgramPool,
@@ -948,8 +1176,107 @@ impl Dataset {
         auto_scale: bool,
         extra_steps: usize,
     ) -> anyhow::Result<Walk> {
-        let from = &self.get(from).context("from index out of bounds.")?.point;
-        let to = &self.get(to).context("to index out of bounds.")?.point;
+        self.rw_between_or_nearest(
+            dp,
+            walker,
+            from,
+            to,
+            time_steps,
+            auto_scale,
+            extra_steps,
+            0,
+            RetryPolicy::default(),
+        )
+    }
+
+    /// Generates a random walk like [`rw_between()`](Dataset::rw_between), but walks to the
+    /// nearest reachable point within `endpoint_tolerance` if the end point cannot be reached.
+    ///
+    /// A substitution of the end point is reported as a warning using the `log` crate. See
+    /// [`Walker::generate_path_or_nearest()`]. Failed attempts are retried as specified by
+    /// `retry_policy` as long as they do not exceed the time limit of `dp`, and only the error of
+    /// the last attempt is returned.
+    pub(crate) fn rw_between_or_nearest(
+        &self,
+        dp: &DynamicProgramPool,
+        walker: &Box<dyn Walker>,
+        from_idx: usize,
+        to_idx: usize,
+        time_steps: usize,
+        auto_scale: bool,
+        extra_steps: usize,
+        endpoint_tolerance: usize,
+        retry_policy: RetryPolicy,
+    ) -> anyhow::Result<Walk> {
+        let (from, to, translated_to, scale) =
+            self.translated_target(from_idx, to_idx, time_steps, auto_scale, extra_steps)?;
+
+        // Check if `to` is still at a position where the walk can be computed with the given
+        // dynamic program
+        if !dp.contains(translated_to.x as isize, translated_to.y as isize) {
+            bail!("start and end point too far apart for given dynamic program");
+        }
+
+        let time_limit = dp.time_limit();
+        let mut retries = 0;
+        let (walk, substitute) = loop {
+            let res = walker.generate_path_or_nearest(
+                dp,
+                translated_to.x as isize,
+                translated_to.y as isize,
+                retry_policy.time_steps(time_steps, retries),
+                endpoint_tolerance,
+            );
+
+            match res {
+                Err(error)
+                    if retry_policy.retries_within(&error, retries, time_steps, time_limit) =>
+                {
+                    retries += 1
+                }
+                res => break res.context("error while generating random walk path")?,
+            }
+        };
+
+        // Translate all coordinates in walk back to original coordinates
+        let translate_back = |p: &XYPoint| -> XYPoint {
+            match scale {
+                Some(scale) => (
+                    (p.x as f64 * scale) as i64 + from.x(),
+                    (p.y as f64 * scale) as i64 + from.y(),
+                )
+                    .into(),
+                None => (p.x + from.x(), p.y + from.y()).into(),
+            }
+        };
+
+        if let Some(substitute) = substitute {
+            let substitute = translate_back(&substitute);
+
+            warn!(
+                "End point ({}, {}) of datapoint {} not reachable, walking to ({}, {}) instead",
+                to.x, to.y, to_idx, substitute.x, substitute.y
+            );
+        }
+
+        Ok(walk.iter().map(translate_back).collect())
+    }
+
+    /// Returns the points at `from_idx` and `to_idx`, the position of `to` relative to `from`
+    /// as used for walk generation, and the scale applied to it when using `auto_scale`.
+    pub(crate) fn translated_target(
+        &self,
+        from_idx: usize,
+        to_idx: usize,
+        time_steps: usize,
+        auto_scale: bool,
+        extra_steps: usize,
+    ) -> anyhow::Result<(XYPoint, XYPoint, XYPoint, Option<f64>)> {
+        let from = &self
+            .get(from_idx)
+            .context("from index out of bounds.")?
+            .point;
+        let to = &self.get(to_idx).context("to index out of bounds.")?.point;
 
         let Point::XY(from) = *from else {
             bail!("Points have to be in XY coordinates.");
@@ -960,55 +1287,27 @@ impl Dataset {
 
         // Translate `to`, s.t. it still has the same relative position from `from`, under the
         // condition that `from` is (0, 0)
-        let mut translated_to = to - from;
-
-        let mut scale = 0.0;
+        let translated_to = to - from;
         let dist = (translated_to.x.abs() + translated_to.y.abs()) as u64;
 
         if auto_scale && dist as usize > time_steps - extra_steps {
             // scale = (dist as f64 + extra_steps as f64) / (time_steps - 1) as f64;
-            scale = dist as f64 / (time_steps - 1 - extra_steps) as f64;
-            translated_to = xy!(
+            let scale = dist as f64 / (time_steps - 1 - extra_steps) as f64;
+            let scaled_to = xy!(
                 (translated_to.x as f64 / scale) as i64,
                 (translated_to.y as f64 / scale) as i64
             );
-        }
 
-        // Check if `to` is still at a position where the walk can be computed with the given
-        // dynamic program
-        let (_, limit_pos) = dp.limits();
-
-        if translated_to.x.abs() > limit_pos as i64 || translated_to.y.abs() > limit_pos as i64 {
-            bail!("start and end point too far apart for given dynamic program");
+            return Ok((from, to, scaled_to, Some(scale)));
         }
 
-        let walk = walker
-            .generate_path(
-                dp,
-                translated_to.x as isize,
-                translated_to.y as isize,
-                time_steps,
-            )
-            .context("error while generating random walk path")?;
+        Ok((from, to, translated_to, None))
+    }
+}
 
-        // Translate all coordinates in walk back to original coordinates
-        if auto_scale && dist as usize > time_steps - extra_steps {
-            Ok(walk
-                .iter()
-                .map(|p| {
-                    (
-                        (p.x as f64 * scale) as i64 + from.x(),
-                        (p.y as f64 * scale) as i64 + from.y(),
-                    )
-                        .into()
-                })
-                .collect())
-        } else {
-            Ok(walk
-                .iter()
-                .map(|p| (p.x + from.x(), p.y + from.y()).into())
-                .collect())
-        }
+impl Extend<Datapoint> for Dataset {
+    fn extend<T: IntoIterator<Item = Datapoint>>(&mut self, iter: T) {
+        self.data.extend(iter);
     }
 }
 
@@ -1016,12 +1315,14 @@ impl Dataset {
 mod tests {
     use crate::dataset::loader::CoordinateType;
     use crate::dataset::point::{Point, XYPoint};
-    use crate::dataset::{Datapoint, Dataset, DatasetFilter};
+    use crate::dataset::{Datapoint, Dataset, DatasetFilter, GapCriterion};
     use crate::dp::builder::DynamicProgramBuilder;
     use crate::dp::DynamicPrograms;
     use crate::kernel::simple_rw::SimpleRwGenerator;
     use crate::kernel::Kernel;
+    use crate::walker::result::RetryPolicy;
     use crate::walker::standard::StandardWalker;
+    use crate::walker::Walker;
     use crate::xy;
     use std::collections::HashMap;
 
@@ -1052,6 +1353,54 @@ mod tests {
             .all(|item| dataset.data.contains(item)));
     }
 
+    #[test]
+    fn test_dataset_insert_remove_extend() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+        let datapoint = |i: i64| Datapoint {
+            point: Point::XY(XYPoint { x: i, y: i }),
+            metadata: HashMap::new(),
+        };
+
+        dataset.extend((0..3).map(datapoint));
+        dataset.insert(1, datapoint(10)).unwrap();
+
+        assert!(dataset.insert(5, datapoint(20)).is_err());
+        assert_eq!(dataset.get(1), Some(&datapoint(10)));
+        assert_eq!(dataset.remove(2).unwrap(), datapoint(1));
+        assert!(dataset.remove(3).is_err());
+
+        dataset
+            .update(2, |datapoint| datapoint.point = xy!(5, 5).into())
+            .unwrap();
+
+        assert_eq!(dataset.get(2), Some(&datapoint(5)));
+        assert_eq!(dataset.len(), 3);
+    }
+
+    #[test]
+    fn test_find_gaps_duration() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for (i, time) in ["10:00:00", "10:01:00", "10:30:00", "10:31:00"]
+            .iter()
+            .enumerate()
+        {
+            let mut metadata = HashMap::new();
+            metadata.insert("time".into(), format!("2023-01-01 {time}"));
+
+            dataset.push(Datapoint::new(xy!(i as i64, 0).into(), metadata));
+        }
+
+        let gaps = dataset
+            .find_gaps(GapCriterion::Duration(600.0, "time".into()))
+            .unwrap();
+
+        assert_eq!(gaps, vec![(1, 2)]);
+        assert!(dataset
+            .find_gaps(GapCriterion::Duration(600.0, "missing".into()))
+            .is_err());
+    }
+
     #[test]
     fn test_dataset_filter_metadata() {
         let mut dataset = Dataset::new(CoordinateType::XY);
@@ -1123,6 +1472,61 @@ mod tests {
             .all(|item| dataset.data.contains(item)));
     }
 
+    #[test]
+    fn test_dataset_clip_to_bbox() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for (x, y) in [(0, 0), (5, 5), (20, 5), (10, 10), (3, 4), (-1, 0)] {
+            dataset.push(Datapoint::new(xy!(x, y).into(), HashMap::new()));
+        }
+
+        let clipped = dataset
+            .clip_to_bbox(xy!(0, 0).into(), xy!(10, 10).into())
+            .unwrap();
+
+        assert_eq!(clipped.len(), 2);
+        assert_eq!(clipped[0].len(), 2);
+        assert_eq!(clipped[1].len(), 2);
+        assert_eq!(clipped[1].get(0).unwrap().point, xy!(10, 10).into());
+        assert!(dataset
+            .clip_to_bbox(Point::GCS(Default::default()), xy!(10, 10).into())
+            .is_err());
+    }
+
+    #[test]
+    fn test_rw_between_retries_within_time_limit() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for (x, y) in [(0, 0), (10, 10)] {
+            dataset.push(Datapoint::new(xy!(x, y).into(), HashMap::new()));
+        }
+
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker: Box<dyn Walker> = Box::new(StandardWalker::new(kernel));
+        let res = dataset.rw_between_or_nearest(
+            &dp,
+            &walker,
+            0,
+            1,
+            8,
+            false,
+            0,
+            0,
+            RetryPolicy::new(3, 2),
+        );
+
+        assert!(res.is_err());
+    }
+
     // #[test]
     // fn test_rw_between_auto_scale() {
     //     let mut dataset = Dataset::new(CoordinateType::XY);
@@ -1142,11 +1546,11 @@ mod tests {
     //     let mut dp = DynamicProgramBuilder::new()
     //         .simple()
     //         .time_limit(100)
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+    //         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
     //         .build()
     //         .unwrap();
     //
-    //     dp.compute();
+    //     dp.compute().unwrap();
     //
     //     let walker = StandardWalker;
     //     let walk1 = dataset.rw_between(&dp, Box::new(walker.clone()), 0, 1, 100, true);
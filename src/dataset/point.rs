@@ -2,6 +2,7 @@
 
 use num::Signed;
 use pyo3::{pyclass, pymethods, FromPyObject, IntoPy, Py, PyCell, PyObject, PyResult, Python};
+use serde::{Deserialize, Serialize};
 use std::ops::{Add, Sub};
 
 /// Specifies points that have an X- and Y-coordinate.
@@ -115,7 +116,7 @@ impl ToString for GCSPoint {
 
 /// A 2d-point in XY coordinate system.
 #[pyclass(get_all, set_all)]
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct XYPoint {
     pub x: i64,
     pub y: i64,
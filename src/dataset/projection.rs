@@ -0,0 +1,106 @@
+//! Provides cached and batched coordinate conversion between coordinate reference systems.
+//!
+//! Creating a [`Proj`] instance is expensive compared to converting a single point. A
+//! [`ProjCache`] therefore keeps the created instances around, so that repeated conversions of a
+//! [`Dataset`](crate::dataset::Dataset) reuse them. Points are converted in batches using
+//! [`Proj::convert_array()`] and large batches are split into chunks which are converted in
+//! parallel.
+
+use anyhow::Context;
+use proj::Proj;
+use std::collections::HashMap;
+use std::thread;
+
+/// The CRS of GCS coordinates (WGS 84).
+pub(crate) const GCS_CRS: &str = "EPSG:4326";
+
+/// The CRS of XY coordinates (Web Mercator).
+pub(crate) const XY_CRS: &str = "EPSG:3857";
+
+/// Minimum number of points for which the conversion is split across multiple threads.
+const PARALLEL_THRESHOLD: usize = 100_000;
+
+/// A cache of [`Proj`] instances keyed by source and target CRS.
+#[derive(Default)]
+pub(crate) struct ProjCache {
+    converters: HashMap<(String, String), Proj>,
+}
+
+impl ProjCache {
+    /// Returns the cached converter from `from` to `to`, creating it if necessary.
+    fn get(&mut self, from: &str, to: &str) -> anyhow::Result<&Proj> {
+        let key = (from.to_string(), to.to_string());
+
+        if !self.converters.contains_key(&key) {
+            self.converters.insert(key.clone(), create(from, to)?);
+        }
+
+        Ok(&self.converters[&key])
+    }
+
+    /// Converts all `points` from CRS `from` to CRS `to` in place.
+    ///
+    /// Batches with at least [`PARALLEL_THRESHOLD`] points are split into chunks that are
+    /// converted in parallel, each thread using its own converter since [`Proj`] instances cannot
+    /// be shared between threads.
+    pub(crate) fn convert(
+        &mut self,
+        from: &str,
+        to: &str,
+        points: &mut [(f64, f64)],
+    ) -> anyhow::Result<()> {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+
+        if points.len() < PARALLEL_THRESHOLD || threads == 1 {
+            self.get(from, to)?
+                .convert_array(points)
+                .context("point conversion failed")?;
+
+            return Ok(());
+        }
+
+        let chunk_size = (points.len() + threads - 1) / threads;
+
+        thread::scope(|s| {
+            let handles: Vec<_> = points
+                .chunks_mut(chunk_size)
+                .map(|chunk| {
+                    s.spawn(move || -> anyhow::Result<()> {
+                        create(from, to)?
+                            .convert_array(chunk)
+                            .context("point conversion failed")?;
+
+                        Ok(())
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().expect("conversion thread panicked"))
+        })
+    }
+}
+
+fn create(from: &str, to: &str) -> anyhow::Result<Proj> {
+    Proj::new_known_crs(from, to, None)
+        .with_context(|| format!("could not create converter from {from} to {to}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::projection::{ProjCache, GCS_CRS, XY_CRS};
+
+    #[test]
+    fn test_convert_cached() {
+        let mut cache = ProjCache::default();
+        let mut points = vec![(0.0, 0.0), (10.0, 20.0)];
+
+        cache.convert(GCS_CRS, XY_CRS, &mut points).unwrap();
+        cache.convert(XY_CRS, GCS_CRS, &mut points).unwrap();
+
+        assert_eq!(cache.converters.len(), 2);
+        assert!((points[1].0 - 10.0).abs() < 1e-6);
+        assert!((points[1].1 - 20.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,151 @@
+//! Provides a transform between XY coordinates and metric coordinates.
+
+use crate::dataset::point::XYPoint;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, PyCell, PyErr, PyResult};
+use thiserror::Error;
+
+/// An error that can occur when creating or modifying a [`Transform`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformError {
+    /// This error occurs when the scale of a transform is not a finite number greater than 0.
+    #[error("invalid transform configuration: scale must be finite and greater than 0")]
+    InvalidConfiguration,
+}
+
+impl From<TransformError> for PyErr {
+    fn from(value: TransformError) -> Self {
+        PyValueError::new_err(value.to_string())
+    }
+}
+
+/// Describes how XY coordinates relate to metric coordinates.
+///
+/// [`Dataset::convert_gcs_to_xy()`](crate::dataset::Dataset::convert_gcs_to_xy) converts GCS
+/// coordinates into metric coordinates and multiplies them by a `scale`. A transform using the
+/// same `scale` maps the resulting XY coordinates back to metric coordinates. `origin` is the
+/// metric position of the XY point `(0, 0)`.
+///
+/// The `scale` has to be finite and greater than 0.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub scale: f64,
+    #[pyo3(set)]
+    pub origin: (f64, f64),
+}
+
+#[pymethods]
+impl Transform {
+    #[new]
+    #[pyo3(signature = (scale, origin=(0.0, 0.0)))]
+    pub fn new(scale: f64, origin: (f64, f64)) -> Result<Self, TransformError> {
+        validate_scale(scale)?;
+
+        Ok(Self { scale, origin })
+    }
+
+    #[setter]
+    pub fn set_scale(&mut self, scale: f64) -> Result<(), TransformError> {
+        validate_scale(scale)?;
+
+        self.scale = scale;
+
+        Ok(())
+    }
+
+    /// Converts an XY point to metric coordinates.
+    pub fn to_metric(&self, point: XYPoint) -> (f64, f64) {
+        (
+            self.origin.0 + point.x as f64 / self.scale,
+            self.origin.1 + point.y as f64 / self.scale,
+        )
+    }
+
+    /// Converts metric coordinates to the nearest XY point.
+    pub fn to_xy(&self, point: (f64, f64)) -> XYPoint {
+        XYPoint {
+            x: ((point.0 - self.origin.0) * self.scale).round() as i64,
+            y: ((point.1 - self.origin.1) * self.scale).round() as i64,
+        }
+    }
+
+    /// Computes the metric distance between two XY points.
+    pub fn distance(&self, from_point: XYPoint, to_point: XYPoint) -> f64 {
+        let (x1, y1) = self.to_metric(from_point);
+        let (x2, y2) = self.to_metric(to_point);
+
+        (x2 - x1).hypot(y2 - y1)
+    }
+
+    pub fn __repr__(slf: &PyCell<Self>) -> PyResult<String> {
+        let class_name: &str = slf.get_type().name()?;
+
+        Ok(format!(
+            "{}({}, {:?})",
+            class_name,
+            slf.borrow().scale,
+            slf.borrow().origin
+        ))
+    }
+}
+
+/// Checks that `scale` is finite and greater than 0.
+fn validate_scale(scale: f64) -> Result<(), TransformError> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(TransformError::InvalidConfiguration);
+    }
+
+    Ok(())
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            origin: (0.0, 0.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dataset::transform::{Transform, TransformError};
+    use crate::xy;
+
+    #[test]
+    fn test_transform_roundtrip() {
+        let transform = Transform::new(0.5, (100.0, -50.0)).unwrap();
+
+        assert_eq!(transform.to_metric(xy!(10, 20)), (120.0, -10.0));
+        assert_eq!(transform.to_xy((120.0, -10.0)), xy!(10, 20));
+    }
+
+    #[test]
+    fn test_transform_distance() {
+        let transform = Transform::new(0.1, (0.0, 0.0)).unwrap();
+
+        assert_eq!(transform.distance(xy!(0, 0), xy!(3, 4)), 50.0);
+    }
+
+    #[test]
+    fn test_transform_invalid_scale() {
+        for scale in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                Transform::new(scale, (0.0, 0.0)),
+                Err(TransformError::InvalidConfiguration)
+            );
+        }
+
+        let mut transform = Transform::default();
+
+        assert_eq!(
+            transform.set_scale(-2.0),
+            Err(TransformError::InvalidConfiguration)
+        );
+        assert_eq!(transform.scale, 1.0);
+        assert_eq!(transform.set_scale(2.0), Ok(()));
+        assert_eq!(transform.scale, 2.0);
+    }
+}
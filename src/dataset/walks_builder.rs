@@ -1,13 +1,15 @@
 use crate::dataset::loader::CoordinateType;
-use crate::dataset::point::Coordinates;
+use crate::dataset::point::{Coordinates, XYPoint};
 use crate::dataset::Dataset;
 use crate::dp::DynamicProgramPool;
 use crate::walk::Walk;
-use crate::walker::Walker;
+use crate::walker::result::RetryPolicy;
+use crate::walker::{nearest_reachable, Walker};
 use anyhow::Context;
+use log::debug;
 use pyo3::pyclass;
 use thiserror::Error;
-use time::format_description::parse_borrowed;
+use time::format_description::{parse_borrowed, FormatItem};
 use time::macros::format_description;
 use time::PrimitiveDateTime;
 
@@ -23,6 +25,12 @@ pub enum DatasetWalksBuilderError {
     NoTimeStepsSet,
     #[error("the dataset must contain XY points for walk computation")]
     DatasetNotXY,
+    #[error("the stride must be greater than zero")]
+    InvalidStride,
+    #[error("index {0} is out of bounds of the dataset")]
+    IndexOutOfBounds(usize),
+    #[error("the pair ({0}, {1}) does not end after its start")]
+    InvalidPair(usize, usize),
 }
 
 #[derive(Clone, Default, Debug, PartialEq)]
@@ -34,17 +42,38 @@ pub enum TimeStepsBy {
     None,
 }
 
+/// Specifies between which pairs of dataset points walks are generated.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub enum Pairing {
+    /// Walks are generated between each point and its direct successor.
+    #[default]
+    Consecutive,
+
+    /// Walks are generated between every `k`-th point, i.e. between the points with indices
+    /// `from` and `from + k`, `from + k` and `from + 2k`, and so on. The last walk ends at `to`,
+    /// even if its distance in the dataset is smaller than `k`.
+    Stride(usize),
+
+    /// Walks are generated between the given pairs of indices `(i, j)`, where `i` must be smaller
+    /// than `j`. The range set with [`from()`](DatasetWalksBuilder::from) and
+    /// [`to()`](DatasetWalksBuilder::to) is ignored.
+    Custom(Vec<(usize, usize)>),
+}
+
 pub struct DatasetWalksBuilder<'a> {
     dataset: Option<&'a Dataset>,
     dp: Option<&'a DynamicProgramPool>,
     walker: Option<&'a Box<dyn Walker>>,
     from: usize,
     to: Option<usize>,
+    pairing: Pairing,
     count: usize,
     time_steps: TimeStepsBy,
     time_format: Option<String>,
     auto_scale: bool,
     extra_steps: usize,
+    endpoint_tolerance: usize,
+    retry_policy: RetryPolicy,
 }
 
 impl<'a> Default for DatasetWalksBuilder<'a> {
@@ -55,11 +84,14 @@ impl<'a> Default for DatasetWalksBuilder<'a> {
             walker: None,
             from: 0,
             to: None,
+            pairing: Pairing::Consecutive,
             count: 1,
             time_steps: TimeStepsBy::None,
             time_format: None,
             auto_scale: false,
             extra_steps: 0,
+            endpoint_tolerance: 0,
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
@@ -101,6 +133,16 @@ impl<'a> DatasetWalksBuilder<'a> {
         self
     }
 
+    /// Set between which pairs of dataset points walks are generated.
+    ///
+    /// By default, walks are generated between consecutive points. See [`Pairing`] for the
+    /// available options.
+    pub fn pairing(mut self, pairing: Pairing) -> Self {
+        self.pairing = pairing;
+
+        self
+    }
+
     pub fn count(mut self, count: usize) -> Self {
         self.count = count;
 
@@ -164,6 +206,27 @@ impl<'a> DatasetWalksBuilder<'a> {
         self
     }
 
+    /// Walk to the nearest reachable point within a Euclidean distance of `tolerance` if the end
+    /// point of a walk cannot be reached with the dynamic program.
+    ///
+    /// Substituted end points are reported as warnings using the `log` crate. By default, no
+    /// substitution is done and an error is returned for unreachable end points. See
+    /// [`Walker::generate_path_or_nearest()`].
+    pub fn endpoint_tolerance(mut self, tolerance: usize) -> Self {
+        self.endpoint_tolerance = tolerance;
+
+        self
+    }
+
+    /// Retry generating walks that failed as specified by `policy`.
+    ///
+    /// By default, walks are not retried and the first error is returned. See [`RetryPolicy`].
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+
+        self
+    }
+
     pub fn build(self) -> anyhow::Result<Vec<Walk>> {
         let Some(dataset) = self.dataset else {
             return Err(DatasetWalksBuilderError::NoDatasetSet)?;
@@ -175,6 +238,109 @@ impl<'a> DatasetWalksBuilder<'a> {
             return Err(DatasetWalksBuilderError::NoWalkerSet)?;
         };
 
+        let pairs = self.segments(dataset)?;
+        let formatting = self.formatting()?;
+        let mut walks = Vec::new();
+
+        for (i, j) in pairs {
+            let time_steps = self.time_steps_between(dataset, i, j, &formatting)?;
+
+            for _ in 0..self.count {
+                walks.push(
+                    dataset
+                        .rw_between_or_nearest(
+                            dp,
+                            walker,
+                            i,
+                            j,
+                            time_steps,
+                            self.auto_scale,
+                            self.extra_steps,
+                            self.endpoint_tolerance,
+                            self.retry_policy,
+                        )
+                        .context("could not generate walk")?,
+                );
+            }
+        }
+
+        Ok(walks)
+    }
+
+    /// Checks for all segments whether a walk can be generated, without generating any walks.
+    ///
+    /// Each segment is checked against the limits of the dynamic program, the reach of its
+    /// kernels and the probabilities computed by it. All segments that will fail are listed in
+    /// the returned [`PrecheckReport`] together with the reason. A walker does not have to be set.
+    ///
+    /// Returns an error if the builder is not configured correctly, i.e. if [`build()`] would
+    /// fail before generating the first walk.
+    ///
+    /// [`build()`]: DatasetWalksBuilder::build
+    pub fn precheck(&self) -> anyhow::Result<PrecheckReport> {
+        let Some(dataset) = self.dataset else {
+            return Err(DatasetWalksBuilderError::NoDatasetSet)?;
+        };
+        let Some(dp) = self.dp else {
+            return Err(DatasetWalksBuilderError::NoDynamicProgramSet)?;
+        };
+
+        let pairs = self.segments(dataset)?;
+        let formatting = self.formatting()?;
+        let dps = dp.programs();
+        let time_limit = dps.iter().map(|dp| dp.time_limit).min().unwrap_or(0);
+        let kernel_reach = dps
+            .iter()
+            .flat_map(|dp| {
+                dp.kernels
+                    .iter()
+                    .chain(dp.kernel_schedule.iter().map(|(_, kernel)| kernel))
+            })
+            .map(|kernel| kernel.size() / 2)
+            .max()
+            .unwrap_or(0);
+        let mut failures = Vec::new();
+
+        for &(i, j) in pairs.iter() {
+            let reason = match self.time_steps_between(dataset, i, j, &formatting) {
+                Ok(time_steps) => {
+                    let (_, _, to, _) = dataset.translated_target(
+                        i,
+                        j,
+                        time_steps,
+                        self.auto_scale,
+                        self.extra_steps,
+                    )?;
+
+                    segment_failure(
+                        dp,
+                        to,
+                        time_steps,
+                        time_limit,
+                        kernel_reach,
+                        self.endpoint_tolerance,
+                    )
+                }
+                Err(e) => Some(FailureReason::InvalidTimeSteps(e.to_string())),
+            };
+
+            if let Some(reason) = reason {
+                failures.push(SegmentFailure {
+                    from: i,
+                    to: j,
+                    reason,
+                });
+            }
+        }
+
+        Ok(PrecheckReport {
+            segments: pairs.len(),
+            failures,
+        })
+    }
+
+    /// Returns the index pairs of all segments walks are generated for.
+    fn segments(&self, dataset: &Dataset) -> anyhow::Result<Vec<(usize, usize)>> {
         if dataset.coordinate_type() != CoordinateType::XY {
             return Err(DatasetWalksBuilderError::DatasetNotXY)?;
         }
@@ -188,87 +354,428 @@ impl<'a> DatasetWalksBuilder<'a> {
             None => dataset.len() - 1,
         };
 
-        let formatting;
-        let mut format = String::new();
+        let pairs = match &self.pairing {
+            Pairing::Consecutive => (self.from..to).map(|i| (i, i + 1)).collect(),
+            Pairing::Stride(0) => return Err(DatasetWalksBuilderError::InvalidStride)?,
+            Pairing::Stride(k) => (self.from..to)
+                .step_by(*k)
+                .map(|i| (i, (i + k).min(to)))
+                .collect(),
+            Pairing::Custom(pairs) => pairs.clone(),
+        };
+
+        if let Some(&(i, j)) = pairs
+            .iter()
+            .find(|(i, j)| *i >= dataset.len() || *j >= dataset.len())
+        {
+            return Err(DatasetWalksBuilderError::IndexOutOfBounds(i.max(j)))?;
+        }
+
+        if let Some(&(i, j)) = pairs.iter().find(|(i, j)| j <= i) {
+            return Err(DatasetWalksBuilderError::InvalidPair(i, j))?;
+        }
+
+        Ok(pairs)
+    }
+
+    /// Returns the format of time strings in metadata.
+    fn formatting(&self) -> anyhow::Result<Vec<FormatItem<'_>>> {
+        match self.time_format.as_deref() {
+            None | Some("") => {
+                Ok(format_description!("[year]-[month]-[day] [hour]:[minute]:[second]").to_vec())
+            }
+            Some(format) => parse_borrowed::<2>(format).context("invalid time format string"),
+        }
+    }
+
+    /// Returns the number of time steps allowed for the walk between the points `i` and `j`.
+    fn time_steps_between(
+        &self,
+        dataset: &Dataset,
+        i: usize,
+        j: usize,
+        formatting: &[FormatItem],
+    ) -> anyhow::Result<usize> {
+        Ok(match self.time_steps.clone() {
+            TimeStepsBy::Fixed(time_steps) => time_steps,
+            TimeStepsBy::TimeDifference(time_step_len, metadata_key) => {
+                let datetime1 = PrimitiveDateTime::parse(
+                    dataset
+                        .get(i)
+                        .unwrap()
+                        .metadata
+                        .get(&metadata_key)
+                        .context("found datapoint without timestamp metadata key")?,
+                    formatting,
+                )?;
+                let datetime2 = PrimitiveDateTime::parse(
+                    dataset
+                        .get(j)
+                        .unwrap()
+                        .metadata
+                        .get(&metadata_key)
+                        .context("found datapoint without timestamp metadata key")?,
+                    formatting,
+                )?;
+
+                let diff = (datetime2 - datetime1).as_seconds_f64();
+
+                debug!(
+                    "Time difference: {}, time steps: {}",
+                    diff,
+                    diff / time_step_len
+                );
+
+                (diff / time_step_len) as usize
+            }
+            TimeStepsBy::Distance(multiplier) => {
+                let point1 = dataset.get(i).unwrap().clone().point;
+                let point2 = dataset.get(j).unwrap().clone().point;
+
+                let (x1, y1): (i64, i64) = (point1.x(), point1.y());
+                let (x2, y2): (i64, i64) = (point2.x(), point2.y());
+
+                let dist = (x1 - x2).abs() + (y1 - y2).abs();
+
+                debug!("Time steps: {}", (dist as f64 * multiplier) as usize);
+
+                (dist as f64 * multiplier) as usize
+            }
+            TimeStepsBy::None => {
+                unimplemented!("this should not happen because of the check above")
+            }
+        })
+    }
+}
+
+/// Returns why no walk to `to` can be generated in `time_steps`, or `None` if a walk can be
+/// generated.
+fn segment_failure(
+    dp: &DynamicProgramPool,
+    to: XYPoint,
+    time_steps: usize,
+    time_limit: usize,
+    kernel_reach: usize,
+    endpoint_tolerance: usize,
+) -> Option<FailureReason> {
+    if time_steps > time_limit {
+        return Some(FailureReason::TooManyTimeSteps);
+    }
+
+    let (x, y) = (to.x as isize, to.y as isize);
+
+    if !dp.contains(x, y) {
+        return Some(FailureReason::TooFar);
+    }
+
+    if !dp.is_zero(x, y, time_steps)
+        || (endpoint_tolerance > 0
+            && nearest_reachable(dp, x, y, time_steps, endpoint_tolerance).is_some())
+    {
+        return None;
+    }
+
+    let dist = to.x.unsigned_abs().max(to.y.unsigned_abs()) as usize;
+    let reachable_in =
+        |mut range: std::ops::RangeInclusive<usize>| range.any(|t| !dp.is_zero(x, y, t));
+
+    if dist > kernel_reach * time_steps || reachable_in(time_steps + 1..=time_limit) {
+        Some(FailureReason::OutOfKernelReach)
+    } else if reachable_in(0..=time_steps) {
+        Some(FailureReason::ZeroProbability)
+    } else {
+        Some(FailureReason::Enclosed)
+    }
+}
+
+/// The reason why no walk can be generated for a segment.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FailureReason {
+    /// The number of time steps could not be computed, e.g. due to missing timestamps.
+    InvalidTimeSteps(String),
+
+    /// The number of time steps exceeds the time limit of the dynamic program.
+    TooManyTimeSteps,
+
+    /// The end point lies outside of the table of the dynamic program.
+    TooFar,
+
+    /// The end point cannot be reached in the given number of time steps with the kernels of the
+    /// dynamic program, but possibly with more time steps.
+    OutOfKernelReach,
+
+    /// The end point has a probability of zero in all time steps of the dynamic program, e.g.
+    /// because it is enclosed by barriers.
+    Enclosed,
+
+    /// The end point can be reached in fewer time steps, but has a probability of zero in the
+    /// given time step, e.g. because the kernel does not allow staying in place.
+    ZeroProbability,
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureReason::InvalidTimeSteps(e) => write!(f, "invalid time steps: {e}"),
+            FailureReason::TooManyTimeSteps => write!(f, "time steps exceed the time limit"),
+            FailureReason::TooFar => write!(f, "end point too far from start point"),
+            FailureReason::OutOfKernelReach => write!(f, "end point out of kernel reach"),
+            FailureReason::Enclosed => write!(f, "end point never reachable"),
+            FailureReason::ZeroProbability => write!(f, "end point has zero probability"),
+        }
+    }
+}
+
+/// A segment for which no walk can be generated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentFailure {
+    /// Index of the start point in the dataset.
+    pub from: usize,
+    /// Index of the end point in the dataset.
+    pub to: usize,
+    pub reason: FailureReason,
+}
+
+/// The result of [`DatasetWalksBuilder::precheck()`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrecheckReport {
+    /// Number of checked segments.
+    pub segments: usize,
+    /// All segments for which no walk can be generated.
+    pub failures: Vec<SegmentFailure>,
+}
+
+impl PrecheckReport {
+    /// Returns whether walks can be generated for all segments.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::loader::CoordinateType;
+    use crate::dataset::point::XYPoint;
+    use crate::dataset::walks_builder::{
+        DatasetWalksBuilder, DatasetWalksBuilderError, FailureReason, Pairing,
+    };
+    use crate::dataset::{Datapoint, Dataset};
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::result::RetryPolicy;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::Walker;
+    use crate::xy;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_pairing() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for i in 0..8 {
+            dataset.push(Datapoint::new(xy!(i, 0).into(), HashMap::new()));
+        }
+
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker: Box<dyn Walker> = Box::new(StandardWalker::new(kernel));
+        let walks = |pairing| {
+            DatasetWalksBuilder::new()
+                .dataset(&dataset)
+                .dp(&dp)
+                .walker(&walker)
+                .time_steps(10)
+                .pairing(pairing)
+                .build()
+        };
+
+        let stride = walks(Pairing::Stride(3)).unwrap();
+
+        assert_eq!(stride.len(), 3);
+        assert_eq!(stride[1][0], xy!(3, 0));
+        assert_eq!(stride[2][stride[2].len() - 1], xy!(7, 0));
+
+        let custom = walks(Pairing::Custom(vec![(0, 7), (2, 4)])).unwrap();
+
+        assert_eq!(custom.len(), 2);
+        assert_eq!(custom[0][custom[0].len() - 1], xy!(7, 0));
+        assert!(walks(Pairing::Stride(0)).is_err());
+        assert!(walks(Pairing::Custom(vec![(0, 8)])).is_err());
+        assert!(walks(Pairing::Custom(vec![(4, 2)])).is_err());
+        assert!(walks(Pairing::Custom(vec![(3, 3)])).is_err());
+    }
+
+    #[test]
+    fn test_pairing_reversed_time_difference() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for i in 0..3 {
+            let metadata =
+                HashMap::from([("time".to_string(), format!("2023-01-01 00:00:{:02}", i * 5))]);
 
-        if let Some(f) = self.time_format {
-            format = f;
+            dataset.push(Datapoint::new(xy!(i, 0).into(), metadata));
         }
-        formatting = match format.as_str() {
-            "" => format_description!("[year]-[month]-[day] [hour]:[minute]:[second]").to_vec(),
-            f @ _ => parse_borrowed::<2>(&format).context("invalid time format string")?,
+
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker: Box<dyn Walker> = Box::new(StandardWalker::new(kernel));
+        let builder = |pairs| {
+            DatasetWalksBuilder::new()
+                .dataset(&dataset)
+                .dp(&dp)
+                .walker(&walker)
+                .time_steps_by_time(1.0, "time".into())
+                .pairing(Pairing::Custom(pairs))
         };
 
-        // let format = match self.time_format {
-        //     Some(format) => parse_borrowed::<2>(&format).context("invalid time format string")?,
-        //     None => format_description!("[year]-[month]-[day] [hour]:[minute]:[second]").to_vec(),
-        // };
+        let walks = builder(vec![(0, 2)]).build().unwrap();
 
-        let mut walks = Vec::new();
+        assert_eq!(walks[0][walks[0].len() - 1], xy!(2, 0));
 
-        for i in self.from..to {
-            let time_steps = match self.time_steps.clone() {
-                TimeStepsBy::Fixed(time_steps) => time_steps,
-                TimeStepsBy::TimeDifference(time_step_len, metadata_key) => {
-                    let datetime1 = PrimitiveDateTime::parse(
-                        dataset.get(i).unwrap().metadata.get(&metadata_key).unwrap(),
-                        &formatting,
-                    )?;
-                    let datetime2 = PrimitiveDateTime::parse(
-                        dataset
-                            .get(i + 1)
-                            .unwrap()
-                            .metadata
-                            .get(&metadata_key)
-                            .unwrap(),
-                        &formatting,
-                    )?;
+        let err = builder(vec![(2, 0)]).build().err().unwrap();
 
-                    let diff = (datetime2 - datetime1).as_seconds_f64();
+        assert!(matches!(
+            err.downcast_ref::<DatasetWalksBuilderError>(),
+            Some(DatasetWalksBuilderError::InvalidPair(2, 0))
+        ));
+        assert!(builder(vec![(2, 0)]).precheck().is_err());
+    }
 
-                    println!(
-                        "Time difference: {}, time steps: {}",
-                        diff,
-                        diff / time_step_len
-                    );
+    #[test]
+    fn test_endpoint_tolerance() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        dataset.push(Datapoint::new(xy!(0, 0).into(), HashMap::new()));
+        dataset.push(Datapoint::new(xy!(5, 0).into(), HashMap::new()));
+
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker: Box<dyn Walker> = Box::new(StandardWalker::new(kernel));
+        let walks = |tolerance| {
+            DatasetWalksBuilder::new()
+                .dataset(&dataset)
+                .dp(&dp)
+                .walker(&walker)
+                .time_steps(4)
+                .endpoint_tolerance(tolerance)
+                .build()
+        };
 
-                    (diff / time_step_len) as usize
-                }
-                TimeStepsBy::Distance(multiplier) => {
-                    let point1 = dataset.get(i).unwrap().clone().point;
-                    let point2 = dataset.get(i + 1).unwrap().clone().point;
+        // The end point is five steps away and thus never reachable in four time steps
+        assert!(walks(0).is_err());
 
-                    let (x1, y1): (i64, i64) = (point1.x(), point1.y());
-                    let (x2, y2): (i64, i64) = (point2.x(), point2.y());
+        let walk = &walks(1).unwrap()[0];
 
-                    let dist = (x1 - x2).abs() + (y1 - y2).abs();
+        assert_eq!(walk[0], xy!(0, 0));
+        assert_eq!(walk[walk.len() - 1], xy!(4, 0));
+    }
 
-                    println!("Time steps: {}", (dist as f64 * multiplier) as usize);
+    #[test]
+    fn test_retry_policy() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        dataset.push(Datapoint::new(xy!(0, 0).into(), HashMap::new()));
+        dataset.push(Datapoint::new(xy!(6, 0).into(), HashMap::new()));
+
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker: Box<dyn Walker> = Box::new(StandardWalker::new(kernel));
+        let walks = |policy| {
+            DatasetWalksBuilder::new()
+                .dataset(&dataset)
+                .dp(&dp)
+                .walker(&walker)
+                .time_steps(5)
+                .retry_policy(policy)
+                .build()
+        };
 
-                    (dist as f64 * multiplier) as usize
-                }
-                TimeStepsBy::None => {
-                    unimplemented!("this should not happen because of the check above")
-                }
-            };
+        assert!(walks(RetryPolicy::new(1, 1)).is_err());
 
-            for _ in 0..self.count {
-                walks.push(
-                    dataset
-                        .rw_between(
-                            dp,
-                            walker,
-                            i,
-                            i + 1,
-                            time_steps,
-                            self.auto_scale,
-                            self.extra_steps,
-                        )
-                        .context("could not generate walk")?,
-                );
-            }
+        let walk = &walks(RetryPolicy::new(2, 1)).unwrap()[0];
+
+        assert_eq!(walk.len(), 7);
+        assert_eq!(walk[walk.len() - 1], xy!(6, 0));
+    }
+
+    #[test]
+    fn test_precheck() {
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for point in [
+            xy!(0, 0),
+            xy!(3, 0),
+            xy!(30, 0),
+            xy!(31, 0),
+            xy!(38, 0),
+            xy!(42, 4),
+        ] {
+            dataset.push(Datapoint::new(point.into(), HashMap::new()));
         }
 
-        Ok(walks)
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let report = DatasetWalksBuilder::new()
+            .dataset(&dataset)
+            .dp(&dp)
+            .time_steps(5)
+            .precheck()
+            .unwrap();
+
+        assert_eq!(report.segments, 5);
+        assert!(!report.is_ok());
+        assert_eq!(
+            report
+                .failures
+                .iter()
+                .map(|failure| (failure.from, failure.reason.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, FailureReason::TooFar),
+                (3, FailureReason::OutOfKernelReach),
+                (4, FailureReason::OutOfKernelReach),
+            ]
+        );
     }
 }
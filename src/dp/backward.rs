@@ -0,0 +1,232 @@
+//! Provides backward tables used to sample exact space-time bridges.
+//!
+//! A [`DynamicProgram`] computes the probability of a walk starting at its initial distribution
+//! to be at each point in each time step. A [`BackwardTable`] complements it with the probability
+//! of reaching a fixed end point in a fixed time step from each point in each earlier time step.
+//! Together, the product of both is proportional to the probability of a walk between the start
+//! and the end point passing through a point at a given time step, so that walks conditioned on
+//! both end points, i.e. space-time bridges, can be sampled forward in time without any bias.
+//!
+//! A backward table is held as an optional second table by a [`DynamicProgramPool::Bridge`],
+//! which is used by the [`StandardWalker`](crate::walker::standard::StandardWalker) whenever a
+//! walk to the end point of the backward table is requested.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::{DynamicProgramPool, DynamicPrograms};
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::biased_rw::BiasedRwGenerator;
+//! # use randomwalks_lib::kernel::Direction;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kernel = Kernel::from_generator(BiasedRwGenerator {
+//!     probability: 0.5,
+//!     direction: Direction::North,
+//! })
+//! .unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(50)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let DynamicProgramPool::Single(dp) = dp else {
+//!     unreachable!();
+//! };
+//!
+//! let dp = DynamicProgramPool::bridge(dp, 5, -5, 40).unwrap();
+//! let walk = StandardWalker::new(kernel).generate_path(&dp, 5, -5, 40).unwrap();
+//! ```
+
+use crate::dp::domain::Domain;
+use crate::dp::simple::DynamicProgram;
+use crate::kernel::Kernel;
+use anyhow::bail;
+
+/// Probabilities of reaching a fixed end point in a fixed time step from each point of the table
+/// of a dynamic program.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackwardTable {
+    pub(crate) to: (isize, isize),
+    pub(crate) time_steps: usize,
+    pub(crate) domain: Domain,
+    /// Probabilities of each time step, indexed as `table[t][x][y]`. Each time step is scaled so
+    /// that its largest probability is 1, to avoid underflow for long walks.
+    table: Vec<Vec<Vec<f64>>>,
+    /// Natural logarithms of the factors each time step was scaled down by.
+    log_scales: Vec<f64>,
+}
+
+impl BackwardTable {
+    /// Computes the probabilities of reaching `(to_x, to_y)` in time step `time_steps` using the
+    /// kernels, kernel schedule, field types, field probabilities, diffusivity and absorbing cells
+    /// of `dp`.
+    ///
+    /// Only the configuration of `dp` is used, so it does not have to be computed beforehand.
+    /// Returns an error if the end point is outside of the table of `dp`.
+    pub fn compute(
+        dp: &DynamicProgram,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> anyhow::Result<Self> {
+        let domain = dp.domain;
+
+        if !domain.contains(to_x, to_y) {
+            bail!("end point ({}, {}) is outside of the table", to_x, to_y);
+        }
+
+        let mut last = vec![vec![0.0; domain.height()]; domain.width()];
+        let (x, y) = domain.index(to_x, to_y);
+        last[x][y] = 1.0;
+
+        let mut table = vec![last];
+        let mut log_scales = vec![0.0];
+
+        for t in (1..=time_steps).rev() {
+            let next = table.last().unwrap();
+            let kernels = dp.kernels_at(t);
+            let radius = kernels.iter().map(|k| k.size() / 2).max().unwrap_or(0) as isize;
+            let mut slice = vec![vec![0.0; domain.height()]; domain.width()];
+
+            for i in domain.x_min..=domain.x_max {
+                for j in domain.y_min..=domain.y_max {
+                    let (ii, ji) = domain.index(i, j);
+
+                    // Walks reaching an absorbing cell stay there
+                    if dp.absorbing.contains(&(i, j)) {
+                        slice[ii][ji] = next[ii][ji];
+                        continue;
+                    }
+
+                    let diffusivity = dp.diffusivity_at(i, j);
+                    let mut sum = 0.0;
+
+                    for x in (i - radius).max(domain.x_min)..=(i + radius).min(domain.x_max) {
+                        for y in (j - radius).max(domain.y_min)..=(j + radius).min(domain.y_max) {
+                            let (xi, yi) = domain.index(x, y);
+                            let kernel = &kernels[dp.field_types[xi][yi]];
+
+                            sum += kernel_prob(kernel, x - i, y - j, diffusivity)
+                                * dp.field_probability_at(x, y)
+                                * next[xi][yi];
+                        }
+                    }
+
+                    slice[ii][ji] = sum;
+                }
+            }
+
+            let max = slice.iter().flatten().copied().fold(0.0, f64::max);
+            let log_scale = *log_scales.last().unwrap();
+
+            if max > 0.0 {
+                slice.iter_mut().flatten().for_each(|val| *val /= max);
+                log_scales.push(log_scale + max.ln());
+            } else {
+                log_scales.push(log_scale);
+            }
+
+            table.push(slice);
+        }
+
+        table.reverse();
+        log_scales.reverse();
+
+        Ok(Self {
+            to: (to_x, to_y),
+            time_steps,
+            domain,
+            table,
+            log_scales,
+        })
+    }
+
+    /// Returns the end point of the bridges sampled using this table.
+    pub fn to(&self) -> (isize, isize) {
+        self.to
+    }
+
+    /// Returns the time step in which the end point is reached.
+    pub fn time_steps(&self) -> usize {
+        self.time_steps
+    }
+
+    /// Returns the natural logarithm of the probability of reaching the end point from `(x, y)`
+    /// in time step `t`. Points outside of the table and time steps after the end point is
+    /// reached have a log-probability of negative infinity.
+    pub fn log_at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        if t > self.time_steps || !self.domain.contains(x, y) {
+            return f64::NEG_INFINITY;
+        }
+
+        let (x, y) = self.domain.index(x, y);
+
+        self.table[t][x][y].ln() + self.log_scales[t]
+    }
+
+    /// Returns the probability of reaching the end point from `(x, y)` in time step `t`.
+    pub fn at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        self.log_at_or(x, y, t).exp()
+    }
+
+    /// Returns whether bridges to `(to_x, to_y)` in time step `time_steps` can be sampled using
+    /// this table.
+    pub(crate) fn matches(&self, to_x: isize, to_y: isize, time_steps: usize) -> bool {
+        self.to == (to_x, to_y) && self.time_steps == time_steps
+    }
+}
+
+/// Returns the probability of `kernel` to move by `(dx, dy)` from a field with the given
+/// `diffusivity`, or zero if the movement exceeds the kernel.
+pub(crate) fn kernel_prob(kernel: &Kernel, dx: isize, dy: isize, diffusivity: f64) -> f64 {
+    let ks = (kernel.size() / 2) as isize;
+
+    if dx.abs() > ks || dy.abs() > ks {
+        return 0.0;
+    }
+
+    kernel.diffused_at(dx, dy, diffusivity)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::backward::BackwardTable;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::biased_rw::BiasedRwGenerator;
+    use crate::kernel::{Direction, Kernel};
+
+    #[test]
+    fn test_backward_table() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(
+                Kernel::from_generator(BiasedRwGenerator {
+                    probability: 0.5,
+                    direction: Direction::East,
+                })
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let dp = dp.try_unwrap().unwrap();
+        let backward = BackwardTable::compute(dp, 3, 2, 15).unwrap();
+
+        // Starting at the origin, reaching the end point backward equals reaching it forward
+        assert!((backward.at_or(0, 0, 0) - dp.at(3, 2, 15)).abs() < 1e-12);
+        assert_eq!(backward.at_or(3, 2, 15), 1.0);
+        assert_eq!(backward.at_or(2, 2, 15), 0.0);
+        assert_eq!(backward.at_or(3, 2, 16), 0.0);
+        assert_eq!(backward.at_or(30, 0, 0), 0.0);
+        assert!(BackwardTable::compute(dp, 21, 0, 15).is_err());
+    }
+}
@@ -16,7 +16,7 @@
 //! let dp = DynamicProgramBuilder::new()
 //!     .simple()
 //!     .time_limit(400)
-//!     .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
 //!     .build();
 //! ```
 //!
@@ -57,19 +57,367 @@
 //! Using [`add_rect_barrier()`](DynamicProgramBuilder::add_rect_barrier), all fields in the given
 //! range (in the example `[10, -10]` to `[10, 10]`) are blocked for walks to use.
 //!
+//! Real-world obstacles can be represented using
+//! [`add_polygon_barrier()`](DynamicProgramBuilder::add_polygon_barrier), e.g. for lakes,
+//! [`add_line_barrier()`](DynamicProgramBuilder::add_line_barrier), e.g. for rivers or fences, and
+//! [`add_circle_barrier()`](DynamicProgramBuilder::add_circle_barrier). These shapes are
+//! rasterized, i.e. all fields whose center is covered by the shape are blocked.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::xy;
+//! #
+//! let dp = DynamicProgramBuilder::new()
+//!     // ...
+//!     .add_polygon_barrier(vec![xy!(5, 5), xy!(15, 8), xy!(10, 15)])
+//!     .add_line_barrier(xy!(-20, -5), xy!(20, -15), 2.0)
+//!     .add_circle_barrier(xy!(-10, 10), 4.0)
+//!     // ...
+//!     .build();
+//! ```
+//!
 //! If other forms of barriers are required or if some fields should not be entirely blocked but the
 //! usage probability should be reduced,
 //! [`field_probabilities()`](DynamicProgramBuilder::field_probabilities) can be used. This function
 //! allows to set the probability of each field separately. A probability of `0.0` means that the
 //! field is not visited in any way, while a probability of `1.0` means that the field has its
 //! normal probability that was assigned to it while computing the dynamic program.
+//!
+//! # Absorbing Cells
+//!
+//! Cells can be marked as absorbing using
+//! [`add_absorbing_cell()`](DynamicProgramBuilder::add_absorbing_cell) or
+//! [`add_absorbing_rect()`](DynamicProgramBuilder::add_absorbing_rect). Probability mass reaching
+//! an absorbing cell accumulates there instead of being redistributed, i.e. a walk ends as soon as
+//! it reaches such a cell, e.g. the den of an animal. The mass absorbed in each time step, i.e.
+//! the distribution of arrival times, can be read using
+//! [`absorbed_mass()`](crate::dp::simple::DynamicProgram::absorbed_mass).
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::{DynamicProgramPool, DynamicPrograms};
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::xy;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .add_absorbing_rect(xy!(10, -2), xy!(12, 2))
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let DynamicProgramPool::Single(dp) = dp else {
+//!     unreachable!();
+//! };
+//!
+//! println!("{:?}", dp.absorbed_mass());
+//! ```
+//!
+//! # Initial Distribution
+//!
+//! By default, all probability mass starts at the origin in time step 0. Using
+//! [`initial_distribution()`](DynamicProgramBuilder::initial_distribution), the mass is instead
+//! distributed among multiple start cells according to their weights, e.g. if the start
+//! location of a walk is uncertain or if there are multiple release sites. The weights are
+//! normalized so that they sum up to one. Walks generated using the dynamic program then start
+//! at one of the start cells, chosen according to the distribution conditioned on the end point.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::xy;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .initial_distribution(vec![(xy!(-10, 0), 3.0), (xy!(10, 0), 1.0)])
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//! ```
+//!
+//! Absorbing cells cannot be used when counting paths or computing on the GPU.
+//!
+//! # Field Types
+//!
+//! Different areas of the map can use different kernels, e.g. to model faster movement along
+//! roads. Each field of the table is assigned a type using
+//! [`field_types()`](DynamicProgramBuilder::field_types) and each type is assigned a kernel using
+//! [`kernels_by_type()`](DynamicProgramBuilder::kernels_by_type).
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::biased_rw::BiasedRwGenerator;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::kernel::Direction;
+//! #
+//! // Fields right of the origin use a kernel biased to the east
+//! let mut field_types = vec![vec![0; 201]; 201];
+//!
+//! for column in field_types.iter_mut().skip(101) {
+//!     column.fill(1);
+//! }
+//!
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernels_by_type(vec![
+//!         (0, Kernel::from_generator(SimpleRwGenerator::default()).unwrap()),
+//!         (1, Kernel::from_generator(BiasedRwGenerator {
+//!             probability: 0.5,
+//!             direction: Direction::East,
+//!         }).unwrap()),
+//!     ])
+//!     .field_types(field_types)
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//! ```
+//!
+//! # Diffusivity
+//!
+//! Terrain in which walks move slower without being blocked, e.g. dense vegetation, can be
+//! modeled using [`diffusivity()`](DynamicProgramBuilder::diffusivity). It sets a value between
+//! `0.0` and `1.0` for each field, by which the probability of each movement starting at the
+//! field is scaled. The remaining probability is added to staying, so that mass concentrates in
+//! fields with a low diffusivity while the total mass is preserved. A diffusivity of `0.0` means
+//! that walks never leave the field, while a diffusivity of `1.0`, the default, leaves the kernel
+//! unchanged.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let mut diffusivity = vec![vec![1.0; 201]; 201];
+//!
+//! // Movement is slowed down to a fifth in the upper half of the map
+//! for column in diffusivity.iter_mut() {
+//!     column[..100].fill(0.2);
+//! }
+//!
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .diffusivity(diffusivity)
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//! ```
+//!
+//! Dynamic programs with a diffusivity field cannot be computed on the GPU.
+//!
+//! # Rolling Storage
+//!
+//! By default, the full table of the dynamic program is kept in memory, which requires memory
+//! cubic in the time limit. Many analyses, however, only need the distribution at a handful of
+//! time steps. Using [`rolling_storage()`](DynamicProgramBuilder::rolling_storage), only the last
+//! two time slices are kept in memory while computing. Completed slices are either streamed to a
+//! file, which can later be loaded using
+//! [`DynamicProgram::load()`](crate::dp::simple::DynamicProgram::load), or discarded.
+//!
+//! The slice of the last time step is always kept in memory. Additional time steps can be kept
+//! using [`capture_time_steps()`](DynamicProgramBuilder::capture_time_steps).
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(400)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .rolling_storage(None)
+//!     .capture_time_steps(vec![100, 200])
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//! ```
+//!
+//! Note that walkers require all time slices and therefore cannot be used with dynamic programs
+//! that discard slices. Walks can, however, be generated from a streamed file by opening it as a
+//! [`LazyDynamicProgram`](crate::dp::lazy::LazyDynamicProgram), which reads single time slices
+//! from disk as they are accessed. This way, dynamic programs whose full table does not fit into
+//! memory can be computed and used for generating walks.
+//!
+//! # Precision
+//!
+//! For large time limits, the full table can alternatively be stored in single precision using
+//! [`single_precision()`](DynamicProgramBuilder::single_precision), which halves its memory
+//! consumption. Single precision is usually sufficient for sampling walks.
+//!
+//! # Sparse Storage
+//!
+//! In early time steps, only a small area around the origin can be reached. Using
+//! [`sparse_storage()`](DynamicProgramBuilder::sparse_storage), each time slice only stores the
+//! region containing non-zero values, so memory scales with the reachable area of each time step
+//! instead of the full table size.
+//!
+//! # Compressed Storage
+//!
+//! Using [`compressed_storage()`](DynamicProgramBuilder::compressed_storage), all time slices
+//! except for the last two are compressed in memory and decompressed on access. Since walkers only
+//! access two consecutive time slices at a time, this trades some computation time for a large
+//! reduction of memory consumption, in particular for large time limits where most of each slice
+//! is zero or tiny. Compressed storage requires the `saving` feature.
+//!
+//! # Quantized Storage
+//!
+//! Using [`quantized_storage()`](DynamicProgramBuilder::quantized_storage), all time slices except
+//! for the last two are stored as 16-bit fixed-point values with a scale per slice, which reduces
+//! memory consumption by a factor of four compared to double precision. Since the computation only
+//! reads the last two slices, it is not affected by the loss of precision, and values are
+//! dequantized transparently when accessed, e.g. using
+//! [`at()`](crate::dp::simple::DynamicProgram::at). The remaining precision is sufficient for
+//! sampling walks, but small probabilities far from the most likely points are only approximated
+//! coarsely.
+//!
+//! # Log Space
+//!
+//! For large time limits or restrictive kernels, probabilities can become too small to be
+//! represented and underflow to zero, so that walkers cannot find any path. Using
+//! [`log_space()`](DynamicProgramBuilder::log_space), the natural logarithms of all probabilities
+//! are stored and computed instead, which avoids the underflow. All walkers support dynamic
+//! programs computed in log space.
+//!
+//! # Bounding Box
+//!
+//! By default, the table of a dynamic program is a square of side length `2 * time_limit + 1`
+//! centered at the origin, which covers all points reachable within the time limit using kernels
+//! of size 3. Using [`bounding_box()`](DynamicProgramBuilder::bounding_box), the table instead
+//! covers an arbitrary rectangle containing the origin. This saves memory when walks are confined
+//! to a narrow corridor or require a larger extent in one direction than in the other.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::xy;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(400)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .bounding_box(xy!(-400, -20), xy!(400, 20))
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//! ```
+//!
+//! Probability mass leaving the bounding box is lost, just like at the boundary of the default
+//! table. Dynamic programs with a bounding box cannot be saved or streamed to disk.
+//!
+//! # Counting Paths
+//!
+//! Some combinatorial analyses require the exact number of paths leading to each field instead of
+//! probabilities. Using [`counting()`](DynamicProgramBuilder::counting), each field stores the
+//! number of paths of length `t` from the origin to it, where each step may use any offset with
+//! a non-zero kernel probability. Counts are stored as arbitrary precision integers and can be
+//! retrieved using [`count_at()`](crate::dp::simple::DynamicProgram::count_at). Since the counts
+//! grow exponentially, this is only feasible for small time limits.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::{DynamicProgramPool, DynamicPrograms};
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(10)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .counting()
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let DynamicProgramPool::Single(dp) = dp else {
+//!     unreachable!();
+//! };
+//!
+//! println!("{:?}", dp.count_at(2, 4, 10));
+//! println!("{}", dp.probability_at(2, 4, 10));
+//! ```
+//!
+//! # Memory-Mapped Storage
+//!
+//! Tables that do not fit into memory can be backed by a file using
+//! [`memory_mapped()`](DynamicProgramBuilder::memory_mapped). The operating system then only
+//! keeps the recently used time slices in memory, which is sufficient for computing the dynamic
+//! program as well as for walkers, which only access a few time slices at a time. Memory-mapped
+//! storage requires the `saving` feature.
+//!
+//! # Checkpoints
+//!
+//! Computing dynamic programs with large time limits can take a long time. Using
+//! [`checkpoint()`](DynamicProgramBuilder::checkpoint), all completed time slices are written to
+//! a file every few time steps. If the computation is interrupted, computing a dynamic program
+//! built with the same options again resumes after the last checkpointed time step instead of
+//! starting over.
+//!
+//! ```no_run
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(400)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .checkpoint("dp_checkpoint.zst".into(), 50)
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//! ```
+//!
+//! Checkpoints require the `saving` feature and cannot be used with rolling storage.
+//!
+//! # Parallel Computation
+//!
+//! [`compute_parallel()`](crate::dp::DynamicPrograms::compute_parallel) splits each time step
+//! into chunks which are computed by a pool of worker threads. By default, one thread is used per
+//! available CPU core. The number of threads and the number of chunks along each axis of the
+//! table can be set using [`threads()`](DynamicProgramBuilder::threads) and
+//! [`chunks()`](DynamicProgramBuilder::chunks).
 
 use crate::dataset::point::XYPoint;
+use crate::dp::checkpoint::Checkpoint;
+use crate::dp::domain::Domain;
 use crate::dp::simple::DynamicProgram;
-use crate::dp::{DynamicProgramPool, DynamicProgramType};
-use crate::kernel::Kernel;
-use num::Zero;
-use std::collections::HashMap;
+use crate::dp::table::Table;
+use crate::dp::{DynamicProgramPool, DynamicProgramType, StepCallback};
+use crate::kernel::schedule::KernelSchedule;
+use crate::kernel::{Kernel, KernelError};
+use geo::{EuclideanDistance, Intersects, Line, LineString, Point, Polygon};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
 /// An error that can occur when using a [`DynamicProgramBuilder`].
@@ -109,12 +457,144 @@ pub enum DynamicProgramBuilderError {
     #[error("field probabilities must be of same size as DP table")]
     WrongSizeOfFieldProbabilities,
 
+    /// This error occurs when the field types given using
+    /// [`field_types()`](DynamicProgramBuilder::field_types) do not match the size of the
+    /// dynamic program's table.
+    #[error("field types must be of same size as DP table")]
+    WrongSizeOfFieldTypes,
+
+    /// This error occurs when a field type given using
+    /// [`field_types()`](DynamicProgramBuilder::field_types) has no kernel assigned to it using
+    /// [`kernels_by_type()`](DynamicProgramBuilder::kernels_by_type).
+    #[error("no kernel was set for field type {0}")]
+    UnknownFieldType(usize),
+
     /// This error occurs when a barrier that was given using
-    /// [`add_single_barrier()`](DynamicProgramBuilder::add_single_barrier) or
-    /// [`add_rect_barrier()`](DynamicProgramBuilder::add_rect_barrier) is entirely or partially
-    /// out of range of the dynamic program's table.
+    /// [`add_single_barrier()`](DynamicProgramBuilder::add_single_barrier),
+    /// [`add_rect_barrier()`](DynamicProgramBuilder::add_rect_barrier),
+    /// [`add_polygon_barrier()`](DynamicProgramBuilder::add_polygon_barrier),
+    /// [`add_line_barrier()`](DynamicProgramBuilder::add_line_barrier) or
+    /// [`add_circle_barrier()`](DynamicProgramBuilder::add_circle_barrier) is entirely or
+    /// partially out of range of the dynamic program's table.
     #[error("barriers must be inside the time limit range")]
     BarrierOutOfRange,
+
+    /// This error occurs when an absorbing cell that was given using
+    /// [`add_absorbing_cell()`](DynamicProgramBuilder::add_absorbing_cell) or
+    /// [`add_absorbing_rect()`](DynamicProgramBuilder::add_absorbing_rect) is out of range of the
+    /// dynamic program's table.
+    #[error("absorbing cells must be inside the time limit range")]
+    AbsorbingCellOutOfRange,
+
+    /// This error occurs when a start cell given using
+    /// [`initial_distribution()`](DynamicProgramBuilder::initial_distribution) is out of range of
+    /// the dynamic program's table.
+    #[error("start cells must be inside the time limit range")]
+    StartCellOutOfRange,
+
+    /// This error occurs when a weight given using
+    /// [`initial_distribution()`](DynamicProgramBuilder::initial_distribution) is negative or not
+    /// finite, or when all weights are zero.
+    #[error("weights of the initial distribution must be non-negative and not all zero")]
+    InvalidInitialDistribution,
+
+    /// This error occurs when the rectangle given using
+    /// [`bounding_box()`](DynamicProgramBuilder::bounding_box) does not contain the origin.
+    #[error("the bounding box must contain the origin")]
+    InvalidBoundingBox,
+
+    /// This error occurs when the time ranges given using
+    /// [`kernel_schedule()`](DynamicProgramBuilder::kernel_schedule) overlap.
+    #[error("time ranges of the kernel schedule must not overlap")]
+    OverlappingKernelSchedule,
+
+    /// This error occurs when a path was given using
+    /// [`rolling_storage()`](DynamicProgramBuilder::rolling_storage), or
+    /// [`memory_mapped()`](DynamicProgramBuilder::memory_mapped),
+    /// [`compressed_storage()`](DynamicProgramBuilder::compressed_storage) or
+    /// [`checkpoint()`](DynamicProgramBuilder::checkpoint) is used but the `saving` feature is
+    /// not enabled.
+    #[error("writing slices to disk requires the `saving` feature")]
+    SavingFeatureRequired,
+
+    /// This error occurs when more than one of
+    /// [`sparse_storage()`](DynamicProgramBuilder::sparse_storage),
+    /// [`single_precision()`](DynamicProgramBuilder::single_precision),
+    /// [`compressed_storage()`](DynamicProgramBuilder::compressed_storage),
+    /// [`quantized_storage()`](DynamicProgramBuilder::quantized_storage) and
+    /// [`memory_mapped()`](DynamicProgramBuilder::memory_mapped) are used, when
+    /// [`memory_mapped()`](DynamicProgramBuilder::memory_mapped),
+    /// [`compressed_storage()`](DynamicProgramBuilder::compressed_storage) or
+    /// [`quantized_storage()`](DynamicProgramBuilder::quantized_storage) is used together with
+    /// [`rolling_storage()`](DynamicProgramBuilder::rolling_storage), when
+    /// [`log_space()`](DynamicProgramBuilder::log_space) is used together with sparse storage,
+    /// quantized storage or streamed rolling storage, when
+    /// [`counting()`](DynamicProgramBuilder::counting) is used together with any other storage
+    /// option, log space, checkpoints, absorbing cells, field probabilities other than `0.0` and
+    /// `1.0` or an [`initial_distribution()`](DynamicProgramBuilder::initial_distribution), or when
+    /// [`bounding_box()`](DynamicProgramBuilder::bounding_box) is used together with streamed
+    /// rolling storage.
+    #[error("the chosen storage options cannot be combined")]
+    ConflictingStorageOptions,
+
+    /// This error occurs when the file backing a table using
+    /// [`memory_mapped()`](DynamicProgramBuilder::memory_mapped) could not be created.
+    #[error("could not create memory-mapped table")]
+    MemoryMapFailed(#[source] std::io::Error),
+
+    /// This error occurs when [`checkpoint()`](DynamicProgramBuilder::checkpoint) is used
+    /// together with [`rolling_storage()`](DynamicProgramBuilder::rolling_storage), which
+    /// discards the slices a checkpoint consists of.
+    #[error("checkpoints cannot be used with rolling storage")]
+    CheckpointWithRollingStorage,
+
+    /// This error occurs when the interval given to
+    /// [`checkpoint()`](DynamicProgramBuilder::checkpoint) is zero.
+    #[error("checkpoint interval must be greater than zero")]
+    InvalidCheckpointInterval,
+
+    /// This error occurs when the tolerance given to
+    /// [`epsilon()`](DynamicProgramBuilder::epsilon) is negative, not finite or at least one.
+    #[error("epsilon must be at least zero and less than one")]
+    InvalidEpsilon,
+
+    /// This error occurs when a field probability given using
+    /// [`field_probabilities()`](DynamicProgramBuilder::field_probabilities) is outside of the
+    /// range `[0, 1]`.
+    #[error("field probabilities must be in the range [0, 1]")]
+    InvalidFieldProbabilities,
+
+    /// This error occurs when a kernel contains a negative or non-finite probability, or when it
+    /// is not square with an odd size.
+    #[error("kernel probabilities must be non-negative and finite and form a square of odd size")]
+    InvalidKernelProbabilities,
+
+    /// This error occurs when the probabilities of a kernel do not sum up to 1. Kernels whose
+    /// probabilities are all zero are allowed, as they prevent walks from entering fields of
+    /// their field type.
+    #[error("kernel probabilities must sum up to 1")]
+    KernelNotNormalized,
+
+    /// This error occurs when a kernel is larger than the dynamic program's table in any
+    /// direction.
+    #[error("kernels must not be larger than the DP table")]
+    KernelTooLarge,
+
+    /// This error occurs when the number given to [`threads()`](DynamicProgramBuilder::threads)
+    /// or [`chunks()`](DynamicProgramBuilder::chunks) is zero.
+    #[error("number of threads and chunks must be greater than zero")]
+    InvalidParallelism,
+
+    /// This error occurs when the diffusivity given using
+    /// [`diffusivity()`](DynamicProgramBuilder::diffusivity) does not match the size of the
+    /// dynamic program's table.
+    #[error("diffusivity must be of same size as DP table")]
+    WrongSizeOfDiffusivity,
+
+    /// This error occurs when a diffusivity given using
+    /// [`diffusivity()`](DynamicProgramBuilder::diffusivity) is outside of the range `[0, 1]`.
+    #[error("diffusivity must be in the range [0, 1]")]
+    InvalidDiffusivity,
 }
 
 /// A builder used to create and initialize dynamic programs.
@@ -128,7 +608,26 @@ pub struct DynamicProgramBuilder {
     kernels: Option<Vec<(usize, Kernel)>>,
     field_probabilities: Option<Vec<Vec<f64>>>,
     field_types: Option<Vec<Vec<usize>>>,
+    diffusivity: Option<Vec<Vec<f64>>>,
+    kernel_schedule: Vec<(Range<usize>, Kernel)>,
     barriers: Vec<XYPoint>,
+    absorbing: Vec<XYPoint>,
+    initial_distribution: Vec<(XYPoint, f64)>,
+    bounding_box: Option<(XYPoint, XYPoint)>,
+    rolling_storage: Option<Option<String>>,
+    capture_time_steps: Vec<usize>,
+    single_precision: bool,
+    sparse_storage: bool,
+    compressed_storage: bool,
+    quantized_storage: bool,
+    memory_mapped: Option<String>,
+    log_space: bool,
+    counting: bool,
+    checkpoint: Option<Checkpoint>,
+    epsilon: f64,
+    threads: Option<usize>,
+    chunks: Option<usize>,
+    step_callbacks: Vec<StepCallback>,
 }
 
 impl DynamicProgramBuilder {
@@ -168,38 +667,393 @@ impl DynamicProgramBuilder {
         self
     }
 
-    /// Sets the [`Kernel`](crate::kernel::Kernel) for the dynamic program. Use this in combination
-    /// with a [`DynamicProgram`].
-    pub fn kernel(mut self, kernel: Kernel) -> Self {
-        self.kernels(vec![(0, kernel)])
+    /// Sets the [`Kernel`](crate::kernel::Kernel) for the dynamic program. Use this in combination
+    /// with a [`DynamicProgram`].
+    pub fn kernel(mut self, kernel: Kernel) -> Self {
+        self.kernels(vec![(0, kernel)])
+    }
+
+    pub fn kernels(mut self, kernels: Vec<(usize, Kernel)>) -> Self {
+        self.kernels = Some(kernels);
+
+        self
+    }
+
+    /// Sets the [`Kernel`](crate::kernel::Kernel) used for each field type.
+    ///
+    /// Each field is computed using the kernel of its type, as set using
+    /// [`field_types()`](DynamicProgramBuilder::field_types). See the
+    /// [`builder`](crate::dp::builder) module for more information.
+    pub fn kernels_by_type(self, kernels: Vec<(usize, Kernel)>) -> Self {
+        self.kernels(kernels)
+    }
+
+    /// Sets the type of each field of the table, indexed as `types[x][y]`.
+    ///
+    /// The outer vector spans the table in x direction and each inner vector spans it in y
+    /// direction, starting at the smallest coordinate. By default, all fields have type `0`.
+    pub fn field_types(mut self, types: Vec<Vec<usize>>) -> Self {
+        self.field_types = Some(types);
+
+        self
+    }
+
+    /// Sets the probability of each field of the table, indexed as `probabilities[x][y]`.
+    ///
+    /// The probability of each field scales the probability of entering it. All probabilities must
+    /// be in the range `[0, 1]`. By default, all fields have a probability of `1.0`. Barriers set
+    /// the probability of their fields to `0.0`. When counting paths, only probabilities of `0.0`
+    /// and `1.0` are allowed. See the [`builder`](crate::dp::builder) module for more information.
+    pub fn field_probabilities(mut self, probabilities: Vec<Vec<f64>>) -> Self {
+        self.field_probabilities = Some(probabilities);
+
+        self
+    }
+
+    /// Sets the diffusivity of each field of the table, indexed as `diffusivity[x][y]`.
+    ///
+    /// The probability of each movement starting at a field is scaled by its diffusivity and the
+    /// remaining probability is added to staying. All values must be in the range `[0, 1]`. By
+    /// default, all fields have a diffusivity of `1.0`. See the [`builder`](crate::dp::builder)
+    /// module for more information.
+    pub fn diffusivity(mut self, diffusivity: Vec<Vec<f64>>) -> Self {
+        self.diffusivity = Some(diffusivity);
+
+        self
+    }
+
+    /// Sets a schedule of kernels that are used for specific ranges of time steps.
+    ///
+    /// While computing a time step `t` that is contained in one of the given ranges, the
+    /// corresponding kernel is used for all fields instead of the kernels set using
+    /// [`kernel()`](DynamicProgramBuilder::kernel) or [`kernels()`](DynamicProgramBuilder::kernels).
+    /// The time ranges must not overlap.
+    pub fn kernel_schedule(mut self, schedule: Vec<(Range<usize>, Kernel)>) -> Self {
+        self.kernel_schedule = schedule;
+
+        self
+    }
+
+    /// Adds a single barrier to the dynamic program.
+    pub fn add_single_barrier(mut self, at: XYPoint) -> Self {
+        self.barriers.push(at);
+
+        self
+    }
+
+    /// Adds multiple barriers in a specified rectangular area to the dynamic program.
+    pub fn add_rect_barrier(mut self, from: XYPoint, to: XYPoint) -> Self {
+        for x in from.x..=to.x {
+            for y in from.y..=to.y {
+                self.barriers.push(XYPoint { x, y })
+            }
+        }
+
+        self
+    }
+
+    /// Adds barriers on all fields whose center lies inside or on the boundary of the polygon
+    /// with the given vertices.
+    ///
+    /// The polygon is closed automatically, i.e. the last vertex is connected to the first one.
+    pub fn add_polygon_barrier(mut self, vertices: Vec<XYPoint>) -> Self {
+        let (Some(x_min), Some(x_max)) = (
+            vertices.iter().map(|p| p.x).min(),
+            vertices.iter().map(|p| p.x).max(),
+        ) else {
+            return self;
+        };
+        let y_min = vertices.iter().map(|p| p.y).min().unwrap();
+        let y_max = vertices.iter().map(|p| p.y).max().unwrap();
+
+        let polygon = Polygon::new(
+            LineString::from(
+                vertices
+                    .iter()
+                    .map(|p| (p.x as f64, p.y as f64))
+                    .collect::<Vec<_>>(),
+            ),
+            Vec::new(),
+        );
+
+        for x in x_min..=x_max {
+            for y in y_min..=y_max {
+                if polygon.intersects(&Point::new(x as f64, y as f64)) {
+                    self.barriers.push(XYPoint { x, y });
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Adds barriers on all fields whose center has a distance of at most `width / 2` to the line
+    /// from `from` to `to`.
+    ///
+    /// A width of at least `1.5` ensures that diagonal lines cannot be crossed by diagonal steps.
+    pub fn add_line_barrier(mut self, from: XYPoint, to: XYPoint, width: f64) -> Self {
+        let line = Line::new((from.x as f64, from.y as f64), (to.x as f64, to.y as f64));
+        let margin = (width / 2.0).ceil() as i64;
+
+        for x in from.x.min(to.x) - margin..=from.x.max(to.x) + margin {
+            for y in from.y.min(to.y) - margin..=from.y.max(to.y) + margin {
+                if Point::new(x as f64, y as f64).euclidean_distance(&line) <= width / 2.0 {
+                    self.barriers.push(XYPoint { x, y });
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Adds barriers on all fields whose center has a distance of at most `radius` to `center`.
+    pub fn add_circle_barrier(mut self, center: XYPoint, radius: f64) -> Self {
+        let margin = radius.floor() as i64;
+
+        for x in center.x - margin..=center.x + margin {
+            for y in center.y - margin..=center.y + margin {
+                let (dx, dy) = ((x - center.x) as f64, (y - center.y) as f64);
+
+                if dx * dx + dy * dy <= radius * radius {
+                    self.barriers.push(XYPoint { x, y });
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Marks a single cell as absorbing.
+    ///
+    /// Probability mass reaching an absorbing cell stays there and is not redistributed in later
+    /// time steps. See the [`builder`](crate::dp::builder) module for more information.
+    pub fn add_absorbing_cell(mut self, at: XYPoint) -> Self {
+        self.absorbing.push(at);
+
+        self
+    }
+
+    /// Marks all cells in a specified rectangular area as absorbing.
+    pub fn add_absorbing_rect(mut self, from: XYPoint, to: XYPoint) -> Self {
+        for x in from.x..=to.x {
+            for y in from.y..=to.y {
+                self.absorbing.push(XYPoint { x, y })
+            }
+        }
+
+        self
+    }
+
+    /// Sets the distribution of probability mass in time step 0.
+    ///
+    /// Each start cell is given together with its weight. Weights of cells given multiple times
+    /// are added up and all weights are normalized to sum up to one. By default, all probability
+    /// mass starts at the origin. See the [`builder`](crate::dp::builder) module for more
+    /// information.
+    pub fn initial_distribution(mut self, cells: Vec<(XYPoint, f64)>) -> Self {
+        self.initial_distribution = cells;
+
+        self
+    }
+
+    /// Restricts the table of the dynamic program to the rectangle from `from` to `to`.
+    ///
+    /// The rectangle must contain the origin. By default, the table is a square of side length
+    /// `2 * time_limit + 1` centered at the origin. See the [`builder`](crate::dp::builder) module
+    /// for more information.
+    pub fn bounding_box(mut self, from: XYPoint, to: XYPoint) -> Self {
+        self.bounding_box = Some((from, to));
+
+        self
+    }
+
+    /// Only keeps the last two time slices in memory while computing the dynamic program.
+    ///
+    /// If `path` is given, completed slices are streamed to a file at that path, otherwise they
+    /// are discarded. Streaming requires the `saving` feature. See the
+    /// [`builder`](crate::dp::builder) module for more information.
+    pub fn rolling_storage(mut self, path: Option<String>) -> Self {
+        self.rolling_storage = Some(path);
+
+        self
+    }
+
+    /// Sets time steps whose slices are kept in memory when using
+    /// [`rolling_storage()`](DynamicProgramBuilder::rolling_storage).
+    ///
+    /// The slice of the last time step is always kept.
+    pub fn capture_time_steps(mut self, time_steps: Vec<usize>) -> Self {
+        self.capture_time_steps = time_steps;
+
+        self
+    }
+
+    /// Stores the table of the dynamic program using `f32` instead of `f64` values.
+    ///
+    /// Computations are still carried out in double precision, only the stored values are
+    /// rounded. This has no effect when using
+    /// [`rolling_storage()`](DynamicProgramBuilder::rolling_storage).
+    pub fn single_precision(mut self) -> Self {
+        self.single_precision = true;
+
+        self
+    }
+
+    /// Only stores the region of each time slice that contains non-zero values.
+    ///
+    /// This reduces the memory consumption of dynamic programs with large time limits, in
+    /// particular for kernels with a small radius. This has no effect when using
+    /// [`rolling_storage()`](DynamicProgramBuilder::rolling_storage) and cannot be combined with
+    /// [`single_precision()`](DynamicProgramBuilder::single_precision).
+    pub fn sparse_storage(mut self) -> Self {
+        self.sparse_storage = true;
+
+        self
+    }
+
+    /// Keeps all time slices except for the last two compressed in memory.
+    ///
+    /// Slices are decompressed on access, which makes computing the dynamic program and
+    /// generating walks slower, but greatly reduces memory consumption. Requires the `saving`
+    /// feature and cannot be combined with other storage options. See the
+    /// [`builder`](crate::dp::builder) module for more information.
+    pub fn compressed_storage(mut self) -> Self {
+        self.compressed_storage = true;
+
+        self
+    }
+
+    /// Keeps all time slices except for the last two quantized to 16-bit fixed-point values.
+    ///
+    /// This reduces memory consumption by a factor of four at the cost of precision when
+    /// accessing completed slices. Cannot be combined with other storage options or
+    /// [`log_space()`](DynamicProgramBuilder::log_space). See the
+    /// [`builder`](crate::dp::builder) module for more information.
+    pub fn quantized_storage(mut self) -> Self {
+        self.quantized_storage = true;
+
+        self
+    }
+
+    /// Sets the tolerance below which probabilities are considered to be zero.
+    ///
+    /// Walkers treat end points whose probability is at most `epsilon` as unreachable, and
+    /// validation of datasets classifies them accordingly. Tiny probabilities, e.g. denormalized
+    /// values far away from the origin in long dynamic programs, carry almost no precision, so
+    /// generating walks to them tends to fail with inconsistent paths. Defaults to zero, i.e.
+    /// only probabilities that are exactly zero are considered to be zero.
+    pub fn epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+
+        self
+    }
+
+    /// Sets the number of worker threads used by
+    /// [`compute_parallel()`](crate::dp::DynamicPrograms::compute_parallel). Defaults to the
+    /// number of available CPU cores.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+
+        self
+    }
+
+    /// Sets the number of chunks along each axis the table is split into by
+    /// [`compute_parallel()`](crate::dp::DynamicPrograms::compute_parallel), so that each time
+    /// step is computed in `chunks * chunks` tasks. Defaults to the smallest number yielding at
+    /// least one task per worker thread. Axes shorter than `chunks` are split into single rows.
+    pub fn chunks(mut self, chunks: usize) -> Self {
+        self.chunks = Some(chunks);
+
+        self
+    }
+
+    /// Computes and stores the natural logarithms of all probabilities.
+    ///
+    /// This prevents probabilities from underflowing to zero. Values returned by
+    /// [`DynamicProgram::at()`] are then log-probabilities. Dynamic programs computed in log space
+    /// cannot be saved, computed on the GPU or combined with sparse storage or streamed rolling
+    /// storage. See the [`builder`](crate::dp::builder) module for more information.
+    pub fn log_space(mut self) -> Self {
+        self.log_space = true;
+
+        self
     }
 
-    pub fn kernels(mut self, kernels: Vec<(usize, Kernel)>) -> Self {
-        self.kernels = Some(kernels);
+    /// Stores the exact number of paths leading to each field instead of probabilities.
+    ///
+    /// A path may use any step whose kernel probability is non-zero. Use
+    /// [`DynamicProgram::probability_at()`] to convert counts to probabilities. Counting paths
+    /// cannot be combined with other storage options, log space or checkpoints. See the
+    /// [`builder`](crate::dp::builder) module for more information.
+    pub fn counting(mut self) -> Self {
+        self.counting = true;
 
         self
     }
 
-    pub fn field_types(mut self, types: Vec<Vec<usize>>) -> Self {
-        self.field_types = Some(types);
+    /// Stores the full table in a memory-mapped file at `path`.
+    ///
+    /// This allows computing and querying dynamic programs whose table is larger than the
+    /// available memory. An existing file at `path` is overwritten and the file is kept after the
+    /// dynamic program is dropped. Requires the `saving` feature and cannot be combined with other
+    /// storage options. See the [`builder`](crate::dp::builder) module for more information.
+    pub fn memory_mapped(mut self, path: String) -> Self {
+        self.memory_mapped = Some(path);
 
         self
     }
 
-    /// Adds a single barrier to the dynamic program.
-    pub fn add_single_barrier(mut self, at: XYPoint) -> Self {
-        self.barriers.push(at);
+    /// Writes a checkpoint to `path` every `interval` time steps while computing.
+    ///
+    /// If a checkpoint exists at `path` when the computation starts, the table is restored from
+    /// it and the computation resumes after the last checkpointed time step. Computing fails if
+    /// the checkpoint was created for a differently configured dynamic program. Step callbacks
+    /// are not invoked again for restored time steps. The checkpoint is removed once the
+    /// computation has finished. Requires the `saving` feature and cannot be combined with
+    /// [`rolling_storage()`](DynamicProgramBuilder::rolling_storage).
+    pub fn checkpoint(mut self, path: String, interval: usize) -> Self {
+        self.checkpoint = Some(Checkpoint::new(path, interval));
 
         self
     }
 
-    /// Adds multiple barriers in a specified rectangular area to the dynamic program.
-    pub fn add_rect_barrier(mut self, from: XYPoint, to: XYPoint) -> Self {
-        for x in from.x..=to.x {
-            for y in from.y..=to.y {
-                self.barriers.push(XYPoint { x, y })
-            }
-        }
+    /// Registers a callback that is invoked after each computed time step.
+    ///
+    /// The callback receives the time step `t` and the probabilities of the corresponding slice of
+    /// the table, indexed as `slice[x][y]`. This allows computing custom observables, e.g. the
+    /// probability mass beyond a boundary, online without keeping the whole table in memory. See
+    /// [`rolling_storage()`](DynamicProgramBuilder::rolling_storage).
+    ///
+    /// Probabilities are passed independent of the computation mode, i.e. when computing in
+    /// [`log_space()`](DynamicProgramBuilder::log_space) or
+    /// [`counting()`](DynamicProgramBuilder::counting) paths, the callback receives the same
+    /// values as [`slice()`](crate::dp::simple::DynamicProgram::slice).
+    ///
+    /// ```
+    /// # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+    /// # use randomwalks_lib::dp::DynamicPrograms;
+    /// # use randomwalks_lib::kernel::Kernel;
+    /// # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+    /// #
+    /// let mut dp = DynamicProgramBuilder::new()
+    ///     .simple()
+    ///     .time_limit(100)
+    ///     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+    ///     .rolling_storage(None)
+    ///     .on_step(|t, slice| {
+    ///         let max = slice.iter().flatten().fold(0.0, |a: f64, &b| a.max(b));
+    ///         println!("Maximum probability in time step {t}: {max}");
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// dp.compute().unwrap();
+    /// ```
+    pub fn on_step<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(usize, &[Vec<f64>]) + Send + 'static,
+    {
+        self.step_callbacks.push(Arc::new(Mutex::new(callback)));
 
         self
     }
@@ -220,49 +1074,132 @@ impl DynamicProgramBuilder {
             return Err(DynamicProgramBuilderError::NoTypeSet);
         };
 
+        let domain = match self.bounding_box {
+            Some((from, to)) => Domain::new(
+                from.x as isize,
+                from.y as isize,
+                to.x as isize,
+                to.y as isize,
+            )
+            .ok_or(DynamicProgramBuilderError::InvalidBoundingBox)?,
+            None => Domain::square(time_limit),
+        };
+
         let mut field_probabilities = match self.field_probabilities {
             Some(fp) => {
-                if fp.len() != 2 * time_limit + 1 {
+                if fp.len() != domain.width() {
                     return Err(DynamicProgramBuilderError::WrongSizeOfFieldProbabilities);
                 }
 
                 for fpp in fp.iter() {
-                    if fpp.len() != 2 * time_limit + 1 {
+                    if fpp.len() != domain.height() {
                         return Err(DynamicProgramBuilderError::WrongSizeOfFieldProbabilities);
                     }
                 }
 
+                if fp.iter().flatten().any(|p| !(0.0..=1.0).contains(p)) {
+                    return Err(DynamicProgramBuilderError::InvalidFieldProbabilities);
+                }
+
                 fp
             }
-            None => vec![vec![1.0; 2 * time_limit + 1]; 2 * time_limit + 1],
+            None => vec![vec![1.0; domain.height()]; domain.width()],
         };
 
+        for (_, kernel) in self.kernel_schedule.iter() {
+            validate_kernel(kernel, domain)?;
+        }
+
+        let kernel_schedule = KernelSchedule::new(self.kernel_schedule)
+            .map_err(|_| DynamicProgramBuilderError::OverlappingKernelSchedule)?;
+
+        if !(0.0..1.0).contains(&self.epsilon) {
+            return Err(DynamicProgramBuilderError::InvalidEpsilon);
+        }
+
+        let (threads, chunks) = parallelism(self.threads, self.chunks)
+            .ok_or(DynamicProgramBuilderError::InvalidParallelism)?;
+
         let mut field_types = match self.field_types {
-            Some(ft) => ft,
-            None => vec![vec![0; 2 * time_limit + 1]; 2 * time_limit + 1],
+            Some(ft) => {
+                if ft.len() != domain.width() || ft.iter().any(|ftt| ftt.len() != domain.height()) {
+                    return Err(DynamicProgramBuilderError::WrongSizeOfFieldTypes);
+                }
+
+                ft
+            }
+            None => vec![vec![0; domain.height()]; domain.width()],
         };
 
-        for (x, y) in self.barriers.iter().map(|p| <(i64, i64)>::from(*p)) {
-            if x < -(time_limit as i64)
-                || x > time_limit as i64
-                || y < -(time_limit as i64)
-                || y > time_limit as i64
+        if let Some(diffusivity) = &self.diffusivity {
+            if diffusivity.len() != domain.width()
+                || diffusivity.iter().any(|d| d.len() != domain.height())
             {
+                return Err(DynamicProgramBuilderError::WrongSizeOfDiffusivity);
+            }
+
+            if diffusivity
+                .iter()
+                .flatten()
+                .any(|d| !(0.0..=1.0).contains(d))
+            {
+                return Err(DynamicProgramBuilderError::InvalidDiffusivity);
+            }
+        }
+
+        for (x, y) in self.barriers.iter().map(|p| <(i64, i64)>::from(*p)) {
+            if !domain.contains(x as isize, y as isize) {
                 return Err(DynamicProgramBuilderError::BarrierOutOfRange);
             }
 
-            let x = (time_limit as i64 + x) as usize;
-            let y = (time_limit as i64 + y) as usize;
+            let (x, y) = domain.index(x as isize, y as isize);
 
             field_probabilities[x][y] = 0.0;
         }
 
+        let reduced = field_probabilities.iter().flatten().any(|&p| p < 1.0);
+        let field_probabilities = reduced.then_some(field_probabilities);
+
+        let mut absorbing = HashSet::new();
+
+        for (x, y) in self.absorbing.iter().map(|p| <(i64, i64)>::from(*p)) {
+            if !domain.contains(x as isize, y as isize) {
+                return Err(DynamicProgramBuilderError::AbsorbingCellOutOfRange);
+            }
+
+            absorbing.insert((x as isize, y as isize));
+        }
+
+        let custom_initial = !self.initial_distribution.is_empty();
+        let initial = if custom_initial {
+            let mut cells = Vec::new();
+
+            for (p, weight) in self.initial_distribution {
+                let (x, y) = (p.x as isize, p.y as isize);
+
+                if !domain.contains(x, y) {
+                    return Err(DynamicProgramBuilderError::StartCellOutOfRange);
+                }
+
+                cells.push(((x, y), weight));
+            }
+
+            normalize_initial_distribution(cells)
+                .ok_or(DynamicProgramBuilderError::InvalidInitialDistribution)?
+        } else {
+            vec![((0, 0), 1.0)]
+        };
+
         match dp_type {
             DynamicProgramType::Simple => {
                 let Some(mut kernels) = self.kernels else {
                     return Err(DynamicProgramBuilderError::NoKernelsSet);
                 };
 
+                for (_, kernel) in kernels.iter() {
+                    validate_kernel(kernel, domain)?;
+                }
+
                 // Map field types to contiguous value range
 
                 let mut kernels_mapped = Vec::new();
@@ -275,31 +1212,189 @@ impl DynamicProgramBuilder {
                     i += 1;
                 }
 
-                for x in 0..2 * time_limit + 1 {
-                    for y in 0..2 * time_limit + 1 {
-                        field_types[x][y] = field_type_map[&field_types[x][y]];
+                for x in 0..domain.width() {
+                    for y in 0..domain.height() {
+                        let field_type = field_types[x][y];
+
+                        field_types[x][y] = *field_type_map
+                            .get(&field_type)
+                            .ok_or(DynamicProgramBuilderError::UnknownFieldType(field_type))?;
+                    }
+                }
+
+                let storage_options = [
+                    self.sparse_storage,
+                    self.single_precision,
+                    self.memory_mapped.is_some(),
+                    self.compressed_storage,
+                    self.quantized_storage,
+                    self.counting,
+                ];
+
+                let streamed = matches!(self.rolling_storage, Some(Some(_)));
+
+                if storage_options.iter().filter(|&&option| option).count() > 1
+                    || ((self.memory_mapped.is_some()
+                        || self.compressed_storage
+                        || self.quantized_storage)
+                        && self.rolling_storage.is_some())
+                    || (self.log_space
+                        && (self.sparse_storage || self.quantized_storage || streamed))
+                    || (self.bounding_box.is_some() && streamed)
+                    || (self.counting
+                        && (self.rolling_storage.is_some()
+                            || field_probabilities
+                                .iter()
+                                .flatten()
+                                .flatten()
+                                .any(|&p| p > 0.0 && p < 1.0)
+                            || self.log_space
+                            || self.checkpoint.is_some()
+                            || !absorbing.is_empty()
+                            || custom_initial))
+                {
+                    return Err(DynamicProgramBuilderError::ConflictingStorageOptions);
+                }
+
+                if let Some(checkpoint) = &self.checkpoint {
+                    if cfg!(not(feature = "saving")) {
+                        return Err(DynamicProgramBuilderError::SavingFeatureRequired);
+                    }
+
+                    if self.rolling_storage.is_some() {
+                        return Err(DynamicProgramBuilderError::CheckpointWithRollingStorage);
+                    }
+
+                    if checkpoint.interval == 0 {
+                        return Err(DynamicProgramBuilderError::InvalidCheckpointInterval);
                     }
                 }
 
+                let table = match self.rolling_storage {
+                    Some(path) => {
+                        if cfg!(not(feature = "saving")) && path.is_some() {
+                            return Err(DynamicProgramBuilderError::SavingFeatureRequired);
+                        }
+
+                        Table::rolling(time_limit, domain, self.capture_time_steps, path)
+                    }
+                    #[cfg(feature = "saving")]
+                    None if self.memory_mapped.is_some() => {
+                        Table::mapped(time_limit, domain, self.memory_mapped.as_ref().unwrap())
+                            .map_err(DynamicProgramBuilderError::MemoryMapFailed)?
+                    }
+                    #[cfg(not(feature = "saving"))]
+                    None if self.memory_mapped.is_some() => {
+                        return Err(DynamicProgramBuilderError::SavingFeatureRequired);
+                    }
+                    #[cfg(feature = "saving")]
+                    None if self.compressed_storage => Table::compressed(time_limit, domain),
+                    #[cfg(not(feature = "saving"))]
+                    None if self.compressed_storage => {
+                        return Err(DynamicProgramBuilderError::SavingFeatureRequired);
+                    }
+                    None if self.quantized_storage => Table::quantized(time_limit, domain),
+                    None if self.counting => Table::counts(time_limit, domain),
+                    None if self.single_precision => Table::full_f32(time_limit, domain),
+                    None if self.sparse_storage => Table::sparse(time_limit, domain),
+                    None => Table::full(time_limit, domain),
+                };
+
                 Ok(DynamicProgramPool::Single(DynamicProgram {
-                    table: vec![
-                        vec![vec![Zero::zero(); 2 * time_limit + 1]; 2 * time_limit + 1];
-                        time_limit + 1
-                    ],
+                    table,
                     time_limit,
+                    domain,
                     kernels: kernels_mapped,
                     field_types,
+                    kernel_schedule,
+                    step_callbacks: self.step_callbacks,
+                    barriers: self.barriers.len(),
+                    absorbing,
+                    initial,
+                    compute_duration: None,
+                    epsilon: self.epsilon,
+                    checkpoint: self.checkpoint,
+                    log_space: self.log_space,
+                    threads,
+                    chunks,
+                    diffusivity: self.diffusivity,
+                    field_probabilities,
                 }))
             }
         }
     }
 }
 
+/// Checks `kernel` using [`Kernel::validate()`] and that it fits into a table covering `domain`.
+fn validate_kernel(kernel: &Kernel, domain: Domain) -> Result<(), DynamicProgramBuilderError> {
+    kernel.validate().map_err(|error| match error {
+        KernelError::NotNormalized => DynamicProgramBuilderError::KernelNotNormalized,
+        _ => DynamicProgramBuilderError::InvalidKernelProbabilities,
+    })?;
+
+    if kernel.size() > domain.width() || kernel.size() > domain.height() {
+        return Err(DynamicProgramBuilderError::KernelTooLarge);
+    }
+
+    Ok(())
+}
+
+/// Returns the number of worker threads and chunks per axis used for parallel computation.
+///
+/// Defaults to the number of available CPU cores and the smallest number of chunks per axis
+/// yielding at least one chunk per thread. Returns `None` if either number is zero.
+pub(crate) fn parallelism(threads: Option<usize>, chunks: Option<usize>) -> Option<(usize, usize)> {
+    let threads = threads
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |threads| threads.get()));
+    let chunks = chunks.unwrap_or_else(|| (threads as f64).sqrt().ceil() as usize);
+
+    (threads > 0 && chunks > 0).then_some((threads, chunks))
+}
+
+/// Merges the weights of start cells given multiple times and normalizes all weights to sum up
+/// to one.
+///
+/// Returns `None` if a weight is negative or not finite, or if all weights are zero.
+pub(crate) fn normalize_initial_distribution(
+    cells: Vec<((isize, isize), f64)>,
+) -> Option<Vec<((isize, isize), f64)>> {
+    if cells
+        .iter()
+        .any(|&(_, weight)| !weight.is_finite() || weight < 0.0)
+    {
+        return None;
+    }
+
+    let mut merged: Vec<((isize, isize), f64)> = Vec::new();
+
+    for (cell, weight) in cells {
+        match merged.iter_mut().find(|(c, _)| *c == cell) {
+            Some((_, w)) => *w += weight,
+            None => merged.push((cell, weight)),
+        }
+    }
+
+    let total: f64 = merged.iter().map(|(_, weight)| weight).sum();
+
+    if total <= 0.0 {
+        return None;
+    }
+
+    Some(
+        merged
+            .into_iter()
+            .filter(|&(_, weight)| weight > 0.0)
+            .map(|(cell, weight)| (cell, weight / total))
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use crate::dataset::point::XYPoint;
     use crate::dp::builder::{DynamicProgramBuilder, DynamicProgramBuilderError};
-    use crate::dp::DynamicProgramType;
+    use crate::dp::{DynamicProgramType, DynamicPrograms};
+    use crate::kernel;
     use crate::kernel::correlated_rw::CorrelatedRwGenerator;
     use crate::kernel::simple_rw::SimpleRwGenerator;
     use crate::kernel::Kernel;
@@ -322,34 +1417,117 @@ mod tests {
         assert!(matches!(dp, Err(DynamicProgramBuilderError::NoTypeSet)));
     }
 
-    // #[test]
-    // fn test_wrong_size_of_field_probabilities() {
-    //     let fps = vec![vec![1.0; 21]; 12];
-    //
-    //     let dp = DynamicProgramBuilder::new()
-    //         .simple()
-    //         .time_limit(10)
-    //         .field_probabilities(fps)
-    //         .build();
-    //
-    //     assert!(matches!(
-    //         dp,
-    //         Err(DynamicProgramBuilderError::WrongSizeOfFieldProbabilities)
-    //     ));
-    //
-    //     let fps = vec![vec![1.0; 8]; 21];
-    //
-    //     let dp = DynamicProgramBuilder::new()
-    //         .simple()
-    //         .time_limit(10)
-    //         .field_probabilities(fps)
-    //         .build();
-    //
-    //     assert!(matches!(
-    //         dp,
-    //         Err(DynamicProgramBuilderError::WrongSizeOfFieldProbabilities)
-    //     ));
-    // }
+    #[test]
+    fn test_field_probabilities_counting() {
+        let builder = |fps| {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+                .field_probabilities(fps)
+                .counting()
+        };
+        let mut fps = vec![vec![1.0; 21]; 21];
+
+        fps[12][10] = 0.0;
+
+        assert!(builder(fps.clone()).build().is_ok());
+
+        fps[8][10] = 0.5;
+
+        assert!(matches!(
+            builder(fps).build(),
+            Err(DynamicProgramBuilderError::ConflictingStorageOptions)
+        ));
+    }
+
+    #[test]
+    fn test_wrong_size_of_field_probabilities() {
+        let fps = vec![vec![1.0; 21]; 12];
+
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .field_probabilities(fps)
+            .build();
+
+        assert!(matches!(
+            dp,
+            Err(DynamicProgramBuilderError::WrongSizeOfFieldProbabilities)
+        ));
+
+        let fps = vec![vec![1.0; 8]; 21];
+
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .field_probabilities(fps)
+            .build();
+
+        assert!(matches!(
+            dp,
+            Err(DynamicProgramBuilderError::WrongSizeOfFieldProbabilities)
+        ));
+    }
+
+    #[test]
+    fn test_kernel_validation() {
+        let builder = |kernel: Kernel| {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(1)
+                .kernel(kernel)
+        };
+        let mut kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+
+        assert!(builder(kernel.clone()).build().is_ok());
+
+        let empty = |size| Kernel::try_new(size, ("Empty".into(), "empty".into())).unwrap();
+
+        // Kernels blocking all movement are allowed
+        assert!(builder(empty(3)).build().is_ok());
+
+        kernel.set(0, 0, f64::NAN);
+
+        assert!(matches!(
+            builder(kernel.clone()).build(),
+            Err(DynamicProgramBuilderError::InvalidKernelProbabilities)
+        ));
+
+        kernel.set(0, 0, -0.2);
+
+        assert!(matches!(
+            builder(kernel.clone()).build(),
+            Err(DynamicProgramBuilderError::InvalidKernelProbabilities)
+        ));
+
+        kernel.set(0, 0, 0.5);
+
+        assert!(matches!(
+            builder(kernel).build(),
+            Err(DynamicProgramBuilderError::KernelNotNormalized)
+        ));
+
+        let mut large = empty(5);
+        large.set(2, 0, 1.0);
+
+        assert!(matches!(
+            builder(large).build(),
+            Err(DynamicProgramBuilderError::KernelTooLarge)
+        ));
+
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .field_probabilities(vec![vec![1.5; 21]; 21])
+            .build();
+
+        assert!(matches!(
+            dp,
+            Err(DynamicProgramBuilderError::InvalidFieldProbabilities)
+        ));
+    }
 
     #[test]
     fn test_barrier_out_of_range() {
@@ -398,12 +1576,132 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_shape_barriers() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+        let triangle = builder()
+            .add_polygon_barrier(vec![xy!(2, 2), xy!(6, 2), xy!(2, 6)])
+            .barriers;
+
+        assert!(triangle.contains(&xy!(2, 2)));
+        assert!(triangle.contains(&xy!(4, 4)));
+        assert!(triangle.contains(&xy!(3, 3)));
+        assert!(!triangle.contains(&xy!(5, 5)));
+
+        let line = builder()
+            .add_line_barrier(xy!(-5, -5), xy!(5, -5), 1.0)
+            .barriers;
+
+        assert!((-5..=5).all(|x| line.contains(&xy!(x, -5))));
+        assert!(!line.contains(&xy!(0, -4)));
+        assert!(!line.contains(&xy!(6, -5)));
+
+        let circle = builder().add_circle_barrier(xy!(-5, 5), 2.0).barriers;
+
+        assert!(circle.contains(&xy!(-5, 7)));
+        assert!(circle.contains(&xy!(-4, 6)));
+        assert!(!circle.contains(&xy!(-3, 7)));
+
+        assert!(matches!(
+            builder().add_circle_barrier(xy!(9, 0), 2.0).build(),
+            Err(DynamicProgramBuilderError::BarrierOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn test_field_types() {
+        let builder = |field_types| {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernels_by_type(vec![
+                    (
+                        0,
+                        Kernel::from_generator(SimpleRwGenerator::default()).unwrap(),
+                    ),
+                    (5, kernel!(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0)),
+                ])
+                .field_types(field_types)
+                .build()
+        };
+
+        let mut field_types = vec![vec![0; 21]; 21];
+
+        field_types[11][10] = 5;
+
+        let mut dp = builder(field_types.clone()).unwrap();
+
+        dp.compute().unwrap();
+
+        let dp = dp.try_unwrap().unwrap();
+
+        assert_eq!(dp.at(1, 0, 1), 0.0);
+        assert!((dp.at(-1, 0, 1) - 0.2).abs() < 1e-12);
+
+        assert!(matches!(
+            builder(vec![vec![0; 21]; 20]),
+            Err(DynamicProgramBuilderError::WrongSizeOfFieldTypes)
+        ));
+
+        field_types[0][0] = 3;
+
+        assert!(matches!(
+            builder(field_types),
+            Err(DynamicProgramBuilderError::UnknownFieldType(3))
+        ));
+    }
+
+    #[test]
+    fn test_diffusivity() {
+        let builder = |diffusivity| {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+                .diffusivity(diffusivity)
+                .build()
+        };
+
+        let mut diffusivity = vec![vec![1.0; 21]; 21];
+
+        diffusivity[10][10] = 0.5;
+
+        let mut dp = builder(diffusivity.clone()).unwrap();
+        let mut parallel = builder(diffusivity.clone()).unwrap();
+
+        let stats = dp.compute().unwrap();
+        parallel.compute_parallel().unwrap();
+
+        let dp = dp.try_unwrap().unwrap();
+
+        assert!((dp.at(0, 0, 1) - 0.6).abs() < 1e-12);
+        assert!((dp.at(1, 0, 1) - 0.1).abs() < 1e-12);
+        assert!(stats.mass_lost.abs() < 1e-12);
+        assert_eq!(dp, parallel.try_unwrap().unwrap());
+
+        diffusivity[0][0] = 1.5;
+
+        assert!(matches!(
+            builder(diffusivity),
+            Err(DynamicProgramBuilderError::InvalidDiffusivity)
+        ));
+        assert!(matches!(
+            builder(vec![vec![1.0; 21]; 20]),
+            Err(DynamicProgramBuilderError::WrongSizeOfDiffusivity)
+        ));
+    }
+
     #[test]
     // fn test_multiple_kernels_for_single() {
     //     let dp = DynamicProgramBuilder::new()
     //         .simple()
     //         .time_limit(10)
-    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator).unwrap(); 10])
+    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator::default()).unwrap(); 10])
     //         .build();
     //
     //     assert!(matches!(
@@ -414,8 +1712,8 @@ mod tests {
     //     let dp = DynamicProgramBuilder::new()
     //         .simple()
     //         .time_limit(10)
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
-    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator).unwrap(); 10])
+    //         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator::default()).unwrap(); 10])
     //         .build();
     //
     //     assert!(matches!(
@@ -426,8 +1724,8 @@ mod tests {
     //     let dp = DynamicProgramBuilder::new()
     //         .simple()
     //         .time_limit(10)
-    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator).unwrap(); 10])
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator::default()).unwrap(); 10])
+    //         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
     //         .build();
     //
     //     assert!(matches!(
@@ -441,7 +1739,7 @@ mod tests {
     //     let dp = DynamicProgramBuilder::new()
     //         .multi()
     //         .time_limit(10)
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+    //         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
     //         .build();
     //
     //     assert!(matches!(
@@ -452,8 +1750,8 @@ mod tests {
     //     let dp = DynamicProgramBuilder::new()
     //         .multi()
     //         .time_limit(10)
-    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator).unwrap(); 10])
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator::default()).unwrap(); 10])
+    //         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
     //         .build();
     //
     //     assert!(matches!(
@@ -464,8 +1762,8 @@ mod tests {
     //     let dp = DynamicProgramBuilder::new()
     //         .multi()
     //         .time_limit(10)
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
-    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator).unwrap(); 10])
+    //         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+    //         .kernels(vec![Kernel::from_generator(SimpleRwGenerator::default()).unwrap(); 10])
     //         .build();
     //
     //     assert!(matches!(
@@ -484,12 +1782,58 @@ mod tests {
         // assert!(matches!(dp, Err(DynamicProgramBuilderError::NoKernelsSet)));
     }
 
+    #[test]
+    fn test_overlapping_kernel_schedule() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .kernel_schedule(vec![(0..5, kernel.clone()), (4..8, kernel)])
+            .build();
+
+        assert!(matches!(
+            dp,
+            Err(DynamicProgramBuilderError::OverlappingKernelSchedule)
+        ));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let dp = builder()
+            .bounding_box(xy!(-10, -2), xy!(5, 2))
+            .add_single_barrier(xy!(5, 0))
+            .build()
+            .unwrap();
+
+        assert_eq!(dp.try_unwrap().unwrap().bounding_box(), (-10, -2, 5, 2));
+        assert!(matches!(
+            builder().bounding_box(xy!(1, -2), xy!(5, 2)).build(),
+            Err(DynamicProgramBuilderError::InvalidBoundingBox)
+        ));
+        assert!(matches!(
+            builder()
+                .bounding_box(xy!(-10, -2), xy!(5, 2))
+                .add_single_barrier(xy!(0, 3))
+                .build(),
+            Err(DynamicProgramBuilderError::BarrierOutOfRange)
+        ));
+    }
+
     // #[test]
     // fn test_correct() {
     //     let dp = DynamicProgramBuilder::new()
     //         .with_type(DynamicProgramType::Simple)
     //         .time_limit(10)
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+    //         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
     //         .field_probabilities(vec![vec![1.0; 21]; 21])
     //         .add_rect_barrier(xy!(5, -5), xy!(5, 5))
     //         .build();
@@ -0,0 +1,364 @@
+//! Provides checkpoints of partially computed dynamic programs.
+//!
+//! Using [`checkpoint()`](crate::dp::builder::DynamicProgramBuilder::checkpoint), all completed
+//! time slices are periodically written to a file while computing. If the computation is
+//! interrupted, e.g. by a crash or a restart, computing a dynamic program with the same
+//! configuration restores the table from the file and continues after the last checkpointed time
+//! step. The file is removed once the computation has finished.
+//!
+//! The file starts with a fingerprint of the configuration of the dynamic program, i.e. its
+//! [`Header`] without the creation time and the computation duration, so that a checkpoint is
+//! never restored into a differently configured dynamic program. Each checkpoint only appends the
+//! time slices computed since the previous one as a separate record, which consists of its length
+//! as a little-endian `u64` followed by the zstd-compressed time steps and values. A record that
+//! was only partially written because of an interruption is discarded when restoring.
+
+use crate::dp::domain::Domain;
+#[cfg(feature = "saving")]
+use crate::dp::format::Header;
+use crate::dp::simple::DynamicProgram;
+use crate::dp::table::Table;
+#[cfg(feature = "saving")]
+use {
+    anyhow::{bail, Context},
+    std::fs::{self, File, OpenOptions},
+    std::io::{BufReader, ErrorKind, Read, Write},
+    std::path::Path,
+};
+
+/// Location and interval of the checkpoints of a dynamic program.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Checkpoint {
+    pub(crate) path: String,
+    pub(crate) interval: usize,
+    /// Fingerprint of the configuration of the dynamic program, set when restoring.
+    pub(crate) fingerprint: Vec<u8>,
+    /// First time step that is not contained in the checkpoint file yet.
+    pub(crate) next: usize,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(path: String, interval: usize) -> Self {
+        Self {
+            path,
+            interval,
+            fingerprint: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Returns whether a checkpoint should be written after time step `t`.
+    pub(crate) fn is_due(&self, t: usize) -> bool {
+        t > 0 && t % self.interval == 0
+    }
+}
+
+#[cfg(feature = "saving")]
+impl Checkpoint {
+    /// Returns the fingerprint of the configuration of `dp` that is stored in the checkpoint.
+    pub(crate) fn fingerprint(dp: &DynamicProgram) -> anyhow::Result<Vec<u8>> {
+        let mut header = Header::new(dp);
+
+        header.created = 0;
+        header.model_card.compute_duration = None;
+
+        Ok(serde_json::to_vec(&header)?)
+    }
+
+    /// Appends the time slices of `table` computed since the last checkpoint up to time step `t`
+    /// to the checkpoint file, which is created if it is the first checkpoint.
+    pub(crate) fn write(&mut self, table: &Table, t: usize) -> anyhow::Result<()> {
+        let mut record = Vec::new();
+
+        record.extend_from_slice(&(self.next as u64).to_le_bytes());
+        record.extend_from_slice(&(t as u64).to_le_bytes());
+
+        for s in self.next..=t {
+            let slice = table
+                .slice(s)
+                .context("time slice is not available for checkpointing")?;
+
+            for column in slice.iter() {
+                for val in column {
+                    record.extend_from_slice(&val.to_le_bytes());
+                }
+            }
+        }
+
+        let record = zstd::encode_all(record.as_slice(), 3)?;
+        let mut file = if self.next == 0 {
+            let mut file = File::create(&self.path)?;
+
+            file.write_all(&(self.fingerprint.len() as u64).to_le_bytes())?;
+            file.write_all(&self.fingerprint)?;
+
+            file
+        } else {
+            OpenOptions::new().append(true).open(&self.path)?
+        };
+
+        file.write_all(&(record.len() as u64).to_le_bytes())?;
+        file.write_all(&record)?;
+        file.sync_data()?;
+
+        self.next = t + 1;
+
+        Ok(())
+    }
+
+    /// Restores the time slices stored in the checkpoint file into `table`. Subsequent checkpoints
+    /// are written for the dynamic program with the given `fingerprint`.
+    ///
+    /// Returns the last time step contained in the checkpoint or `None` if no checkpoint exists.
+    /// Returns an error if the checkpoint was created for a dynamic program with a different
+    /// fingerprint.
+    pub(crate) fn restore(
+        &mut self,
+        table: &mut Table,
+        fingerprint: Vec<u8>,
+        time_limit: usize,
+        domain: Domain,
+    ) -> anyhow::Result<Option<usize>> {
+        self.fingerprint = fingerprint;
+        self.next = 0;
+
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut reader = BufReader::new(file);
+
+        // The checkpoint was interrupted while writing the fingerprint, so it contains no slices
+        let Some(stored) = read_record(&mut reader)? else {
+            return Ok(None);
+        };
+
+        if stored != self.fingerprint {
+            bail!("checkpoint was created for a differently configured dynamic program");
+        }
+
+        let values = domain.width() * domain.height();
+        let mut buf = [0u8; 8];
+        let mut last = None;
+        let mut valid = 8 + stored.len() as u64;
+
+        // Records are only complete up to the first one that was interrupted while writing
+        while let Some(compressed) = read_record(&mut reader)? {
+            let record = zstd::decode_all(compressed.as_slice())
+                .context("checkpoint record is corrupted")?;
+            let mut record = record.as_slice();
+
+            record.read_exact(&mut buf)?;
+            let first = u64::from_le_bytes(buf) as usize;
+            record.read_exact(&mut buf)?;
+            let t = u64::from_le_bytes(buf) as usize;
+
+            if first != last.map_or(0, |last| last + 1) || t < first || t > time_limit {
+                bail!("checkpoint contains invalid time steps");
+            }
+
+            if record.len() != (t - first + 1) * values * 8 {
+                bail!("checkpoint record does not match the domain");
+            }
+
+            for s in first..=t {
+                for x in 0..domain.width() {
+                    for y in 0..domain.height() {
+                        record.read_exact(&mut buf)?;
+                        table.set(x, y, s, f64::from_le_bytes(buf));
+                    }
+                }
+
+                table.finish_slice(s);
+            }
+
+            last = Some(t);
+            valid += 8 + compressed.len() as u64;
+        }
+
+        // Discard an interrupted record, so that the next checkpoint is appended after the last
+        // complete one
+        let file = OpenOptions::new().write(true).open(&self.path)?;
+
+        if file.metadata()?.len() > valid {
+            file.set_len(valid)?;
+        }
+
+        self.next = last.map_or(0, |last| last + 1);
+
+        Ok(last)
+    }
+
+    /// Removes the checkpoint file, if it exists.
+    pub(crate) fn remove(&mut self) -> anyhow::Result<()> {
+        if Path::new(&self.path).exists() {
+            fs::remove_file(&self.path)?;
+        }
+
+        self.next = 0;
+
+        Ok(())
+    }
+}
+
+/// Reads the next record, i.e. its length as a little-endian `u64` followed by its data, from
+/// `reader`.
+///
+/// Returns `None` if `reader` ends before the record is complete.
+#[cfg(feature = "saving")]
+fn read_record<R: Read>(reader: &mut R) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut length = [0u8; 8];
+
+    if let Err(e) = reader.read_exact(&mut length) {
+        return match e.kind() {
+            ErrorKind::UnexpectedEof => Ok(None),
+            _ => Err(e.into()),
+        };
+    }
+
+    let mut record = Vec::new();
+    let length = u64::from_le_bytes(length);
+
+    reader.take(length).read_to_end(&mut record)?;
+
+    Ok((record.len() as u64 == length).then_some(record))
+}
+
+#[cfg(not(feature = "saving"))]
+impl Checkpoint {
+    pub(crate) fn fingerprint(_dp: &DynamicProgram) -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!("checkpoints require the `saving` feature")
+    }
+
+    pub(crate) fn write(&mut self, _table: &Table, _t: usize) -> anyhow::Result<()> {
+        anyhow::bail!("checkpoints require the `saving` feature")
+    }
+
+    pub(crate) fn restore(
+        &mut self,
+        _table: &mut Table,
+        _fingerprint: Vec<u8>,
+        _time_limit: usize,
+        _domain: Domain,
+    ) -> anyhow::Result<Option<usize>> {
+        anyhow::bail!("checkpoints require the `saving` feature")
+    }
+
+    pub(crate) fn remove(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "saving")]
+mod tests {
+    use crate::dp::checkpoint::Checkpoint;
+    use crate::dp::domain::Domain;
+    use crate::dp::table::Table;
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let path = std::env::temp_dir().join("randomwalks_test_checkpoint_roundtrip.zst");
+        let mut checkpoint = Checkpoint::new(path.to_str().unwrap().into(), 2);
+        let domain = Domain::square(3);
+        let mut table = Table::full(3, domain);
+
+        for t in 0..=3 {
+            table.set(3, 3, t, t as f64 + 1.0);
+            table.finish_slice(t);
+        }
+
+        checkpoint.fingerprint = b"config".to_vec();
+        checkpoint.write(&table, 2).unwrap();
+
+        let size = std::fs::metadata(&path).unwrap().len();
+
+        // Only the new time slice is appended
+        checkpoint.write(&table, 3).unwrap();
+
+        let appended = std::fs::metadata(&path).unwrap().len() - size;
+
+        assert!(appended > 0 && appended < size);
+
+        let mut restored = Table::full(3, domain);
+
+        assert_eq!(
+            checkpoint
+                .restore(&mut restored, b"config".to_vec(), 3, domain)
+                .unwrap(),
+            Some(3)
+        );
+        assert_eq!(restored.get(3, 3, 2), Some(3.0));
+        assert_eq!(restored.get(3, 3, 3), Some(4.0));
+        assert!(checkpoint
+            .restore(&mut Table::full(3, domain), b"other".to_vec(), 3, domain)
+            .is_err());
+
+        let corridor = Domain::new(-3, -1, 3, 1).unwrap();
+
+        assert!(checkpoint
+            .restore(
+                &mut Table::full(3, corridor),
+                b"config".to_vec(),
+                3,
+                corridor
+            )
+            .is_err());
+
+        checkpoint.remove().unwrap();
+
+        assert_eq!(
+            checkpoint
+                .restore(&mut restored, b"config".to_vec(), 3, domain)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_interrupted_write() {
+        let path = std::env::temp_dir().join("randomwalks_test_checkpoint_interrupted.zst");
+        let mut checkpoint = Checkpoint::new(path.to_str().unwrap().into(), 1);
+        let domain = Domain::square(2);
+        let mut table = Table::full(2, domain);
+
+        for t in 0..=2 {
+            table.set(2, 2, t, t as f64 + 1.0);
+            table.finish_slice(t);
+        }
+
+        checkpoint.fingerprint = b"config".to_vec();
+        checkpoint.write(&table, 1).unwrap();
+
+        let size = std::fs::metadata(&path).unwrap().len();
+
+        checkpoint.write(&table, 2).unwrap();
+
+        // Simulate an interruption while appending the second record
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(size + 4).unwrap();
+
+        let mut restored = Table::full(2, domain);
+
+        assert_eq!(
+            checkpoint
+                .restore(&mut restored, b"config".to_vec(), 2, domain)
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), size);
+
+        checkpoint.write(&table, 2).unwrap();
+
+        assert_eq!(
+            checkpoint
+                .restore(&mut restored, b"config".to_vec(), 2, domain)
+                .unwrap(),
+            Some(2)
+        );
+        assert_eq!(restored.get(2, 2, 2), Some(3.0));
+
+        checkpoint.remove().unwrap();
+    }
+}
@@ -0,0 +1,242 @@
+//! Provides comparisons between the probability distributions of two dynamic programs.
+//!
+//! A [`DpComparison`] is created using [`compare()`] and contains the Kullback-Leibler
+//! divergence, the L1 distance and the maximum absolute difference of both dynamic programs for
+//! each time step. This allows quantifying the impact of changed kernel parameters, or checking
+//! that two ways of computing a dynamic program produce the same result.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::compare::compare;
+//! # use randomwalks_lib::dp::{DynamicProgramPool, DynamicPrograms};
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let builder = || {
+//!     DynamicProgramBuilder::new()
+//!         .simple()
+//!         .time_limit(10)
+//!         .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!         .build()
+//!         .unwrap()
+//! };
+//!
+//! let mut dp = builder();
+//! let mut parallel = builder();
+//!
+//! dp.compute().unwrap();
+//! parallel.compute_parallel().unwrap();
+//!
+//! let DynamicProgramPool::Single(dp) = dp else {
+//!     unreachable!();
+//! };
+//! let DynamicProgramPool::Single(parallel) = parallel else {
+//!     unreachable!();
+//! };
+//! let comparison = compare(&dp, &parallel).unwrap();
+//!
+//! println!("{}", comparison.to_markdown());
+//! ```
+
+use crate::dp::simple::DynamicProgram;
+use anyhow::bail;
+use pyo3::{pyclass, pymethods};
+use serde::{Deserialize, Serialize};
+
+/// Differences between the probability distributions of two dynamic programs, per time step.
+///
+/// Created using [`compare()`]. All vectors are indexed by time step.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DpComparison {
+    /// Kullback-Leibler divergence of the second dynamic program from the first one. This is
+    /// infinite if the second dynamic program assigns a probability of zero to a point that is
+    /// reachable in the first one.
+    pub kl_divergence: Vec<f64>,
+    /// Sum of the absolute differences of all probabilities.
+    pub l1_distance: Vec<f64>,
+    /// Largest absolute difference of any probability.
+    pub max_abs_difference: Vec<f64>,
+}
+
+#[pymethods]
+impl DpComparison {
+    #[staticmethod]
+    #[pyo3(name = "compare")]
+    pub fn py_compare(first: DynamicProgram, second: DynamicProgram) -> anyhow::Result<Self> {
+        compare(&first, &second)
+    }
+
+    /// Returns the number of compared time steps.
+    pub fn time_steps(&self) -> usize {
+        self.kl_divergence.len()
+    }
+
+    /// Returns whether no probability differs by more than `tolerance` in any time step.
+    pub fn is_close(&self, tolerance: f64) -> bool {
+        self.max_abs_difference
+            .iter()
+            .all(|&diff| diff <= tolerance)
+    }
+
+    /// Renders a summary of the comparison as a Markdown table, containing the largest value of
+    /// each metric together with the time step it occurs in, and the mean over all time steps.
+    pub fn to_markdown(&self) -> String {
+        let mut res = String::new();
+
+        res += &format!("Comparison of {} time steps\n\n", self.time_steps());
+        res += "| Metric | Max | At time step | Mean |\n";
+        res += "|---|---|---|---|\n";
+
+        for (name, values) in [
+            ("KL divergence", &self.kl_divergence),
+            ("L1 distance", &self.l1_distance),
+            ("Max. absolute difference", &self.max_abs_difference),
+        ] {
+            let (t, max) = argmax(values);
+
+            res += &format!(
+                "| {} | {:.3e} | {} | {:.3e} |\n",
+                name,
+                max,
+                t,
+                values.iter().sum::<f64>() / values.len() as f64
+            );
+        }
+
+        res
+    }
+
+    /// Renders the comparison as JSON.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "DpComparison(time_steps={}, max_kl_divergence={:.3e}, max_abs_difference={:.3e})",
+            self.time_steps(),
+            argmax(&self.kl_divergence).1,
+            argmax(&self.max_abs_difference).1
+        )
+    }
+}
+
+/// Compares the probability distributions of two computed dynamic programs in all time steps
+/// up to the smaller of both time limits.
+///
+/// Probabilities are compared independent of the computation mode, so that e.g. a dynamic
+/// program computed in log space can be compared with one computed normally.
+///
+/// # Errors
+///
+/// Returns an error if the tables of both dynamic programs cover different regions, or if a
+/// compared time step is not kept in memory.
+pub fn compare(first: &DynamicProgram, second: &DynamicProgram) -> anyhow::Result<DpComparison> {
+    if first.bounding_box() != second.bounding_box() {
+        bail!("dynamic programs must cover the same region");
+    }
+
+    let mut comparison = DpComparison {
+        kl_divergence: Vec::new(),
+        l1_distance: Vec::new(),
+        max_abs_difference: Vec::new(),
+    };
+
+    for t in 0..=first.time_limit.min(second.time_limit) {
+        let (p, q) = (first.slice(t)?, second.slice(t)?);
+        let (mut kl, mut l1, mut max) = (0.0, 0.0, 0.0f64);
+
+        for (&p, &q) in p.iter().flatten().zip(q.iter().flatten()) {
+            if p > 0.0 {
+                kl += p * (p / q).ln();
+            }
+
+            l1 += (p - q).abs();
+            max = max.max((p - q).abs());
+        }
+
+        comparison.kl_divergence.push(kl);
+        comparison.l1_distance.push(l1);
+        comparison.max_abs_difference.push(max);
+    }
+
+    Ok(comparison)
+}
+
+/// Returns the index and value of the largest element of `values`.
+fn argmax(values: &[f64]) -> (usize, f64) {
+    values.iter().copied().enumerate().fold(
+        (0, 0.0),
+        |acc, (i, val)| if val > acc.1 { (i, val) } else { acc },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::compare::compare;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::biased_rw::BiasedRwGenerator;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::{Direction, Kernel};
+    use crate::xy;
+
+    #[test]
+    fn test_compare() {
+        let builder = |kernel| {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(kernel)
+                .build()
+                .unwrap()
+        };
+        let simple = || Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let biased = Kernel::from_generator(BiasedRwGenerator {
+            probability: 0.5,
+            direction: Direction::North,
+        })
+        .unwrap();
+
+        let mut dp = builder(simple());
+        let mut log_dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(simple())
+            .log_space()
+            .build()
+            .unwrap();
+        let mut biased_dp = builder(biased);
+
+        dp.compute().unwrap();
+        log_dp.compute().unwrap();
+        biased_dp.compute().unwrap();
+
+        let dp = dp.try_unwrap().unwrap();
+        let same = compare(dp, log_dp.try_unwrap().unwrap()).unwrap();
+
+        assert_eq!(same.time_steps(), 11);
+        assert!(same.is_close(1e-12));
+        assert!(same.kl_divergence.iter().all(|kl| kl.abs() < 1e-9));
+
+        let different = compare(dp, biased_dp.try_unwrap().unwrap()).unwrap();
+
+        assert_eq!(different.kl_divergence[0], 0.0);
+        assert!(different.kl_divergence[1] > 0.0);
+        assert!(different.l1_distance[5] > different.max_abs_difference[5]);
+        assert!(!different.is_close(1e-3));
+        assert!(different.to_markdown().contains("| KL divergence |"));
+
+        let bounded = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(simple())
+            .bounding_box(xy!(-5, -5), xy!(5, 5))
+            .build()
+            .unwrap();
+
+        assert!(compare(dp, bounded.try_unwrap().unwrap()).is_err());
+    }
+}
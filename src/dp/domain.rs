@@ -0,0 +1,89 @@
+//! Provides the domain of a dynamic program, i.e. the region of the plane covered by its table.
+//!
+//! By default, the table is a square of side length `2 * time_limit + 1` centered at the origin.
+//! Using [`bounding_box()`](crate::dp::builder::DynamicProgramBuilder::bounding_box), any
+//! rectangle containing the origin can be used instead, so that e.g. narrow corridors do not
+//! waste memory in the unused dimension.
+
+/// A rectangle containing the origin, given by its smallest and largest coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Domain {
+    pub(crate) x_min: isize,
+    pub(crate) x_max: isize,
+    pub(crate) y_min: isize,
+    pub(crate) y_max: isize,
+}
+
+impl Domain {
+    /// Creates the default domain, a square of side length `2 * time_limit + 1` centered at the
+    /// origin.
+    pub(crate) fn square(time_limit: usize) -> Self {
+        let limit = time_limit as isize;
+
+        Self {
+            x_min: -limit,
+            x_max: limit,
+            y_min: -limit,
+            y_max: limit,
+        }
+    }
+
+    /// Creates a domain spanning from `(x_min, y_min)` to `(x_max, y_max)`.
+    ///
+    /// Returns `None` if the rectangle does not contain the origin.
+    pub(crate) fn new(x_min: isize, y_min: isize, x_max: isize, y_max: isize) -> Option<Self> {
+        if x_min > 0 || y_min > 0 || x_max < 0 || y_max < 0 {
+            return None;
+        }
+
+        Some(Self {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+        })
+    }
+
+    /// Returns the number of columns of the table, i.e. its extent in x direction.
+    pub(crate) fn width(&self) -> usize {
+        (self.x_max - self.x_min + 1) as usize
+    }
+
+    /// Returns the number of rows of the table, i.e. its extent in y direction.
+    pub(crate) fn height(&self) -> usize {
+        (self.y_max - self.y_min + 1) as usize
+    }
+
+    pub(crate) fn contains(&self, x: isize, y: isize) -> bool {
+        x >= self.x_min && x <= self.x_max && y >= self.y_min && y <= self.y_max
+    }
+
+    /// Maps coordinates to table indices.
+    pub(crate) fn index(&self, x: isize, y: isize) -> (usize, usize) {
+        ((x - self.x_min) as usize, (y - self.y_min) as usize)
+    }
+
+    /// Returns whether the domain is the default square for `time_limit`.
+    pub(crate) fn is_square(&self, time_limit: usize) -> bool {
+        *self == Self::square(time_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::domain::Domain;
+
+    #[test]
+    fn test_domain() {
+        let domain = Domain::new(-10, -2, 5, 2).unwrap();
+
+        assert_eq!(domain.width(), 16);
+        assert_eq!(domain.height(), 5);
+        assert_eq!(domain.index(0, 0), (10, 2));
+        assert!(domain.contains(5, -2));
+        assert!(!domain.contains(0, 3));
+        assert!(!domain.is_square(2));
+        assert!(Domain::new(1, 0, 5, 5).is_none());
+        assert_eq!(Domain::square(3).width(), 7);
+    }
+}
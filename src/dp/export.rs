@@ -0,0 +1,212 @@
+//! Provides exports of single time steps of dynamic programs to common file formats.
+//!
+//! Using [`export_slice()`](DynamicProgram::export_slice), the probability distribution of a
+//! single time step can be written to a file that can be post-processed with other tools, without
+//! having to read the whole [saved dynamic program](crate::dp::format). The following formats are
+//! supported:
+//!
+//! - [`ExportFormat::Npy`]: A NumPy `.npy` file containing a two-dimensional array of `f64`
+//! values indexed as `[x][y]`, like [`slice_numpy()`](DynamicProgram::slice_numpy). Index `[0][0]`
+//! corresponds to the point `(x_min, y_min)` of the
+//! [`bounding_box()`](DynamicProgram::bounding_box).
+//! - [`ExportFormat::Parquet`]: A Parquet file containing one row per point, with the columns
+//! `x`, `y` and `probability`. Requires the `polars_loading` feature.
+//!
+//! ```no_run
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::export::ExportFormat;
+//! # use randomwalks_lib::dp::{DynamicProgramPool, DynamicPrograms};
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let DynamicProgramPool::Single(dp) = dp else {
+//!     unreachable!();
+//! };
+//!
+//! dp.export_slice(100, ExportFormat::Npy, "t100.npy".into()).unwrap();
+//! ```
+
+use crate::dp::simple::DynamicProgram;
+use crate::workspace;
+#[cfg(feature = "polars_loading")]
+use polars::prelude::{DataFrame, NamedFrom, ParquetWriter, Series};
+use pyo3::pyclass;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+/// Magic bytes every `.npy` file starts with.
+const NPY_MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// A file format time steps of dynamic programs can be exported to.
+#[pyclass]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// NumPy `.npy` file containing a two-dimensional array.
+    Npy,
+
+    /// Parquet file containing one row per point. Requires the `polars_loading` feature.
+    Parquet,
+}
+
+/// Exports the probability distribution in time step `t` of `dp` to `path`.
+pub(crate) fn export_slice(
+    dp: &DynamicProgram,
+    t: usize,
+    format: ExportFormat,
+    path: &str,
+) -> anyhow::Result<()> {
+    let slice = dp.slice(t)?;
+
+    match format {
+        ExportFormat::Npy => write_npy(&slice, &workspace::output_path(path, "slice")?),
+        #[cfg(feature = "polars_loading")]
+        ExportFormat::Parquet => write_parquet(dp, &slice, &workspace::output_path(path, "slice")?),
+        #[cfg(not(feature = "polars_loading"))]
+        ExportFormat::Parquet => {
+            anyhow::bail!("exporting to Parquet requires the `polars_loading` feature")
+        }
+    }
+}
+
+/// Writes `slice` as a two-dimensional array of little-endian `f64` values in C order to a
+/// `.npy` file, using version 1.0 of the format.
+fn write_npy(slice: &[Vec<f64>], path: &str) -> anyhow::Result<()> {
+    let (width, height) = (slice.len(), slice.first().map_or(0, |column| column.len()));
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': False, 'shape': ({}, {}), }}",
+        width, height
+    );
+
+    // The header is padded with spaces and terminated by a newline, so that the data starts at
+    // a multiple of 64 bytes
+    let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+    header += &" ".repeat((64 - unpadded % 64) % 64);
+    header += "\n";
+
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    writer.write_all(NPY_MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+
+    for val in slice.iter().flatten() {
+        writer.write_all(&val.to_le_bytes())?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes `slice` to a Parquet file with one row per point of the table of `dp`.
+#[cfg(feature = "polars_loading")]
+fn write_parquet(dp: &DynamicProgram, slice: &[Vec<f64>], path: &str) -> anyhow::Result<()> {
+    let (x_min, y_min, _, _) = dp.bounding_box();
+    let (mut xs, mut ys, mut probs) = (Vec::new(), Vec::new(), Vec::new());
+
+    for (i, column) in slice.iter().enumerate() {
+        for (j, &prob) in column.iter().enumerate() {
+            xs.push((x_min + i as isize) as i64);
+            ys.push((y_min + j as isize) as i64);
+            probs.push(prob);
+        }
+    }
+
+    let mut df = DataFrame::new(vec![
+        Series::new("x", xs),
+        Series::new("y", ys),
+        Series::new("probability", probs),
+    ])?;
+
+    ParquetWriter::new(File::create(path)?).finish(&mut df)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::export::{ExportFormat, NPY_MAGIC};
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+
+    #[test]
+    fn test_export_slice() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let dp = dp.try_unwrap().unwrap();
+        let path = std::env::temp_dir().join("randomwalks_test_export_slice.npy");
+        let path = path.to_str().unwrap().to_string();
+
+        dp.export_slice(3, ExportFormat::Npy, path.clone()).unwrap();
+
+        let data = std::fs::read(&path).unwrap();
+        let header_len = u16::from_le_bytes([data[8], data[9]]) as usize;
+        let header = std::str::from_utf8(&data[10..10 + header_len]).unwrap();
+        let values = &data[10 + header_len..];
+
+        assert_eq!(&data[..6], NPY_MAGIC);
+        assert_eq!((10 + header_len) % 64, 0);
+        assert!(header.contains("'shape': (11, 11)"));
+        assert_eq!(values.len(), 11 * 11 * 8);
+
+        // Index [6][5] corresponds to the point (1, 0)
+        let at = |i: usize| f64::from_le_bytes(values[8 * i..8 * i + 8].try_into().unwrap());
+
+        assert_eq!(at(6 * 11 + 5), dp.at(1, 0, 3));
+        assert!(dp.export_slice(6, ExportFormat::Npy, path.clone()).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "polars_loading")]
+    #[test]
+    fn test_export_slice_parquet() {
+        use polars::prelude::{ParquetReader, SerReader};
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let path = std::env::temp_dir().join("randomwalks_test_export_slice.parquet");
+        let path = path.to_str().unwrap().to_string();
+
+        dp.try_unwrap()
+            .unwrap()
+            .export_slice(3, ExportFormat::Parquet, path.clone())
+            .unwrap();
+
+        let df = ParquetReader::new(std::fs::File::open(&path).unwrap())
+            .finish()
+            .unwrap();
+
+        assert_eq!(df.height(), 11 * 11);
+        assert_eq!(df.get_column_names(), vec!["x", "y", "probability"]);
+        assert!((df.column("probability").unwrap().sum::<f64>().unwrap() - 1.0).abs() < 1e-12);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
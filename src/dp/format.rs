@@ -0,0 +1,213 @@
+//! Provides the file format used to save dynamic programs.
+//!
+//! Files written by [`save()`](crate::dp::DynamicPrograms::save) and by
+//! [`rolling_storage()`](crate::dp::builder::DynamicProgramBuilder::rolling_storage) are
+//! compressed using zstd. The decompressed data starts with a header, followed by the values of
+//! the table as little-endian `f64` values, ordered by time step, then x and then y coordinate.
+//!
+//! The header consists of
+//!
+//! - the magic bytes `RWDP`,
+//! - the format version as a little-endian `u32`,
+//! - the length of the metadata in bytes as a little-endian `u64` and
+//! - the metadata as JSON, containing a [`ModelCard`], the kernels, the kernel schedule, the field
+//! types, the field probabilities, the diffusivity and the absorbing cells of the dynamic program,
+//! together with the time the file was created at.
+//!
+//! Files streamed to disk by rolling storage compress the header and each time slice as separate
+//! zstd frames, so that single time slices can be decompressed without decompressing all earlier
+//! ones. Since concatenated frames form a valid zstd stream, both kinds of files are read the same
+//! way.
+//!
+//! When loading, the header is validated, so that files which are not dynamic programs, were
+//! written by a newer version of the format or are truncated are rejected with a descriptive
+//! error instead of producing a garbled table.
+
+use crate::dp::model_card::ModelCard;
+use crate::dp::simple::DynamicProgram;
+use crate::kernel::Kernel;
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bytes every saved dynamic program starts with.
+pub(crate) const MAGIC: &[u8; 4] = b"RWDP";
+
+/// Version of the format written by this version of the library.
+///
+/// Version 2 adds the field probabilities and absorbing cells to the metadata. Files of version 1
+/// are loaded without them.
+pub(crate) const FORMAT_VERSION: u32 = 2;
+
+/// Upper bound for the length of the metadata, protecting against allocating huge buffers when
+/// reading corrupted files.
+const MAX_METADATA_LENGTH: u64 = 1 << 30;
+
+/// Metadata stored in the header of a saved dynamic program.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Header {
+    /// Time the file was created at, in seconds since the Unix epoch.
+    pub(crate) created: u64,
+    pub(crate) model_card: ModelCard,
+    /// Kernels used for each field type, indexed by field type.
+    pub(crate) kernels: Vec<Kernel>,
+    pub(crate) kernel_schedule: Vec<(Range<usize>, Kernel)>,
+    /// Type of each field of the table, indexed as `field_types[x][y]`.
+    pub(crate) field_types: Vec<Vec<usize>>,
+    /// Diffusivity of each field of the table, indexed as `diffusivity[x][y]`, if set.
+    #[serde(default)]
+    pub(crate) diffusivity: Option<Vec<Vec<f64>>>,
+    /// Probability of each field of the table, indexed as `field_probabilities[x][y]`, if any
+    /// field has a probability below 1.
+    #[serde(default)]
+    pub(crate) field_probabilities: Option<Vec<Vec<f64>>>,
+    /// Absorbing cells, sorted by their coordinates.
+    #[serde(default)]
+    pub(crate) absorbing: Vec<(isize, isize)>,
+}
+
+impl Header {
+    pub(crate) fn new(dp: &DynamicProgram) -> Self {
+        let mut absorbing: Vec<_> = dp.absorbing.iter().copied().collect();
+        absorbing.sort();
+
+        Self {
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            model_card: dp.model_card(),
+            kernels: dp.kernels.clone(),
+            kernel_schedule: dp.kernel_schedule.iter().cloned().collect(),
+            field_types: dp.field_types.clone(),
+            diffusivity: dp.diffusivity.clone(),
+            field_probabilities: dp.field_probabilities.clone(),
+            absorbing,
+        }
+    }
+
+    pub(crate) fn write<W: Write>(&self, writer: &mut W) -> anyhow::Result<()> {
+        let metadata = serde_json::to_vec(self)?;
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&(metadata.len() as u64).to_le_bytes())?;
+        writer.write_all(&metadata)?;
+
+        Ok(())
+    }
+
+    /// Reads and validates the header at the start of `reader`.
+    pub(crate) fn read<R: Read>(reader: &mut R) -> anyhow::Result<Self> {
+        let mut magic = [0u8; 4];
+
+        reader
+            .read_exact(&mut magic)
+            .context("file is too short to contain a dynamic program")?;
+
+        if &magic != MAGIC {
+            bail!(
+                "file is not a saved dynamic program, or it was saved by a version of this library \
+                without a versioned file format"
+            );
+        }
+
+        let mut version = [0u8; 4];
+        reader
+            .read_exact(&mut version)
+            .context("could not read format version")?;
+        let version = u32::from_le_bytes(version);
+
+        if version == 0 || version > FORMAT_VERSION {
+            bail!(
+                "file was saved using format version {}, but only versions up to {} are supported",
+                version,
+                FORMAT_VERSION
+            );
+        }
+
+        let mut length = [0u8; 8];
+        reader
+            .read_exact(&mut length)
+            .context("could not read metadata length")?;
+        let length = u64::from_le_bytes(length);
+
+        if length > MAX_METADATA_LENGTH {
+            bail!("metadata length of {} bytes is invalid", length);
+        }
+
+        let mut metadata = vec![0u8; length as usize];
+        reader
+            .read_exact(&mut metadata)
+            .context("file is truncated within its metadata")?;
+
+        let header: Header =
+            serde_json::from_slice(&metadata).context("could not parse metadata")?;
+        let size = 2 * header.model_card.time_limit + 1;
+
+        if header.kernels.is_empty() {
+            bail!("metadata does not contain any kernels");
+        }
+
+        if header.field_types.len() != size || header.field_types.iter().any(|c| c.len() != size) {
+            bail!("field types do not match the time limit of the dynamic program");
+        }
+
+        if let Some(field_type) = header
+            .field_types
+            .iter()
+            .flatten()
+            .find(|&&field_type| field_type >= header.kernels.len())
+        {
+            bail!("field type {} has no kernel assigned to it", field_type);
+        }
+
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::format::{Header, FORMAT_VERSION, MAGIC};
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+
+    #[test]
+    fn test_header_roundtrip() {
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(3)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap()
+            .try_unwrap()
+            .unwrap()
+            .clone();
+
+        let mut buf = Vec::new();
+
+        Header::new(&dp).write(&mut buf).unwrap();
+
+        let header = Header::read(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(header.model_card.time_limit, 3);
+        assert_eq!(header.kernels.len(), 1);
+        assert_eq!(header.field_types, dp.field_types);
+
+        assert!(Header::read(&mut &b"garbage data"[..]).is_err());
+        assert!(Header::read(&mut &buf[..20]).is_err());
+
+        let mut newer = MAGIC.to_vec();
+        newer.extend((FORMAT_VERSION + 1).to_le_bytes());
+        newer.extend(buf[8..].iter());
+
+        assert!(Header::read(&mut newer.as_slice())
+            .unwrap_err()
+            .to_string()
+            .contains("format version"));
+    }
+}
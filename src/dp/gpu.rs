@@ -0,0 +1,288 @@
+//! Provides a GPU compute backend for dynamic programs.
+//!
+//! Each time step of a dynamic program is a convolution of the previous time slice with the
+//! kernel of each field. This maps well onto a compute shader which computes all fields of a
+//! time step in parallel. Computations on the GPU are carried out in single precision.
+//!
+//! Requires the `gpu` feature.
+
+use crate::dp::simple::DynamicProgram;
+use crate::dp::table::SliceWriter;
+use crate::kernel::Kernel;
+use anyhow::{bail, Context};
+use std::sync::mpsc::channel;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, Buffer, BufferDescriptor, BufferUsages,
+    CommandEncoderDescriptor, ComputePipelineDescriptor, Device, DeviceDescriptor, Features,
+    Instance, Maintain, MapMode, PowerPreference, RequestAdapterOptions, ShaderModuleDescriptor,
+    ShaderSource,
+};
+
+const WORKGROUP_SIZE: u32 = 8;
+
+/// Computes the time steps from `first` up to the time limit of the dynamic program on the GPU,
+/// passing each completed slice to `writer`.
+///
+/// Returns an error if no suitable GPU is available or if the table does not fit into a single
+/// GPU buffer.
+pub(crate) fn compute(
+    dp: &mut DynamicProgram,
+    first: usize,
+    writer: &mut Option<SliceWriter>,
+) -> anyhow::Result<()> {
+    pollster::block_on(compute_async(dp, first, writer))
+}
+
+async fn compute_async(
+    dp: &mut DynamicProgram,
+    first: usize,
+    writer: &mut Option<SliceWriter>,
+) -> anyhow::Result<()> {
+    let domain = dp.domain;
+    let (width, height) = (domain.width(), domain.height());
+    let slice_bytes = (width * height * std::mem::size_of::<f32>()) as u64;
+
+    let instance = Instance::default();
+    let adapter = instance
+        .request_adapter(&RequestAdapterOptions {
+            power_preference: PowerPreference::HighPerformance,
+            ..Default::default()
+        })
+        .await
+        .context("no suitable GPU adapter found")?;
+    let limits = adapter.limits();
+
+    if slice_bytes > limits.max_storage_buffer_binding_size as u64 {
+        bail!("time limit too large for the buffers of the GPU");
+    }
+
+    let (device, queue) = adapter
+        .request_device(
+            &DeviceDescriptor {
+                label: None,
+                features: Features::empty(),
+                limits,
+            },
+            None,
+        )
+        .await
+        .context("could not create GPU device")?;
+
+    let shader = device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("dynamic program"),
+        source: ShaderSource::Wgsl(include_str!("gpu.wgsl").into()),
+    });
+    let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+        label: Some("dynamic program"),
+        layout: None,
+        module: &shader,
+        entry_point: "main",
+    });
+
+    let kernel_size = dp
+        .kernels
+        .iter()
+        .chain(dp.kernel_schedule.iter().map(|(_, kernel)| kernel))
+        .map(|kernel| kernel.size())
+        .max()
+        .unwrap_or(1);
+
+    let params = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("params"),
+        contents: bytemuck::cast_slice(&[width as u32, height as u32, kernel_size as u32, 0]),
+        usage: BufferUsages::UNIFORM,
+    });
+    let field_types: Vec<u32> = dp
+        .field_types
+        .iter()
+        .flat_map(|column| column.iter().map(|&field_type| field_type as u32))
+        .collect();
+    let field_types = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("field types"),
+        contents: bytemuck::cast_slice(&field_types),
+        usage: BufferUsages::STORAGE,
+    });
+    let field_probabilities: Vec<f32> = match &dp.field_probabilities {
+        Some(field_probabilities) => field_probabilities
+            .iter()
+            .flat_map(|column| column.iter().map(|&p| p as f32))
+            .collect(),
+        None => vec![1.0; width * height],
+    };
+    let field_probabilities = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("field probabilities"),
+        contents: bytemuck::cast_slice(&field_probabilities),
+        usage: BufferUsages::STORAGE,
+    });
+    let kernels = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("kernels"),
+        contents: bytemuck::cast_slice(&flatten_kernels(&dp.kernels_at(1), kernel_size)),
+        usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+    });
+
+    // Both slice buffers start with the last computed slice, so that computing time step `first`
+    // reads from it regardless of which buffer is used
+    let initial: Vec<f32> = dp
+        .table
+        .slice(first - 1)
+        .unwrap()
+        .iter()
+        .flat_map(|column| column.iter().map(|&val| val as f32))
+        .collect();
+
+    let slices: Vec<Buffer> = (0..2)
+        .map(|i| {
+            device.create_buffer_init(&BufferInitDescriptor {
+                label: Some(&format!("slice {i}")),
+                contents: bytemuck::cast_slice(&initial),
+                usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            })
+        })
+        .collect();
+    let staging = device.create_buffer(&BufferDescriptor {
+        label: Some("staging"),
+        size: slice_bytes,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    // Bind group `i` reads from slice `i` and writes to the other slice
+    let bind_groups: Vec<BindGroup> = (0..2)
+        .map(|i| {
+            device.create_bind_group(&BindGroupDescriptor {
+                label: None,
+                layout: &pipeline.get_bind_group_layout(0),
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: params.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: slices[i].as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: slices[1 - i].as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: field_types.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: kernels.as_entire_binding(),
+                    },
+                    BindGroupEntry {
+                        binding: 5,
+                        resource: field_probabilities.as_entire_binding(),
+                    },
+                ],
+            })
+        })
+        .collect();
+
+    let workgroups_x = (width as u32 + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+    let workgroups_y = (height as u32 + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+    let mut kernels_used = dp.kernels_at(1);
+
+    for t in first..=dp.time_limit {
+        let kernels_t = dp.kernels_at(t);
+
+        if kernels_t != kernels_used {
+            queue.write_buffer(
+                &kernels,
+                0,
+                bytemuck::cast_slice(&flatten_kernels(&kernels_t, kernel_size)),
+            );
+            kernels_used = kernels_t;
+        }
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_groups[(t - 1) % 2], &[]);
+            pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
+        }
+
+        encoder.copy_buffer_to_buffer(&slices[t % 2], 0, &staging, 0, slice_bytes);
+        queue.submit(Some(encoder.finish()));
+
+        let values = read_buffer(&device, &staging)?;
+
+        for x in domain.x_min..=domain.x_max {
+            for y in domain.y_min..=domain.y_max {
+                let (xi, yi) = domain.index(x, y);
+
+                dp.set(x, y, t, values[xi * height + yi] as f64);
+            }
+        }
+
+        dp.finish_slice(t, writer)?;
+    }
+
+    Ok(())
+}
+
+/// Pads all kernels to `kernel_size` and stores them one after another.
+fn flatten_kernels(kernels: &[Kernel], kernel_size: usize) -> Vec<f32> {
+    let radius = (kernel_size / 2) as isize;
+    let mut flattened = Vec::with_capacity(kernels.len() * kernel_size * kernel_size);
+
+    for kernel in kernels {
+        let kernel_radius = (kernel.size() / 2) as isize;
+
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                if x.abs() <= kernel_radius && y.abs() <= kernel_radius {
+                    flattened.push(kernel.at(x, y) as f32);
+                } else {
+                    flattened.push(0.0);
+                }
+            }
+        }
+    }
+
+    flattened
+}
+
+/// Reads the contents of a mappable buffer.
+fn read_buffer(device: &Device, buffer: &Buffer) -> anyhow::Result<Vec<f32>> {
+    let (tx, rx) = channel();
+    let slice = buffer.slice(..);
+
+    slice.map_async(MapMode::Read, move |res| {
+        let _ = tx.send(res);
+    });
+    device.poll(Maintain::Wait);
+    rx.recv()?.context("could not read GPU buffer")?;
+
+    let view = slice.get_mapped_range();
+    let values = bytemuck::cast_slice::<u8, f32>(&view).to_vec();
+
+    drop(view);
+    buffer.unmap();
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::gpu::flatten_kernels;
+    use crate::kernel;
+    use crate::kernel::Kernel;
+
+    #[test]
+    fn test_flatten_kernels() {
+        let small = kernel!(0.0, 0.25, 0.0, 0.25, 0.0, 0.25, 0.0, 0.25, 0.0);
+        let flattened = flatten_kernels(&[small.clone()], 5);
+
+        assert_eq!(flattened.len(), 25);
+        assert_eq!(flattened.iter().sum::<f32>(), 1.0);
+        assert_eq!(flattened[2 * 5 + 1], small.at(0, -1) as f32);
+        assert_eq!(flattened[0], 0.0);
+    }
+}
@@ -0,0 +1,49 @@
+// Computes a single time step of a dynamic program.
+//
+// All buffers are indexed as `buffer[x * height + y]`. Kernels are padded to a common size and
+// stored one after another, where the value for the offset `(dx, dy)` is stored at index
+// `(dx + radius) * kernel_size + (dy + radius)`.
+
+struct Params {
+    width: u32,
+    height: u32,
+    kernel_size: u32,
+    _padding: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> prev: array<f32>;
+@group(0) @binding(2) var<storage, read_write> next: array<f32>;
+@group(0) @binding(3) var<storage, read> field_types: array<u32>;
+@group(0) @binding(4) var<storage, read> kernels: array<f32>;
+@group(0) @binding(5) var<storage, read> field_probabilities: array<f32>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let width = i32(params.width);
+    let height = i32(params.height);
+    let x = i32(id.x);
+    let y = i32(id.y);
+
+    if (x >= width || y >= height) {
+        return;
+    }
+
+    let kernel_size = i32(params.kernel_size);
+    let radius = kernel_size / 2;
+    let kernel_offset = i32(field_types[id.x * params.height + id.y]) * kernel_size * kernel_size;
+    var sum = 0.0;
+
+    for (var i = max(x - radius, 0); i <= min(x + radius, width - 1); i++) {
+        for (var j = max(y - radius, 0); j <= min(y + radius, height - 1); j++) {
+            // Kernel coordinates are inverted offset, i.e. -(i - x) and -(j - y)
+            let kernel_x = x - i + radius;
+            let kernel_y = y - j + radius;
+
+            sum += prev[i * height + j] * kernels[kernel_offset + kernel_x * kernel_size + kernel_y];
+        }
+    }
+
+    // Entering a field is scaled by its probability
+    next[id.x * params.height + id.y] = sum * field_probabilities[id.x * params.height + id.y];
+}
@@ -0,0 +1,325 @@
+//! Provides lazy access to dynamic programs saved to disk.
+//!
+//! Loading a saved dynamic program using
+//! [`DynamicProgram::load()`](crate::dp::simple::DynamicProgram::load) decompresses the whole
+//! table up front. A [`LazyDynamicProgram`] only reads the header when it is opened and decodes
+//! time slices on first access, so that e.g. walks with few time steps only require the first
+//! slices of the table to be read.
+//!
+//! Files written using [`save()`](crate::dp::DynamicPrograms::save) are compressed as a single
+//! stream, so accessing a time step decodes all earlier time steps as well, and all decoded
+//! slices are kept in memory. Files streamed to disk using
+//! [`rolling_storage()`](crate::dp::builder::DynamicProgramBuilder::rolling_storage) compress
+//! each time slice separately instead. Their slices are decoded independently of each other and
+//! only the two most recently accessed slices are kept in memory, as walkers only access two
+//! consecutive time steps at a time. Together, this allows computing and using dynamic programs
+//! whose full table does not fit into memory.
+//!
+//! A [`LazyDynamicProgram`] implements [`DpAccess`], so walks can be generated from it using
+//! [`generate_path_from()`](crate::walker::Walker::generate_path_from). Requires the `saving`
+//! feature.
+//!
+//! ```no_run
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::lazy::LazyDynamicProgram;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(2000)
+//!     .kernel(kernel.clone())
+//!     .rolling_storage(Some("dp.zst".into()))
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let dp = LazyDynamicProgram::open("dp.zst").unwrap();
+//! let walker = StandardWalker::new(kernel);
+//! let walk = walker.generate_path_from(&dp, 10, 10, 50).unwrap();
+//! ```
+
+use crate::dp::domain::Domain;
+use crate::dp::format::Header;
+use crate::dp::model_card::ModelCard;
+use crate::dp::table::{decompress_slice, Slice};
+use crate::dp::DpAccess;
+use anyhow::Context;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::ops::Range;
+use std::sync::Mutex;
+use zstd::zstd_safe::find_frame_compressed_size;
+use zstd::Decoder;
+
+/// A saved dynamic program whose time slices are read from disk on first access.
+pub struct LazyDynamicProgram {
+    header: Header,
+    state: Mutex<LazyState>,
+}
+
+enum LazyState {
+    /// The file is compressed as a single stream, which is decoded sequentially.
+    Stream {
+        decoder: Decoder<'static, BufReader<File>>,
+        /// All time slices decoded so far, indexed as `slices[t][x][y]`.
+        slices: Vec<Slice>,
+        /// Whether decoding has failed, e.g. because the file is truncated.
+        failed: bool,
+    },
+
+    /// Each time slice is compressed as a separate frame, which is decoded on access.
+    Frames {
+        mmap: Mmap,
+        /// Byte ranges of the frames of all time slices contained in the file.
+        frames: Vec<Range<usize>>,
+        /// The most recently decoded slices together with their time steps.
+        cache: Vec<(usize, Slice)>,
+    },
+}
+
+impl LazyDynamicProgram {
+    /// Number of decoded slices kept in memory for files compressing each slice separately.
+    const CACHE_SIZE: usize = 2;
+
+    /// Opens the dynamic program saved at `path` and validates its header.
+    ///
+    /// Returns an error if the file cannot be opened or is not a saved dynamic program.
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let file = File::open(path)?;
+        let mut decoder = Decoder::new(file.try_clone()?).context("could not create decoder")?;
+        let header = Header::read(&mut decoder)?;
+
+        // SAFETY: The file is not expected to be modified by other processes while it is mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let mut frames = Vec::new();
+        let mut pos = 0;
+
+        while pos < mmap.len() {
+            match find_frame_compressed_size(&mmap[pos..]) {
+                Ok(size) if size > 0 => {
+                    frames.push(pos..pos + size);
+                    pos += size;
+                }
+                _ => break,
+            }
+        }
+
+        // The first frame contains the header, files with a single frame are a single stream
+        let state = if frames.len() > 1 {
+            frames.remove(0);
+
+            LazyState::Frames {
+                mmap,
+                frames,
+                cache: Vec::new(),
+            }
+        } else {
+            LazyState::Stream {
+                decoder,
+                slices: Vec::new(),
+                failed: false,
+            }
+        };
+
+        Ok(Self {
+            header,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Returns the model card stored in the header of the file.
+    pub fn model_card(&self) -> ModelCard {
+        self.header.model_card.clone()
+    }
+
+    pub fn time_limit(&self) -> usize {
+        self.header.model_card.time_limit
+    }
+
+    /// Returns the number of time slices currently held in memory.
+    pub fn loaded_time_steps(&self) -> usize {
+        match &*self.state.lock().unwrap() {
+            LazyState::Stream { slices, .. } => slices.len(),
+            LazyState::Frames { cache, .. } => cache.len(),
+        }
+    }
+
+    /// Returns whether each time slice is compressed separately, so that slices are decoded
+    /// independently of each other.
+    pub fn is_framed(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), LazyState::Frames { .. })
+    }
+
+    /// Returns the value at the table indices `x` and `y` in time step `t`, decoding the slice
+    /// if necessary, or `None` if the slice cannot be read from the file.
+    fn value_at(&self, x: usize, y: usize, t: usize) -> Option<f64> {
+        let size = 2 * self.time_limit() + 1;
+        let mut state = self.state.lock().unwrap();
+
+        match &mut *state {
+            LazyState::Stream {
+                decoder,
+                slices,
+                failed,
+            } => {
+                let mut buf = [0u8; 8];
+
+                while slices.len() <= t && !*failed {
+                    let mut slice = vec![vec![0.0; size]; size];
+
+                    'decode: for column in slice.iter_mut() {
+                        for val in column.iter_mut() {
+                            if decoder.read_exact(&mut buf).is_err() {
+                                *failed = true;
+                                break 'decode;
+                            }
+
+                            *val = f64::from_le_bytes(buf);
+                        }
+                    }
+
+                    if !*failed {
+                        slices.push(slice);
+                    }
+                }
+
+                slices.get(t).map(|slice| slice[x][y])
+            }
+            LazyState::Frames {
+                mmap,
+                frames,
+                cache,
+            } => {
+                if let Some((_, slice)) = cache.iter().find(|(s, _)| *s == t) {
+                    return Some(slice[x][y]);
+                }
+
+                let frame = frames.get(t)?.clone();
+                let domain = Domain::square(self.time_limit());
+                let slice = decompress_slice(&mmap[frame], domain).ok()?;
+                let val = slice[x][y];
+
+                if cache.len() >= Self::CACHE_SIZE {
+                    cache.remove(0);
+                }
+
+                cache.push((t, slice));
+
+                Some(val)
+            }
+        }
+    }
+}
+
+impl DpAccess for LazyDynamicProgram {
+    /// Returns the natural logarithm of the probability at `(x, y)` in time step `t`.
+    ///
+    /// Time steps that cannot be read from the file, e.g. because it is truncated, are treated
+    /// like points outside of the table.
+    fn log_at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        let limit = self.time_limit() as isize;
+
+        if t > self.time_limit() || x.abs() > limit || y.abs() > limit {
+            return f64::NEG_INFINITY;
+        }
+
+        self.value_at((x + limit) as usize, (y + limit) as usize, t)
+            .map_or(f64::NEG_INFINITY, f64::ln)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::lazy::LazyDynamicProgram;
+    use crate::dp::simple::DynamicProgram;
+    use crate::dp::{DpAccess, DynamicProgramPool, DynamicPrograms};
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::Walker;
+
+    #[test]
+    fn test_lazy_dp() {
+        let path = std::env::temp_dir().join("randomwalks_test_lazy_dp.dp");
+        let path = path.to_str().unwrap().to_string();
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+        dp.save(path.clone()).unwrap();
+
+        let lazy = LazyDynamicProgram::open(&path).unwrap();
+
+        assert_eq!(lazy.time_limit(), 10);
+        assert_eq!(lazy.loaded_time_steps(), 0);
+        assert!((lazy.at_or(1, 0, 1) - 0.2).abs() < 1e-12);
+        assert_eq!(lazy.loaded_time_steps(), 2);
+        assert_eq!(lazy.log_at_or(11, 0, 1), f64::NEG_INFINITY);
+
+        let walker = StandardWalker::new(kernel);
+
+        assert!(!lazy.is_framed());
+        assert!(walker.generate_path_from(&lazy, 2, 3, 5).is_ok());
+        assert_eq!(lazy.loaded_time_steps(), 6);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_lazy_dp_streamed() {
+        let path = std::env::temp_dir().join("randomwalks_test_lazy_dp_streamed.dp");
+        let path = path.to_str().unwrap().to_string();
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(kernel.clone())
+        };
+
+        let mut full = builder().build().unwrap();
+        let mut streamed = builder()
+            .rolling_storage(Some(path.clone()))
+            .build()
+            .unwrap();
+
+        full.compute().unwrap();
+        streamed.compute().unwrap();
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+        let lazy = LazyDynamicProgram::open(&path).unwrap();
+
+        assert!(lazy.is_framed());
+        assert!((lazy.at_or(3, -2, 9) - full.at(3, -2, 9)).abs() < 1e-15);
+        assert_eq!(lazy.at_or(0, 0, 0), 1.0);
+        assert_eq!(lazy.loaded_time_steps(), 2);
+
+        let walker = StandardWalker::new(kernel);
+
+        assert!(walker.generate_path_from(&lazy, 2, 3, 10).is_ok());
+        // Only the slices of two consecutive time steps are kept in memory
+        assert_eq!(lazy.loaded_time_steps(), 2);
+
+        // Streamed files can also be loaded entirely
+        let loaded = DynamicProgram::load(path.clone()).unwrap();
+
+        assert_eq!(loaded.try_unwrap().unwrap().table, full.table);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -34,7 +34,7 @@
 //! let dp = DynamicProgramBuilder::new()
 //!     .simple()
 //!     .time_limit(400)
-//!     .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
 //!     .build()
 //!     .unwrap();
 //! ```
@@ -57,30 +57,102 @@
 //! # let mut dp = DynamicProgramBuilder::new()
 //! #     .simple()
 //! #     .time_limit(400)
-//! #     .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+//! #     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
 //! #     .build()
 //! #     .unwrap();
 //! #
-//! dp.compute();
+//! dp.compute().unwrap();
 //! ```
 //!
 //! can be run.
 //!
+//! ## Saving and Loading
+//!
+//! Computed dynamic programs can be saved using [`save()`](DynamicPrograms::save) and loaded
+//! again using [`DynamicProgramPool::load()`]. Pools holding multiple dynamic programs, e.g. one
+//! for each direction of a correlated random walk, are saved to one file per variant. This allows
+//! computing them once in Rust and reusing them from Python:
+//!
+//! ```python
+//! dpp = randomwalks_lib.dp.DynamicProgramPool.load("correlated.zst")
+//!
+//! for i in range(len(dpp)):
+//!     dpp[i].heatmap(f"variant_{i}.png", 100)
+//! ```
+//!
 
+use crate::dp::backward::BackwardTable;
 use crate::dp::simple::DynamicProgram;
-use pyo3::{pyclass, pymethods, FromPyObject, PyCell, PyObject, PyResult};
+use crate::dp::stats::ComputeStats;
+use anyhow::Context;
+#[cfg(feature = "plotting")]
+use plotters::prelude::*;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::{pyclass, pymethods, FromPyObject, PyAny, PyErr, PyResult};
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
+pub mod backward;
 pub mod builder;
+pub(crate) mod checkpoint;
+pub mod compare;
+pub(crate) mod domain;
+pub mod export;
+#[cfg(feature = "saving")]
+pub(crate) mod format;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "saving")]
+pub mod lazy;
+pub mod model_card;
+pub mod multigrid;
 pub mod simple;
+pub mod stats;
+pub(crate) mod table;
+
+/// A callback that is invoked after each computed time step of a dynamic program.
+///
+/// It receives the time step and the probabilities of the corresponding slice of the table,
+/// indexed as `slice[x][y]`, independent of the computation mode. See
+/// [`on_step()`](builder::DynamicProgramBuilder::on_step).
+pub type StepCallback = Arc<Mutex<dyn FnMut(usize, &[Vec<f64>]) + Send>>;
+
+/// Read access to the probabilities of a computed dynamic program.
+///
+/// Walkers only need to look up probabilities, so they can generate walks from any type
+/// implementing this trait, independent of how its table is stored. It is implemented by
+/// [`DynamicProgram`] for all of its storage options, including memory-mapped tables, and by
+/// [`LazyDynamicProgram`](lazy::LazyDynamicProgram), which reads a saved dynamic program on
+/// demand.
+pub trait DpAccess {
+    /// Returns the natural logarithm of the probability at `(x, y)` in time step `t`.
+    ///
+    /// Points outside of the table have a log-probability of negative infinity.
+    fn log_at_or(&self, x: isize, y: isize, t: usize) -> f64;
+
+    /// Returns the probability at `(x, y)` in time step `t`, or zero for points outside of the
+    /// table.
+    fn at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        self.log_at_or(x, y, t).exp()
+    }
+
+    /// Returns whether `(x, y)` is considered unreachable in time step `t`. Unless overridden,
+    /// this is the case if its probability is exactly zero.
+    fn is_zero(&self, x: isize, y: isize, t: usize) -> bool {
+        self.log_at_or(x, y, t) == f64::NEG_INFINITY
+    }
+}
 
 pub trait DynamicPrograms {
     fn limits(&self) -> (isize, isize);
 
-    fn compute(&mut self);
+    fn compute(&mut self) -> anyhow::Result<ComputeStats>;
 
-    fn compute_parallel(&mut self);
+    fn compute_parallel(&mut self) -> anyhow::Result<ComputeStats>;
+
+    #[cfg(feature = "gpu")]
+    fn compute_gpu(&mut self) -> anyhow::Result<ComputeStats>;
 
     fn field_types(&self) -> Vec<Vec<usize>>;
 
@@ -89,6 +161,7 @@ pub trait DynamicPrograms {
 
     fn print(&self, t: usize);
 
+    #[cfg(feature = "saving")]
     fn save(&self, filename: String) -> anyhow::Result<()>;
 }
 
@@ -98,6 +171,21 @@ pub enum DynamicProgramError {
     /// dynamic programs.
     #[error("try_unwrap() can only be called on a single dynamic program")]
     UnwrapOnMultiple,
+
+    /// This error occurs when a time step beyond the time limit of a dynamic program is accessed.
+    #[error("time step {0} exceeds the time limit")]
+    TimeStepOutOfRange(usize),
+
+    /// This error occurs when a time step is accessed whose slice was discarded by the rolling
+    /// storage.
+    #[error("time step {0} is not kept in memory")]
+    TimeStepNotInMemory(usize),
+}
+
+impl From<DynamicProgramError> for PyErr {
+    fn from(value: DynamicProgramError) -> Self {
+        PyValueError::new_err(value.to_string())
+    }
 }
 
 #[pyclass]
@@ -109,64 +197,313 @@ pub struct PyDynamicProgramPool {
 
 #[pymethods]
 impl PyDynamicProgramPool {
-    pub fn single(slf: &PyCell<Self>, dp: PyObject) -> PyResult<Self> {
-        let dp = dp.extract(slf.py())?;
+    #[staticmethod]
+    pub fn single(dp: DynamicProgram) -> Self {
+        Self {
+            dpp: DynamicProgramPool::Single(dp),
+        }
+    }
 
+    #[staticmethod]
+    pub fn multiple(dps: Vec<DynamicProgram>) -> Self {
+        Self {
+            dpp: DynamicProgramPool::Multiple(dps),
+        }
+    }
+
+    /// Creates a pool holding `dp` together with a backward table for walks ending at
+    /// `(to_x, to_y)` in time step `time_steps`. See [`DynamicProgramPool::bridge()`].
+    #[staticmethod]
+    #[pyo3(name = "bridge")]
+    pub fn py_bridge(
+        dp: DynamicProgram,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
-            dpp: DynamicProgramPool::Single(dp),
+            dpp: DynamicProgramPool::bridge(dp, to_x, to_y, time_steps)?,
         })
     }
 
-    pub fn multiple(slf: &PyCell<Self>, dps: Vec<PyObject>) -> PyResult<Self> {
-        let dps = dps.iter().map(|dp| dp.extract(slf.py()).unwrap()).collect();
+    pub fn compute(&mut self) -> anyhow::Result<ComputeStats> {
+        self.dpp.compute()
+    }
+
+    pub fn compute_parallel(&mut self) -> anyhow::Result<ComputeStats> {
+        self.dpp.compute_parallel()
+    }
+
+    pub fn limits(&self) -> (isize, isize) {
+        self.dpp.limits()
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.dpp.programs().len()
+    }
+
+    /// Returns a copy of the dynamic program at index `variant` in the pool.
+    pub fn __getitem__(&self, variant: usize) -> PyResult<DynamicProgram> {
+        self.dpp
+            .programs()
+            .get(variant)
+            .cloned()
+            .ok_or_else(|| PyIndexError::new_err("variant does not exist"))
+    }
+
+    #[pyo3(signature = (t, variant=None))]
+    pub fn print(&self, t: usize, variant: Option<usize>) -> anyhow::Result<()> {
+        match variant {
+            Some(variant) => self.dpp.print_variant(t, variant),
+            None => {
+                self.dpp.print(t);
 
+                Ok(())
+            }
+        }
+    }
+
+    #[cfg(feature = "saving")]
+    pub fn save(&self, filename: String) -> anyhow::Result<()> {
+        self.dpp.save(filename)
+    }
+
+    #[cfg(feature = "saving")]
+    #[staticmethod]
+    #[pyo3(name = "load")]
+    pub fn py_load(filename: String) -> anyhow::Result<Self> {
         Ok(Self {
-            dpp: DynamicProgramPool::Multiple(dps),
+            dpp: DynamicProgramPool::load(filename)?,
         })
     }
+
+    #[cfg(feature = "plotting")]
+    #[pyo3(signature = (path, t, variant=None))]
+    pub fn heatmap(&self, path: String, t: usize, variant: Option<usize>) -> anyhow::Result<()> {
+        match variant {
+            Some(variant) => self.dpp.heatmap_variant(path, t, variant),
+            None => self.dpp.heatmap(path, t),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum DynamicProgramPool {
     Single(DynamicProgram),
     Multiple(Vec<DynamicProgram>),
+    /// A single dynamic program together with a [`BackwardTable`] used to sample exact bridges
+    /// to the end point of the backward table. See the [`backward`] module.
+    Bridge(DynamicProgram, BackwardTable),
 }
 
 #[cfg(not(tarpaulin_include))]
 impl DynamicProgramPool {
     fn try_unwrap(&self) -> Result<&DynamicProgram, DynamicProgramError> {
         match self {
-            DynamicProgramPool::Single(single) => Ok(single),
+            DynamicProgramPool::Single(single) | DynamicProgramPool::Bridge(single, _) => {
+                Ok(single)
+            }
             DynamicProgramPool::Multiple(_) => Err(DynamicProgramError::UnwrapOnMultiple),
         }
     }
 
     fn try_unwrap_mut(&mut self) -> Result<&mut DynamicProgram, DynamicProgramError> {
         match self {
-            DynamicProgramPool::Single(single) => Ok(single),
+            DynamicProgramPool::Single(single) | DynamicProgramPool::Bridge(single, _) => {
+                Ok(single)
+            }
             DynamicProgramPool::Multiple(_) => Err(DynamicProgramError::UnwrapOnMultiple),
         }
     }
+
+    /// Returns all dynamic programs in the pool.
+    pub(crate) fn programs(&self) -> &[DynamicProgram] {
+        match self {
+            DynamicProgramPool::Single(single) | DynamicProgramPool::Bridge(single, _) => {
+                std::slice::from_ref(single)
+            }
+            DynamicProgramPool::Multiple(multiple) => multiple,
+        }
+    }
+
+    fn programs_mut(&mut self) -> &mut [DynamicProgram] {
+        match self {
+            DynamicProgramPool::Single(single) | DynamicProgramPool::Bridge(single, _) => {
+                std::slice::from_mut(single)
+            }
+            DynamicProgramPool::Multiple(multiple) => multiple,
+        }
+    }
+
+    /// Creates a pool holding `dp` together with a [`BackwardTable`] for walks ending at
+    /// `(to_x, to_y)` in time step `time_steps`, so that walkers can sample exact bridges to that
+    /// end point. Returns an error if the end point is outside of the table of `dp`.
+    pub fn bridge(
+        dp: DynamicProgram,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> anyhow::Result<Self> {
+        let backward = BackwardTable::compute(&dp, to_x, to_y, time_steps)?;
+
+        Ok(DynamicProgramPool::Bridge(dp, backward))
+    }
+
+    /// Returns the backward table of the pool, if it holds one.
+    pub fn backward(&self) -> Option<&BackwardTable> {
+        match self {
+            DynamicProgramPool::Bridge(_, backward) => Some(backward),
+            _ => None,
+        }
+    }
+
+    /// Renders the heatmap of time step `t` of the dynamic program at index `variant` in the
+    /// pool, e.g. a single direction of a correlated random walk.
+    #[cfg(feature = "plotting")]
+    pub fn heatmap_variant(&self, path: String, t: usize, variant: usize) -> anyhow::Result<()> {
+        self.programs()
+            .get(variant)
+            .context("variant does not exist")?
+            .heatmap(path, t)
+    }
+
+    /// Prints time step `t` of the dynamic program at index `variant` in the pool.
+    pub fn print_variant(&self, t: usize, variant: usize) -> anyhow::Result<()> {
+        self.programs()
+            .get(variant)
+            .context("variant does not exist")?
+            .print(t);
+
+        Ok(())
+    }
+
+    /// Loads a pool saved using [`save()`](DynamicPrograms::save).
+    ///
+    /// If a file called `filename` exists, it is loaded as a single dynamic program. Otherwise,
+    /// the files of all variants of a pool holding multiple dynamic programs are loaded, starting
+    /// at index 0 and stopping at the first index without a file. Returns an error if no file
+    /// belongs to the pool or if any of its files cannot be loaded.
+    #[cfg(feature = "saving")]
+    pub fn load(filename: String) -> anyhow::Result<Self> {
+        if std::path::Path::new(&filename).exists() {
+            return DynamicProgram::load(filename);
+        }
+
+        let mut dps = Vec::new();
+
+        while std::path::Path::new(&indexed_path(&filename, dps.len())).exists() {
+            let DynamicProgramPool::Single(dp) =
+                DynamicProgram::load(indexed_path(&filename, dps.len()))?
+            else {
+                unreachable!();
+            };
+
+            dps.push(dp);
+        }
+
+        if dps.is_empty() {
+            anyhow::bail!("no saved dynamic program found at {}", filename);
+        }
+
+        Ok(DynamicProgramPool::Multiple(dps))
+    }
+
+    /// Returns the smallest time limit of all dynamic programs in the pool, or `0` if it is empty.
+    pub(crate) fn time_limit(&self) -> usize {
+        self.programs()
+            .iter()
+            .map(|dp| dp.time_limit)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Returns whether `(x, y)` is covered by the tables of all dynamic programs in the pool.
+    pub(crate) fn contains(&self, x: isize, y: isize) -> bool {
+        self.programs().iter().all(|dp| dp.contains(x, y))
+    }
+
+    /// Returns whether `(x, y)` is considered unreachable in time step `t` by any dynamic program
+    /// in the pool. See [`DynamicProgram::is_zero()`].
+    pub(crate) fn is_zero(&self, x: isize, y: isize, t: usize) -> bool {
+        let dps = self.programs();
+
+        dps.is_empty()
+            || dps
+                .iter()
+                .any(|dp| t > dp.time_limit || !dp.contains(x, y) || dp.is_zero(x, y, t))
+    }
+
+    /// Returns the smallest log-probability of `(x, y)` in time step `t` among all dynamic
+    /// programs in the pool. Points outside of the tables have a log-probability of negative
+    /// infinity.
+    pub(crate) fn min_log_at(&self, x: isize, y: isize, t: usize) -> f64 {
+        let dps = self.programs();
+
+        if dps.is_empty() || dps.iter().any(|dp| t > dp.time_limit) {
+            return f64::NEG_INFINITY;
+        }
+
+        dps.iter()
+            .map(|dp| dp.log_at_or(x, y, t))
+            .fold(f64::INFINITY, f64::min)
+    }
 }
 
 #[cfg(not(tarpaulin_include))]
 impl DynamicPrograms for DynamicProgramPool {
-    /// Wrapper for `SimpleDynamicProgram::limits()`. Fails if called on a `DynamicProgramPool`
-    /// holding multiple dynamic programs.
+    /// Wrapper for `SimpleDynamicProgram::limits()`. If called on a `DynamicProgramPool` holding
+    /// multiple dynamic programs, the limits covered by all of them are returned. Fails if the
+    /// pool is empty.
     fn limits(&self) -> (isize, isize) {
-        self.try_unwrap().unwrap().limits()
+        self.programs()
+            .iter()
+            .map(|dp| dp.limits())
+            .reduce(|(a_neg, a_pos), (b_neg, b_pos)| (a_neg.max(b_neg), a_pos.min(b_pos)))
+            .expect("the pool does not contain any dynamic programs")
     }
 
-    /// Wrapper for `SimpleDynamicProgram::compute()`. Fails if called on a `DynamicProgramPool`
-    /// holding multiple dynamic programs.
-    fn compute(&mut self) {
-        self.try_unwrap_mut().unwrap().compute()
+    /// Wrapper for `SimpleDynamicProgram::compute()`. If called on a `DynamicProgramPool` holding
+    /// multiple dynamic programs, they are computed one after another and their statistics are
+    /// combined.
+    fn compute(&mut self) -> anyhow::Result<ComputeStats> {
+        let stats = self
+            .programs_mut()
+            .iter_mut()
+            .map(|dp| dp.compute())
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(ComputeStats::combine(stats, false))
     }
 
-    /// Wrapper for `SimpleDynamicProgram::compute_parallel()`. Fails if called on a
+    /// Wrapper for `SimpleDynamicProgram::compute_parallel()`. If called on a
+    /// `DynamicProgramPool` holding multiple dynamic programs, all of them are computed
+    /// concurrently, each one in parallel over chunks of its table, and their statistics are
+    /// combined.
+    fn compute_parallel(&mut self) -> anyhow::Result<ComputeStats> {
+        match self {
+            DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _) => {
+                dp.compute_parallel()
+            }
+            DynamicProgramPool::Multiple(dps) => std::thread::scope(|s| {
+                let handles = dps
+                    .iter_mut()
+                    .map(|dp| s.spawn(move || dp.compute_parallel()))
+                    .collect::<Vec<_>>();
+                let stats = handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap())
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+
+                Ok(ComputeStats::combine(stats, true))
+            }),
+        }
+    }
+
+    /// Wrapper for `SimpleDynamicProgram::compute_gpu()`. Fails if called on a
     /// `DynamicProgramPool` holding multiple dynamic programs.
-    fn compute_parallel(&mut self) {
-        self.try_unwrap_mut().unwrap().compute_parallel()
+    #[cfg(feature = "gpu")]
+    fn compute_gpu(&mut self) -> anyhow::Result<ComputeStats> {
+        self.try_unwrap_mut()?.compute_gpu()
     }
 
     /// Wrapper for `SimpleDynamicProgram::field_types()`. Fails if called on a `DynamicProgramPool`
@@ -175,34 +512,202 @@ impl DynamicPrograms for DynamicProgramPool {
         self.try_unwrap().unwrap().field_types()
     }
 
-    /// Wrapper for `SimpleDynamicProgram::heatmap()`. Fails if called on a `DynamicProgramPool`
-    /// holding multiple dynamic programs.
+    /// Wrapper for `SimpleDynamicProgram::heatmap()`. If called on a `DynamicProgramPool` holding
+    /// multiple dynamic programs, the heatmaps of all of them are rendered in a grid. Use
+    /// [`heatmap_variant()`](DynamicProgramPool::heatmap_variant) to render a single one.
     #[cfg(feature = "plotting")]
     fn heatmap(&self, path: String, t: usize) -> anyhow::Result<()> {
-        self.try_unwrap().unwrap().heatmap(path, t)
+        let dps = match self {
+            DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _) => {
+                return dp.heatmap(path, t)
+            }
+            DynamicProgramPool::Multiple(dps) => dps,
+        };
+
+        if dps.is_empty() {
+            anyhow::bail!("the pool does not contain any dynamic programs");
+        }
+
+        let cols = (dps.len() as f64).sqrt().ceil() as usize;
+        let rows = dps.len().div_ceil(cols);
+
+        let path = crate::workspace::output_path(&path, "plot")?;
+        let root =
+            BitMapBackend::new(&path, (500 * cols as u32, 500 * rows as u32)).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        for (i, (dp, area)) in dps.iter().zip(root.split_evenly((rows, cols))).enumerate() {
+            let area = area.margin(10, 10, 10, 10);
+
+            dp.draw_heatmap(&area, t, &format!("Variant {} for t = {}", i, t))?;
+        }
+
+        root.present()?;
+
+        Ok(())
     }
 
-    /// Wrapper for `SimpleDynamicProgram::print()`. Fails if called on a `DynamicProgramPool`
-    /// holding multiple dynamic programs.
+    /// Wrapper for `SimpleDynamicProgram::print()`. If called on a `DynamicProgramPool` holding
+    /// multiple dynamic programs, all of them are printed one after another. Use
+    /// [`print_variant()`](DynamicProgramPool::print_variant) to print a single one.
     fn print(&self, t: usize) {
-        self.try_unwrap().unwrap().print(t)
+        match self {
+            DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _) => dp.print(t),
+            DynamicProgramPool::Multiple(dps) => {
+                for (i, dp) in dps.iter().enumerate() {
+                    println!("Variant {}:", i);
+                    dp.print(t);
+                }
+            }
+        }
     }
 
-    /// Wrapper for `SimpleDynamicProgram::save()`. Fails if called on a `DynamicProgramPool`
-    /// holding multiple dynamic programs.
+    /// Wrapper for `SimpleDynamicProgram::save()`. If called on a `DynamicProgramPool` holding
+    /// multiple dynamic programs, each one is saved to a separate file whose name contains its
+    /// index in the pool, e.g. `dp.0.zst`, `dp.1.zst` and so on for `dp.zst`. The pool can be
+    /// restored using [`DynamicProgramPool::load()`]. Backward tables are not saved, as they can
+    /// be recomputed from the dynamic program.
+    #[cfg(feature = "saving")]
     fn save(&self, filename: String) -> anyhow::Result<()> {
-        self.try_unwrap().unwrap().save(filename)
+        let dps = match self {
+            DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _) => {
+                return dp.save(filename)
+            }
+            DynamicProgramPool::Multiple(dps) => dps,
+        };
+
+        if dps.is_empty() {
+            anyhow::bail!("the pool does not contain any dynamic programs");
+        }
+
+        for (i, dp) in dps.iter().enumerate() {
+            dp.save(indexed_path(&filename, i))?;
+        }
+
+        Ok(())
     }
 }
 
+/// Inserts `index` before the extension of `filename`, e.g. `dp.zst` becomes `dp.3.zst`.
+#[cfg(feature = "saving")]
+fn indexed_path(filename: &str, index: usize) -> String {
+    let path = std::path::Path::new(filename);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, index, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, index),
+    };
+
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
 impl From<PyDynamicProgramPool> for DynamicProgramPool {
     fn from(value: PyDynamicProgramPool) -> Self {
         value.dpp
     }
 }
 
+/// Allows Python functions to accept either a `DynamicProgramPool`, a single `DynamicProgram`,
+/// which is then wrapped into [`DynamicProgramPool::Single`], or a list of dynamic programs,
+/// which is then wrapped into [`DynamicProgramPool::Multiple`].
+impl<'source> FromPyObject<'source> for DynamicProgramPool {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(pool) = ob.extract::<PyDynamicProgramPool>() {
+            return Ok(pool.into());
+        }
+
+        if let Ok(dps) = ob.extract::<Vec<DynamicProgram>>() {
+            return Ok(DynamicProgramPool::Multiple(dps));
+        }
+
+        Ok(DynamicProgramPool::Single(ob.extract()?))
+    }
+}
+
 #[derive(Default, Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub enum DynamicProgramType {
     #[default]
     Simple,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::{DynamicProgramPool, DynamicPrograms};
+    use crate::kernel::correlated_rw::CorrelatedRwGenerator;
+    use crate::kernel::Kernel;
+
+    fn pool() -> DynamicProgramPool {
+        let kernels =
+            Kernel::multiple_from_generator(CorrelatedRwGenerator { persistence: 0.5 }).unwrap();
+
+        DynamicProgramPool::Multiple(
+            kernels
+                .into_iter()
+                .map(|kernel| {
+                    DynamicProgramBuilder::new()
+                        .simple()
+                        .time_limit(10)
+                        .kernel(kernel)
+                        .build()
+                        .unwrap()
+                        .try_unwrap()
+                        .unwrap()
+                        .clone()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_pool_compute_parallel() {
+        let mut serial = pool();
+        let mut parallel = pool();
+
+        serial.compute().unwrap();
+        parallel.compute_parallel().unwrap();
+
+        let (DynamicProgramPool::Multiple(serial), DynamicProgramPool::Multiple(parallel)) =
+            (serial, parallel)
+        else {
+            unreachable!();
+        };
+
+        assert_eq!(serial.len(), 5);
+        assert_eq!(serial, parallel);
+        assert!(serial[1].at(1, 0, 1) > 0.0);
+    }
+
+    #[cfg(feature = "saving")]
+    #[test]
+    fn test_pool_save_load() {
+        let dir = std::env::temp_dir().join(format!(
+            "randomwalks_test_pool_save_load_{}",
+            std::process::id()
+        ));
+        let path = dir.join("dp.zst").to_str().unwrap().to_string();
+        let mut dpp = pool();
+
+        // Remove leftovers of an aborted run so that no stale files are found
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dpp.compute().unwrap();
+        dpp.save(path.clone()).unwrap();
+
+        assert_eq!(dpp.limits(), (-10, 10));
+        assert!(dpp.print_variant(0, 4).is_ok());
+        assert!(dpp.print_variant(0, 5).is_err());
+        assert!(dir.join("dp.4.zst").exists());
+        assert!(!dir.join("dp.zst").exists());
+
+        let loaded = DynamicProgramPool::load(path.clone()).unwrap();
+
+        assert_eq!(loaded.programs().len(), 5);
+        assert_eq!(loaded.programs()[3].table, dpp.programs()[3].table);
+        let missing = dir.join("missing.zst").to_str().unwrap().to_string();
+
+        assert!(DynamicProgramPool::load(missing).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+//! Provides a structured summary of the configuration of a dynamic program.
+//!
+//! A [`ModelCard`] is created using [`DynamicProgram::model_card()`] and contains everything
+//! needed to trace results back to the dynamic program they were generated with. It can be
+//! serialized to JSON and attached to outputs.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::{DynamicProgramPool, DynamicPrograms};
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(10)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let DynamicProgramPool::Single(dp) = dp else {
+//!     unreachable!();
+//! };
+//! let card = dp.model_card();
+//!
+//! println!("{}", card.to_json().unwrap());
+//! ```
+
+use crate::dp::simple::DynamicProgram;
+use crate::kernel::Kernel;
+use pyo3::{pyclass, pymethods};
+use serde::{Deserialize, Serialize};
+
+/// Summary of a kernel used by a dynamic program.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KernelCard {
+    /// Long name of the kernel.
+    pub name: String,
+    /// Short name of the kernel.
+    pub short_name: String,
+    /// Kernel probabilities, indexed as `probabilities[x][y]`.
+    pub probabilities: Vec<Vec<f64>>,
+}
+
+impl From<&Kernel> for KernelCard {
+    fn from(kernel: &Kernel) -> Self {
+        Self {
+            name: kernel.name(false),
+            short_name: kernel.name(true),
+            probabilities: kernel.probabilities.clone(),
+        }
+    }
+}
+
+/// A kernel that is scheduled for a range of time steps.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledKernelCard {
+    /// First time step the kernel is used in.
+    pub start: usize,
+    /// First time step the kernel is not used in anymore.
+    pub end: usize,
+    pub kernel: KernelCard,
+}
+
+/// A structured summary of a dynamic program.
+///
+/// Created using [`DynamicProgram::model_card()`].
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelCard {
+    /// Version of this library used to create the dynamic program.
+    pub library_version: String,
+    pub time_limit: usize,
+    /// Region covered by the table as `(x_min, y_min, x_max, y_max)`.
+    pub bounding_box: (isize, isize, isize, isize),
+    /// Kernels used for each field type.
+    pub kernels: Vec<KernelCard>,
+    /// Kernels scheduled for specific time ranges, overriding the kernels of all field types.
+    pub kernel_schedule: Vec<ScheduledKernelCard>,
+    /// Number of fields of each field type, in the same order as `kernels`.
+    pub field_type_counts: Vec<usize>,
+    /// Behavior at the boundary of the table. Probability mass leaving the table is lost.
+    pub boundary: String,
+    /// Number of fields blocked by barriers.
+    pub barriers: usize,
+    /// Number of absorbing fields.
+    pub absorbing: usize,
+    /// Start cells as `(x, y, weight)`, i.e. the distribution of time step 0.
+    #[serde(default)]
+    pub initial_distribution: Vec<(isize, isize, f64)>,
+    /// How the table is stored, e.g. `full` or `rolling`.
+    pub storage: String,
+    /// Whether the table stores log-probabilities.
+    pub log_space: bool,
+    /// Tolerance below which probabilities are considered to be zero.
+    #[serde(default)]
+    pub epsilon: f64,
+    /// Duration of the last computation in seconds, if the dynamic program has been computed.
+    pub compute_duration: Option<f64>,
+}
+
+#[pymethods]
+impl ModelCard {
+    /// Serializes the model card as JSON.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "ModelCard(time_limit={}, kernels={}, storage={})",
+            self.time_limit,
+            self.kernels.len(),
+            self.storage
+        )
+    }
+}
+
+impl From<&DynamicProgram> for ModelCard {
+    fn from(dp: &DynamicProgram) -> Self {
+        let mut field_type_counts = vec![0; dp.kernels.len()];
+
+        for field_type in dp.field_types.iter().flatten() {
+            field_type_counts[*field_type] += 1;
+        }
+
+        Self {
+            library_version: env!("CARGO_PKG_VERSION").into(),
+            time_limit: dp.time_limit,
+            bounding_box: dp.bounding_box(),
+            kernels: dp.kernels.iter().map(KernelCard::from).collect(),
+            kernel_schedule: dp
+                .kernel_schedule
+                .iter()
+                .map(|(range, kernel)| ScheduledKernelCard {
+                    start: range.start,
+                    end: range.end,
+                    kernel: kernel.into(),
+                })
+                .collect(),
+            field_type_counts,
+            boundary: "absorbing".into(),
+            barriers: dp.barriers,
+            absorbing: dp.absorbing.len(),
+            initial_distribution: dp.initial_distribution(),
+            storage: dp.table.kind().into(),
+            log_space: dp.log_space,
+            epsilon: dp.epsilon,
+            compute_duration: dp.compute_duration.map(|d| d.as_secs_f64()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::xy;
+
+    #[test]
+    fn test_model_card() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .add_rect_barrier(xy!(1, 1), xy!(2, 2))
+            .build()
+            .unwrap();
+
+        assert!(dp
+            .try_unwrap()
+            .unwrap()
+            .model_card()
+            .compute_duration
+            .is_none());
+
+        dp.compute().unwrap();
+
+        let card = dp.try_unwrap().unwrap().model_card();
+
+        assert_eq!(card.time_limit, 5);
+        assert_eq!(card.bounding_box, (-5, -5, 5, 5));
+        assert_eq!(card.kernels.len(), 1);
+        assert_eq!(card.kernels[0].probabilities.len(), 3);
+        assert_eq!(card.field_type_counts, vec![121]);
+        assert_eq!(card.barriers, 4);
+        assert_eq!(card.absorbing, 0);
+        assert_eq!(card.storage, "full");
+        assert!(!card.log_space);
+        assert!(card.compute_duration.is_some());
+        assert!(card.to_json().unwrap().contains("\"time_limit\": 5"));
+    }
+}
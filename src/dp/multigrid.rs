@@ -0,0 +1,270 @@
+//! Provides an approximate computation of dynamic programs on a coarser grid.
+//!
+//! For very large time limits, computing the full table is expensive, while walkers often only
+//! require accurate probabilities close to the end points of their walks. Using
+//! [`compute_multigrid()`](DynamicProgram::compute_multigrid), the dynamic program is first
+//! computed on a coarse grid only containing every second cell along each axis. Each step on the
+//! coarse grid covers two time steps at once using the convolution of the kernels of both time
+//! steps, so that the coarse computation requires only a fraction of the work of the full one.
+//!
+//! The coarse solution is then interpolated onto the full table and serves as a warm start for
+//! refining it: inside the given regions of interest, e.g. around the end points of walks, all
+//! time steps are recomputed using the original kernels, reading the approximation for all cells
+//! outside of the regions.
+//!
+//! Steps ending between two cells of the coarse grid are split between both, so the approximation
+//! spreads probability mass further than the exact computation. Probabilities outside of
+//! the regions of interest, and close to their boundaries, are therefore only approximate. Regions
+//! covering the whole table yield the exact result.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicProgramPool;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::xy;
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! #
+//! let dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(200)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .build()
+//!     .unwrap();
+//!
+//! let DynamicProgramPool::Single(mut dp) = dp else {
+//!     unreachable!();
+//! };
+//!
+//! dp.compute_multigrid(vec![(xy!(10, 10), xy!(30, 30))]).unwrap();
+//! ```
+
+use crate::dp::simple::DynamicProgram;
+use crate::dp::table::Slice;
+use crate::kernel::Kernel;
+
+/// A dynamic program computed on a grid only containing the cells with even coordinates.
+///
+/// The coarse cell at `(x, y)` corresponds to the cell at `(2 * x, 2 * y)` of the full table and
+/// coarse time step `s` corresponds to time step `2 * s`.
+pub(crate) struct CoarseGrid {
+    x_min: isize,
+    x_max: isize,
+    y_min: isize,
+    y_max: isize,
+    /// Slices of all coarse time steps, indexed as `slices[s][x][y]`.
+    slices: Vec<Slice>,
+}
+
+impl CoarseGrid {
+    /// Computes the coarse approximation of `dp` covering all of its time steps.
+    pub(crate) fn compute(dp: &DynamicProgram) -> Self {
+        let domain = dp.domain;
+        let (x_min, x_max) = ((domain.x_min + 1).div_euclid(2), domain.x_max.div_euclid(2));
+        let (y_min, y_max) = ((domain.y_min + 1).div_euclid(2), domain.y_max.div_euclid(2));
+        let (width, height) = ((x_max - x_min + 1) as usize, (y_max - y_min + 1) as usize);
+
+        // Start cells with odd coordinates are split between their neighboring coarse cells
+        let mut first = vec![vec![0.0; height]; width];
+
+        for &((x, y), weight) in dp.initial.iter() {
+            for (i, wi) in axis_weights(x, x_min, x_max) {
+                for (j, wj) in axis_weights(y, y_min, y_max) {
+                    first[i][j] += weight * wi * wj;
+                }
+            }
+        }
+
+        let mut slices = vec![first];
+
+        // Odd time steps are interpolated between two coarse time steps, so the last one may
+        // exceed the time limit
+        for s in 1..=(dp.time_limit + 1) / 2 {
+            let kernels = dp
+                .kernels_at(2 * s - 1)
+                .iter()
+                .zip(dp.kernels_at(2 * s).iter())
+                .map(|(first, second)| coarse_kernel(first, second))
+                .collect::<Vec<_>>();
+            let prev = slices.last().unwrap();
+            let mut slice = vec![vec![0.0; height]; width];
+
+            for x in x_min..=x_max {
+                for y in y_min..=y_max {
+                    let (xi, yi) = domain.index(2 * x, 2 * y);
+                    let kernel = &kernels[dp.field_types[xi][yi]];
+                    let ks = (kernel.size() / 2) as isize;
+                    let mut sum = 0.0;
+
+                    for i in (x - ks).max(x_min)..=(x + ks).min(x_max) {
+                        for j in (y - ks).max(y_min)..=(y + ks).min(y_max) {
+                            sum += prev[(i - x_min) as usize][(j - y_min) as usize]
+                                * kernel.at(x - i, y - j);
+                        }
+                    }
+
+                    slice[(x - x_min) as usize][(y - y_min) as usize] = sum;
+                }
+            }
+
+            slices.push(slice);
+        }
+
+        Self {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+            slices,
+        }
+    }
+
+    /// Returns the approximate probability at `(x, y)` of the full table in time step `t`.
+    ///
+    /// Each coarse cell holds the probability mass of four cells of the full table, which is
+    /// interpolated bilinearly for cells with odd coordinates. Odd time steps are interpolated
+    /// linearly between the surrounding coarse time steps.
+    pub(crate) fn interpolate(&self, x: isize, y: isize, t: usize) -> f64 {
+        let at = |s: usize| {
+            let mut sum = 0.0;
+
+            for (i, wi) in axis_weights(x, self.x_min, self.x_max) {
+                for (j, wj) in axis_weights(y, self.y_min, self.y_max) {
+                    sum += self.slices[s][i][j] * wi * wj;
+                }
+            }
+
+            sum / 4.0
+        };
+
+        if t % 2 == 0 {
+            at(t / 2)
+        } else {
+            (at(t / 2) + at(t / 2 + 1)) / 2.0
+        }
+    }
+
+    /// Returns the number of computed cells of the coarse grid over all coarse time steps.
+    pub(crate) fn cells_computed(&self) -> u64 {
+        let cells = (self.x_max - self.x_min + 1) * (self.y_max - self.y_min + 1);
+
+        (cells as usize * (self.slices.len() - 1)) as u64
+    }
+}
+
+/// Returns the indices of the coarse cells between `min` and `max` along one axis that are
+/// closest to the coordinate `x` of the full table, together with their normalized weights.
+fn axis_weights(x: isize, min: isize, max: isize) -> Vec<(usize, f64)> {
+    let cells = if x % 2 == 0 {
+        vec![x / 2]
+    } else {
+        vec![(x - 1).div_euclid(2), (x + 1).div_euclid(2)]
+    };
+    let cells = cells
+        .into_iter()
+        .filter(|&cell| (min..=max).contains(&cell))
+        .map(|cell| (cell - min) as usize)
+        .collect::<Vec<_>>();
+    let weight = 1.0 / cells.len() as f64;
+
+    cells.into_iter().map(|cell| (cell, weight)).collect()
+}
+
+/// Returns the kernel of a single step on the coarse grid, covering a step using `first`
+/// followed by a step using `second`.
+///
+/// Displacements by an odd number of cells along an axis end between two coarse cells, so their
+/// probability is split evenly between both.
+fn coarse_kernel(first: &Kernel, second: &Kernel) -> Kernel {
+    let (r1, r2) = ((first.size() / 2) as isize, (second.size() / 2) as isize);
+    let radius = (r1 + r2 + 1) / 2;
+    let mut kernel = Kernel::try_new(2 * radius as usize + 1, ("Coarse".into(), "coarse".into()))
+        .expect("coarse kernels have an odd size");
+
+    let split = |d: isize| {
+        if d % 2 == 0 {
+            vec![(d / 2, 1.0)]
+        } else {
+            vec![((d - 1).div_euclid(2), 0.5), ((d + 1).div_euclid(2), 0.5)]
+        }
+    };
+
+    for a in -r1..=r1 {
+        for b in -r1..=r1 {
+            for c in -r2..=r2 {
+                for d in -r2..=r2 {
+                    let prob = first.at(a, b) * second.at(c, d);
+
+                    if prob == 0.0 {
+                        continue;
+                    }
+
+                    for (i, wi) in split(a + c) {
+                        for (j, wj) in split(b + d) {
+                            kernel.set(i, j, kernel.at(i, j) + prob * wi * wj);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    kernel
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::multigrid::coarse_kernel;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::xy;
+
+    #[test]
+    fn test_compute_multigrid() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let coarse = coarse_kernel(&kernel, &kernel);
+
+        assert_eq!(coarse.size(), 3);
+        assert!((coarse.sum() - 1.0).abs() < 1e-12);
+        assert_eq!(coarse.at(1, 0), coarse.at(-1, 0));
+
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(20)
+                .kernel(kernel.clone())
+                .build()
+                .unwrap()
+        };
+
+        let mut full = builder();
+        full.compute().unwrap();
+
+        let full = full.try_unwrap().unwrap();
+        let mut approx = builder();
+        let approx = approx.try_unwrap_mut().unwrap();
+        let stats = approx.compute_multigrid(Vec::new()).unwrap();
+
+        assert_eq!(stats.cells_computed, 21 * 21 * 10);
+        assert!(stats.mass_lost.abs() < 1e-6);
+        assert_eq!(approx.at(0, 0, 0), 1.0);
+        assert!(approx.at(3, -2, 15) > 0.0);
+        assert!((approx.at(0, 0, 20) - full.at(0, 0, 20)).abs() < 0.5 * full.at(0, 0, 20));
+
+        // Refining the whole table yields the exact result
+        let mut refined = builder();
+        let refined = refined.try_unwrap_mut().unwrap();
+
+        refined
+            .compute_multigrid(vec![(xy!(-20, -20), xy!(20, 20))])
+            .unwrap();
+
+        assert_eq!(refined.table, full.table);
+        assert!(refined
+            .compute_multigrid(vec![(xy!(0, 0), xy!(21, 0))])
+            .is_err());
+    }
+}
@@ -1,51 +1,225 @@
-use crate::dp::builder::DynamicProgramBuilder;
-use crate::dp::{DynamicProgramPool, DynamicPrograms};
-use crate::kernel;
+use crate::dataset::loader::CoordinateType;
+use crate::dataset::point::XYPoint;
+use crate::dataset::{Datapoint, Dataset};
+use crate::dp::backward::kernel_prob;
+use crate::dp::builder::{normalize_initial_distribution, parallelism};
+use crate::dp::checkpoint::Checkpoint;
+use crate::dp::domain::Domain;
+use crate::dp::export;
+use crate::dp::export::ExportFormat;
+use crate::dp::model_card::ModelCard;
+use crate::dp::multigrid::CoarseGrid;
+use crate::dp::stats::ComputeStats;
+use crate::dp::table::{SliceWriter, Table};
+use crate::dp::{DpAccess, DynamicProgramError, DynamicProgramPool, DynamicPrograms, StepCallback};
+use crate::kernel::schedule::KernelSchedule;
 use crate::kernel::Kernel;
+use crate::walk::Walk;
+#[cfg(any(feature = "plotting", feature = "saving"))]
+use crate::workspace;
 use anyhow::{bail, Context};
-use num::Zero;
-#[cfg(feature = "plotting")]
-use plotters::prelude::*;
-use pyo3::{pyclass, pymethods, PyCell, PyResult};
-use std::collections::HashMap;
+use log::{debug, info};
+use ndarray::Array2;
+use num::{BigRational, BigUint, ToPrimitive};
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::{pyclass, pymethods, PyCell, PyResult, Python};
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::ops::Range;
 use std::sync::mpsc::channel;
 use std::sync::{Arc, RwLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use workerpool::thunk::{Thunk, ThunkWorker};
 use workerpool::Pool;
 #[cfg(feature = "saving")]
 use {
+    crate::dp::builder::DynamicProgramBuilder,
+    crate::dp::format::Header,
     std::fs::File,
     std::io::{BufReader, Read},
     std::io::{BufWriter, Write},
     zstd::{Decoder, Encoder},
 };
+#[cfg(feature = "plotting")]
+use {num::Zero, plotters::coord::Shift, plotters::prelude::*};
 
 #[pyclass]
 #[derive(Clone)]
 pub struct DynamicProgram {
-    pub(crate) table: Vec<Vec<Vec<f64>>>,
+    pub(crate) table: Table,
     pub(crate) time_limit: usize,
+    pub(crate) domain: Domain,
     pub(crate) kernels: Vec<Kernel>,
     pub(crate) field_types: Vec<Vec<usize>>,
+    pub(crate) kernel_schedule: KernelSchedule,
+    pub(crate) step_callbacks: Vec<StepCallback>,
+    pub(crate) barriers: usize,
+    pub(crate) absorbing: HashSet<(isize, isize)>,
+    /// Start cells together with their normalized weights, i.e. the distribution of time step 0.
+    pub(crate) initial: Vec<((isize, isize), f64)>,
+    pub(crate) compute_duration: Option<Duration>,
+    pub(crate) epsilon: f64,
+    pub(crate) checkpoint: Option<Checkpoint>,
+    pub(crate) log_space: bool,
+    /// Number of worker threads used for parallel computation.
+    pub(crate) threads: usize,
+    /// Number of chunks along each axis the table is split into for parallel computation.
+    pub(crate) chunks: usize,
+    /// Diffusivity of each field, indexed as `diffusivity[x][y]`, or `None` if all fields have a
+    /// diffusivity of 1.
+    pub(crate) diffusivity: Option<Vec<Vec<f64>>>,
+    /// Probability of each field, indexed as `field_probabilities[x][y]`, or `None` if all fields
+    /// have a probability of 1. Barriers have a probability of 0.
+    pub(crate) field_probabilities: Option<Vec<Vec<f64>>>,
 }
 
 #[pymethods]
 impl DynamicProgram {
     #[new]
-    #[pyo3(signature = (time_limit, kernel=None, kernels=Vec::new(), field_types=Vec::new()))]
+    #[pyo3(signature = (
+        time_limit,
+        kernel=None,
+        kernels=Vec::new(),
+        field_types=Vec::new(),
+        kernel_schedule=Vec::new(),
+        single_precision=false,
+        sparse=false,
+        memory_mapped=None,
+        log_space=false,
+        counting=false,
+        checkpoint=None,
+        checkpoint_interval=100,
+        bounding_box=None,
+        absorbing=Vec::new(),
+        epsilon=0.0,
+        initial_distribution=Vec::new(),
+        compressed=false,
+        threads=None,
+        chunks=None,
+        quantized=false,
+        diffusivity=Vec::new(),
+    ))]
     pub fn new(
         time_limit: usize,
         kernel: Option<Kernel>,
         kernels: Vec<(usize, Kernel)>,
         mut field_types: Vec<Vec<usize>>,
-    ) -> Self {
+        kernel_schedule: Vec<(usize, usize, Kernel)>,
+        single_precision: bool,
+        sparse: bool,
+        memory_mapped: Option<String>,
+        log_space: bool,
+        counting: bool,
+        checkpoint: Option<String>,
+        checkpoint_interval: usize,
+        bounding_box: Option<(isize, isize, isize, isize)>,
+        absorbing: Vec<(isize, isize)>,
+        epsilon: f64,
+        initial_distribution: Vec<(isize, isize, f64)>,
+        compressed: bool,
+        threads: Option<usize>,
+        chunks: Option<usize>,
+        quantized: bool,
+        diffusivity: Vec<Vec<f64>>,
+    ) -> anyhow::Result<Self> {
+        if checkpoint.is_some() && checkpoint_interval == 0 {
+            bail!("checkpoint interval must be greater than zero");
+        }
+
+        if cfg!(not(feature = "saving")) && checkpoint.is_some() {
+            bail!("checkpoints require the `saving` feature");
+        }
+
+        if log_space && sparse {
+            bail!("log-space computation and sparse storage cannot be combined");
+        }
+
+        if log_space && quantized {
+            bail!("log-space computation and quantized storage cannot be combined");
+        }
+
+        if counting
+            && (single_precision
+                || sparse
+                || memory_mapped.is_some()
+                || compressed
+                || quantized
+                || log_space
+                || checkpoint.is_some())
+        {
+            bail!("counting paths cannot be combined with other storage options or checkpoints");
+        }
+
+        if counting && !absorbing.is_empty() {
+            bail!("counting paths cannot be combined with absorbing cells");
+        }
+
+        if counting && !initial_distribution.is_empty() {
+            bail!("counting paths cannot be combined with an initial distribution");
+        }
+
+        let domain = match bounding_box {
+            Some((x_min, y_min, x_max, y_max)) => Domain::new(x_min, y_min, x_max, y_max)
+                .context("bounding box must contain the origin")?,
+            None => Domain::square(time_limit),
+        };
+
+        if absorbing.iter().any(|&(x, y)| !domain.contains(x, y)) {
+            bail!("absorbing cells must be inside the table");
+        }
+
+        if !(0.0..1.0).contains(&epsilon) {
+            bail!("epsilon must be at least zero and less than one");
+        }
+
+        let (threads, chunks) = parallelism(threads, chunks)
+            .context("number of threads and chunks must be greater than zero")?;
+
+        if initial_distribution
+            .iter()
+            .any(|&(x, y, _)| !domain.contains(x, y))
+        {
+            bail!("start cells must be inside the table");
+        }
+
+        let initial = if initial_distribution.is_empty() {
+            vec![((0, 0), 1.0)]
+        } else {
+            normalize_initial_distribution(
+                initial_distribution
+                    .into_iter()
+                    .map(|(x, y, weight)| ((x, y), weight))
+                    .collect(),
+            )
+            .context("weights of the initial distribution must be non-negative and not all zero")?
+        };
+
         if field_types.is_empty() {
-            field_types = vec![vec![0; 2 * time_limit + 1]; 2 * time_limit + 1];
+            field_types = vec![vec![0; domain.height()]; domain.width()];
         }
 
+        let diffusivity = if diffusivity.is_empty() {
+            None
+        } else {
+            if diffusivity.len() != domain.width()
+                || diffusivity
+                    .iter()
+                    .any(|column| column.len() != domain.height())
+            {
+                bail!("diffusivity must be of same size as DP table");
+            }
+
+            if diffusivity
+                .iter()
+                .flatten()
+                .any(|d| !(0.0..=1.0).contains(d))
+            {
+                bail!("diffusivity must be in the range [0, 1]");
+            }
+
+            Some(diffusivity)
+        };
+
         let kernels = if let Some(kernel) = kernel {
             vec![(0, kernel)]
         } else {
@@ -64,65 +238,623 @@ impl DynamicProgram {
             i += 1;
         }
 
-        for x in 0..2 * time_limit + 1 {
-            for y in 0..2 * time_limit + 1 {
+        for x in 0..domain.width() {
+            for y in 0..domain.height() {
                 field_types[x][y] = field_type_map[&field_types[x][y]];
             }
         }
 
-        Self {
-            table: vec![
-                vec![vec![Zero::zero(); 2 * time_limit + 1]; 2 * time_limit + 1];
-                time_limit + 1
-            ],
+        let kernel_schedule = KernelSchedule::new(
+            kernel_schedule
+                .into_iter()
+                .map(|(start, end, kernel)| (start..end, kernel))
+                .collect(),
+        )?;
+
+        let table = match (single_precision, sparse, memory_mapped) {
+            _ if counting => Table::counts(time_limit, domain),
+            (false, false, None) if quantized && !compressed => {
+                Table::quantized(time_limit, domain)
+            }
+            _ if quantized => bail!("the chosen storage options cannot be combined"),
+            #[cfg(feature = "saving")]
+            (false, false, None) if compressed => Table::compressed(time_limit, domain),
+            #[cfg(not(feature = "saving"))]
+            (false, false, None) if compressed => {
+                bail!("compressed storage requires the `saving` feature")
+            }
+            _ if compressed => bail!("the chosen storage options cannot be combined"),
+            (true, false, None) => Table::full_f32(time_limit, domain),
+            (false, true, None) => Table::sparse(time_limit, domain),
+            #[cfg(feature = "saving")]
+            (false, false, Some(path)) => Table::mapped(time_limit, domain, &path)
+                .context("could not create memory-mapped table")?,
+            #[cfg(not(feature = "saving"))]
+            (false, false, Some(_)) => bail!("memory-mapped storage requires the `saving` feature"),
+            (false, false, None) => Table::full(time_limit, domain),
+            _ => bail!("the chosen storage options cannot be combined"),
+        };
+
+        Ok(Self {
+            table,
             time_limit,
+            domain,
             kernels: kernels_mapped,
             field_types,
+            kernel_schedule,
+            step_callbacks: Vec::new(),
+            barriers: 0,
+            absorbing: absorbing.into_iter().collect(),
+            initial,
+            compute_duration: None,
+            epsilon,
+            checkpoint: checkpoint.map(|path| Checkpoint::new(path, checkpoint_interval)),
+            log_space,
+            threads,
+            chunks,
+            diffusivity,
+            field_probabilities: None,
+        })
+    }
+
+    /// Returns the value stored at `(x, y)` in time step `t`. See [`at()`](DynamicProgram::at).
+    ///
+    /// Returns an error if time step `t` exceeds the time limit or is not kept in memory.
+    #[pyo3(name = "at")]
+    pub fn py_at(&self, x: isize, y: isize, t: usize) -> Result<f64, DynamicProgramError> {
+        self.check_time_step(t)?;
+
+        Ok(self.at(x, y, t))
+    }
+
+    /// Returns the value stored at `(x, y)` in time step `t`, or `default` if the point is outside
+    /// of the table. See [`at_or()`](DynamicProgram::at_or).
+    ///
+    /// Returns an error if time step `t` exceeds the time limit or is not kept in memory.
+    #[pyo3(name = "at_or")]
+    pub fn py_at_or(
+        &self,
+        x: isize,
+        y: isize,
+        t: usize,
+        default: f64,
+    ) -> Result<f64, DynamicProgramError> {
+        self.check_time_step(t)?;
+
+        Ok(self.at_or(x, y, t, default))
+    }
+
+    /// Returns whether `(x, y)` is covered by the table of the dynamic program.
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        self.domain.contains(x, y)
+    }
+
+    /// Returns the region covered by the table as `(x_min, y_min, x_max, y_max)`.
+    ///
+    /// Unless a bounding box was set, this is a square of side length `2 * time_limit + 1`
+    /// centered at the origin.
+    pub fn bounding_box(&self) -> (isize, isize, isize, isize) {
+        let domain = self.domain;
+
+        (domain.x_min, domain.y_min, domain.x_max, domain.y_max)
+    }
+
+    /// Returns the natural logarithm of the probability at `(x, y)` in time step `t`. See
+    /// [`log_at_or()`](DynamicProgram::log_at_or).
+    ///
+    /// Returns an error if time step `t` exceeds the time limit or is not kept in memory.
+    #[pyo3(name = "log_at_or")]
+    pub fn py_log_at_or(&self, x: isize, y: isize, t: usize) -> Result<f64, DynamicProgramError> {
+        self.check_time_step(t)?;
+
+        Ok(self.log_at_or(x, y, t))
+    }
+
+    /// Returns the tolerance below which probabilities are considered to be zero. See
+    /// [`epsilon()`](crate::dp::builder::DynamicProgramBuilder::epsilon).
+    #[getter]
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Returns whether the probability of `(x, y)` in time step `t` is at most
+    /// [`epsilon()`](DynamicProgram::epsilon). See [`is_zero()`](DynamicProgram::is_zero).
+    ///
+    /// Returns an error if time step `t` exceeds the time limit or is not kept in memory.
+    #[pyo3(name = "is_zero")]
+    pub fn py_is_zero(&self, x: isize, y: isize, t: usize) -> Result<bool, DynamicProgramError> {
+        self.check_time_step(t)?;
+
+        Ok(self.is_zero(x, y, t))
+    }
+
+    /// Returns the start cells as `(x, y, weight)` with weights summing up to one. See
+    /// [`initial_distribution()`](crate::dp::builder::DynamicProgramBuilder::initial_distribution).
+    #[getter]
+    pub fn initial_distribution(&self) -> Vec<(isize, isize, f64)> {
+        self.initial
+            .iter()
+            .map(|&((x, y), weight)| (x, y, weight))
+            .collect()
+    }
+
+    /// Returns whether the dynamic program is computed in log space.
+    #[getter]
+    pub fn log_space(&self) -> bool {
+        self.log_space
+    }
+
+    /// Returns the exact number of paths leading to `(x, y)` in time step `t`.
+    ///
+    /// Returns `None` if the dynamic program does not count paths or if the point is outside of
+    /// the table.
+    pub fn count_at(&self, x: isize, y: isize, t: usize) -> Option<BigUint> {
+        if !self.domain.contains(x, y) {
+            return None;
         }
+
+        let (x, y) = self.domain.index(x, y);
+
+        self.table.count(x, y, t).cloned()
     }
 
-    pub fn at(&self, x: isize, y: isize, t: usize) -> f64 {
-        let x = (self.time_limit as isize + x) as usize;
-        let y = (self.time_limit as isize + y) as usize;
+    /// Returns the probability of `(x, y)` in time step `t` independent of the computation mode.
+    /// See [`probability_at()`](DynamicProgram::probability_at).
+    ///
+    /// Returns an error if time step `t` exceeds the time limit or is not kept in memory.
+    #[pyo3(name = "probability_at")]
+    pub fn py_probability_at(
+        &self,
+        x: isize,
+        y: isize,
+        t: usize,
+    ) -> Result<f64, DynamicProgramError> {
+        self.check_time_step(t)?;
+
+        Ok(self.probability_at(x, y, t))
+    }
+
+    /// Returns the probability distribution in time step `t` independent of the computation mode.
+    ///
+    /// The distribution is indexed as `[x][y]`, where index `[0][0]` corresponds to the point
+    /// `(x_min, y_min)` of the [`bounding_box()`](DynamicProgram::bounding_box). Returns an error
+    /// if time step `t` is not kept in memory.
+    pub fn slice(&self, t: usize) -> anyhow::Result<Vec<Vec<f64>>> {
+        let slice = self
+            .table
+            .slice(t)
+            .context("time step is not kept in memory")?;
+
+        if self.log_space {
+            return Ok(slice
+                .iter()
+                .map(|column| column.iter().map(|val| val.exp()).collect())
+                .collect());
+        }
+
+        if !self.table.is_counting() {
+            return Ok(slice.into_owned());
+        }
 
-        self.table[t][x][y]
+        let total = self.total_count(t);
+
+        Ok((0..self.domain.width())
+            .map(|x| {
+                (0..self.domain.height())
+                    .map(|y| {
+                        self.table
+                            .count(x, y, t)
+                            .map_or(0.0, |count| count_fraction(count, &total))
+                    })
+                    .collect()
+            })
+            .collect())
     }
 
-    pub fn at_or(&self, x: isize, y: isize, t: usize, default: f64) -> f64 {
-        let (limit_neg, limit_pos) = self.limits();
+    /// Returns the probability distribution in time step `t` as a NumPy array. See
+    /// [`slice()`](DynamicProgram::slice).
+    pub fn slice_numpy<'py>(
+        &self,
+        py: Python<'py>,
+        t: usize,
+    ) -> anyhow::Result<&'py PyArray2<f64>> {
+        let slice = self.slice(t)?;
+        let shape = (self.domain.width(), self.domain.height());
+
+        Ok(Array2::from_shape_vec(shape, slice.into_iter().flatten().collect())?.into_pyarray(py))
+    }
+
+    /// Exports the probability distribution in time step `t` to `path` in the given `format`.
+    /// See the [`export`](crate::dp::export) module for the supported formats.
+    ///
+    /// Returns an error if time step `t` is not kept in memory or the file cannot be written.
+    pub fn export_slice(&self, t: usize, format: ExportFormat, path: String) -> anyhow::Result<()> {
+        export::export_slice(self, t, format, &path)
+    }
 
-        if x >= limit_neg && x <= limit_pos && y >= limit_neg && y <= limit_pos {
-            let x = (self.time_limit as isize + x) as usize;
-            let y = (self.time_limit as isize + y) as usize;
+    /// Returns the marginal distribution of the x coordinate in time step `t`, starting at the
+    /// smallest x coordinate of the [`bounding_box()`](DynamicProgram::bounding_box).
+    pub fn marginal_x(&self, t: usize) -> anyhow::Result<Vec<f64>> {
+        Ok(self
+            .slice(t)?
+            .iter()
+            .map(|column| column.iter().sum())
+            .collect())
+    }
 
-            self.table[t][x][y]
-        } else {
-            default
+    /// Returns the marginal distribution of the y coordinate in time step `t`, starting at the
+    /// smallest y coordinate of the [`bounding_box()`](DynamicProgram::bounding_box).
+    pub fn marginal_y(&self, t: usize) -> anyhow::Result<Vec<f64>> {
+        let slice = self.slice(t)?;
+
+        Ok((0..self.domain.height())
+            .map(|y| slice.iter().map(|column| column[y]).sum())
+            .collect())
+    }
+
+    /// Returns the expected displacement `(x, y)` from the origin in time step `t`.
+    ///
+    /// The expectation is taken over the probability mass remaining in the table, i.e. mass lost
+    /// at the borders of the table is not considered. Returns an error if no probability mass
+    /// remains in time step `t`.
+    pub fn expected_displacement(&self, t: usize) -> anyhow::Result<(f64, f64)> {
+        let marginal_x = self.marginal_x(t)?;
+        let marginal_y = self.marginal_y(t)?;
+        let mass: f64 = marginal_x.iter().sum();
+
+        if mass <= 0.0 {
+            bail!("no probability mass in time step {}", t);
+        }
+
+        let expected = |marginal: &[f64], min: isize| {
+            marginal
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i as isize + min) as f64 * p)
+                .sum::<f64>()
+                / mass
+        };
+
+        Ok((
+            expected(&marginal_x, self.domain.x_min),
+            expected(&marginal_y, self.domain.y_min),
+        ))
+    }
+
+    /// Returns the probability mass outside of the region from `min` to `max` in time step `t`.
+    ///
+    /// The region includes its boundary and may extend beyond the table. Since the initial
+    /// distribution has a total mass of one, this includes the mass that has left the table
+    /// entirely. Returns an error if time step `t` is not kept in memory.
+    pub fn mass_outside(&self, min: XYPoint, max: XYPoint, t: usize) -> anyhow::Result<f64> {
+        let domain = self.domain;
+        let inside: f64 = self
+            .slice(t)?
+            .iter()
+            .enumerate()
+            .flat_map(|(x, column)| {
+                column.iter().enumerate().map(move |(y, p)| {
+                    let x = (x as isize + domain.x_min) as i64;
+                    let y = (y as isize + domain.y_min) as i64;
+
+                    if x >= min.x && x <= max.x && y >= min.y && y <= max.y {
+                        *p
+                    } else {
+                        0.0
+                    }
+                })
+            })
+            .sum();
+
+        Ok((1.0 - inside).max(0.0))
+    }
+
+    /// Returns the probability mass outside of the region from `min` to `max` in each time step
+    /// from `0` to the time limit. See [`mass_outside()`](DynamicProgram::mass_outside).
+    pub fn mass_outside_series(&self, min: XYPoint, max: XYPoint) -> anyhow::Result<Vec<f64>> {
+        (0..=self.time_limit)
+            .map(|t| self.mass_outside(min, max, t))
+            .collect()
+    }
+
+    /// Returns the distribution of the first-passage time to the cells in `region`, i.e. for each
+    /// time step `t` up to the time limit, the probability that a walk enters `region` for the
+    /// first time in time step `t`.
+    ///
+    /// Walks starting inside of `region` have a first-passage time of zero. The probabilities do
+    /// not sum up to one if some walks do not reach `region` within the time limit. They are
+    /// computed using an auxiliary dynamic program, so this does not require the table of this
+    /// dynamic program to be computed. Returns an error if `region` is empty, contains a point
+    /// outside of the table or if the dynamic program counts paths.
+    pub fn first_passage_time(&self, region: Vec<XYPoint>) -> anyhow::Result<Vec<f64>> {
+        let absorbed = self.absorbed_in_region(&region)?;
+
+        Ok((0..=self.time_limit)
+            .map(|t| match t {
+                0 => absorbed[0],
+                _ => absorbed[t] - absorbed[t - 1],
+            })
+            .collect())
+    }
+
+    /// Returns the probability that a walk has entered `region` by time step `t`, i.e. that its
+    /// [first-passage time](DynamicProgram::first_passage_time) to `region` is at most `t`.
+    ///
+    /// Returns an error if `t` exceeds the time limit, or under the same conditions as
+    /// [`first_passage_time()`](DynamicProgram::first_passage_time).
+    pub fn hitting_probability(&self, region: Vec<XYPoint>, t: usize) -> anyhow::Result<f64> {
+        if t > self.time_limit {
+            bail!("time step {} exceeds the time limit", t);
+        }
+
+        Ok(self.absorbed_in_region(&region)?[t])
+    }
+
+    /// Returns the natural logarithm of the probability of `walk` under the model of the dynamic
+    /// program. See [`log_likelihood()`](DynamicProgram::log_likelihood).
+    #[pyo3(name = "log_likelihood")]
+    pub fn py_log_likelihood(&self, walk: Walk) -> f64 {
+        self.log_likelihood(&walk)
+    }
+
+    /// Computes an approximation of the table using a coarse grid as a warm start, and refines it
+    /// inside of `regions`, given as pairs of their smallest and largest corner. See the
+    /// [`multigrid`](crate::dp::multigrid) module for details.
+    ///
+    /// Regions covering the whole table yield the same result as
+    /// [`compute()`](DynamicPrograms::compute). Returns an error if a region is outside of the
+    /// table, or if the dynamic program counts paths, uses log-space arithmetic, has absorbing
+    /// cells, field probabilities or a diffusivity field, or writes checkpoints.
+    pub fn compute_multigrid(
+        &mut self,
+        regions: Vec<(XYPoint, XYPoint)>,
+    ) -> anyhow::Result<ComputeStats> {
+        if self.table.is_counting() || self.log_space {
+            bail!("multigrid computation requires probabilities in linear space");
+        }
+
+        if !self.absorbing.is_empty() {
+            bail!("multigrid computation does not support absorbing cells");
+        }
+
+        if self.diffusivity.is_some() {
+            bail!("multigrid computation does not support a diffusivity field");
+        }
+
+        if self.field_probabilities.is_some() {
+            bail!("multigrid computation does not support field probabilities");
+        }
+
+        if self.checkpoint.is_some() {
+            bail!("multigrid computation does not support checkpoints");
+        }
+
+        let mut cells = HashSet::new();
+
+        for (min, max) in regions {
+            let (x_min, y_min) = (min.x as isize, min.y as isize);
+            let (x_max, y_max) = (max.x as isize, max.y as isize);
+
+            if !self.contains(x_min, y_min) || !self.contains(x_max, y_max) {
+                bail!(
+                    "region from ({}, {}) to ({}, {}) is outside of the table",
+                    x_min,
+                    y_min,
+                    x_max,
+                    y_max
+                );
+            }
+
+            for x in x_min..=x_max {
+                for y in y_min..=y_max {
+                    cells.insert((x, y));
+                }
+            }
+        }
+
+        let domain = self.domain;
+
+        let start = Instant::now();
+        let mut writer = self.slice_writer()?;
+        let first = self.start_computation(&mut writer)?;
+        let mass = self.total_probability(first - 1);
+        let coarse = CoarseGrid::compute(self);
+        let initialization = start.elapsed();
+
+        let start = Instant::now();
+
+        for t in first..=self.time_limit {
+            for x in domain.x_min..=domain.x_max {
+                for y in domain.y_min..=domain.y_max {
+                    self.set(x, y, t, coarse.interpolate(x, y, t));
+                }
+            }
+
+            for &(x, y) in cells.iter() {
+                self.apply_kernel_at(x, y, t);
+            }
+
+            self.finish_slice(t, &mut writer)?;
+
+            debug!("Computed time step {} of {}", t, self.time_limit);
+        }
+
+        let computation = start.elapsed();
+
+        let start = Instant::now();
+        self.finish_writer(writer)?;
+        let finalization = start.elapsed();
+
+        let mut stats = self.finish_stats(first, mass, initialization, computation, finalization);
+        stats.cells_computed = coarse.cells_computed() + (cells.len() * stats.time_steps) as u64;
+
+        Ok(stats)
+    }
+
+    /// Returns the expected number of time steps walks from `from_point` to `to_point` with
+    /// `time_steps` steps spend in each cell, computed analytically from the table instead of by
+    /// sampling walks.
+    ///
+    /// For each time step `t`, the probability of reaching a cell in `t` steps from `from_point`
+    /// is multiplied by the probability of reaching `to_point` in the remaining steps from that
+    /// cell, and divided by the probability of reaching `to_point` in `time_steps` steps. Like the
+    /// walkers, this assumes that the probabilities only depend on the displacement between two
+    /// points.
+    ///
+    /// The occupancy is indexed as `[x][y]`, where index `[0][0]` corresponds to the point
+    /// `from_point + (x_min, y_min)` of the [`bounding_box()`](DynamicProgram::bounding_box). It
+    /// sums up to `time_steps + 1`. Returns an error if `time_steps` exceeds the time limit, a
+    /// required time step is not kept in memory or `to_point` cannot be reached from `from_point`
+    /// in `time_steps` steps.
+    pub fn bridge_occupancy(
+        &self,
+        from_point: XYPoint,
+        to_point: XYPoint,
+        time_steps: usize,
+    ) -> anyhow::Result<Vec<Vec<f64>>> {
+        if time_steps > self.time_limit {
+            bail!("number of time steps exceeds the time limit");
+        }
+
+        let domain = self.domain;
+        let slices = (0..=time_steps)
+            .map(|t| self.slice(t))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let prob = |t: usize, x: isize, y: isize| {
+            if !domain.contains(x, y) {
+                return 0.0;
+            }
+
+            let (x, y) = domain.index(x, y);
+
+            slices[t][x][y]
+        };
+
+        let (dx, dy) = (
+            (to_point.x - from_point.x) as isize,
+            (to_point.y - from_point.y) as isize,
+        );
+        let total = prob(time_steps, dx, dy);
+
+        if total == 0.0 {
+            bail!(
+                "target point cannot be reached in {} time steps",
+                time_steps
+            );
+        }
+
+        // Cells are given relative to `from_point`, so the remaining displacement to `to_point` is
+        // (dx, dy) minus the displacement of the cell
+        Ok((domain.x_min..=domain.x_max)
+            .map(|x| {
+                (domain.y_min..=domain.y_max)
+                    .map(|y| {
+                        (0..=time_steps)
+                            .map(|t| prob(t, x, y) * prob(time_steps - t, dx - x, dy - y))
+                            .sum::<f64>()
+                            / total
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Converts the probability distribution in time step `t` into a [`Dataset`].
+    ///
+    /// Each point whose probability is greater than `threshold` becomes a datapoint in XY
+    /// coordinates, with its probability stored in the metadata under the key `probability`.
+    /// Datapoints are ordered by their x and then their y coordinate. Returns an error if time
+    /// step `t` is not kept in memory.
+    #[pyo3(signature = (t, threshold=0.0))]
+    pub fn to_dataset(&self, t: usize, threshold: f64) -> anyhow::Result<Dataset> {
+        let domain = self.domain;
+        let mut dataset = Dataset::new(CoordinateType::XY);
+
+        for (x, column) in self.slice(t)?.iter().enumerate() {
+            for (y, &p) in column.iter().enumerate() {
+                if p <= threshold {
+                    continue;
+                }
+
+                let point = XYPoint {
+                    x: (x as isize + domain.x_min) as i64,
+                    y: (y as isize + domain.y_min) as i64,
+                };
+                let metadata = HashMap::from([("probability".to_string(), p.to_string())]);
+
+                dataset.push(Datapoint::new(point.into(), metadata));
+            }
+        }
+
+        Ok(dataset)
+    }
+
+    /// Returns the probability mass absorbed in time step `t`. See
+    /// [`absorbed_at()`](DynamicProgram::absorbed_at).
+    ///
+    /// Returns an error if time step `t` exceeds the time limit or if one of the time steps `t - 1`
+    /// and `t` is not kept in memory.
+    #[pyo3(name = "absorbed_at")]
+    pub fn py_absorbed_at(&self, t: usize) -> Result<f64, DynamicProgramError> {
+        self.check_time_step(t)?;
+
+        if t > 0 {
+            self.check_time_step(t - 1)?;
+        }
+
+        Ok(self.absorbed_at(t))
+    }
+
+    /// Returns the probability mass absorbed in each time step from `0` to the time limit. See
+    /// [`absorbed_mass()`](DynamicProgram::absorbed_mass).
+    ///
+    /// Returns an error if one of the time steps is not kept in memory.
+    #[pyo3(name = "absorbed_mass")]
+    pub fn py_absorbed_mass(&self) -> Result<Vec<f64>, DynamicProgramError> {
+        for t in 0..=self.time_limit {
+            self.check_time_step(t)?;
         }
+
+        Ok(self.absorbed_mass())
+    }
+
+    /// Returns whether the dynamic program counts paths instead of computing probabilities.
+    #[getter]
+    pub fn counting(&self) -> bool {
+        self.table.is_counting()
     }
 
     pub fn set(&mut self, x: isize, y: isize, t: usize, val: f64) {
-        let x = (self.time_limit as isize + x) as usize;
-        let y = (self.time_limit as isize + y) as usize;
+        let (x, y) = self.domain.index(x, y);
 
-        self.table[t][x][y] = val;
+        self.table.set(x, y, t, val);
     }
 
     fn apply_kernel_at(&mut self, x: isize, y: isize, t: usize) {
         let field_type = self.field_type_at(x, y);
-        let kernel = self.kernels[field_type].clone();
+        let kernel = match self.kernel_schedule.kernel_at(t) {
+            Some(kernel) => kernel.clone(),
+            None => self.kernels[field_type].clone(),
+        };
 
-        let ks = (kernel.size() / 2) as isize;
-        let (limit_neg, limit_pos) = self.limits();
-        let mut sum = 0.0;
+        if self.table.is_counting() {
+            self.count_paths_at(x, y, t, &kernel);
 
-        for i in x - ks..=x + ks {
-            if i < limit_neg || i > limit_pos {
-                continue;
-            }
+            return;
+        }
+
+        let ks = (kernel.size() / 2) as isize;
+        let domain = self.domain;
+        let mut sum = if self.log_space {
+            f64::NEG_INFINITY
+        } else {
+            0.0
+        };
 
-            for j in y - ks..=y + ks {
-                if j < limit_neg || j > limit_pos {
+        for i in (x - ks).max(domain.x_min)..=(x + ks).min(domain.x_max) {
+            for j in (y - ks).max(domain.y_min)..=(y + ks).min(domain.y_max) {
+                // Probability mass in absorbing cells is not redistributed
+                if self.absorbing.contains(&(i, j)) {
                     continue;
                 }
 
@@ -130,27 +862,36 @@ impl DynamicProgram {
                 let kernel_x = x - i;
                 let kernel_y = y - j;
 
-                sum += self.at(i, j, t - 1) * kernel.at(kernel_x, kernel_y);
+                // Movements are scaled by the diffusivity of the field they start at
+                let prob = kernel.diffused_at(kernel_x, kernel_y, self.diffusivity_at(i, j));
+
+                if self.log_space {
+                    sum = log_add(sum, self.at(i, j, t - 1) + prob.ln());
+                } else {
+                    sum += self.at(i, j, t - 1) * prob;
+                }
             }
         }
 
+        // Entering a field is scaled by its probability
+        let field_probability = self.field_probability_at(x, y);
+
+        if self.log_space {
+            sum += field_probability.ln();
+        } else {
+            sum *= field_probability;
+        }
+
         self.set(x, y, t, sum);
     }
 
     fn field_type_at(&self, x: isize, y: isize) -> usize {
-        let x = (self.time_limit as isize + x) as usize;
-        let y = (self.time_limit as isize + y) as usize;
+        let (x, y) = self.domain.index(x, y);
 
         self.field_types[x][y]
     }
 
-    fn field_type_set(&mut self, x: isize, y: isize, val: usize) {
-        let x = (self.time_limit as isize + x) as usize;
-        let y = (self.time_limit as isize + y) as usize;
-
-        self.field_types[x][y] = val;
-    }
-
+    #[cfg(feature = "saving")]
     #[staticmethod]
     #[pyo3(name = "load")]
     pub fn py_load(filename: String) -> anyhow::Result<DynamicProgram> {
@@ -167,14 +908,25 @@ impl DynamicProgram {
         DynamicPrograms::limits(self)
     }
 
-    pub fn compute(&mut self) {
+    pub fn compute(&mut self) -> anyhow::Result<ComputeStats> {
         DynamicPrograms::compute(self)
     }
 
+    #[cfg(feature = "gpu")]
+    pub fn compute_gpu(&mut self) -> anyhow::Result<ComputeStats> {
+        DynamicPrograms::compute_gpu(self)
+    }
+
     pub fn field_types(&self) -> Vec<Vec<usize>> {
         DynamicPrograms::field_types(self)
     }
 
+    /// Returns a structured summary of the dynamic program's configuration. See [`ModelCard`].
+    pub fn model_card(&self) -> ModelCard {
+        ModelCard::from(self)
+    }
+
+    #[cfg(feature = "plotting")]
     pub fn heatmap(&self, path: String, t: usize) -> anyhow::Result<()> {
         DynamicPrograms::heatmap(self, path, t)
     }
@@ -202,112 +954,642 @@ impl DynamicProgram {
 }
 
 impl DynamicProgram {
+    /// Returns the value stored at `(x, y)` in time step `t`.
+    ///
+    /// When computing in log space, this is the natural logarithm of the probability. Use
+    /// [`log_at_or()`](DynamicProgram::log_at_or) to get log-probabilities independent of the
+    /// computation mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if time step `t` is not kept in memory.
+    pub fn at(&self, x: isize, y: isize, t: usize) -> f64 {
+        let (x, y) = self.domain.index(x, y);
+
+        self.table
+            .get(x, y, t)
+            .expect("time step is not kept in memory")
+    }
+
+    pub fn at_or(&self, x: isize, y: isize, t: usize, default: f64) -> f64 {
+        if self.domain.contains(x, y) {
+            self.at(x, y, t)
+        } else {
+            default
+        }
+    }
+
+    /// Returns the natural logarithm of the probability at `(x, y)` in time step `t`.
+    ///
+    /// Points outside of the table have a log-probability of negative infinity.
+    pub fn log_at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        if self.log_space {
+            self.at_or(x, y, t, f64::NEG_INFINITY)
+        } else {
+            self.at_or(x, y, t, 0.0).ln()
+        }
+    }
+
+    /// Returns whether the probability of `(x, y)` in time step `t` is at most
+    /// [`epsilon()`](DynamicProgram::epsilon), i.e. whether the point is considered unreachable.
+    pub fn is_zero(&self, x: isize, y: isize, t: usize) -> bool {
+        self.log_at_or(x, y, t) <= self.epsilon.ln()
+    }
+
+    /// Returns the probability of `(x, y)` in time step `t` independent of the computation mode.
+    ///
+    /// When counting paths, this is the fraction of all paths of length `t` that lead to
+    /// `(x, y)`. Points outside of the table have a probability of zero.
+    pub fn probability_at(&self, x: isize, y: isize, t: usize) -> f64 {
+        if self.log_space {
+            return self.log_at_or(x, y, t).exp();
+        }
+
+        if !self.table.is_counting() {
+            return self.at_or(x, y, t, 0.0);
+        }
+
+        let Some(count) = self.count_at(x, y, t) else {
+            return 0.0;
+        };
+
+        count_fraction(&count, &self.total_count(t))
+    }
+
+    /// Returns the probability mass absorbed in time step `t`, i.e. the probability that a walk
+    /// reaches one of the absorbing cells for the first time in time step `t`.
+    ///
+    /// Requires the time slices `t - 1` and `t` to be available.
+    pub fn absorbed_at(&self, t: usize) -> f64 {
+        self.absorbing
+            .iter()
+            .map(|&(x, y)| {
+                let prev = if t > 0 {
+                    self.probability_at(x, y, t - 1)
+                } else {
+                    0.0
+                };
+
+                self.probability_at(x, y, t) - prev
+            })
+            .sum()
+    }
+
+    /// Returns the probability mass absorbed in each time step from `0` to the time limit. See
+    /// [`absorbed_at()`](DynamicProgram::absorbed_at).
+    pub fn absorbed_mass(&self) -> Vec<f64> {
+        (0..=self.time_limit).map(|t| self.absorbed_at(t)).collect()
+    }
+
+    /// Returns an error if time step `t` exceeds the time limit or is not kept in memory.
+    fn check_time_step(&self, t: usize) -> Result<(), DynamicProgramError> {
+        if t > self.time_limit {
+            return Err(DynamicProgramError::TimeStepOutOfRange(t));
+        }
+
+        if !self.table.keeps(t) {
+            return Err(DynamicProgramError::TimeStepNotInMemory(t));
+        }
+
+        Ok(())
+    }
+
+    /// Loads a dynamic program saved using [`save()`](DynamicPrograms::save).
+    ///
+    /// The kernels, kernel schedule and field types are restored from the header of the file.
+    /// Returns an error if the file is not a saved dynamic program, was saved using a newer
+    /// version of the [file format](crate::dp::format) or is truncated.
     #[cfg(feature = "saving")]
     pub fn load(filename: String) -> anyhow::Result<DynamicProgramPool> {
         let file = File::open(filename)?;
         let reader = BufReader::new(file);
         let mut decoder = Decoder::new(reader).context("could not create decoder")?;
+        let header = Header::read(&mut decoder)?;
+        let time_limit = header.model_card.time_limit;
 
-        let mut time_limit = [0u8; 8];
-        let time_limit = match decoder.read_exact(&mut time_limit) {
-            Ok(()) => u64::from_le_bytes(time_limit),
-            Err(_) => bail!("could not read time limit from file"),
+        let builder = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(time_limit)
+            .kernels(header.kernels.into_iter().enumerate().collect())
+            .kernel_schedule(header.kernel_schedule)
+            .field_types(header.field_types)
+            .epsilon(header.model_card.epsilon)
+            .initial_distribution(
+                header
+                    .model_card
+                    .initial_distribution
+                    .iter()
+                    .map(|&(x, y, weight)| {
+                        let point = XYPoint {
+                            x: x as i64,
+                            y: y as i64,
+                        };
+
+                        (point, weight)
+                    })
+                    .collect(),
+            );
+        let builder = match header.diffusivity {
+            Some(diffusivity) => builder.diffusivity(diffusivity),
+            None => builder,
         };
+        let builder = match header.field_probabilities {
+            Some(field_probabilities) => builder.field_probabilities(field_probabilities),
+            None => builder,
+        };
+        let builder = header
+            .absorbing
+            .into_iter()
+            .fold(builder, |builder, (x, y)| {
+                builder.add_absorbing_cell(XYPoint {
+                    x: x as i64,
+                    y: y as i64,
+                })
+            });
 
-        let DynamicProgramPool::Single(mut dp) = DynamicProgramBuilder::new()
-            .simple()
-            .time_limit(time_limit as usize)
-            .kernel(kernel!(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0))
-            .build()?
-        else {
+        let DynamicProgramPool::Single(mut dp) = builder.build()? else {
             unreachable!();
         };
 
         let (limit_neg, limit_pos) = dp.limits();
         let mut buf = [0u8; 8];
 
-        for t in 0..=limit_pos as usize {
+        for t in 0..=time_limit {
             for x in limit_neg..=limit_pos {
                 for y in limit_neg..=limit_pos {
-                    decoder.read_exact(&mut buf)?;
+                    decoder.read_exact(&mut buf).with_context(|| {
+                        format!("file is truncated, time step {} is incomplete", t)
+                    })?;
                     dp.set(x, y, t, f64::from_le_bytes(buf));
                 }
             }
         }
 
-        for x in limit_neg..=limit_pos {
-            for y in limit_neg..=limit_pos {
-                decoder.read_exact(&mut buf)?;
-                dp.field_type_set(x, y, u64::from_le_bytes(buf) as usize);
-            }
-        }
-
         Ok(DynamicProgramPool::Single(dp))
     }
-}
 
-impl DynamicPrograms for DynamicProgram {
-    #[cfg(not(tarpaulin_include))]
-    fn limits(&self) -> (isize, isize) {
-        (-(self.time_limit as isize), self.time_limit as isize)
+    /// Returns the total number of paths of length `t` when counting paths.
+    fn total_count(&self, t: usize) -> BigUint {
+        let height = self.domain.height();
+
+        (0..self.domain.width())
+            .flat_map(|i| (0..height).filter_map(move |j| self.table.count(i, j, t)))
+            .sum()
     }
 
-    fn compute(&mut self) {
-        let (limit_neg, limit_pos) = self.limits();
+    /// Returns the kernels used for each field type in time step `t`. If a kernel is scheduled
+    /// for `t`, it is used for all field types.
+    pub(crate) fn kernels_at(&self, t: usize) -> Vec<Kernel> {
+        match self.kernel_schedule.kernel_at(t) {
+            Some(kernel) => vec![kernel.clone(); self.kernels.len()],
+            None => self.kernels.clone(),
+        }
+    }
 
-        self.set(0, 0, 0, 1.0);
+    /// Returns the natural logarithm of the probability of `walk` under the model of the dynamic
+    /// program, i.e. of starting at its first point and taking each of its steps in order.
+    ///
+    /// The `k`-th step of the walk is taken in time step `k`, using the same kernels, field types,
+    /// field probabilities, diffusivity and absorbing cells as the computation. Walks that are
+    /// empty, start outside of the initial distribution, leave the table or take steps the kernels
+    /// do not allow have a log-likelihood of negative infinity. Subtracting the
+    /// [`log_at_or()`](Self::log_at_or) of the last point yields the log-likelihood conditioned on
+    /// the end point of the walk.
+    pub fn log_likelihood(&self, walk: &Walk) -> f64 {
+        let Some(start) = walk.0.first() else {
+            return f64::NEG_INFINITY;
+        };
 
-        let start = Instant::now();
+        let start = (start.x as isize, start.y as isize);
+        let mut log_likelihood = self
+            .initial
+            .iter()
+            .find(|&&(cell, _)| cell == start)
+            .map_or(f64::NEG_INFINITY, |&(_, weight)| weight.ln());
 
-        for t in 1..=limit_pos as usize {
-            for x in limit_neg..=limit_pos {
-                for y in limit_neg..=limit_pos {
-                    self.apply_kernel_at(x, y, t);
-                }
+        for (t, step) in walk.0.windows(2).enumerate() {
+            let (x, y) = (step[0].x as isize, step[0].y as isize);
+            let (i, j) = (step[1].x as isize, step[1].y as isize);
+
+            if !self.contains(i, j) {
+                return f64::NEG_INFINITY;
             }
-        }
 
-        let duration = start.elapsed();
+            // Walks reaching an absorbing cell stay there
+            let prob = if self.absorbing.contains(&(x, y)) {
+                if (x, y) == (i, j) {
+                    1.0
+                } else {
+                    0.0
+                }
+            } else {
+                let kernels = self.kernels_at(t + 1);
+                let kernel = &kernels[self.field_type_at(i, j)];
+
+                kernel_prob(kernel, i - x, j - y, self.diffusivity_at(x, y))
+                    * self.field_probability_at(i, j)
+            };
+
+            log_likelihood += prob.ln();
+        }
 
-        println!("Computation took {:?}", duration);
+        log_likelihood
     }
 
-    fn compute_parallel(&mut self) {
-        let (limit_neg, limit_pos) = self.limits();
-        let kernels = Arc::new(RwLock::new(self.kernels.clone()));
+    /// Returns the probability of the field at `(x, y)`.
+    pub(crate) fn field_probability_at(&self, x: isize, y: isize) -> f64 {
+        match &self.field_probabilities {
+            Some(field_probabilities) => {
+                let (x, y) = self.domain.index(x, y);
+
+                field_probabilities[x][y]
+            }
+            None => 1.0,
+        }
+    }
+
+    /// Returns the diffusivity of the field at `(x, y)`.
+    pub(crate) fn diffusivity_at(&self, x: isize, y: isize) -> f64 {
+        match &self.diffusivity {
+            Some(diffusivity) => {
+                let (x, y) = self.domain.index(x, y);
+
+                diffusivity[x][y]
+            }
+            None => 1.0,
+        }
+    }
+
+    /// Sets the number of paths leading to `(x, y)` in time step `t` to the sum of the path counts
+    /// of all fields in time step `t - 1` from which `kernel` allows a step to `(x, y)`.
+    fn count_paths_at(&mut self, x: isize, y: isize, t: usize, kernel: &Kernel) {
+        let ks = (kernel.size() / 2) as isize;
+        let domain = self.domain;
+        let mut count = BigUint::default();
+
+        // Barriers are not entered by any path
+        if self.field_probability_at(x, y) == 0.0 {
+            let (x, y) = domain.index(x, y);
+            self.table.set_count(x, y, t, count);
+
+            return;
+        }
+
+        for i in (x - ks).max(domain.x_min)..=(x + ks).min(domain.x_max) {
+            for j in (y - ks).max(domain.y_min)..=(y + ks).min(domain.y_max) {
+                if kernel.diffused_at(x - i, y - j, self.diffusivity_at(i, j)) > 0.0 {
+                    let (i, j) = domain.index(i, j);
+
+                    count += self.table.count(i, j, t - 1).unwrap();
+                }
+            }
+        }
+
+        let (x, y) = domain.index(x, y);
+
+        self.table.set_count(x, y, t, count);
+    }
+
+    /// Adds the probability mass already absorbed in time step `t - 1` to all absorbing cells,
+    /// so that it accumulates there.
+    fn absorb(&mut self, t: usize) {
+        for (x, y) in self.absorbing.clone() {
+            let (prev, cur) = (self.at(x, y, t - 1), self.at(x, y, t));
+
+            if self.log_space {
+                self.set(x, y, t, log_add(prev, cur));
+            } else {
+                self.set(x, y, t, prev + cur);
+            }
+        }
+    }
+
+    /// Computes an auxiliary copy of the dynamic program in which all cells in `region` are
+    /// absorbing, and returns the probability mass absorbed in `region` up to each time step.
+    fn absorbed_in_region(&self, region: &[XYPoint]) -> anyhow::Result<Vec<f64>> {
+        if self.table.is_counting() {
+            bail!("dynamic programs counting paths do not support absorbing cells");
+        }
+
+        if region.is_empty() {
+            bail!("region does not contain any cells");
+        }
+
+        let mut cells = HashSet::new();
+
+        for point in region {
+            let (x, y) = (point.x as isize, point.y as isize);
+
+            if !self.contains(x, y) {
+                bail!("point ({}, {}) is outside of the table", x, y);
+            }
+
+            cells.insert((x, y));
+        }
+
+        let mut dp = DynamicProgram {
+            table: Table::full(self.time_limit, self.domain),
+            time_limit: self.time_limit,
+            domain: self.domain,
+            kernels: self.kernels.clone(),
+            field_types: self.field_types.clone(),
+            kernel_schedule: self.kernel_schedule.clone(),
+            step_callbacks: Vec::new(),
+            barriers: self.barriers,
+            absorbing: self.absorbing.union(&cells).copied().collect(),
+            initial: self.initial.clone(),
+            compute_duration: None,
+            epsilon: self.epsilon,
+            checkpoint: None,
+            log_space: false,
+            threads: self.threads,
+            chunks: self.chunks,
+            diffusivity: self.diffusivity.clone(),
+            field_probabilities: self.field_probabilities.clone(),
+        };
+
+        DynamicPrograms::compute(&mut dp)?;
+
+        Ok((0..=self.time_limit)
+            .map(|t| cells.iter().map(|&(x, y)| dp.at(x, y, t)).sum())
+            .collect())
+    }
+
+    /// Opens a [`SliceWriter`] if completed slices should be streamed to disk.
+    pub(crate) fn slice_writer(&self) -> anyhow::Result<Option<SliceWriter>> {
+        self.table
+            .path()
+            .map(|path| {
+                SliceWriter::create(path, self).context("could not create file for rolling storage")
+            })
+            .transpose()
+    }
+
+    /// Initializes time step 0 or restores the table from a checkpoint if one exists.
+    ///
+    /// Returns the first time step that still has to be computed.
+    pub(crate) fn start_computation(
+        &mut self,
+        writer: &mut Option<SliceWriter>,
+    ) -> anyhow::Result<usize> {
+        if self.checkpoint.is_some() {
+            let fingerprint = Checkpoint::fingerprint(self)?;
+
+            if let Some(checkpoint) = &mut self.checkpoint {
+                let restored = checkpoint
+                    .restore(&mut self.table, fingerprint, self.time_limit, self.domain)
+                    .context("could not restore checkpoint")?;
+
+                if let Some(t) = restored {
+                    return Ok(t + 1);
+                }
+            }
+        }
+
+        if self.log_space {
+            let domain = self.domain;
+
+            for x in domain.x_min..=domain.x_max {
+                for y in domain.y_min..=domain.y_max {
+                    self.set(x, y, 0, f64::NEG_INFINITY);
+                }
+            }
+
+            for ((x, y), weight) in self.initial.clone() {
+                self.set(x, y, 0, weight.ln());
+            }
+        } else {
+            for ((x, y), weight) in self.initial.clone() {
+                self.set(x, y, 0, weight);
+            }
+        }
+
+        self.finish_slice(0, writer)?;
+
+        Ok(1)
+    }
+
+    /// Finishes the computation of time step `t`, invokes all step callbacks, streams the slice
+    /// to disk and writes a checkpoint if requested.
+    pub(crate) fn finish_slice(
+        &mut self,
+        t: usize,
+        writer: &mut Option<SliceWriter>,
+    ) -> anyhow::Result<()> {
+        self.table.finish_slice(t);
+
+        if let Some(checkpoint) = &mut self.checkpoint {
+            if checkpoint.is_due(t) {
+                checkpoint
+                    .write(&self.table, t)
+                    .context("could not write checkpoint")?;
+            }
+        }
+
+        if !self.step_callbacks.is_empty() {
+            // Callbacks receive probabilities independent of the computation mode
+            let slice = self.slice(t)?;
+
+            for callback in self.step_callbacks.iter() {
+                let mut callback = callback.lock().unwrap();
+                (*callback)(t, &slice);
+            }
+        }
+
+        if let Some(writer) = writer {
+            let slice = self
+                .table
+                .slice(t)
+                .context("time step is not kept in memory")?;
+
+            writer
+                .write_slice(&slice)
+                .context("could not write slice to rolling storage")?;
+        }
+
+        Ok(())
+    }
+
+    /// Finishes the computation by writing the remaining data to disk if requested and removing
+    /// the checkpoint, which is not needed anymore.
+    pub(crate) fn finish_writer(&mut self, writer: Option<SliceWriter>) -> anyhow::Result<()> {
+        if let Some(writer) = writer {
+            writer
+                .finish()
+                .context("could not finish writing rolling storage")?;
+        }
+
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.remove().context("could not remove checkpoint")?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the total probability mass in time step `t`.
+    fn total_probability(&self, t: usize) -> f64 {
+        // Path counts are normalized in every time step, so no mass can be lost
+        if self.table.is_counting() {
+            return 1.0;
+        }
+
+        let domain = self.domain;
+
+        (domain.x_min..=domain.x_max)
+            .flat_map(|x| (domain.y_min..=domain.y_max).map(move |y| (x, y)))
+            .map(|(x, y)| self.probability_at(x, y, t))
+            .sum()
+    }
+
+    /// Collects the statistics of a computation that started at time step `first` with a total
+    /// probability mass of `mass`, and records its duration.
+    fn finish_stats(
+        &mut self,
+        first: usize,
+        mass: f64,
+        initialization: Duration,
+        computation: Duration,
+        finalization: Duration,
+    ) -> ComputeStats {
+        let time_steps = (self.time_limit + 1).saturating_sub(first);
+        let stats = ComputeStats {
+            initialization,
+            computation,
+            finalization,
+            time_steps,
+            cells_computed: (self.domain.width() * self.domain.height() * time_steps) as u64,
+            mass_lost: mass - self.total_probability(self.time_limit),
+        };
+
+        info!("Computation took {:?}", stats.total());
+
+        self.compute_duration = Some(stats.total());
+
+        stats
+    }
+}
+
+impl DpAccess for DynamicProgram {
+    fn log_at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        DynamicProgram::log_at_or(self, x, y, t)
+    }
+
+    fn at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        self.probability_at(x, y, t)
+    }
+
+    fn is_zero(&self, x: isize, y: isize, t: usize) -> bool {
+        DynamicProgram::is_zero(self, x, y, t)
+    }
+}
+
+impl DynamicPrograms for DynamicProgram {
+    #[cfg(not(tarpaulin_include))]
+    fn limits(&self) -> (isize, isize) {
+        (-(self.time_limit as isize), self.time_limit as isize)
+    }
+
+    fn compute(&mut self) -> anyhow::Result<ComputeStats> {
+        let domain = self.domain;
+
+        let start = Instant::now();
+        let mut writer = self.slice_writer()?;
+        let first = self.start_computation(&mut writer)?;
+        let mass = self.total_probability(first - 1);
+        let initialization = start.elapsed();
+
+        let start = Instant::now();
+
+        for t in first..=self.time_limit {
+            for x in domain.x_min..=domain.x_max {
+                for y in domain.y_min..=domain.y_max {
+                    self.apply_kernel_at(x, y, t);
+                }
+            }
+
+            self.absorb(t);
+            self.finish_slice(t, &mut writer)?;
+
+            debug!("Computed time step {} of {}", t, self.time_limit);
+        }
+
+        let computation = start.elapsed();
+
+        let start = Instant::now();
+        self.finish_writer(writer)?;
+        let finalization = start.elapsed();
+
+        Ok(self.finish_stats(first, mass, initialization, computation, finalization))
+    }
+
+    fn compute_parallel(&mut self) -> anyhow::Result<ComputeStats> {
+        // Path counts are not representable in the slices passed to the worker threads
+        if self.table.is_counting() {
+            return self.compute();
+        }
+
+        let domain = self.domain;
         let field_types = Arc::new(RwLock::new(self.field_types.clone()));
-        let pool = Pool::<ThunkWorker<(Range<isize>, Range<isize>, Vec<Vec<f64>>)>>::new(10);
+        let diffusivity = Arc::new(RwLock::new(self.diffusivity.clone()));
+        let field_probabilities = Arc::new(RwLock::new(self.field_probabilities.clone()));
+        let pool =
+            Pool::<ThunkWorker<(Range<isize>, Range<isize>, Vec<Vec<f64>>)>>::new(self.threads);
         let (tx, rx) = channel();
 
         // Define chunks
 
-        let chunk_size = ((self.time_limit + 1) / 3) as isize;
-        let mut ranges = Vec::new();
+        let chunks_per_axis = self.chunks as isize;
+        let chunk_ranges = |min: isize, max: isize| {
+            let len = max - min + 1;
+            let n = chunks_per_axis.min(len);
 
-        for i in 0..3 - 1 {
-            ranges.push((limit_neg + i * chunk_size..limit_neg + (i + 1) * chunk_size));
-        }
+            (0..n)
+                .map(|i| min + i * len / n..min + (i + 1) * len / n)
+                .collect::<Vec<_>>()
+        };
 
-        ranges.push(limit_neg + 2 * chunk_size..limit_pos + 1);
+        let x_ranges = chunk_ranges(domain.x_min, domain.x_max);
+        let y_ranges = chunk_ranges(domain.y_min, domain.y_max);
         let mut chunks = Vec::new();
 
-        for x in 0..3 {
-            for y in 0..3 {
-                chunks.push((ranges[x].clone(), ranges[y].clone()));
+        for x_range in x_ranges.iter() {
+            for y_range in y_ranges.iter() {
+                chunks.push((x_range.clone(), y_range.clone()));
             }
         }
 
-        self.set(0, 0, 0, 1.0);
+        let start = Instant::now();
+        let mut writer = self.slice_writer()?;
+        let first = self.start_computation(&mut writer)?;
+        let mass = self.total_probability(first - 1);
+        let initialization = start.elapsed();
 
         let start = Instant::now();
 
-        for t in 1..=limit_pos as usize {
-            let table_old = Arc::new(RwLock::new(self.table[t - 1].clone()));
+        for t in first..=self.time_limit {
+            let mut table_old = self.table.slice(t - 1).unwrap().into_owned();
+
+            // Probability mass in absorbing cells is not redistributed
+            for &(x, y) in self.absorbing.iter() {
+                let (x, y) = domain.index(x, y);
+
+                table_old[x][y] = if self.log_space {
+                    f64::NEG_INFINITY
+                } else {
+                    0.0
+                };
+            }
+
+            let table_old = Arc::new(RwLock::new(table_old));
+            let kernels = Arc::new(RwLock::new(self.kernels_at(t)));
 
             for (x_range, y_range) in chunks.clone() {
                 let kernels = kernels.clone();
                 let field_types = field_types.clone();
+                let diffusivity = diffusivity.clone();
+                let field_probabilities = field_probabilities.clone();
                 let table_old = table_old.clone();
+                let log_space = self.log_space;
 
                 pool.execute_to(
                     tx.clone(),
@@ -321,9 +1603,12 @@ impl DynamicPrograms for DynamicProgram {
                                     &table_old.read().unwrap(),
                                     &kernels.read().unwrap(),
                                     &field_types.read().unwrap(),
-                                    (limit_neg, limit_pos),
+                                    &diffusivity.read().unwrap(),
+                                    &field_probabilities.read().unwrap(),
+                                    &domain,
                                     x,
                                     y,
+                                    log_space,
                                 );
 
                                 j += 1;
@@ -338,13 +1623,12 @@ impl DynamicPrograms for DynamicProgram {
                 );
             }
 
-            for (x_range, y_range, probs) in rx.iter().take(9) {
+            for (x_range, y_range, probs) in rx.iter().take(chunks.len()) {
                 let (mut i, mut j) = (0, 0);
 
                 for x in x_range.clone() {
                     for y in y_range.clone() {
-                        self.table[t][(self.time_limit as isize + x) as usize]
-                            [(self.time_limit as isize + y) as usize] = probs[i][j];
+                        self.set(x, y, t, probs[i][j]);
 
                         j += 1;
                     }
@@ -353,14 +1637,58 @@ impl DynamicPrograms for DynamicProgram {
                     j = 0;
                 }
             }
+
+            self.absorb(t);
+            self.finish_slice(t, &mut writer)?;
+
+            debug!("Computed time step {} of {}", t, self.time_limit);
         }
 
-        let duration = start.elapsed();
+        let computation = start.elapsed();
+
+        let start = Instant::now();
+        self.finish_writer(writer)?;
+        let finalization = start.elapsed();
 
-        println!("Computation took {:?}", duration);
+        Ok(self.finish_stats(first, mass, initialization, computation, finalization))
     }
 
     #[cfg(not(tarpaulin_include))]
+    #[cfg(feature = "gpu")]
+    fn compute_gpu(&mut self) -> anyhow::Result<ComputeStats> {
+        if self.log_space {
+            bail!("dynamic programs computed in log space cannot be computed on the GPU");
+        }
+
+        if self.table.is_counting() {
+            bail!("dynamic programs counting paths cannot be computed on the GPU");
+        }
+
+        if !self.absorbing.is_empty() {
+            bail!("dynamic programs with absorbing cells cannot be computed on the GPU");
+        }
+
+        if self.diffusivity.is_some() {
+            bail!("dynamic programs with a diffusivity field cannot be computed on the GPU");
+        }
+
+        let start = Instant::now();
+        let mut writer = self.slice_writer()?;
+        let first = self.start_computation(&mut writer)?;
+        let mass = self.total_probability(first - 1);
+        let initialization = start.elapsed();
+
+        let start = Instant::now();
+        crate::dp::gpu::compute(self, first, &mut writer)?;
+        let computation = start.elapsed();
+
+        let start = Instant::now();
+        self.finish_writer(writer)?;
+        let finalization = start.elapsed();
+
+        Ok(self.finish_stats(first, mass, initialization, computation, finalization))
+    }
+
     fn field_types(&self) -> Vec<Vec<usize>> {
         self.field_types.clone()
     }
@@ -368,53 +1696,12 @@ impl DynamicPrograms for DynamicProgram {
     #[cfg(not(tarpaulin_include))]
     #[cfg(feature = "plotting")]
     fn heatmap(&self, path: String, t: usize) -> anyhow::Result<()> {
-        let (limit_neg, limit_pos) = self.limits();
-        let coordinate_range = limit_neg as i32..(limit_pos + 1) as i32;
-
+        let path = workspace::output_path(&path, "plot")?;
         let root = BitMapBackend::new(&path, (1000, 1000)).into_drawing_area();
         root.fill(&WHITE).unwrap();
         let root = root.margin(10, 10, 10, 10);
 
-        let mut chart = ChartBuilder::on(&root)
-            .caption(format!("Heatmap for t = {}", t), ("sans-serif", 20))
-            .x_label_area_size(40)
-            .y_label_area_size(40)
-            .build_cartesian_2d(coordinate_range.clone(), coordinate_range.clone())?;
-
-        chart.configure_mesh().draw()?;
-
-        let iter = self.table[t].iter().enumerate().flat_map(|(x, l)| {
-            l.iter()
-                .enumerate()
-                .map(move |(y, v)| (x as i32 - limit_pos as i32, y as i32 - limit_pos as i32, v))
-        });
-
-        let min = iter
-            .clone()
-            .min_by(|(_, _, v1), (_, _, v2)| v1.total_cmp(v2))
-            .context("Could not compute minimum value")?
-            .2;
-        let max = iter
-            .clone()
-            .max_by(|(_, _, v1), (_, _, v2)| v1.total_cmp(v2))
-            .context("Could not compute minimum value")?
-            .2;
-
-        chart.draw_series(PointSeries::of_element(iter, 1, &BLACK, &|c, s, _st| {
-            Rectangle::new(
-                [(c.0, c.1), (c.0 + s, c.1 + s)],
-                HSLColor(
-                    (*c.2 - min) / (max - min),
-                    0.7,
-                    if c.2.is_zero() {
-                        0.0
-                    } else {
-                        ((*c.2 - min).ln_1p() / (max - min).ln_1p()).clamp(0.1, 1.0)
-                    },
-                )
-                .filled(),
-            )
-        }))?;
+        self.draw_heatmap(&root, t, &format!("Heatmap for t = {}", t))?;
 
         root.present()?;
 
@@ -423,9 +1710,14 @@ impl DynamicPrograms for DynamicProgram {
 
     #[cfg(not(tarpaulin_include))]
     fn print(&self, t: usize) {
-        for y in 0..2 * self.time_limit + 1 {
-            for x in 0..2 * self.time_limit + 1 {
-                print!("{} ", self.table[t][x][y]);
+        let Some(slice) = self.table.slice(t) else {
+            println!("Time step {} is not kept in memory", t);
+            return;
+        };
+
+        for y in 0..self.domain.height() {
+            for x in 0..self.domain.width() {
+                print!("{} ", slice[x][y]);
             }
 
             println!();
@@ -434,8 +1726,24 @@ impl DynamicPrograms for DynamicProgram {
 
     #[cfg(feature = "saving")]
     fn save(&self, filename: String) -> anyhow::Result<()> {
+        if !self.table.is_full() {
+            bail!("dynamic programs using rolling storage cannot be saved");
+        }
+
+        if self.log_space {
+            bail!("dynamic programs computed in log space cannot be saved");
+        }
+
+        if self.table.is_counting() {
+            bail!("dynamic programs counting paths cannot be saved");
+        }
+
+        if !self.domain.is_square(self.time_limit) {
+            bail!("dynamic programs with a bounding box cannot be saved");
+        }
+
         let (limit_neg, limit_pos) = self.limits();
-        let file = File::create(filename)?;
+        let file = File::create(workspace::output_path(&filename, "dynamic_program")?)?;
         let writer = BufWriter::new(file);
         let mut encoder = Encoder::new(writer, 9).context("could not create encoder")?;
 
@@ -445,21 +1753,82 @@ impl DynamicPrograms for DynamicProgram {
 
         let mut encoder = encoder.auto_finish();
 
-        encoder.write(&(self.time_limit as u64).to_le_bytes())?;
+        Header::new(self).write(&mut encoder)?;
 
         for t in 0..=limit_pos as usize {
             for x in limit_neg..=limit_pos {
                 for y in limit_neg..=limit_pos {
-                    encoder.write(&self.at(x, y, t).to_le_bytes())?;
+                    encoder.write_all(&self.at(x, y, t).to_le_bytes())?;
                 }
             }
         }
 
-        for x in limit_neg..=limit_pos {
-            for y in limit_neg..=limit_pos {
-                encoder.write(&(self.field_type_at(x, y) as u64).to_le_bytes())?;
-            }
-        }
+        Ok(())
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(feature = "plotting")]
+impl DynamicProgram {
+    /// Draws the heatmap of time step `t` onto `area`, e.g. a single cell of a grid of heatmaps.
+    pub(crate) fn draw_heatmap(
+        &self,
+        area: &DrawingArea<BitMapBackend<'_>, Shift>,
+        t: usize,
+        caption: &str,
+    ) -> anyhow::Result<()> {
+        let domain = self.domain;
+        let x_range = domain.x_min as i32..(domain.x_max + 1) as i32;
+        let y_range = domain.y_min as i32..(domain.y_max + 1) as i32;
+
+        let mut chart = ChartBuilder::on(area)
+            .caption(caption, ("sans-serif", 20))
+            .x_label_area_size(40)
+            .y_label_area_size(40)
+            .build_cartesian_2d(x_range, y_range)?;
+
+        chart.configure_mesh().draw()?;
+
+        let slice = self
+            .table
+            .slice(t)
+            .context("time step is not kept in memory by the rolling storage")?;
+        let iter = slice.iter().enumerate().flat_map(|(x, l)| {
+            l.iter().enumerate().map(move |(y, v)| {
+                (
+                    x as i32 + domain.x_min as i32,
+                    y as i32 + domain.y_min as i32,
+                    v,
+                )
+            })
+        });
+
+        let min = iter
+            .clone()
+            .min_by(|(_, _, v1), (_, _, v2)| v1.total_cmp(v2))
+            .context("Could not compute minimum value")?
+            .2;
+        let max = iter
+            .clone()
+            .max_by(|(_, _, v1), (_, _, v2)| v1.total_cmp(v2))
+            .context("Could not compute minimum value")?
+            .2;
+
+        chart.draw_series(PointSeries::of_element(iter, 1, &BLACK, &|c, s, _st| {
+            Rectangle::new(
+                [(c.0, c.1), (c.0 + s, c.1 + s)],
+                HSLColor(
+                    (*c.2 - min) / (max - min),
+                    0.7,
+                    if c.2.is_zero() {
+                        0.0
+                    } else {
+                        ((*c.2 - min).ln_1p() / (max - min).ln_1p()).clamp(0.1, 1.0)
+                    },
+                )
+                .filled(),
+            )
+        }))?;
 
         Ok(())
     }
@@ -469,40 +1838,73 @@ fn apply_kernel(
     table_old: &Vec<Vec<f64>>,
     kernels: &Vec<Kernel>,
     field_types: &Vec<Vec<usize>>,
-    (limit_neg, limit_pos): (isize, isize),
+    diffusivity: &Option<Vec<Vec<f64>>>,
+    field_probabilities: &Option<Vec<Vec<f64>>>,
+    domain: &Domain,
     x: isize,
     y: isize,
+    log_space: bool,
 ) -> f64 {
-    let field_type = field_types[(limit_pos + x) as usize][(limit_pos + y) as usize];
-    let kernel = kernels[field_type].clone();
+    let (xi, yi) = domain.index(x, y);
+    let kernel = kernels[field_types[xi][yi]].clone();
 
     let ks = (kernel.size() / 2) as isize;
-    let mut sum = 0.0;
-
-    for i in x - ks..=x + ks {
-        if i < limit_neg || i > limit_pos {
-            continue;
-        }
-
-        for j in y - ks..=y + ks {
-            if j < limit_neg || j > limit_pos {
-                continue;
-            }
+    let mut sum = if log_space { f64::NEG_INFINITY } else { 0.0 };
 
+    for i in (x - ks).max(domain.x_min)..=(x + ks).min(domain.x_max) {
+        for j in (y - ks).max(domain.y_min)..=(y + ks).min(domain.y_max) {
             // Kernel coordinates are inverted offset, i.e. -(i - x) and -(j - y)
             let kernel_x = x - i;
             let kernel_y = y - j;
 
-            sum += table_old[(limit_pos + i) as usize][(limit_pos + j) as usize]
-                * kernel.at(kernel_x, kernel_y);
+            let (ii, ji) = domain.index(i, j);
+            let prev = table_old[ii][ji];
+            let diffusivity = diffusivity.as_ref().map_or(1.0, |d| d[ii][ji]);
+            let prob = kernel.diffused_at(kernel_x, kernel_y, diffusivity);
+
+            if log_space {
+                sum = log_add(sum, prev + prob.ln());
+            } else {
+                sum += prev * prob;
+            }
         }
     }
 
-    sum
+    let field_probability = field_probabilities.as_ref().map_or(1.0, |fp| fp[xi][yi]);
+
+    if log_space {
+        sum + field_probability.ln()
+    } else {
+        sum * field_probability
+    }
 }
 
-#[cfg(not(tarpaulin_include))]
-impl Debug for DynamicProgram {
+/// Returns `count / total` as a floating point number, or zero if `total` is zero.
+fn count_fraction(count: &BigUint, total: &BigUint) -> f64 {
+    if *total == BigUint::default() {
+        return 0.0;
+    }
+
+    BigRational::new(count.clone().into(), total.clone().into())
+        .to_f64()
+        .unwrap_or(0.0)
+}
+
+/// Returns `ln(exp(a) + exp(b))` without leaving log space.
+fn log_add(a: f64, b: f64) -> f64 {
+    if a == f64::NEG_INFINITY {
+        return b;
+    }
+
+    if b == f64::NEG_INFINITY {
+        return a;
+    }
+
+    a.max(b) + (-(a - b).abs()).exp().ln_1p()
+}
+
+#[cfg(not(tarpaulin_include))]
+impl Debug for DynamicProgram {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DynamicProgram")
             .field("time_limit", &self.time_limit)
@@ -513,8 +1915,12 @@ impl Debug for DynamicProgram {
 impl PartialEq for DynamicProgram {
     fn eq(&self, other: &Self) -> bool {
         self.time_limit == other.time_limit
+            && self.domain == other.domain
             && self.table == other.table
             && self.field_types == other.field_types
+            && self.field_probabilities == other.field_probabilities
+            && self.absorbing == other.absorbing
+            && self.initial == other.initial
     }
 }
 
@@ -522,22 +1928,31 @@ impl Eq for DynamicProgram {}
 
 #[cfg(test)]
 mod tests {
-    use crate::dp::builder::DynamicProgramBuilder;
-    use crate::dp::{DynamicProgram, DynamicProgramPool, DynamicPrograms};
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::{DynamicProgramBuilder, DynamicProgramBuilderError};
+    #[cfg(feature = "saving")]
+    use crate::dp::checkpoint::Checkpoint;
+    use crate::dp::{DynamicProgram, DynamicProgramError, DynamicProgramPool, DynamicPrograms};
     use crate::kernel::biased_rw::BiasedRwGenerator;
     use crate::kernel::simple_rw::SimpleRwGenerator;
     use crate::kernel::{Direction, Kernel};
+    use crate::walk::Walk;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::{Walker, WalkerError};
+    use crate::xy;
+    use num::BigUint;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_simple_dp_at() {
         let mut dp = DynamicProgramBuilder::new()
             .simple()
             .time_limit(10)
-            .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
             .build()
             .unwrap();
 
-        dp.compute();
+        dp.compute().unwrap();
 
         let DynamicProgramPool::Single(dp) = dp else {
             unreachable!();
@@ -551,7 +1966,7 @@ mod tests {
         let dp = DynamicProgramBuilder::new()
             .simple()
             .time_limit(10)
-            .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
             .build()
             .unwrap();
 
@@ -564,44 +1979,45 @@ mod tests {
         assert_eq!(dp.at(0, 0, 0,), 10.0);
     }
 
-    // #[test]
-    // #[rustfmt::skip]
-    // fn test_simple_dp_apply_kernel_at() {
-    //     let mut fps = vec![vec![1.0; 21]; 21];
-    //
-    //     fps[10][10] = 0.75;
-    //
-    //     let dp = DynamicProgramBuilder::new()
-    //         .simple()
-    //         .time_limit(10)
-    //         .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
-    //         .field_probabilities(fps)
-    //         .build()
-    //         .unwrap();
-    //
-    //     let DynamicProgram::Simple(mut dp) = dp else {
-    //         unreachable!();
-    //     };
-    //
-    //     dp.set(0, 0, 0, 0.5);
-    //     dp.set(-1, 0, 0, 0.5);
-    //     dp.apply_kernel_at(0, 0, 1);
-    //
-    //     let rounded_res = format!("{:.2}", dp.at(0, 0, 1)).parse::<f64>().unwrap();
-    //
-    //     assert_eq!(rounded_res, 0.15);
-    // }
+    #[test]
+    fn test_simple_dp_apply_kernel_at() {
+        let mut fps = vec![vec![1.0; 21]; 21];
+
+        fps[10][10] = 0.75;
+
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .field_probabilities(fps)
+            .build()
+            .unwrap();
+
+        let DynamicProgramPool::Single(mut dp) = dp else {
+            unreachable!();
+        };
+
+        dp.set(0, 0, 0, 0.5);
+        dp.set(-1, 0, 0, 0.5);
+        dp.apply_kernel_at(0, 0, 1);
+
+        assert!((dp.at(0, 0, 1) - 0.15).abs() < 1e-12);
+
+        dp.apply_kernel_at(-1, 0, 1);
+
+        assert!((dp.at(-1, 0, 1) - 0.2).abs() < 1e-12);
+    }
 
     #[test]
     fn test_compute() {
         let mut dp = DynamicProgramBuilder::new()
             .simple()
             .time_limit(1)
-            .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
             .build()
             .unwrap();
 
-        dp.compute();
+        dp.compute().unwrap();
 
         let DynamicProgramPool::Single(mut dp) = dp else {
             unreachable!();
@@ -614,25 +2030,1152 @@ mod tests {
         assert_eq!(dp.at(0, 1, 1), 0.2);
     }
 
+    #[test]
+    fn test_compute_kernel_schedule() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(2)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .kernel_schedule(vec![(
+                2..3,
+                Kernel::from_generator(BiasedRwGenerator {
+                    probability: 1.0,
+                    direction: Direction::North,
+                })
+                .unwrap(),
+            )])
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        assert_eq!(dp.at(0, 0, 1), 0.2);
+        assert_eq!(dp.at(0, -1, 2), 0.2);
+        assert_eq!(dp.at(0, -2, 2), 0.2);
+        assert_eq!(dp.at(0, 0, 2), 0.2);
+        assert_eq!(dp.at(0, 1, 2), 0.0);
+    }
+
+    #[test]
+    fn test_compute_rolling_storage() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut full = builder().build().unwrap();
+        let mut rolling = builder()
+            .rolling_storage(None)
+            .capture_time_steps(vec![5])
+            .build()
+            .unwrap();
+
+        full.compute().unwrap();
+        rolling.compute().unwrap();
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(rolling) = rolling else {
+            unreachable!();
+        };
+
+        for t in [5, 9, 10] {
+            for x in -10..=10 {
+                for y in -10..=10 {
+                    assert_eq!(full.at(x, y, t), rolling.at(x, y, t));
+                }
+            }
+        }
+
+        assert!(rolling.table.slice(3).is_none());
+        assert!(matches!(
+            rolling.py_at(0, 0, 3),
+            Err(DynamicProgramError::TimeStepNotInMemory(3))
+        ));
+        assert!(matches!(
+            rolling.py_probability_at(0, 0, 11),
+            Err(DynamicProgramError::TimeStepOutOfRange(11))
+        ));
+        assert!(rolling.py_absorbed_mass().is_err());
+        assert_eq!(rolling.py_at(0, 0, 5).unwrap(), full.at(0, 0, 5));
+    }
+
+    #[test]
+    fn test_compute_rolling_storage_unwritable() {
+        let path = std::env::temp_dir()
+            .join("randomwalks_test_missing_directory")
+            .join("rolling_storage.dp");
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .rolling_storage(Some(path.to_str().unwrap().to_string()))
+            .build()
+            .unwrap();
+
+        assert!(dp.compute().is_err());
+    }
+
+    #[test]
+    fn test_compute_sparse_storage() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut full = builder().build().unwrap();
+        let mut sparse = builder().sparse_storage().build().unwrap();
+
+        full.compute().unwrap();
+        sparse.compute().unwrap();
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(sparse) = sparse else {
+            unreachable!();
+        };
+
+        for t in 0..=10 {
+            for x in -10..=10 {
+                for y in -10..=10 {
+                    assert_eq!(full.at(x, y, t), sparse.at(x, y, t));
+                }
+            }
+        }
+
+        assert!(builder()
+            .sparse_storage()
+            .single_precision()
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_compute_single_precision() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut double = builder().build().unwrap();
+        let mut single = builder().single_precision().build().unwrap();
+
+        double.compute().unwrap();
+        single.compute().unwrap();
+
+        let DynamicProgramPool::Single(double) = double else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(single) = single else {
+            unreachable!();
+        };
+
+        for t in 0..=10 {
+            for x in -10..=10 {
+                for y in -10..=10 {
+                    assert!((double.at(x, y, t) - single.at(x, y, t)).abs() < 1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_log_space() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut linear = builder().build().unwrap();
+        let mut log = builder().log_space().build().unwrap();
+
+        linear.compute().unwrap();
+        log.compute().unwrap();
+
+        let walker =
+            StandardWalker::new(Kernel::from_generator(SimpleRwGenerator::default()).unwrap());
+
+        assert!(walker.generate_path(&log, 2, 3, 10).is_ok());
+
+        let DynamicProgramPool::Single(linear) = linear else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(log) = log else {
+            unreachable!();
+        };
+
+        assert!(log.log_space());
+        assert_eq!(log.at(1, 0, 0), f64::NEG_INFINITY);
+
+        for t in 0..=10 {
+            for x in -10..=10 {
+                for y in -10..=10 {
+                    assert!((linear.at(x, y, t) - log.at(x, y, t).exp()).abs() < 1e-12);
+                    assert_eq!(linear.log_at_or(x, y, t), linear.at(x, y, t).ln());
+                }
+            }
+        }
+
+        assert!(builder().log_space().sparse_storage().build().is_err());
+    }
+
+    #[test]
+    fn test_compute_counting() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut probs = builder().build().unwrap();
+        let mut counts = builder().counting().build().unwrap();
+
+        probs.compute().unwrap();
+        counts.compute().unwrap();
+
+        let DynamicProgramPool::Single(probs) = probs else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(counts) = counts else {
+            unreachable!();
+        };
+
+        assert!(counts.counting());
+        assert_eq!(counts.count_at(0, 0, 1), Some(BigUint::from(1u32)));
+        assert_eq!(counts.count_at(0, 0, 2), Some(BigUint::from(5u32)));
+        assert_eq!(counts.count_at(11, 0, 2), None);
+        assert_eq!(probs.count_at(0, 0, 2), None);
+
+        let total: BigUint = (-10..=10)
+            .flat_map(|x| (-10..=10).map(move |y| (x, y)))
+            .map(|(x, y)| counts.count_at(x, y, 10).unwrap())
+            .sum();
+
+        // The default kernel allows five equally likely steps
+        assert_eq!(total, BigUint::from(5u32).pow(10));
+
+        for x in -10..=10 {
+            for y in -10..=10 {
+                assert!((probs.at(x, y, 10) - counts.probability_at(x, y, 10)).abs() < 1e-12);
+            }
+        }
+
+        assert!(builder().counting().single_precision().build().is_err());
+    }
+
+    #[test]
+    fn test_compute_bounding_box() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+                .bounding_box(xy!(-3, -1), xy!(10, 2))
+        };
+
+        let mut dp = builder().build().unwrap();
+        let mut parallel = builder().build().unwrap();
+
+        dp.compute().unwrap();
+        parallel.compute_parallel().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(parallel) = parallel else {
+            unreachable!();
+        };
+
+        assert_eq!(dp, parallel);
+
+        assert_eq!(dp.table.slice(10).unwrap().len(), 14);
+        assert_eq!(dp.table.slice(10).unwrap()[0].len(), 4);
+        assert!(dp.contains(10, -1));
+        assert!(!dp.contains(0, -2));
+        assert_eq!(dp.at_or(0, -2, 1, 0.0), 0.0);
+        assert!((dp.at(0, -1, 1) - 0.2).abs() < 1e-12);
+
+        let mass = |t| dp.table.slice(t).unwrap().iter().flatten().sum::<f64>();
+
+        // Probability mass leaving the bounding box is lost
+        assert!((mass(1) - 1.0).abs() < 1e-12);
+        assert!(mass(10) < mass(2));
+    }
+
+    #[test]
+    fn test_compute_parallel_chunks() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut dp = builder().build().unwrap();
+
+        dp.compute().unwrap();
+
+        // More chunks than rows, a single chunk and the automatically chosen parallelism
+        for parallel in [
+            builder().threads(3).chunks(30),
+            builder().threads(1).chunks(1),
+            builder().chunks(7),
+            builder(),
+        ] {
+            let mut parallel = parallel.build().unwrap();
+
+            parallel.compute_parallel().unwrap();
+
+            assert_eq!(dp.try_unwrap().unwrap(), parallel.try_unwrap().unwrap());
+        }
+
+        assert!(matches!(
+            builder().threads(0).build(),
+            Err(DynamicProgramBuilderError::InvalidParallelism)
+        ));
+        assert!(matches!(
+            builder().chunks(0).build(),
+            Err(DynamicProgramBuilderError::InvalidParallelism)
+        ));
+    }
+
+    #[test]
+    fn test_compute_barriers() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+                .add_circle_barrier(xy!(3, 3), 1.0)
+                .add_line_barrier(xy!(-5, -2), xy!(5, -2), 1.0)
+                .add_polygon_barrier(vec![xy!(-8, 2), xy!(-3, 2), xy!(-3, 7)])
+        };
+        let barriers = [(3, 3), (3, 4), (0, -2), (5, -2), (-4, 3)];
+
+        let mut dp = builder().build().unwrap();
+        let mut parallel = builder().threads(2).build().unwrap();
+        let mut log_space = builder().log_space().build().unwrap();
+
+        dp.compute().unwrap();
+        parallel.compute_parallel().unwrap();
+        log_space.compute().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(parallel) = parallel else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(log_space) = log_space else {
+            unreachable!();
+        };
+
+        for t in 1..=10 {
+            for &(x, y) in barriers.iter() {
+                assert_eq!(dp.at(x, y, t), 0.0);
+                assert_eq!(log_space.probability_at(x, y, t), 0.0);
+            }
+        }
+
+        assert!(dp.at(0, 0, 2) > 0.0);
+        assert!(dp.total_probability(10) < 1.0);
+        assert_eq!(dp, parallel);
+    }
+
+    #[test]
+    fn test_compute_absorbing() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+                .add_absorbing_cell(xy!(1, 0))
+        };
+
+        let mut dp = builder().build().unwrap();
+        let mut parallel = builder().build().unwrap();
+
+        dp.compute().unwrap();
+        parallel.compute_parallel().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(parallel) = parallel else {
+            unreachable!();
+        };
+
+        assert_eq!(dp, parallel);
+
+        let absorbed = dp.absorbed_mass();
+
+        assert_eq!(absorbed.len(), 11);
+        assert_eq!(absorbed[0], 0.0);
+        assert!((absorbed[1] - 0.2).abs() < 1e-12);
+        assert!((absorbed.iter().sum::<f64>() - dp.at(1, 0, 10)).abs() < 1e-12);
+
+        for t in 1..=10 {
+            let mass: f64 = dp.table.slice(t).unwrap().iter().flatten().sum();
+
+            // Absorbed probability mass is neither lost nor redistributed
+            assert!((mass - 1.0).abs() < 1e-12);
+            assert!(dp.at(1, 0, t) >= dp.at(1, 0, t - 1));
+        }
+
+        assert!(matches!(
+            builder().counting().build(),
+            Err(DynamicProgramBuilderError::ConflictingStorageOptions)
+        ));
+        assert!(matches!(
+            builder().add_absorbing_cell(xy!(11, 0)).build(),
+            Err(DynamicProgramBuilderError::AbsorbingCellOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn test_initial_distribution() {
+        let builder = |cells| {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+                .initial_distribution(cells)
+        };
+
+        let mut dp = builder(vec![(xy!(-5, 0), 3.0), (xy!(5, 0), 0.5), (xy!(5, 0), 0.5)])
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker =
+            StandardWalker::new(Kernel::from_generator(SimpleRwGenerator::default()).unwrap());
+        let walk = walker.generate_path(&dp, 7, 0, 3).unwrap();
+
+        // Only the start cell at (5, 0) is within reach of (7, 0)
+        assert_eq!(walk[0], xy!(5, 0));
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        assert_eq!(dp.initial_distribution(), vec![(-5, 0, 0.75), (5, 0, 0.25)]);
+        assert_eq!(dp.at(0, 0, 0), 0.0);
+        assert!((dp.at(-4, 0, 1) - 0.15).abs() < 1e-12);
+
+        assert!(matches!(
+            builder(vec![(xy!(11, 0), 1.0)]).build(),
+            Err(DynamicProgramBuilderError::StartCellOutOfRange)
+        ));
+        assert!(matches!(
+            builder(vec![(xy!(1, 0), 0.0)]).build(),
+            Err(DynamicProgramBuilderError::InvalidInitialDistribution)
+        ));
+        assert!(matches!(
+            builder(vec![(xy!(1, 0), 1.0)]).counting().build(),
+            Err(DynamicProgramBuilderError::ConflictingStorageOptions)
+        ));
+    }
+
+    #[test]
+    fn test_epsilon() {
+        let builder = |epsilon| {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+                .epsilon(epsilon)
+                .build()
+        };
+
+        let mut dp = builder(1e-3).unwrap();
+
+        dp.compute().unwrap();
+
+        let walker =
+            StandardWalker::new(Kernel::from_generator(SimpleRwGenerator::default()).unwrap());
+
+        // The probability of (10, 0) in time step 10 is 0.2^10
+        assert_eq!(
+            walker.generate_path(&dp, 10, 0, 10),
+            Err(WalkerError::NoPathExists)
+        );
+        assert!(walker.generate_path(&dp, 1, 0, 10).is_ok());
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        assert!(dp.is_zero(10, 0, 10));
+        assert!(!dp.is_zero(0, 0, 10));
+        assert!(dp.is_zero(11, 0, 10));
+        assert!(matches!(
+            builder(1.5),
+            Err(DynamicProgramBuilderError::InvalidEpsilon)
+        ));
+        assert!(matches!(
+            builder(-1.0),
+            Err(DynamicProgramBuilderError::InvalidEpsilon)
+        ));
+    }
+
+    #[test]
+    fn test_slice() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+        let mut counting = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .counting()
+            .build()
+            .unwrap();
+        let mut biased = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(
+                Kernel::from_generator(BiasedRwGenerator {
+                    probability: 1.0,
+                    direction: Direction::North,
+                })
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+        counting.compute().unwrap();
+        biased.compute().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(counting) = counting else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(biased) = biased else {
+            unreachable!();
+        };
+
+        let slice = dp.slice(3).unwrap();
+
+        assert_eq!(slice.len(), 11);
+        assert_eq!(slice[6][5], dp.at(1, 0, 3));
+        assert!((slice.iter().flatten().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(
+            counting.slice(3).unwrap()[6][5],
+            counting.probability_at(1, 0, 3)
+        );
+        assert!((dp.marginal_x(3).unwrap().iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(dp
+            .marginal_x(3)
+            .unwrap()
+            .iter()
+            .zip(dp.marginal_y(3).unwrap())
+            .all(|(x, y)| (x - y).abs() < 1e-12));
+
+        let (x, y) = dp.expected_displacement(3).unwrap();
+
+        assert!(x.abs() < 1e-12 && y.abs() < 1e-12);
+        assert_eq!(biased.expected_displacement(4).unwrap(), (0.0, -4.0));
+        assert!(dp.slice(6).is_err());
+    }
+
+    #[test]
+    fn test_to_dataset() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        let dataset = dp.to_dataset(1, 0.0).unwrap();
+
+        assert_eq!(dataset.len(), 5);
+        assert_eq!(dataset.get(0).unwrap().point, xy!(-1, 0).into());
+        assert_eq!(dataset.get(0).unwrap().metadata["probability"], "0.2");
+        // Points far from the origin have a probability below the threshold in time step 5
+        let len = dp.to_dataset(5, 1e-3).unwrap().len();
+
+        assert!(len > 0 && len < 61);
+        assert!(dp.to_dataset(6, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_mass_outside() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .bounding_box(xy!(-5, -1), xy!(5, 1))
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        let series = dp.mass_outside_series(xy!(-1, -1), xy!(1, 1)).unwrap();
+
+        assert_eq!(series.len(), 6);
+        assert_eq!(series[0], 0.0);
+        assert!(series[1] < 1e-12);
+        assert!(series[2] > 0.0);
+        // Mass that has left the table through its borders counts as outside
+        assert!(dp.mass_outside(xy!(-10, -10), xy!(10, 10), 5).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_first_passage_time() {
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        let fpt = dp.first_passage_time(vec![xy!(1, 0)]).unwrap();
+
+        assert_eq!(fpt.len(), 11);
+        assert_eq!(fpt[0], 0.0);
+        assert!((fpt[1] - 0.2).abs() < 1e-12);
+        // Walks first stay at the origin and then step onto the target cell
+        assert!((fpt[2] - 0.04).abs() < 1e-12);
+        assert!(fpt.iter().sum::<f64>() < 1.0);
+
+        let fpt = dp.first_passage_time(vec![xy!(0, 0), xy!(0, 0)]).unwrap();
+
+        assert_eq!(fpt[0], 1.0);
+        assert!(fpt[1..].iter().all(|&p| p == 0.0));
+
+        assert!(dp.first_passage_time(vec![xy!(11, 0)]).is_err());
+        assert!(dp.first_passage_time(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_hitting_probability() {
+        let dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        let region = vec![xy!(2, -1), xy!(2, 0), xy!(2, 1)];
+        let fpt = dp.first_passage_time(region.clone()).unwrap();
+
+        assert_eq!(dp.hitting_probability(region.clone(), 1).unwrap(), 0.0);
+        assert!((dp.hitting_probability(vec![xy!(1, 0)], 2).unwrap() - 0.24).abs() < 1e-12);
+
+        for t in 2..=10 {
+            let p = dp.hitting_probability(region.clone(), t).unwrap();
+
+            assert!((p - fpt[..=t].iter().sum::<f64>()).abs() < 1e-12);
+            assert!(p > dp.hitting_probability(region.clone(), t - 1).unwrap());
+        }
+
+        assert!(dp.hitting_probability(region, 11).is_err());
+    }
+
+    #[test]
+    fn test_log_likelihood() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .add_absorbing_cell(xy!(2, 0))
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let dp = dp.try_unwrap().unwrap();
+        let walk = |points: &[(i64, i64)]| Walk(points.iter().map(|&p| p.into()).collect());
+
+        // Each step of the simple random walk has a probability of 0.2
+        let ll = dp.log_likelihood(&walk(&[(0, 0), (1, 0), (1, 0), (1, 1)]));
+
+        assert!((ll - 3.0 * 0.2_f64.ln()).abs() < 1e-12);
+        assert!(
+            (dp.log_likelihood(&walk(&[(0, 0), (0, 1)])) - dp.log_at_or(0, 1, 1)).abs() < 1e-12
+        );
+
+        // Walks stay in absorbing cells
+        let absorbed = dp.log_likelihood(&walk(&[(0, 0), (1, 0), (2, 0), (2, 0)]));
+
+        assert!((absorbed - 2.0 * 0.2_f64.ln()).abs() < 1e-12);
+        assert_eq!(
+            dp.log_likelihood(&walk(&[(0, 0), (1, 0), (2, 0), (3, 0)])),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(
+            dp.log_likelihood(&walk(&[(0, 0), (2, 0)])),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(
+            dp.log_likelihood(&walk(&[(1, 0), (1, 1)])),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(
+            dp.log_likelihood(&walk(&[(0, 0), (0, 11)])),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(dp.log_likelihood(&Walk::default()), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_bridge_occupancy() {
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        // The only walk from (3, 4) to (5, 4) in two steps passes (4, 4)
+        let occupancy = dp.bridge_occupancy(xy!(3, 4), xy!(5, 4), 2).unwrap();
+
+        assert_eq!(occupancy.len(), 21);
+        assert!((occupancy[10][10] - 1.0).abs() < 1e-12);
+        assert!((occupancy[11][10] - 1.0).abs() < 1e-12);
+        assert!((occupancy[12][10] - 1.0).abs() < 1e-12);
+
+        let occupancy = dp.bridge_occupancy(xy!(0, 0), xy!(2, -3), 8).unwrap();
+        let total: f64 = occupancy.iter().flatten().sum();
+
+        assert!((total - 9.0).abs() < 1e-9);
+        assert!(occupancy.iter().flatten().all(|&p| p >= 0.0));
+
+        assert!(dp.bridge_occupancy(xy!(0, 0), xy!(2, 0), 1).is_err());
+        assert!(dp.bridge_occupancy(xy!(0, 0), xy!(2, 0), 11).is_err());
+    }
+
+    #[test]
+    fn test_compute_on_step() {
+        let masses = Arc::new(Mutex::new(Vec::new()));
+        let masses_cb = masses.clone();
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .rolling_storage(None)
+            .on_step(move |t, slice| {
+                let mass: f64 = slice.iter().flatten().sum();
+                masses_cb.lock().unwrap().push((t, mass));
+            })
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let masses = masses.lock().unwrap();
+
+        assert_eq!(masses.len(), 11);
+
+        for (i, (t, mass)) in masses.iter().enumerate() {
+            assert_eq!(i, *t);
+            assert!((mass - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_compute_on_step_log_space() {
+        let masses = Arc::new(Mutex::new(Vec::new()));
+        let masses_cb = masses.clone();
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .log_space()
+            .on_step(move |_, slice| {
+                let mass: f64 = slice.iter().flatten().sum();
+                masses_cb.lock().unwrap().push(mass);
+            })
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let masses = masses.lock().unwrap();
+
+        // Callbacks receive probabilities instead of log-probabilities
+        assert_eq!(masses.len(), 11);
+        assert!(masses.iter().all(|mass| (mass - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    #[cfg(feature = "saving")]
+    fn test_save_load() {
+        let path = std::env::temp_dir().join("randomwalks_test_save_load.dp");
+        let path = path.to_str().unwrap().to_string();
+        let mut field_types = vec![vec![0; 11]; 11];
+
+        field_types[7][5] = 1;
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(5)
+            .kernels(vec![
+                (
+                    0,
+                    Kernel::from_generator(SimpleRwGenerator::default()).unwrap(),
+                ),
+                (
+                    1,
+                    Kernel::from_generator(BiasedRwGenerator {
+                        probability: 0.5,
+                        direction: Direction::North,
+                    })
+                    .unwrap(),
+                ),
+            ])
+            .field_types(field_types)
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+        dp.save(path.clone()).unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(loaded) = DynamicProgram::load(path.clone()).unwrap() else {
+            unreachable!();
+        };
+
+        assert_eq!(dp, loaded);
+        assert_eq!(loaded.kernels.len(), 2);
+        assert_eq!(loaded.kernels[1].probabilities, dp.kernels[1].probabilities);
+
+        std::fs::write(&path, b"not a dynamic program").unwrap();
+
+        assert!(DynamicProgram::load(path.clone()).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "saving")]
+    #[test]
+    fn test_save_load_absorbing() {
+        let path = std::env::temp_dir().join("randomwalks_test_save_load_absorbing.dp");
+        let path = path.to_str().unwrap().to_string();
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+            .add_absorbing_rect(xy!(2, -1), xy!(3, 1))
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+        dp.save(path.clone()).unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(loaded) = DynamicProgram::load(path.clone()).unwrap() else {
+            unreachable!();
+        };
+
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.absorbing.len(), 6);
+        assert_eq!(loaded.absorbing, dp.absorbing);
+        assert_eq!(loaded.absorbed_mass(), dp.absorbed_mass());
+        assert!(loaded.absorbed_mass().iter().sum::<f64>() > 0.0);
+    }
+
+    #[test]
+    #[cfg(feature = "saving")]
+    fn test_compute_rolling_storage_streamed() {
+        let path = std::env::temp_dir().join("randomwalks_test_rolling_storage.dp");
+        let path = path.to_str().unwrap().to_string();
+
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(5)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut full = builder().build().unwrap();
+        let mut rolling = builder()
+            .rolling_storage(Some(path.clone()))
+            .build()
+            .unwrap();
+
+        full.compute().unwrap();
+        rolling.compute().unwrap();
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(loaded) = DynamicProgram::load(path.clone()).unwrap() else {
+            unreachable!();
+        };
+
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(full, loaded);
+    }
+
+    #[test]
+    #[cfg(feature = "saving")]
+    fn test_compute_memory_mapped() {
+        let path = std::env::temp_dir().join("randomwalks_test_memory_mapped.dp");
+        let path = path.to_str().unwrap().to_string();
+
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(5)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut full = builder().build().unwrap();
+        let mut mapped = builder().memory_mapped(path.clone()).build().unwrap();
+
+        full.compute().unwrap();
+        mapped.compute().unwrap();
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(mapped) = mapped else {
+            unreachable!();
+        };
+
+        assert_eq!(mapped.table.kind(), "memory-mapped");
+
+        for t in 0..=5 {
+            assert_eq!(full.table.slice(t).unwrap(), mapped.table.slice(t).unwrap());
+        }
+
+        drop(mapped);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "saving")]
+    fn test_compute_compressed_storage() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut full = builder().build().unwrap();
+        let mut compressed = builder().compressed_storage().build().unwrap();
+        let mut parallel = builder().compressed_storage().build().unwrap();
+
+        full.compute().unwrap();
+        compressed.compute().unwrap();
+        parallel.compute_parallel().unwrap();
+
+        let walker =
+            StandardWalker::new(Kernel::from_generator(SimpleRwGenerator::default()).unwrap());
+
+        assert!(walker.generate_path(&compressed, 2, 3, 10).is_ok());
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(compressed) = compressed else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(parallel) = parallel else {
+            unreachable!();
+        };
+
+        assert_eq!(compressed.table.kind(), "compressed");
+        assert_eq!(compressed, parallel);
+
+        for t in 0..=10 {
+            assert_eq!(full.table.slice(t), compressed.table.slice(t));
+        }
+
+        assert!(matches!(
+            builder().compressed_storage().sparse_storage().build(),
+            Err(DynamicProgramBuilderError::ConflictingStorageOptions)
+        ));
+        assert!(matches!(
+            builder().compressed_storage().rolling_storage(None).build(),
+            Err(DynamicProgramBuilderError::ConflictingStorageOptions)
+        ));
+    }
+
+    #[test]
+    fn test_compute_quantized_storage() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut full = builder().build().unwrap();
+        let mut quantized = builder().quantized_storage().build().unwrap();
+        let mut parallel = builder().quantized_storage().build().unwrap();
+
+        full.compute().unwrap();
+        quantized.compute().unwrap();
+        parallel.compute_parallel().unwrap();
+
+        let walker =
+            StandardWalker::new(Kernel::from_generator(SimpleRwGenerator::default()).unwrap());
+
+        assert!(walker.generate_path(&quantized, 2, 3, 10).is_ok());
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(quantized) = quantized else {
+            unreachable!();
+        };
+        let DynamicProgramPool::Single(parallel) = parallel else {
+            unreachable!();
+        };
+
+        assert_eq!(quantized.table.kind(), "quantized");
+        assert_eq!(quantized, parallel);
+
+        for t in 0..=10 {
+            let (full, quantized) = (
+                full.table.slice(t).unwrap(),
+                quantized.table.slice(t).unwrap(),
+            );
+            let max = full.iter().flatten().copied().fold(0.0, f64::max);
+
+            for (a, b) in full.iter().flatten().zip(quantized.iter().flatten()) {
+                assert!((a - b).abs() <= max / u16::MAX as f64);
+                assert_eq!(*a == 0.0, *b == 0.0);
+            }
+        }
+
+        assert_eq!(quantized.at(0, 0, 10), full.at(0, 0, 10));
+        assert!(matches!(
+            builder().quantized_storage().single_precision().build(),
+            Err(DynamicProgramBuilderError::ConflictingStorageOptions)
+        ));
+        assert!(matches!(
+            builder().quantized_storage().log_space().build(),
+            Err(DynamicProgramBuilderError::ConflictingStorageOptions)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "saving")]
+    fn test_compute_resume_from_checkpoint() {
+        let path = std::env::temp_dir().join("randomwalks_test_resume_checkpoint.zst");
+        let path = path.to_str().unwrap().to_string();
+
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(5)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut full = builder().build().unwrap();
+        full.compute().unwrap();
+
+        let DynamicProgramPool::Single(full) = full else {
+            unreachable!();
+        };
+
+        // Mark the checkpoint to recognize restored slices
+        let mut interrupted = full.clone();
+        interrupted.set(0, 0, 0, 2.0);
+
+        let mut checkpoint = Checkpoint::new(path.clone(), 2);
+        checkpoint.fingerprint = Checkpoint::fingerprint(&interrupted).unwrap();
+        checkpoint.write(&interrupted.table, 3).unwrap();
+
+        let mut resumed = builder().checkpoint(path.clone(), 2).build().unwrap();
+        resumed.compute().unwrap();
+
+        let DynamicProgramPool::Single(resumed) = resumed else {
+            unreachable!();
+        };
+
+        assert_eq!(resumed.at(0, 0, 0), 2.0);
+        assert!(!std::path::Path::new(&path).exists());
+
+        for t in 1..=5 {
+            for x in -5..=5 {
+                for y in -5..=5 {
+                    assert_eq!(resumed.at(x, y, t), full.at(x, y, t));
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_dp_eq() {
         let mut dp1 = DynamicProgramBuilder::new()
             .simple()
             .time_limit(10)
-            .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
             .build()
             .unwrap();
 
-        dp1.compute();
+        dp1.compute().unwrap();
 
         let mut dp2 = DynamicProgramBuilder::new()
             .simple()
             .time_limit(10)
-            .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
             .build()
             .unwrap();
 
-        dp2.compute();
+        dp2.compute().unwrap();
 
         let DynamicProgramPool::Single(mut dp1) = dp1 else {
             unreachable!();
@@ -649,11 +3192,11 @@ mod tests {
         let mut dp1 = DynamicProgramBuilder::new()
             .simple()
             .time_limit(10)
-            .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+            .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
             .build()
             .unwrap();
 
-        dp1.compute();
+        dp1.compute().unwrap();
 
         let mut dp2 = DynamicProgramBuilder::new()
             .simple()
@@ -668,7 +3211,7 @@ mod tests {
             .build()
             .unwrap();
 
-        dp2.compute();
+        dp2.compute().unwrap();
 
         let DynamicProgramPool::Single(mut dp1) = dp1 else {
             unreachable!();
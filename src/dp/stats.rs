@@ -0,0 +1,156 @@
+//! Provides statistics collected while computing dynamic programs.
+//!
+//! [`compute()`](crate::dp::DynamicPrograms::compute) and its variants return a [`ComputeStats`]
+//! describing how long each phase of the computation took, how many cells were computed and how
+//! much probability mass was lost, e.g. because it left the table at its boundaries.
+//!
+//! Progress is additionally reported using the [`log`] crate. A message is emitted at debug
+//! level for every computed time step and at info level once the computation is finished, so
+//! that applications can display progress by installing a logger.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! #
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+//!     .build()
+//!     .unwrap();
+//!
+//! let stats = dp.compute().unwrap();
+//!
+//! println!("Computed {} cells in {:?}", stats.cells_computed, stats.total());
+//! ```
+
+use pyo3::{pyclass, pymethods};
+use std::time::Duration;
+
+/// Statistics about the computation of a dynamic program.
+#[pyclass]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComputeStats {
+    /// Time spent initializing the first time step or restoring a checkpoint.
+    pub initialization: Duration,
+    /// Time spent computing all remaining time steps, including step callbacks, checkpoints and
+    /// streaming slices to disk.
+    pub computation: Duration,
+    /// Time spent finishing rolling storage and removing the checkpoint.
+    pub finalization: Duration,
+    /// Number of computed time steps. Time steps restored from a checkpoint are not included.
+    #[pyo3(get)]
+    pub time_steps: usize,
+    /// Number of computed cells over all computed time steps.
+    #[pyo3(get)]
+    pub cells_computed: u64,
+    /// Probability mass lost between the first and the last computed time step, e.g. because it
+    /// left the table at its boundaries or moved onto barriers. Always zero when counting paths.
+    #[pyo3(get)]
+    pub mass_lost: f64,
+}
+
+#[pymethods]
+impl ComputeStats {
+    /// Time spent in the initialization phase, in seconds.
+    #[getter(initialization)]
+    pub fn py_initialization(&self) -> f64 {
+        self.initialization.as_secs_f64()
+    }
+
+    /// Time spent in the computation phase, in seconds.
+    #[getter(computation)]
+    pub fn py_computation(&self) -> f64 {
+        self.computation.as_secs_f64()
+    }
+
+    /// Time spent in the finalization phase, in seconds.
+    #[getter(finalization)]
+    pub fn py_finalization(&self) -> f64 {
+        self.finalization.as_secs_f64()
+    }
+
+    /// Total time spent, in seconds.
+    #[pyo3(name = "total")]
+    pub fn py_total(&self) -> f64 {
+        self.total().as_secs_f64()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "ComputeStats(total={:?}, time_steps={}, cells_computed={}, mass_lost={:.3e})",
+            self.total(),
+            self.time_steps,
+            self.cells_computed,
+            self.mass_lost
+        )
+    }
+}
+
+impl ComputeStats {
+    /// Returns the total time spent in all phases.
+    pub fn total(&self) -> Duration {
+        self.initialization + self.computation + self.finalization
+    }
+
+    /// Combines the statistics of multiple dynamic programs into one.
+    ///
+    /// Counts and lost mass are added up. Durations are added up as well if the dynamic programs
+    /// were computed one after another, and the longest one is used for each phase if they were
+    /// computed `concurrently`.
+    pub(crate) fn combine(stats: impl IntoIterator<Item = Self>, concurrently: bool) -> Self {
+        let phase = |a: Duration, b: Duration| if concurrently { a.max(b) } else { a + b };
+
+        stats.into_iter().fold(Self::default(), |acc, stats| Self {
+            initialization: phase(acc.initialization, stats.initialization),
+            computation: phase(acc.computation, stats.computation),
+            finalization: phase(acc.finalization, stats.finalization),
+            time_steps: acc.time_steps + stats.time_steps,
+            cells_computed: acc.cells_computed + stats.cells_computed,
+            mass_lost: acc.mass_lost + stats.mass_lost,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::xy;
+
+    #[test]
+    fn test_compute_stats() {
+        let builder = || {
+            DynamicProgramBuilder::new()
+                .simple()
+                .time_limit(10)
+                .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
+        };
+
+        let mut dp = builder().build().unwrap();
+        let stats = dp.compute().unwrap();
+
+        assert_eq!(stats.time_steps, 10);
+        assert_eq!(stats.cells_computed, 21 * 21 * 10);
+        assert!(stats.mass_lost.abs() < 1e-12);
+        assert_eq!(
+            stats.total(),
+            dp.try_unwrap().unwrap().compute_duration.unwrap()
+        );
+
+        // Probability mass leaves the table at the boundaries of the bounding box
+        let mut bounded = builder()
+            .bounding_box(xy!(-2, -2), xy!(2, 2))
+            .build()
+            .unwrap();
+        let stats = bounded.compute_parallel().unwrap();
+
+        assert_eq!(stats.cells_computed, 5 * 5 * 10);
+        assert!(stats.mass_lost > 0.1 && stats.mass_lost < 1.0);
+    }
+}
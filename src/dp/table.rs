@@ -0,0 +1,1118 @@
+//! Provides the storage used for the table of a
+//! [`DynamicProgram`](crate::dp::simple::DynamicProgram).
+//!
+//! By default, the full table containing all time slices is kept in memory. Using
+//! [`rolling_storage()`](crate::dp::builder::DynamicProgramBuilder::rolling_storage), only the
+//! last two time slices are kept in memory during computation, while completed slices are either
+//! streamed to disk or discarded. Slices for specific time steps, e.g. the endpoint
+//! distribution, can be captured and are kept in memory nonetheless.
+//!
+//! Using [`single_precision()`](crate::dp::builder::DynamicProgramBuilder::single_precision), the
+//! full table is stored using `f32` instead of `f64` values, halving its memory consumption.
+//!
+//! Using [`sparse_storage()`](crate::dp::builder::DynamicProgramBuilder::sparse_storage), each
+//! time slice only stores the smallest square around the origin that contains all non-zero
+//! values. Since early time steps only reach a small area around the origin, memory then scales
+//! with the reachable area of each time step instead of the full table size.
+//!
+//! Using [`memory_mapped()`](crate::dp::builder::DynamicProgramBuilder::memory_mapped), the full
+//! table is backed by a memory-mapped file. The operating system then only keeps the recently
+//! used parts of the table in memory, so tables larger than the available memory can be computed
+//! and queried.
+//!
+//! Using [`compressed_storage()`](crate::dp::builder::DynamicProgramBuilder::compressed_storage),
+//! only the last two time slices are kept uncompressed, while all earlier slices are compressed
+//! using zstd and decompressed on access. Since walkers only access the slices of two consecutive
+//! time steps at a time, the two most recently decompressed slices are cached.
+//!
+//! Using [`quantized_storage()`](crate::dp::builder::DynamicProgramBuilder::quantized_storage),
+//! only the last two time slices are kept in double precision, while all earlier slices are
+//! stored as 16-bit fixed-point values with a scale per slice, reducing memory consumption by a
+//! factor of four. Values are dequantized transparently on access.
+//!
+//! Using [`counting()`](crate::dp::builder::DynamicProgramBuilder::counting), the full table stores
+//! the exact number of paths leading to each field instead of probabilities. Since path counts
+//! grow exponentially, this is only feasible for small time limits.
+//!
+//! All storage options cover the [`Domain`] of the dynamic program, which is not necessarily a
+//! square centered at the origin.
+
+use crate::dp::domain::Domain;
+use crate::dp::simple::DynamicProgram;
+use num::{BigUint, FromPrimitive, ToPrimitive};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "saving")]
+use {
+    crate::dp::format::Header,
+    crate::workspace,
+    memmap2::MmapMut,
+    std::fs::{File, OpenOptions},
+    std::io::{BufWriter, Write},
+    std::sync::{Arc, Mutex},
+    zstd::Encoder,
+};
+
+/// A single time slice of a dynamic program's table, indexed as `slice[x][y]`.
+pub type Slice = Vec<Vec<f64>>;
+
+/// A single time slice stored in single precision, indexed as `slice[x][y]`.
+type SliceF32 = Vec<Vec<f32>>;
+
+/// A single time slice storing exact path counts, indexed as `slice[x][y]`.
+type CountSlice = Vec<Vec<BigUint>>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Table {
+    /// All time slices are kept in memory.
+    Full(Vec<Slice>),
+
+    /// All time slices are kept in memory using single precision.
+    FullF32(Vec<SliceF32>),
+
+    /// All time slices are kept in memory, each only storing the region containing non-zero
+    /// values.
+    Sparse {
+        domain: Domain,
+        slices: Vec<SparseSlice>,
+    },
+
+    /// Only the last two time slices and captured time slices are kept in memory.
+    Rolling {
+        /// The last two slices, the slice of time step `t` is stored at index `t % 2`.
+        slices: [Slice; 2],
+        /// The latest time step that has been written to.
+        latest: usize,
+        /// Time steps whose slices are kept in memory.
+        capture: BTreeSet<usize>,
+        /// Slices of captured time steps.
+        captured: BTreeMap<usize, Slice>,
+        /// Path to stream completed slices to. If `None`, completed slices are discarded.
+        path: Option<String>,
+    },
+
+    /// All time slices are kept in memory, storing exact path counts instead of probabilities.
+    Counts(Vec<CountSlice>),
+
+    /// All time slices are kept in memory, quantizing all but the last two slices.
+    Quantized(QuantizedTable),
+
+    /// All time slices are stored in a memory-mapped file.
+    #[cfg(feature = "saving")]
+    Mapped(MappedTable),
+
+    /// All time slices are kept in memory, compressing all but the last two slices.
+    #[cfg(feature = "saving")]
+    Compressed(CompressedTable),
+}
+
+impl Table {
+    /// Creates a full table for the given time limit.
+    pub(crate) fn full(time_limit: usize, domain: Domain) -> Self {
+        Table::Full(vec![empty_slice(domain); time_limit + 1])
+    }
+
+    /// Creates a rolling table for the given time limit.
+    ///
+    /// The slice of the last time step is always captured.
+    pub(crate) fn rolling(
+        time_limit: usize,
+        domain: Domain,
+        capture: Vec<usize>,
+        path: Option<String>,
+    ) -> Self {
+        let mut capture: BTreeSet<usize> = capture.into_iter().collect();
+        capture.insert(time_limit);
+
+        Table::Rolling {
+            slices: [empty_slice(domain), empty_slice(domain)],
+            latest: 0,
+            capture,
+            captured: BTreeMap::new(),
+            path,
+        }
+    }
+
+    /// Creates a full table for the given time limit storing values in single precision.
+    pub(crate) fn full_f32(time_limit: usize, domain: Domain) -> Self {
+        Table::FullF32(vec![
+            vec![vec![0.0; domain.height()]; domain.width()];
+            time_limit + 1
+        ])
+    }
+
+    /// Creates a sparse table for the given time limit.
+    pub(crate) fn sparse(time_limit: usize, domain: Domain) -> Self {
+        Table::Sparse {
+            domain,
+            slices: vec![SparseSlice::default(); time_limit + 1],
+        }
+    }
+
+    /// Creates a full table for the given time limit storing exact path counts.
+    pub(crate) fn counts(time_limit: usize, domain: Domain) -> Self {
+        Table::Counts(vec![
+            vec![
+                vec![BigUint::default(); domain.height()];
+                domain.width()
+            ];
+            time_limit + 1
+        ])
+    }
+
+    /// Creates a full table for the given time limit which keeps completed slices quantized.
+    pub(crate) fn quantized(time_limit: usize, domain: Domain) -> Self {
+        Table::Quantized(QuantizedTable::new(time_limit, domain))
+    }
+
+    /// Creates a full table for the given time limit which is backed by a memory-mapped file at
+    /// `path`. An existing file at `path` is overwritten.
+    #[cfg(feature = "saving")]
+    pub(crate) fn mapped(time_limit: usize, domain: Domain, path: &str) -> std::io::Result<Self> {
+        MappedTable::create(time_limit, domain, path).map(Table::Mapped)
+    }
+
+    /// Creates a full table for the given time limit which keeps completed slices compressed.
+    #[cfg(feature = "saving")]
+    pub(crate) fn compressed(time_limit: usize, domain: Domain) -> Self {
+        Table::Compressed(CompressedTable::new(time_limit, domain))
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        match self {
+            Table::Full(_)
+            | Table::FullF32(_)
+            | Table::Sparse { .. }
+            | Table::Counts(_)
+            | Table::Quantized(_) => true,
+            Table::Rolling { .. } => false,
+            #[cfg(feature = "saving")]
+            Table::Mapped(_) | Table::Compressed(_) => true,
+        }
+    }
+
+    /// Returns whether the table stores exact path counts instead of probabilities.
+    pub(crate) fn is_counting(&self) -> bool {
+        matches!(self, Table::Counts(_))
+    }
+
+    /// Returns the slice of time step `t` if it is available in memory.
+    ///
+    /// Slices stored in single precision, quantized or as path counts are converted to double
+    /// precision.
+    pub(crate) fn slice(&self, t: usize) -> Option<Cow<'_, Slice>> {
+        match self {
+            Table::Full(table) => table.get(t).map(Cow::Borrowed),
+            Table::FullF32(table) => table.get(t).map(|slice| {
+                Cow::Owned(
+                    slice
+                        .iter()
+                        .map(|column| column.iter().map(|&val| val as f64).collect())
+                        .collect(),
+                )
+            }),
+            Table::Sparse { domain, slices } => slices
+                .get(t)
+                .map(|slice| Cow::Owned(slice.to_dense(domain))),
+            Table::Counts(table) => table.get(t).map(|slice| {
+                Cow::Owned(
+                    slice
+                        .iter()
+                        .map(|column| column.iter().map(count_to_f64).collect())
+                        .collect(),
+                )
+            }),
+            Table::Quantized(table) => table.slice(t).map(Cow::Owned),
+            Table::Rolling {
+                slices,
+                latest,
+                captured,
+                ..
+            } => {
+                if t <= *latest && *latest - t <= 1 {
+                    Some(Cow::Borrowed(&slices[t % 2]))
+                } else {
+                    captured.get(&t).map(Cow::Borrowed)
+                }
+            }
+            #[cfg(feature = "saving")]
+            Table::Mapped(table) => table.slice(t).map(Cow::Owned),
+            #[cfg(feature = "saving")]
+            Table::Compressed(table) => table.slice(t).map(Cow::Owned),
+        }
+    }
+
+    /// Returns whether the slice of time step `t` is available in memory.
+    pub(crate) fn keeps(&self, t: usize) -> bool {
+        match self {
+            Table::Rolling {
+                latest, captured, ..
+            } => (t <= *latest && *latest - t <= 1) || captured.contains_key(&t),
+            _ => true,
+        }
+    }
+
+    /// Returns the value at the table indices `x` and `y` in time step `t`, or `None` if the slice
+    /// of time step `t` is not available in memory.
+    pub(crate) fn get(&self, x: usize, y: usize, t: usize) -> Option<f64> {
+        let val = match self {
+            Table::Full(table) => table[t][x][y],
+            Table::FullF32(table) => table[t][x][y] as f64,
+            Table::Sparse { domain, slices } => slices[t].get(x, y, domain.index(0, 0)),
+            Table::Counts(table) => count_to_f64(&table[t][x][y]),
+            Table::Quantized(table) => table.get(x, y, t),
+            Table::Rolling { .. } => return self.slice(t).map(|slice| slice[x][y]),
+            #[cfg(feature = "saving")]
+            Table::Mapped(table) => table.get(x, y, t),
+            #[cfg(feature = "saving")]
+            Table::Compressed(table) => table.get(x, y, t),
+        };
+
+        Some(val)
+    }
+
+    /// Sets the value at the table indices `x` and `y` in time step `t`.
+    ///
+    /// Tables storing path counts truncate `val` to an integer. Use
+    /// [`set_count()`](Table::set_count) to set exact counts.
+    pub(crate) fn set(&mut self, x: usize, y: usize, t: usize, val: f64) {
+        match self {
+            Table::Full(table) => table[t][x][y] = val,
+            Table::FullF32(table) => table[t][x][y] = val as f32,
+            Table::Sparse { domain, slices } => slices[t].set(x, y, domain.index(0, 0), val),
+            Table::Counts(table) => table[t][x][y] = BigUint::from_f64(val).unwrap_or_default(),
+            Table::Quantized(table) => table.set(x, y, t, val),
+            Table::Rolling { slices, latest, .. } => {
+                *latest = (*latest).max(t);
+                slices[t % 2][x][y] = val;
+            }
+            #[cfg(feature = "saving")]
+            Table::Mapped(table) => table.set(x, y, t, val),
+            #[cfg(feature = "saving")]
+            Table::Compressed(table) => table.set(x, y, t, val),
+        }
+    }
+
+    /// Returns the exact path count at the table indices `x` and `y` in time step `t` if the table
+    /// stores path counts.
+    pub(crate) fn count(&self, x: usize, y: usize, t: usize) -> Option<&BigUint> {
+        match self {
+            Table::Counts(table) => Some(&table[t][x][y]),
+            _ => None,
+        }
+    }
+
+    /// Sets the exact path count at the table indices `x` and `y` in time step `t`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table does not store path counts.
+    pub(crate) fn set_count(&mut self, x: usize, y: usize, t: usize, count: BigUint) {
+        match self {
+            Table::Counts(table) => table[t][x][y] = count,
+            _ => panic!("table does not store path counts"),
+        }
+    }
+
+    /// Marks the slice of time step `t` as completely computed.
+    ///
+    /// In rolling mode, this captures the slice if requested.
+    pub(crate) fn finish_slice(&mut self, t: usize) {
+        if let Table::Rolling {
+            slices,
+            capture,
+            captured,
+            ..
+        } = self
+        {
+            if capture.contains(&t) {
+                captured.insert(t, slices[t % 2].clone());
+            }
+        }
+    }
+
+    /// Returns a short description of how the table is stored.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Table::Full(_) => "full",
+            Table::FullF32(_) => "full (single precision)",
+            Table::Sparse { .. } => "sparse",
+            Table::Rolling { .. } => "rolling",
+            Table::Counts(_) => "counts",
+            Table::Quantized(_) => "quantized",
+            #[cfg(feature = "saving")]
+            Table::Mapped(_) => "memory-mapped",
+            #[cfg(feature = "saving")]
+            Table::Compressed(_) => "compressed",
+        }
+    }
+
+    /// Returns the path completed slices are streamed to, if any.
+    pub(crate) fn path(&self) -> Option<&str> {
+        match self {
+            Table::Full(_)
+            | Table::FullF32(_)
+            | Table::Sparse { .. }
+            | Table::Counts(_)
+            | Table::Quantized(_) => None,
+            Table::Rolling { path, .. } => path.as_deref(),
+            #[cfg(feature = "saving")]
+            Table::Mapped(_) | Table::Compressed(_) => None,
+        }
+    }
+}
+
+/// A time slice only storing the square of the given radius around the origin.
+///
+/// All values outside of the square are zero. The square grows when a non-zero value is set
+/// outside of it.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SparseSlice {
+    radius: usize,
+    values: Slice,
+}
+
+impl Default for SparseSlice {
+    fn default() -> Self {
+        Self {
+            radius: 0,
+            values: vec![vec![0.0]],
+        }
+    }
+}
+
+impl SparseSlice {
+    /// Returns the value at the table indices `x` and `y`, where the origin is located at the
+    /// table indices `center`.
+    fn get(&self, x: usize, y: usize, center: (usize, usize)) -> f64 {
+        match self.local(x, y, center) {
+            Some((i, j)) => self.values[i][j],
+            None => 0.0,
+        }
+    }
+
+    /// Sets the value at the table indices `x` and `y`, where the origin is located at the table
+    /// indices `center`, growing the stored square if necessary.
+    fn set(&mut self, x: usize, y: usize, center: (usize, usize), val: f64) {
+        if self.local(x, y, center).is_none() {
+            if val == 0.0 {
+                return;
+            }
+
+            self.grow(x.abs_diff(center.0).max(y.abs_diff(center.1)));
+        }
+
+        let (i, j) = self.local(x, y, center).unwrap();
+        self.values[i][j] = val;
+    }
+
+    /// Maps table indices to indices into the stored square.
+    fn local(&self, x: usize, y: usize, center: (usize, usize)) -> Option<(usize, usize)> {
+        if x.abs_diff(center.0) > self.radius || y.abs_diff(center.1) > self.radius {
+            return None;
+        }
+
+        Some((x + self.radius - center.0, y + self.radius - center.1))
+    }
+
+    fn grow(&mut self, radius: usize) {
+        let offset = radius - self.radius;
+        let mut values = vec![vec![0.0; 2 * radius + 1]; 2 * radius + 1];
+
+        for (i, column) in self.values.iter().enumerate() {
+            values[i + offset][offset..offset + column.len()].copy_from_slice(column);
+        }
+
+        self.radius = radius;
+        self.values = values;
+    }
+
+    /// Converts the stored square to a full slice of `domain`.
+    ///
+    /// Parts of the square outside of the domain only contain zeros and are cut off.
+    fn to_dense(&self, domain: &Domain) -> Slice {
+        let mut slice = empty_slice(*domain);
+        let radius = self.radius as isize;
+
+        for x in (-radius).max(domain.x_min)..=radius.min(domain.x_max) {
+            for y in (-radius).max(domain.y_min)..=radius.min(domain.y_max) {
+                let (i, j) = domain.index(x, y);
+
+                slice[i][j] = self.values[(x + radius) as usize][(y + radius) as usize];
+            }
+        }
+
+        slice
+    }
+}
+
+/// A full table keeping all but the last two time slices quantized in memory.
+///
+/// Slices are quantized once a later time step is written to, i.e. once they are complete, so
+/// that the computation itself only reads slices stored in double precision.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct QuantizedTable {
+    time_limit: usize,
+    domain: Domain,
+    /// Quantized slices, `None` for the last two time steps and time steps not written yet.
+    quantized: Vec<Option<QuantizedSlice>>,
+    /// The last two slices, the slice of time step `t` is stored at index `t % 2`.
+    latest_slices: [Slice; 2],
+    /// The latest time step that has been written to.
+    latest: usize,
+}
+
+impl QuantizedTable {
+    fn new(time_limit: usize, domain: Domain) -> Self {
+        Self {
+            time_limit,
+            domain,
+            quantized: vec![None; time_limit + 1],
+            latest_slices: [empty_slice(domain), empty_slice(domain)],
+            latest: 0,
+        }
+    }
+
+    /// Returns whether the slice of time step `t` is one of the last two slices.
+    fn is_latest(&self, t: usize) -> bool {
+        t <= self.latest && self.latest - t <= 1
+    }
+
+    /// Makes `t` the latest time step, quantizing the slices that are no longer among the last
+    /// two slices.
+    fn advance(&mut self, t: usize) {
+        for s in self.latest + 1..=t {
+            let slice = &mut self.latest_slices[s % 2];
+
+            if s >= 2 {
+                self.quantized[s - 2] = Some(QuantizedSlice::new(slice));
+            }
+
+            *slice = empty_slice(self.domain);
+        }
+
+        self.latest = t;
+    }
+
+    fn get(&self, x: usize, y: usize, t: usize) -> f64 {
+        if self.is_latest(t) {
+            self.latest_slices[t % 2][x][y]
+        } else {
+            self.quantized[t]
+                .as_ref()
+                .map_or(0.0, |slice| slice.get(x, y))
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, t: usize, val: f64) {
+        if t > self.latest {
+            self.advance(t);
+        }
+
+        if self.is_latest(t) {
+            self.latest_slices[t % 2][x][y] = val;
+        } else {
+            // Slices are usually not modified once they are complete, so quantizing them again
+            // is acceptable
+            let mut slice = self.slice(t).unwrap();
+
+            slice[x][y] = val;
+            self.quantized[t] = Some(QuantizedSlice::new(&slice));
+        }
+    }
+
+    fn slice(&self, t: usize) -> Option<Slice> {
+        if t > self.time_limit {
+            None
+        } else if self.is_latest(t) {
+            Some(self.latest_slices[t % 2].clone())
+        } else {
+            Some(match &self.quantized[t] {
+                Some(slice) => slice.to_dense(),
+                None => empty_slice(self.domain),
+            })
+        }
+    }
+}
+
+/// A time slice stored as 16-bit fixed-point values, indexed as `values[x][y]`.
+///
+/// A stored value `q` represents the value `q * scale`, where the scale is chosen such that the
+/// largest value of the slice is represented by [`u16::MAX`]. Positive values too small to be
+/// represented are rounded up to the smallest representable value instead of zero, so that
+/// points with a non-zero probability remain reachable for walkers.
+#[derive(Clone, Debug, PartialEq)]
+struct QuantizedSlice {
+    scale: f64,
+    values: Vec<Vec<u16>>,
+}
+
+impl QuantizedSlice {
+    fn new(slice: &Slice) -> Self {
+        let max = slice.iter().flatten().copied().fold(0.0, f64::max);
+        let scale = max / u16::MAX as f64;
+        let values = slice
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|&val| {
+                        if val > 0.0 {
+                            ((val / scale).round() as u16).max(1)
+                        } else {
+                            0
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self { scale, values }
+    }
+
+    fn get(&self, x: usize, y: usize) -> f64 {
+        self.values[x][y] as f64 * self.scale
+    }
+
+    fn to_dense(&self) -> Slice {
+        self.values
+            .iter()
+            .map(|column| column.iter().map(|&q| q as f64 * self.scale).collect())
+            .collect()
+    }
+}
+
+/// A full table stored in a memory-mapped file.
+///
+/// Values are stored as `f64` in native byte order, ordered by time step, then `x` and then `y`.
+/// Clones share the same mapping. Modifying a table while it is shared first copies the mapping
+/// into anonymous memory, so that the modification is not visible to the other clones.
+#[cfg(feature = "saving")]
+#[derive(Clone)]
+pub(crate) struct MappedTable {
+    time_limit: usize,
+    domain: Domain,
+    path: String,
+    mmap: Arc<MmapMut>,
+}
+
+#[cfg(feature = "saving")]
+impl MappedTable {
+    fn create(time_limit: usize, domain: Domain, path: &str) -> std::io::Result<Self> {
+        let size = domain.width() * domain.height();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+
+        // Newly allocated file contents are zero, which is the bit pattern of 0.0
+        file.set_len((size * (time_limit + 1) * std::mem::size_of::<f64>()) as u64)?;
+
+        // SAFETY: The file has just been created by us and is not expected to be modified by
+        // other processes while it is mapped.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        Ok(Self {
+            time_limit,
+            domain,
+            path: path.into(),
+            mmap: Arc::new(mmap),
+        })
+    }
+
+    fn offset(&self, x: usize, y: usize, t: usize) -> usize {
+        let (width, height) = (self.domain.width(), self.domain.height());
+
+        ((t * width + x) * height + y) * std::mem::size_of::<f64>()
+    }
+
+    fn get(&self, x: usize, y: usize, t: usize) -> f64 {
+        let offset = self.offset(x, y, t);
+
+        f64::from_ne_bytes(self.mmap[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn set(&mut self, x: usize, y: usize, t: usize, val: f64) {
+        let offset = self.offset(x, y, t);
+
+        if Arc::get_mut(&mut self.mmap).is_none() {
+            let mut copy = MmapMut::map_anon(self.mmap.len())
+                .expect("could not allocate memory for a copy of the memory-mapped table");
+
+            copy.copy_from_slice(&self.mmap);
+            self.mmap = Arc::new(copy);
+        }
+
+        let mmap = Arc::get_mut(&mut self.mmap).unwrap();
+
+        mmap[offset..offset + 8].copy_from_slice(&val.to_ne_bytes());
+    }
+
+    fn slice(&self, t: usize) -> Option<Slice> {
+        if t > self.time_limit {
+            return None;
+        }
+
+        Some(
+            (0..self.domain.width())
+                .map(|x| {
+                    (0..self.domain.height())
+                        .map(|y| self.get(x, y, t))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(feature = "saving")]
+impl std::fmt::Debug for MappedTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedTable")
+            .field("time_limit", &self.time_limit)
+            .field("domain", &self.domain)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+#[cfg(feature = "saving")]
+impl PartialEq for MappedTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.time_limit == other.time_limit
+            && self.domain == other.domain
+            && self.mmap[..] == other.mmap[..]
+    }
+}
+
+/// A full table keeping all but the last two time slices compressed in memory.
+///
+/// Slices are compressed once a later time step is written to, i.e. once they are complete.
+/// Clones start with an empty cache of decompressed slices.
+#[cfg(feature = "saving")]
+pub(crate) struct CompressedTable {
+    time_limit: usize,
+    domain: Domain,
+    /// Compressed slices, `None` for the last two time steps and time steps not written yet.
+    compressed: Vec<Option<Vec<u8>>>,
+    /// The last two slices, the slice of time step `t` is stored at index `t % 2`.
+    latest_slices: [Slice; 2],
+    /// The latest time step that has been written to.
+    latest: usize,
+    /// The most recently decompressed slices together with their time steps.
+    cache: Mutex<Vec<(usize, Arc<Slice>)>>,
+}
+
+#[cfg(feature = "saving")]
+impl CompressedTable {
+    /// Compression level used for completed slices, favoring speed over compression ratio.
+    const LEVEL: i32 = 3;
+
+    /// Number of decompressed slices kept in the cache.
+    const CACHE_SIZE: usize = 2;
+
+    fn new(time_limit: usize, domain: Domain) -> Self {
+        Self {
+            time_limit,
+            domain,
+            compressed: vec![None; time_limit + 1],
+            latest_slices: [empty_slice(domain), empty_slice(domain)],
+            latest: 0,
+            cache: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns whether the slice of time step `t` is one of the last two slices.
+    fn is_latest(&self, t: usize) -> bool {
+        t <= self.latest && self.latest - t <= 1
+    }
+
+    fn compress(slice: &Slice) -> Vec<u8> {
+        compress_slice(slice, Self::LEVEL).expect("could not compress slice")
+    }
+
+    fn decompress(&self, data: &[u8]) -> Slice {
+        decompress_slice(data, self.domain).expect("could not decompress slice")
+    }
+
+    /// Returns the decompressed slice of time step `t`, which must not be one of the last two
+    /// slices, using the cache if possible.
+    fn decompressed(&self, t: usize) -> Arc<Slice> {
+        let mut cache = self.cache.lock().unwrap();
+
+        if let Some((_, slice)) = cache.iter().find(|(s, _)| *s == t) {
+            return slice.clone();
+        }
+
+        let slice = Arc::new(match &self.compressed[t] {
+            Some(data) => self.decompress(data),
+            None => empty_slice(self.domain),
+        });
+
+        if cache.len() >= Self::CACHE_SIZE {
+            cache.remove(0);
+        }
+
+        cache.push((t, slice.clone()));
+
+        slice
+    }
+
+    /// Makes `t` the latest time step, compressing the slices that are no longer among the last
+    /// two slices.
+    fn advance(&mut self, t: usize) {
+        for s in self.latest + 1..=t {
+            let slice = &mut self.latest_slices[s % 2];
+
+            if s >= 2 {
+                self.compressed[s - 2] = Some(Self::compress(slice));
+                self.cache.get_mut().unwrap().retain(|(c, _)| *c != s - 2);
+            }
+
+            *slice = empty_slice(self.domain);
+        }
+
+        self.latest = t;
+    }
+
+    fn get(&self, x: usize, y: usize, t: usize) -> f64 {
+        if self.is_latest(t) {
+            self.latest_slices[t % 2][x][y]
+        } else if t > self.latest {
+            0.0
+        } else {
+            self.decompressed(t)[x][y]
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, t: usize, val: f64) {
+        if t > self.latest {
+            self.advance(t);
+        }
+
+        if self.is_latest(t) {
+            self.latest_slices[t % 2][x][y] = val;
+        } else {
+            // Slices are usually not modified once they are complete, so recompressing them is
+            // acceptable
+            let mut slice = (*self.decompressed(t)).clone();
+
+            slice[x][y] = val;
+            self.compressed[t] = Some(Self::compress(&slice));
+            self.cache.get_mut().unwrap().retain(|(c, _)| *c != t);
+        }
+    }
+
+    fn slice(&self, t: usize) -> Option<Slice> {
+        if t > self.time_limit {
+            None
+        } else if self.is_latest(t) {
+            Some(self.latest_slices[t % 2].clone())
+        } else if t > self.latest {
+            Some(empty_slice(self.domain))
+        } else {
+            Some((*self.decompressed(t)).clone())
+        }
+    }
+}
+
+#[cfg(feature = "saving")]
+impl Clone for CompressedTable {
+    fn clone(&self) -> Self {
+        Self {
+            time_limit: self.time_limit,
+            domain: self.domain,
+            compressed: self.compressed.clone(),
+            latest_slices: self.latest_slices.clone(),
+            latest: self.latest,
+            cache: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[cfg(feature = "saving")]
+impl std::fmt::Debug for CompressedTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompressedTable")
+            .field("time_limit", &self.time_limit)
+            .field("domain", &self.domain)
+            .field("latest", &self.latest)
+            .finish()
+    }
+}
+
+#[cfg(feature = "saving")]
+impl PartialEq for CompressedTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.time_limit == other.time_limit
+            && self.domain == other.domain
+            && (0..=self.time_limit).all(|t| self.slice(t) == other.slice(t))
+    }
+}
+
+/// Writes completed slices of a dynamic program to disk.
+///
+/// The header and each slice are compressed as separate zstd frames, so that single slices can
+/// be read without decompressing all earlier ones, e.g. by a
+/// [`LazyDynamicProgram`](crate::dp::lazy::LazyDynamicProgram). Since concatenated frames form a
+/// valid zstd stream, the resulting file has the same format as files written by
+/// [`save()`](crate::dp::DynamicPrograms::save) and can thus be loaded using
+/// [`DynamicProgram::load()`](crate::dp::simple::DynamicProgram::load). Requires the `saving`
+/// feature.
+pub(crate) struct SliceWriter {
+    #[cfg(feature = "saving")]
+    writer: BufWriter<File>,
+}
+
+#[cfg(feature = "saving")]
+impl SliceWriter {
+    /// Compression level used for the header and all slices.
+    const LEVEL: i32 = 9;
+
+    pub(crate) fn create(path: &str, dp: &DynamicProgram) -> anyhow::Result<Self> {
+        let file = File::create(workspace::output_path(path, "dynamic_program")?)?;
+        let mut writer = BufWriter::new(file);
+        let mut encoder = Encoder::new(&mut writer, Self::LEVEL)?;
+
+        Header::new(dp).write(&mut encoder)?;
+        encoder.finish()?;
+
+        Ok(Self { writer })
+    }
+
+    pub(crate) fn write_slice(&mut self, slice: &Slice) -> anyhow::Result<()> {
+        self.writer
+            .write_all(&compress_slice(slice, Self::LEVEL)?)?;
+
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "saving"))]
+impl SliceWriter {
+    pub(crate) fn create(_path: &str, _dp: &DynamicProgram) -> anyhow::Result<Self> {
+        anyhow::bail!("streaming slices to disk requires the `saving` feature")
+    }
+
+    pub(crate) fn write_slice(&mut self, _slice: &Slice) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+fn empty_slice(domain: Domain) -> Slice {
+    vec![vec![0.0; domain.height()]; domain.width()]
+}
+
+/// Compresses a slice as a single zstd frame of little-endian `f64` values, ordered by `x` and
+/// then `y` coordinate.
+#[cfg(feature = "saving")]
+pub(crate) fn compress_slice(slice: &Slice, level: i32) -> std::io::Result<Vec<u8>> {
+    let bytes: Vec<u8> = slice
+        .iter()
+        .flatten()
+        .flat_map(|val| val.to_le_bytes())
+        .collect();
+
+    zstd::bulk::compress(&bytes, level)
+}
+
+/// Decompresses a slice compressed using [`compress_slice()`] covering `domain`.
+///
+/// Returns an error if the data is corrupted or does not match the size of `domain`.
+#[cfg(feature = "saving")]
+pub(crate) fn decompress_slice(data: &[u8], domain: Domain) -> std::io::Result<Slice> {
+    let height = domain.height();
+    let size = domain.width() * height * 8;
+    let bytes = zstd::bulk::decompress(data, size)?;
+
+    if bytes.len() != size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "slice does not match the size of the table",
+        ));
+    }
+
+    Ok(bytes
+        .chunks_exact(height * 8)
+        .map(|column| {
+            column
+                .chunks_exact(8)
+                .map(|val| f64::from_le_bytes(val.try_into().unwrap()))
+                .collect()
+        })
+        .collect())
+}
+
+/// Converts a path count to `f64`, saturating at infinity.
+fn count_to_f64(count: &BigUint) -> f64 {
+    count.to_f64().unwrap_or(f64::INFINITY)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::domain::Domain;
+    use crate::dp::table::Table;
+    use num::BigUint;
+
+    #[test]
+    fn test_rolling_table() {
+        let mut table = Table::rolling(3, Domain::square(3), vec![1], None);
+
+        for t in 0..=3 {
+            table.set(3, 3, t, t as f64);
+            table.finish_slice(t);
+        }
+
+        assert_eq!(table.get(3, 3, 3), Some(3.0));
+        assert_eq!(table.get(3, 3, 2), Some(2.0));
+        assert_eq!(table.get(3, 3, 1), Some(1.0));
+        assert_eq!(table.get(3, 3, 0), None);
+        assert!(table.slice(0).is_none());
+        assert!(table.keeps(1));
+        assert!(!table.keeps(0));
+    }
+
+    #[test]
+    fn test_full_f32_table() {
+        let mut table = Table::full_f32(3, Domain::square(3));
+
+        table.set(1, 2, 3, 0.25);
+
+        assert!(table.is_full());
+        assert_eq!(table.get(1, 2, 3), Some(0.25));
+        assert_eq!(table.slice(3).unwrap()[1][2], 0.25);
+    }
+
+    #[test]
+    fn test_sparse_table() {
+        let mut table = Table::sparse(3, Domain::square(3));
+
+        table.set(3, 3, 1, 0.5);
+        table.set(0, 0, 1, 0.0);
+
+        let Table::Sparse { slices, .. } = &table else {
+            unreachable!();
+        };
+
+        assert_eq!(slices[1].radius, 0);
+
+        table.set(4, 2, 1, 0.25);
+
+        assert_eq!(table.get(3, 3, 1), Some(0.5));
+        assert_eq!(table.get(4, 2, 1), Some(0.25));
+        assert_eq!(table.get(0, 6, 1), Some(0.0));
+        assert_eq!(table.slice(1).unwrap()[4][2], 0.25);
+        assert_eq!(table.slice(1).unwrap()[3][3], 0.5);
+    }
+
+    #[test]
+    #[cfg(feature = "saving")]
+    fn test_mapped_table_clone() {
+        let path = std::env::temp_dir().join("randomwalks_test_mapped_table_clone.dp");
+        let mut table = Table::mapped(2, Domain::square(2), path.to_str().unwrap()).unwrap();
+
+        table.set(2, 2, 1, 0.5);
+
+        let mut clone = table.clone();
+
+        // Modifying a shared table copies it instead of panicking
+        clone.set(2, 2, 1, 0.25);
+        table.set(1, 2, 1, 0.75);
+
+        assert_eq!(table.get(2, 2, 1), Some(0.5));
+        assert_eq!(table.get(1, 2, 1), Some(0.75));
+        assert_eq!(clone.get(2, 2, 1), Some(0.25));
+        assert_eq!(clone.get(1, 2, 1), Some(0.0));
+
+        drop(table);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "saving")]
+    fn test_compressed_table() {
+        let mut table = Table::compressed(5, Domain::square(5));
+
+        for t in 0..=5 {
+            table.set(5, 5, t, t as f64);
+            table.finish_slice(t);
+        }
+
+        let Table::Compressed(compressed) = &table else {
+            unreachable!();
+        };
+
+        assert_eq!(compressed.compressed.iter().flatten().count(), 4);
+        assert_eq!(table.get(5, 5, 1), Some(1.0));
+        assert_eq!(table.get(5, 5, 5), Some(5.0));
+        assert_eq!(table.slice(2).unwrap()[5][5], 2.0);
+        assert_eq!(table.get(4, 5, 2), Some(0.0));
+
+        // Modifying a compressed slice recompresses it
+        table.set(4, 5, 2, 0.5);
+
+        assert_eq!(table.get(4, 5, 2), Some(0.5));
+        assert_eq!(table.get(5, 5, 2), Some(2.0));
+        assert_eq!(table.clone(), table);
+    }
+
+    #[test]
+    fn test_quantized_table() {
+        let mut table = Table::quantized(5, Domain::square(5));
+
+        for t in 0..=5 {
+            table.set(5, 5, t, 0.5);
+            table.set(4, 5, t, 0.123456789);
+            table.set(6, 5, t, 1e-12);
+            table.finish_slice(t);
+        }
+
+        let Table::Quantized(quantized) = &table else {
+            unreachable!();
+        };
+
+        // The error is at most half of the scale of a slice
+        let eps = 0.5 * 0.5 / u16::MAX as f64;
+
+        assert_eq!(quantized.quantized.iter().flatten().count(), 4);
+        assert_eq!(table.get(4, 5, 5), Some(0.123456789));
+        assert!((table.get(5, 5, 1).unwrap() - 0.5).abs() <= eps);
+        assert!((table.get(4, 5, 1).unwrap() - 0.123456789).abs() <= eps);
+        assert!(table.get(6, 5, 1).unwrap() > 0.0);
+        assert_eq!(table.get(3, 5, 1), Some(0.0));
+        assert!((table.slice(2).unwrap()[5][5] - 0.5).abs() <= eps);
+
+        // Modifying a quantized slice quantizes it again
+        table.set(3, 5, 2, 0.25);
+
+        assert!((table.get(3, 5, 2).unwrap() - 0.25).abs() <= 2.0 * eps);
+        assert!((table.get(5, 5, 2).unwrap() - 0.5).abs() <= eps);
+    }
+
+    #[test]
+    fn test_counts_table() {
+        let mut table = Table::counts(3, Domain::square(3));
+        let count = BigUint::from(2u32).pow(80);
+
+        table.set(3, 3, 0, 1.0);
+        table.set_count(1, 2, 3, count.clone());
+
+        assert!(table.is_counting());
+        assert_eq!(table.get(3, 3, 0), Some(1.0));
+        assert_eq!(table.count(1, 2, 3), Some(&count));
+        assert_eq!(table.slice(3).unwrap()[1][2], 2f64.powi(80));
+        assert!(Table::full(3, Domain::square(3)).count(1, 2, 3).is_none());
+    }
+}
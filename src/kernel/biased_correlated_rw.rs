@@ -1,7 +1,7 @@
 use crate::kernel::biased_rw::BiasedRwGenerator;
 use crate::kernel::correlated_rw::CorrelatedRwGenerator;
-use crate::kernel::generator::{KernelGenerator, KernelGeneratorError};
-use crate::kernel::{Direction, Kernel};
+use crate::kernel::generator::KernelGenerator;
+use crate::kernel::{Direction, Kernel, KernelError};
 
 pub struct BiasedCorrelatedRwGenerator {
     pub probability: f64,
@@ -10,37 +10,48 @@ pub struct BiasedCorrelatedRwGenerator {
 }
 
 impl KernelGenerator for BiasedCorrelatedRwGenerator {
-    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn validate(&self) -> Result<(), KernelError> {
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(KernelError::ProbabilityOutOfRange);
+        }
+
+        if !(0.0..=1.0).contains(&self.persistence) {
+            return Err(KernelError::PersistenceOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         if kernels.len() != self.generates_qty() {
-            Err(KernelGeneratorError::NotEnoughKernels)
+            Err(KernelError::NotEnoughKernels)
         } else {
             for kernel in kernels.iter_mut() {
-                kernel.initialize(3).unwrap();
+                kernel.initialize(3)?;
             }
 
             Ok(())
         }
     }
 
-    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         if kernels.len() != self.generates_qty() {
-            Err(KernelGeneratorError::NotEnoughKernels)
+            Err(KernelError::NotEnoughKernels)
         } else {
             let mut correlated = Kernel::multiple_from_generator(CorrelatedRwGenerator {
                 persistence: self.persistence,
-            })
-            .unwrap();
+            })?;
 
             let biased = Kernel::from_generator(BiasedRwGenerator {
                 probability: self.probability,
                 direction: self.direction,
-            })
-            .unwrap();
+            })?;
 
             for kernel in correlated.iter_mut() {
                 *kernel *= biased.clone();
                 // Normalize such that all probabilities still sum to 1
-                *kernel /= Kernel::try_from_value(kernel.size(), kernel.sum()).unwrap();
+                let sum = kernel.sum();
+                *kernel /= sum;
             }
 
             *kernels = correlated;
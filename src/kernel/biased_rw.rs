@@ -1,5 +1,5 @@
-use crate::kernel::generator::{KernelGenerator, KernelGeneratorError};
-use crate::kernel::{Direction, Kernel};
+use crate::kernel::generator::KernelGenerator;
+use crate::kernel::{Direction, Kernel, KernelError};
 use strum::IntoEnumIterator;
 
 pub struct BiasedRwGenerator {
@@ -8,20 +8,27 @@ pub struct BiasedRwGenerator {
 }
 
 impl KernelGenerator for BiasedRwGenerator {
-    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn validate(&self) -> Result<(), KernelError> {
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(KernelError::ProbabilityOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         kernels
             .get_mut(0)
-            .ok_or(KernelGeneratorError::OneKernelRequired)?
-            .initialize(3)
-            .unwrap();
+            .ok_or(KernelError::OneKernelRequired)?
+            .initialize(3)?;
 
         Ok(())
     }
 
-    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         let kernel = kernels
             .get_mut(0)
-            .ok_or(KernelGeneratorError::OneKernelRequired)?;
+            .ok_or(KernelError::OneKernelRequired)?;
         let (direction_x, direction_y) = self.direction.into();
         let other_prob = (1.0 - self.probability) / 4.0;
 
@@ -51,7 +58,7 @@ impl KernelGenerator for BiasedRwGenerator {
 mod tests {
     use crate::kernel;
     use crate::kernel::biased_rw::BiasedRwGenerator;
-    use crate::kernel::{Direction, Kernel};
+    use crate::kernel::{Direction, Kernel, KernelError};
 
     #[test]
     #[rustfmt::skip]
@@ -70,4 +77,14 @@ mod tests {
         assert!(kernel.is_ok());
         assert_eq!(kernel.unwrap(), kernel_correct);
     }
+
+    #[test]
+    fn test_biased_rw_invalid_probability() {
+        let kernel = Kernel::from_generator(BiasedRwGenerator {
+            probability: 1.5,
+            direction: Direction::North,
+        });
+
+        assert!(matches!(kernel, Err(KernelError::ProbabilityOutOfRange)));
+    }
 }
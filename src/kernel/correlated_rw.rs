@@ -1,6 +1,6 @@
 use crate::kernel::biased_rw::BiasedRwGenerator;
-use crate::kernel::generator::{KernelGenerator, KernelGeneratorError};
-use crate::kernel::{Direction, Kernel};
+use crate::kernel::generator::KernelGenerator;
+use crate::kernel::{Direction, Kernel, KernelError};
 use strum::IntoEnumIterator;
 
 pub struct CorrelatedRwGenerator {
@@ -8,28 +8,35 @@ pub struct CorrelatedRwGenerator {
 }
 
 impl KernelGenerator for CorrelatedRwGenerator {
-    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn validate(&self) -> Result<(), KernelError> {
+        if !(0.0..=1.0).contains(&self.persistence) {
+            return Err(KernelError::PersistenceOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         if kernels.len() != self.generates_qty() {
-            Err(KernelGeneratorError::NotEnoughKernels)
+            Err(KernelError::NotEnoughKernels)
         } else {
             for kernel in kernels.iter_mut() {
-                kernel.initialize(3).unwrap();
+                kernel.initialize(3)?;
             }
 
             Ok(())
         }
     }
 
-    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         if kernels.len() != self.generates_qty() {
-            Err(KernelGeneratorError::NotEnoughKernels)
+            Err(KernelError::NotEnoughKernels)
         } else {
             for (i, direction) in Direction::iter().enumerate() {
                 kernels[i] = Kernel::from_generator(BiasedRwGenerator {
                     probability: self.persistence,
                     direction,
-                })
-                .unwrap();
+                })?;
             }
 
             Ok(())
@@ -0,0 +1,157 @@
+use crate::kernel::generator::KernelGenerator;
+use crate::kernel::{Kernel, KernelError};
+use statrs::distribution::{Continuous, MultivariateNormal};
+
+/// Generates an anisotropic kernel by discretizing a bivariate normal distribution.
+///
+/// The distribution is given by a 2x2 `covariance` matrix and an optional `drift` vector which
+/// shifts the mean of the distribution away from the center of the kernel. Both are given in
+/// cells per time step, with the first component being the X-axis and the second component being
+/// the Y-axis. This is the usual parameterization of movement models in movement ecology and
+/// allows using parameters that were fitted elsewhere.
+///
+/// ```
+/// # use randomwalks_lib::kernel::covariance::CovarianceGenerator;
+/// # use randomwalks_lib::kernel::Kernel;
+/// #
+/// let kernel = Kernel::from_generator(CovarianceGenerator {
+///     covariance: [[2.0, 0.5], [0.5, 1.0]],
+///     drift: (1.0, 0.0),
+///     size: 7,
+/// })
+/// .unwrap();
+/// ```
+pub struct CovarianceGenerator {
+    pub covariance: [[f64; 2]; 2],
+    pub drift: (f64, f64),
+    pub size: usize,
+}
+
+impl CovarianceGenerator {
+    pub fn new(covariance: [[f64; 2]; 2], drift: (f64, f64), size: usize) -> Self {
+        Self {
+            covariance,
+            drift,
+            size,
+        }
+    }
+}
+
+impl KernelGenerator for CovarianceGenerator {
+    fn validate(&self) -> Result<(), KernelError> {
+        let [[a, b], [c, d]] = self.covariance;
+
+        // The covariance matrix must be symmetric and positive definite
+        if b != c || a <= 0.0 || a * d - b * c <= 0.0 {
+            return Err(KernelError::InvalidCovariance);
+        }
+
+        if !self.drift.0.is_finite() || !self.drift.1.is_finite() {
+            return Err(KernelError::InvalidCovariance);
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
+        kernels
+            .get_mut(0)
+            .ok_or(KernelError::OneKernelRequired)?
+            .initialize(self.size)?;
+
+        Ok(())
+    }
+
+    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
+        let kernel = kernels.get_mut(0).ok_or(KernelError::OneKernelRequired)?;
+
+        let center = (self.size / 2) as f64;
+        let mean = vec![center + self.drift.0, center + self.drift.1];
+        let [[a, b], [c, d]] = self.covariance;
+        let distribution = MultivariateNormal::new(mean, vec![a, b, c, d])
+            .map_err(|_| KernelError::InvalidCovariance)?;
+
+        for x in 0..self.size {
+            for y in 0..self.size {
+                kernel.probabilities[x][y] = distribution.pdf(&vec![x as f64, y as f64].into());
+            }
+        }
+
+        // Normalize values so that they sum up to 1.0
+        let sum = kernel.sum();
+
+        if sum <= 0.0 {
+            return Err(KernelError::NotNormalized);
+        }
+
+        *kernel /= sum;
+
+        Ok(())
+    }
+
+    fn generates_qty(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> (String, String) {
+        ("cov".into(), "Anisotropic Normal Distribution".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernel::covariance::CovarianceGenerator;
+    use crate::kernel::normal_dist::NormalDistGenerator;
+    use crate::kernel::{Kernel, KernelError};
+
+    #[test]
+    fn test_isotropic_equals_normal_dist() {
+        let kernel1 = Kernel::from_generator(CovarianceGenerator {
+            covariance: [[2.0, 0.0], [0.0, 2.0]],
+            drift: (0.0, 0.0),
+            size: 5,
+        })
+        .unwrap();
+        let kernel2 = Kernel::from_generator(NormalDistGenerator::new(2.0, 5)).unwrap();
+
+        for x in -2..=2 {
+            for y in -2..=2 {
+                assert!((kernel1.at(x, y) - kernel2.at(x, y)).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_anisotropy_and_drift() {
+        let kernel = Kernel::from_generator(CovarianceGenerator {
+            covariance: [[4.0, 0.0], [0.0, 1.0]],
+            drift: (1.0, 0.0),
+            size: 5,
+        })
+        .unwrap();
+
+        // Larger variance along the X-axis
+        assert!(kernel.at(0, 2) < kernel.at(2, 0));
+        // Drift to the east
+        assert!(kernel.at(1, 0) > kernel.at(-1, 0));
+        assert!(kernel.at(1, 0) > kernel.at(0, 0));
+    }
+
+    #[test]
+    fn test_invalid_covariance() {
+        for covariance in [
+            [[1.0, 0.5], [0.2, 1.0]],
+            [[-1.0, 0.0], [0.0, 1.0]],
+            [[1.0, 2.0], [2.0, 1.0]],
+        ] {
+            assert_eq!(
+                Kernel::from_generator(CovarianceGenerator {
+                    covariance,
+                    drift: (0.0, 0.0),
+                    size: 5,
+                }),
+                Err(KernelError::InvalidCovariance)
+            );
+        }
+    }
+}
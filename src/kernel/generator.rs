@@ -1,21 +1,14 @@
-use crate::kernel::Kernel;
-use pyo3::pyclass;
-use thiserror::Error;
+use crate::kernel::{Kernel, KernelError};
 
 pub trait KernelGenerator {
-    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError>;
-    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError>;
+    /// Checks whether the parameters of the generator are valid. This is called before
+    /// [`prepare()`](KernelGenerator::prepare) and [`generate()`](KernelGenerator::generate).
+    fn validate(&self) -> Result<(), KernelError> {
+        Ok(())
+    }
+
+    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError>;
+    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError>;
     fn generates_qty(&self) -> usize;
     fn name(&self) -> (String, String);
 }
-
-#[derive(Error, Debug)]
-#[pyclass]
-pub enum KernelGeneratorError {
-    #[error("one kernel required, found none")]
-    OneKernelRequired,
-    #[error("multiple kernels required, not enough kernels were found")]
-    NotEnoughKernels,
-    #[error("kernel size must be odd")]
-    SizeEven,
-}
@@ -3,62 +3,166 @@
 use crate::kernel::biased_correlated_rw::BiasedCorrelatedRwGenerator;
 use crate::kernel::biased_rw::BiasedRwGenerator;
 use crate::kernel::correlated_rw::CorrelatedRwGenerator;
-use crate::kernel::generator::{KernelGenerator, KernelGeneratorError};
+use crate::kernel::covariance::CovarianceGenerator;
+use crate::kernel::generator::KernelGenerator;
 use crate::kernel::normal_dist::NormalDistGenerator;
 use crate::kernel::simple_rw::SimpleRwGenerator;
-use anyhow::bail;
-use pyo3::{pyclass, pymethods};
+use ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, PyErr, Python};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
-use std::ops::{Div, DivAssign, Index, IndexMut, Mul, MulAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign};
 use strum::EnumIter;
+use thiserror::Error;
 
 pub mod biased_correlated_rw;
 pub mod biased_rw;
 pub mod correlated_rw;
+pub mod covariance;
 pub mod generator;
 pub mod normal_dist;
+pub mod scale_space;
+pub mod schedule;
 pub mod simple_rw;
 
+/// Maximum deviation from 1.0 that is tolerated for the sum of a kernel's probabilities.
+const SUM_TOLERANCE: f64 = 1e-9;
+
+/// An error that can occur when creating or modifying a [`Kernel`].
+#[pyclass]
+#[derive(Error, Debug, PartialEq)]
+pub enum KernelError {
+    /// This error occurs when a generator requires a single kernel but none was given.
+    #[error("one kernel required, found none")]
+    OneKernelRequired,
+
+    /// This error occurs when a generator requires more kernels than were given.
+    #[error("multiple kernels required, not enough kernels were found")]
+    NotEnoughKernels,
+
+    /// This error occurs when a kernel is created with an even size. Kernels always need a
+    /// center, so their size must be odd.
+    #[error("kernel size must be odd")]
+    SizeEven,
+
+    /// This error occurs when a kernel is created from an array that is not square.
+    #[error("kernel must be square")]
+    NotSquare,
+
+    /// This error occurs when a probability parameter is outside of the range `[0, 1]`.
+    #[error("probability must be in the range [0, 1]")]
+    ProbabilityOutOfRange,
+
+    /// This error occurs when a persistence parameter is outside of the range `[0, 1]`.
+    #[error("persistence must be in the range [0, 1]")]
+    PersistenceOutOfRange,
+
+    /// This error occurs when a parameter of a normal distribution is not strictly positive.
+    #[error("diffusion must be greater than 0")]
+    NonPositiveDiffusion,
+
+    /// This error occurs when a covariance matrix is not symmetric and positive definite, or a
+    /// drift vector is not finite.
+    #[error("covariance matrix must be symmetric and positive definite")]
+    InvalidCovariance,
+
+    /// This error occurs when a tuple of offsets does not describe a valid [`Direction`].
+    #[error("invalid direction")]
+    InvalidDirection,
+
+    /// This error occurs when the probabilities of a generated kernel do not sum up to 1.
+    #[error("kernel probabilities must sum up to 1")]
+    NotNormalized,
+
+    /// This error occurs when a kernel contains a negative or non-finite probability.
+    #[error("kernel probabilities must be non-negative and finite")]
+    InvalidProbabilities,
+
+    /// This error occurs when a kernel is rotated by a number of degrees that is not a multiple
+    /// of 90.
+    #[error("degrees must be a multiple of 90")]
+    InvalidRotation,
+
+    /// This error occurs when the time ranges of a
+    /// [`KernelSchedule`](schedule::KernelSchedule) overlap.
+    #[error("time ranges of a kernel schedule must not overlap")]
+    OverlappingSchedule,
+
+    /// This error occurs when a kernel family is generated using a scale factor that is not
+    /// strictly positive.
+    #[error("scale factors must be greater than 0")]
+    InvalidScaleFactor,
+}
+
+impl From<KernelError> for PyErr {
+    fn from(value: KernelError) -> Self {
+        PyValueError::new_err(value.to_string())
+    }
+}
+
+/// A kernel describing the probabilities of moving to each neighboring field in one time step.
+///
+/// Deserialized kernels, e.g. in walker configurations, are checked using
+/// [`validate()`](Kernel::validate).
 #[pyclass]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "KernelData")]
 pub struct Kernel {
     pub probabilities: Vec<Vec<f64>>,
+    #[serde(default)]
     name: (String, String),
 }
 
+/// The serialized form of a [`Kernel`], which is validated before being turned into a kernel.
+#[derive(Deserialize)]
+struct KernelData {
+    probabilities: Vec<Vec<f64>>,
+    #[serde(default)]
+    name: (String, String),
+}
+
+impl TryFrom<KernelData> for Kernel {
+    type Error = KernelError;
+
+    fn try_from(data: KernelData) -> Result<Self, Self::Error> {
+        let kernel = Kernel {
+            probabilities: data.probabilities,
+            name: data.name,
+        };
+
+        kernel.validate()?;
+
+        Ok(kernel)
+    }
+}
+
 #[pymethods]
 impl Kernel {
     #[new]
     #[pyo3(signature = (size = 3, short_name = String::new(), long_name = String::new()))]
-    pub fn py_new(size: usize, short_name: String, long_name: String) -> anyhow::Result<Self> {
-        if size % 2 == 0 {
-            bail!("size must be odd");
-        }
-
-        Ok(Self {
-            probabilities: vec![vec![0.0; size]; size],
-            name: (short_name, long_name),
-        })
+    pub fn py_new(size: usize, short_name: String, long_name: String) -> Result<Self, KernelError> {
+        Kernel::try_new(size, (short_name, long_name))
     }
 
     #[staticmethod]
-    pub fn simple_rw() -> Self {
-        Kernel::from_generator(SimpleRwGenerator).unwrap()
+    #[pyo3(signature = (stay_probability = 0.2))]
+    pub fn simple_rw(stay_probability: f64) -> Result<Self, KernelError> {
+        Kernel::from_generator(SimpleRwGenerator { stay_probability })
     }
 
     #[staticmethod]
-    pub fn biased_rw(probability: f64, direction: Direction) -> Self {
+    pub fn biased_rw(probability: f64, direction: Direction) -> Result<Self, KernelError> {
         Kernel::from_generator(BiasedRwGenerator {
             probability,
             direction,
         })
-        .unwrap()
     }
 
     #[staticmethod]
-    pub fn correlated_rw(persistence: f64) -> Vec<Self> {
-        Kernel::multiple_from_generator(CorrelatedRwGenerator { persistence }).unwrap()
+    pub fn correlated_rw(persistence: f64) -> Result<Vec<Self>, KernelError> {
+        Kernel::multiple_from_generator(CorrelatedRwGenerator { persistence })
     }
 
     #[staticmethod]
@@ -66,25 +170,109 @@ impl Kernel {
         probability: f64,
         direction: Direction,
         persistence: f64,
-    ) -> Vec<Self> {
+    ) -> Result<Vec<Self>, KernelError> {
         Kernel::multiple_from_generator(BiasedCorrelatedRwGenerator {
             probability,
             direction,
             persistence,
         })
-        .unwrap()
     }
 
     #[staticmethod]
-    pub fn normal_dist(diffusion: f64, size: usize) -> Self {
-        Kernel::from_generator(NormalDistGenerator { diffusion, size }).unwrap()
+    pub fn normal_dist(diffusion: f64, size: usize) -> Result<Self, KernelError> {
+        Kernel::from_generator(NormalDistGenerator { diffusion, size })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (covariance, size, drift = (0.0, 0.0)))]
+    pub fn covariance(
+        covariance: [[f64; 2]; 2],
+        size: usize,
+        drift: (f64, f64),
+    ) -> Result<Self, KernelError> {
+        Kernel::from_generator(CovarianceGenerator {
+            covariance,
+            drift,
+            size,
+        })
+    }
+
+    #[staticmethod]
+    pub fn from_numpy(array: PyReadonlyArray2<f64>) -> Result<Self, KernelError> {
+        Kernel::from_array(array.as_array().to_owned())
+    }
+
+    pub fn to_numpy<'py>(&self, py: Python<'py>) -> &'py PyArray2<f64> {
+        self.to_array().into_pyarray(py)
+    }
+
+    /// Returns the expected displacement of a single step, i.e. the drift of the kernel.
+    ///
+    /// Like all following moments, it is computed from the normalized probabilities of the kernel.
+    pub fn mean_displacement(&self) -> (f64, f64) {
+        self.movements()
+            .fold((0.0, 0.0), |(mx, my), (x, y, p)| (mx + p * x, my + p * y))
+    }
+
+    /// Returns the covariance matrix of the displacement of a single step.
+    pub fn displacement_covariance(&self) -> [[f64; 2]; 2] {
+        let (mx, my) = self.mean_displacement();
+        let mut covariance = [[0.0; 2]; 2];
+
+        for (x, y, p) in self.movements() {
+            let (dx, dy) = (x - mx, y - my);
+
+            covariance[0][0] += p * dx * dx;
+            covariance[0][1] += p * dx * dy;
+            covariance[1][1] += p * dy * dy;
+        }
+
+        covariance[1][0] = covariance[0][1];
+
+        covariance
+    }
+
+    /// Returns the expected Euclidean length of a single step.
+    pub fn mean_step_length(&self) -> f64 {
+        self.movements().map(|(x, y, p)| p * x.hypot(y)).sum()
+    }
+
+    /// Returns the root-mean-square distance from the start point after `time_steps` steps.
+    ///
+    /// As steps are independent, the mean squared distance grows linearly with the number of
+    /// steps due to the dispersion of the kernel and quadratically due to its drift.
+    pub fn rms_distance(&self, time_steps: usize) -> f64 {
+        let (dispersion, drift) = self.dispersion_and_drift();
+        let t = time_steps as f64;
+
+        (t * dispersion + t * t * drift).sqrt()
+    }
+
+    /// Returns the number of time steps after which the root-mean-square distance from the start
+    /// point equals `distance`.
+    ///
+    /// Dividing it by `distance` gives the multiplier for
+    /// [`time_steps_by_dist()`](crate::dataset::walks_builder::DatasetWalksBuilder::time_steps_by_dist)
+    /// at which walks typically cover the distance between two points. Multipliers far below it
+    /// lead to walks that are unlikely under the kernel, while multipliers far above it lead to
+    /// walks wandering around aimlessly. Returns infinity if the kernel never moves.
+    pub fn expected_time_steps(&self, distance: f64) -> f64 {
+        let (dispersion, drift) = self.dispersion_and_drift();
+        let squared = distance * distance;
+
+        if drift == 0.0 {
+            return squared / dispersion;
+        }
+
+        // Positive solution of drift * t^2 + dispersion * t = distance^2
+        ((dispersion * dispersion + 4.0 * drift * squared).sqrt() - dispersion) / (2.0 * drift)
     }
 }
 
 impl Kernel {
-    pub fn try_new(size: usize, name: (String, String)) -> anyhow::Result<Self> {
+    pub fn try_new(size: usize, name: (String, String)) -> Result<Self, KernelError> {
         if size % 2 == 0 {
-            bail!("size must be odd");
+            return Err(KernelError::SizeEven);
         }
 
         Ok(Self {
@@ -93,37 +281,54 @@ impl Kernel {
         })
     }
 
-    pub fn from_generator(generator: impl KernelGenerator) -> Result<Kernel, KernelGeneratorError> {
+    /// Creates a kernel using the given [`KernelGenerator`].
+    ///
+    /// The parameters of the generator are validated before generation and the resulting kernel
+    /// is checked to sum up to 1.
+    pub fn from_generator(generator: impl KernelGenerator) -> Result<Kernel, KernelError> {
         let kernel = Kernel {
             probabilities: Vec::new(),
             name: generator.name(),
         };
         let mut kernels = vec![kernel];
 
+        generator.validate()?;
         generator.prepare(&mut kernels)?;
         generator.generate(&mut kernels)?;
 
-        Ok(kernels[0].clone())
+        let kernel = kernels.swap_remove(0);
+        kernel.check_normalized()?;
+
+        Ok(kernel)
     }
 
+    /// Creates multiple kernels using the given [`KernelGenerator`].
+    ///
+    /// The parameters of the generator are validated before generation and each resulting kernel
+    /// is checked to sum up to 1.
     pub fn multiple_from_generator(
         generator: impl KernelGenerator,
-    ) -> Result<Vec<Kernel>, KernelGeneratorError> {
+    ) -> Result<Vec<Kernel>, KernelError> {
         let kernel = Kernel {
             probabilities: Vec::new(),
             name: generator.name(),
         };
-        let mut kernels = vec![kernel.clone(); generator.generates_qty()];
+        let mut kernels = vec![kernel; generator.generates_qty()];
 
+        generator.validate()?;
         generator.prepare(&mut kernels)?;
         generator.generate(&mut kernels)?;
 
+        for kernel in kernels.iter() {
+            kernel.check_normalized()?;
+        }
+
         Ok(kernels)
     }
 
-    pub fn try_from_value(size: usize, value: f64) -> anyhow::Result<Self> {
+    pub fn try_from_value(size: usize, value: f64) -> Result<Self, KernelError> {
         if size % 2 == 0 {
-            bail!("size must be odd")
+            return Err(KernelError::SizeEven);
         }
 
         Ok(Self {
@@ -132,13 +337,59 @@ impl Kernel {
         })
     }
 
-    pub fn initialize(&mut self, size: usize) -> Result<(), KernelGeneratorError> {
+    /// Creates a kernel from a square array of probabilities.
+    ///
+    /// The array is indexed as `array[[y, x]]`, i.e. its rows correspond to the y-axis of the
+    /// kernel. This is the same layout that is used by the [`kernel!`] macro.
+    ///
+    /// ```
+    /// # use randomwalks_lib::kernel;
+    /// # use randomwalks_lib::kernel::Kernel;
+    /// # use ndarray::array;
+    /// #
+    /// let kernel = Kernel::from_array(array![
+    ///     [0.0, 0.2, 0.0],
+    ///     [0.2, 0.2, 0.2],
+    ///     [0.0, 0.2, 0.0],
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(kernel.at(0, -1), 0.2);
+    /// ```
+    pub fn from_array(array: Array2<f64>) -> Result<Self, KernelError> {
+        let (rows, columns) = array.dim();
+
+        if rows != columns {
+            return Err(KernelError::NotSquare);
+        }
+
+        if rows % 2 == 0 {
+            return Err(KernelError::SizeEven);
+        }
+
+        Ok(Self {
+            probabilities: (0..columns)
+                .map(|x| (0..rows).map(|y| array[[y, x]]).collect())
+                .collect(),
+            name: ("ck".into(), "Custom Kernel".into()),
+        })
+    }
+
+    /// Returns the probabilities of the kernel as an array indexed as `array[[y, x]]`.
+    ///
+    /// See [`from_array()`](Kernel::from_array) for details on the layout.
+    pub fn to_array(&self) -> Array2<f64> {
+        Array2::from_shape_fn((self.size(), self.size()), |(y, x)| {
+            self.probabilities[x][y]
+        })
+    }
+
+    pub fn initialize(&mut self, size: usize) -> Result<(), KernelError> {
         if size % 2 == 1 {
             self.probabilities = vec![vec![0.0; size]; size];
 
             Ok(())
         } else {
-            Err(KernelGeneratorError::SizeEven)
+            Err(KernelError::SizeEven)
         }
     }
 
@@ -158,6 +409,46 @@ impl Kernel {
         sum
     }
 
+    /// Checks whether all probabilities of the kernel sum up to 1.
+    pub fn check_normalized(&self) -> Result<(), KernelError> {
+        if (self.sum() - 1.0).abs() > SUM_TOLERANCE {
+            Err(KernelError::NotNormalized)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks whether the kernel can be used in a dynamic program.
+    ///
+    /// The kernel must be square with an odd size, and all of its probabilities must be
+    /// non-negative and finite and sum up to 1, unless all of them are zero.
+    pub fn validate(&self) -> Result<(), KernelError> {
+        let size = self.size();
+
+        if self.probabilities.iter().any(|column| column.len() != size) {
+            return Err(KernelError::NotSquare);
+        }
+
+        if size % 2 == 0 {
+            return Err(KernelError::SizeEven);
+        }
+
+        if self
+            .probabilities
+            .iter()
+            .flatten()
+            .any(|&p| !p.is_finite() || p < 0.0)
+        {
+            return Err(KernelError::InvalidProbabilities);
+        }
+
+        if self.sum() != 0.0 {
+            self.check_normalized()?;
+        }
+
+        Ok(())
+    }
+
     pub fn set(&mut self, x: isize, y: isize, val: f64) {
         let x = ((self.probabilities.len() / 2) as isize + x) as usize;
         let y = ((self.probabilities.len() / 2) as isize + y) as usize;
@@ -172,10 +463,27 @@ impl Kernel {
         self.probabilities[x][y]
     }
 
+    /// Returns the probability of moving by `(x, y)` from a field with the given `diffusivity`.
+    ///
+    /// All movements are scaled by the diffusivity and the remaining probability is added to
+    /// staying, so that a diffusivity below 1 slows walks down without blocking them. Kernels
+    /// whose probabilities are all zero stay zero.
+    pub(crate) fn diffused_at(&self, x: isize, y: isize, diffusivity: f64) -> f64 {
+        if diffusivity == 1.0 {
+            return self.at(x, y);
+        }
+
+        if x == 0 && y == 0 {
+            diffusivity * self.at(0, 0) + (1.0 - diffusivity) * self.sum()
+        } else {
+            diffusivity * self.at(x, y)
+        }
+    }
+
     /// Rotate kernel matrix clockwise by `degrees`. Only multiples of 90° are supported.
-    pub fn rotate(&mut self, degrees: usize) -> Result<(), String> {
+    pub fn rotate(&mut self, degrees: usize) -> Result<(), KernelError> {
         if degrees % 90 != 0 {
-            Err("degrees must be a multiple of 90.".into())
+            Err(KernelError::InvalidRotation)
         } else {
             let n = self.probabilities.len();
 
@@ -204,6 +512,24 @@ impl Kernel {
             self.name.1.clone()
         }
     }
+
+    /// Returns all movements of the kernel together with their normalized probabilities.
+    fn movements(&self) -> impl Iterator<Item = (f64, f64, f64)> + '_ {
+        let ks = (self.size() / 2) as isize;
+        let sum = self.sum();
+
+        (-ks..=ks)
+            .flat_map(move |x| (-ks..=ks).map(move |y| (x as f64, y as f64, self.at(x, y) / sum)))
+    }
+
+    /// Returns the trace of the covariance matrix of a single step and the squared length of
+    /// its mean displacement.
+    fn dispersion_and_drift(&self) -> (f64, f64) {
+        let [[var_x, _], [_, var_y]] = self.displacement_covariance();
+        let (mx, my) = self.mean_displacement();
+
+        (var_x + var_y, mx * mx + my * my)
+    }
 }
 
 impl Debug for Kernel {
@@ -308,6 +634,80 @@ impl DivAssign for Kernel {
     }
 }
 
+impl Add for Kernel {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.size() == rhs.size() {
+            let mut new_kernel = self.clone();
+
+            for x in 0..self.size() {
+                for y in 0..self.size() {
+                    new_kernel.probabilities[x][y] += rhs.probabilities[x][y];
+                }
+            }
+
+            new_kernel
+        } else {
+            panic!("both kernels must have the same size for addition");
+        }
+    }
+}
+
+impl AddAssign for Kernel {
+    fn add_assign(&mut self, rhs: Self) {
+        if self.size() == rhs.size() {
+            for x in 0..self.size() {
+                for y in 0..self.size() {
+                    self.probabilities[x][y] += rhs.probabilities[x][y];
+                }
+            }
+        } else {
+            panic!("both kernels must have the same size for addition");
+        }
+    }
+}
+
+impl Mul<f64> for Kernel {
+    type Output = Self;
+
+    fn mul(mut self, rhs: f64) -> Self::Output {
+        self *= rhs;
+
+        self
+    }
+}
+
+impl MulAssign<f64> for Kernel {
+    fn mul_assign(&mut self, rhs: f64) {
+        for x in 0..self.size() {
+            for y in 0..self.size() {
+                self.probabilities[x][y] *= rhs;
+            }
+        }
+    }
+}
+
+impl Div<f64> for Kernel {
+    type Output = Self;
+
+    fn div(mut self, rhs: f64) -> Self::Output {
+        self /= rhs;
+
+        self
+    }
+}
+
+impl DivAssign<f64> for Kernel {
+    fn div_assign(&mut self, rhs: f64) {
+        for x in 0..self.size() {
+            for y in 0..self.size() {
+                self.probabilities[x][y] /= rhs;
+            }
+        }
+    }
+}
+
 /// A macro that allows quick creation of a custom kernel.
 #[macro_export]
 macro_rules! kernel {
@@ -350,7 +750,7 @@ pub struct Directions<T> {
 }
 
 impl TryFrom<(isize, isize)> for Direction {
-    type Error = &'static str;
+    type Error = KernelError;
 
     fn try_from(value: (isize, isize)) -> Result<Self, Self::Error> {
         match value {
@@ -359,7 +759,7 @@ impl TryFrom<(isize, isize)> for Direction {
             (0, 1) => Ok(Self::South),
             (-1, 0) => Ok(Self::West),
             (0, 0) => Ok(Self::Stay),
-            _ => Err("Invalid direction"),
+            _ => Err(KernelError::InvalidDirection),
         }
     }
 }
@@ -417,7 +817,9 @@ impl<T> IndexMut<Direction> for Directions<T> {
 #[cfg(test)]
 #[rustfmt::skip]
 mod tests {
-    use crate::kernel::Kernel;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::{Direction, Kernel, KernelError};
+    use ndarray::{array, Array2};
 
     #[test]
     fn test_rotate_invalid() {
@@ -427,7 +829,7 @@ mod tests {
             7.0, 8.0, 9.0,
         ];
 
-        assert!(kernel.rotate(87).is_err());
+        assert_eq!(kernel.rotate(87), Err(KernelError::InvalidRotation));
     }
 
     #[test]
@@ -503,4 +905,186 @@ mod tests {
 
         assert_eq!(kernel, kernel_correct);
     }
+
+    #[test]
+    fn test_from_to_array() {
+        let kernel = kernel![
+            1.0, 2.0, 3.0,
+            4.0, 5.0, 6.0,
+            7.0, 8.0, 9.0,
+        ];
+
+        let array = array![
+            [1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0],
+            [7.0, 8.0, 9.0],
+        ];
+
+        assert_eq!(kernel.to_array(), array);
+        assert_eq!(Kernel::from_array(array).unwrap(), kernel);
+        assert_eq!(
+            Kernel::from_array(Array2::zeros((3, 5))),
+            Err(KernelError::NotSquare)
+        );
+        assert_eq!(
+            Kernel::from_array(Array2::zeros((4, 4))),
+            Err(KernelError::SizeEven)
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_kernel_arithmetic() {
+        let kernel1 = kernel![
+            1.0, 2.0, 3.0,
+            4.0, 5.0, 6.0,
+            7.0, 8.0, 9.0,
+        ];
+
+        let kernel2 = Kernel::try_from_value(3, 2.0).unwrap();
+
+        assert_eq!(kernel1.sum(), 45.0);
+        assert_eq!(kernel2.sum(), 18.0);
+
+        assert_eq!(kernel1.clone() + kernel2.clone(), kernel![
+            3.0, 4.0, 5.0,
+            6.0, 7.0, 8.0,
+            9.0, 10.0, 11.0,
+        ]);
+        assert_eq!(kernel1.clone() * kernel2.clone(), kernel1.clone() * 2.0);
+        assert_eq!(kernel1.clone() / kernel2.clone(), kernel1.clone() / 2.0);
+        assert_eq!(kernel1.clone() / 2.0, kernel![
+            0.5, 1.0, 1.5,
+            2.0, 2.5, 3.0,
+            3.5, 4.0, 4.5,
+        ]);
+
+        let mut kernel3 = kernel1.clone();
+        kernel3 += kernel2.clone();
+        kernel3 *= 2.0;
+        kernel3 /= kernel2;
+        let sum = kernel3.sum();
+        kernel3 /= sum;
+
+        assert_eq!(kernel3, (kernel1 + Kernel::try_from_value(3, 2.0).unwrap()) / 63.0);
+        assert!(kernel3.check_normalized().is_ok());
+    }
+
+    #[test]
+    fn test_try_from_value_size_even() {
+        assert_eq!(Kernel::try_from_value(2, 1.0), Err(KernelError::SizeEven));
+    }
+
+    #[test]
+    fn test_try_new_size_even() {
+        assert!(matches!(
+            Kernel::try_new(4, ("".into(), "".into())),
+            Err(KernelError::SizeEven)
+        ));
+    }
+
+    #[test]
+    fn test_from_generator_normalized() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+
+        assert!(kernel.check_normalized().is_ok());
+    }
+
+    #[test]
+    fn test_check_normalized() {
+        let kernel = kernel![
+            0.0, 0.5, 0.0,
+            0.5, 0.5, 0.5,
+            0.0, 0.5, 0.0,
+        ];
+
+        assert_eq!(kernel.check_normalized(), Err(KernelError::NotNormalized));
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+
+        assert_eq!(kernel.validate(), Ok(()));
+        assert_eq!(Kernel::try_new(5, Default::default()).unwrap().validate(), Ok(()));
+
+        kernel.set(0, 0, f64::NAN);
+
+        assert_eq!(kernel.validate(), Err(KernelError::InvalidProbabilities));
+
+        kernel.set(0, 0, -0.2);
+
+        assert_eq!(kernel.validate(), Err(KernelError::InvalidProbabilities));
+
+        kernel.set(0, 0, 0.5);
+
+        assert_eq!(kernel.validate(), Err(KernelError::NotNormalized));
+
+        kernel.probabilities[0].pop();
+
+        assert_eq!(kernel.validate(), Err(KernelError::NotSquare));
+
+        let even = Kernel {
+            probabilities: vec![vec![0.25; 2]; 2],
+            name: Default::default(),
+        };
+
+        assert_eq!(even.validate(), Err(KernelError::SizeEven));
+    }
+
+    #[test]
+    fn test_deserialize_validated() {
+        let kernel: Result<Kernel, _> = serde_json::from_value(serde_json::json!({
+            "probabilities": [[0.0, 0.2, 0.0], [0.2, 0.2, 0.2], [0.0, 0.2, 0.0]]
+        }));
+
+        assert!(kernel.is_ok());
+
+        let kernel: Result<Kernel, _> = serde_json::from_value(serde_json::json!({
+            "probabilities": [[0.0, -0.2, 0.0], [0.2, 0.6, 0.2], [0.0, 0.2, 0.0]]
+        }));
+
+        assert!(kernel.is_err());
+    }
+
+    #[test]
+    fn test_diffused_at() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::new(0.2)).unwrap();
+
+        assert_eq!(kernel.diffused_at(1, 0, 1.0), kernel.at(1, 0));
+        assert!((kernel.diffused_at(1, 0, 0.5) - 0.1).abs() < 1e-12);
+        assert!((kernel.diffused_at(0, 0, 0.5) - 0.6).abs() < 1e-12);
+        assert_eq!(kernel.diffused_at(0, 0, 0.0), 1.0);
+        assert_eq!(Kernel::try_from_value(3, 0.0).unwrap().diffused_at(0, 0, 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_displacement_moments() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::new(0.2)).unwrap();
+        let covariance = kernel.displacement_covariance();
+
+        assert_eq!(kernel.mean_displacement(), (0.0, 0.0));
+        assert!((covariance[0][0] - 0.4).abs() < 1e-12);
+        assert!((covariance[1][1] - 0.4).abs() < 1e-12);
+        assert_eq!(covariance[0][1], 0.0);
+        assert!((kernel.mean_step_length() - 0.8).abs() < 1e-12);
+        assert!((kernel.rms_distance(100) - 80.0_f64.sqrt()).abs() < 1e-9);
+        assert!((kernel.expected_time_steps(80.0_f64.sqrt()) - 100.0).abs() < 1e-9);
+
+        let drifting = kernel![
+            0.0, 0.0, 0.0,
+            0.0, 0.0, 1.0,
+            0.0, 0.0, 0.0,
+        ];
+
+        assert_eq!(drifting.mean_displacement(), (1.0, 0.0));
+        assert_eq!(drifting.rms_distance(10), 10.0);
+        assert!((drifting.expected_time_steps(10.0) - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_direction_try_from() {
+        assert_eq!(Direction::try_from((0, -1)), Ok(Direction::North));
+        assert_eq!(Direction::try_from((1, 1)), Err(KernelError::InvalidDirection));
+    }
 }
@@ -1,5 +1,5 @@
-use crate::kernel::generator::{KernelGenerator, KernelGeneratorError};
-use crate::kernel::Kernel;
+use crate::kernel::generator::KernelGenerator;
+use crate::kernel::{Kernel, KernelError};
 use statrs::distribution::{Continuous, MultivariateNormal};
 
 pub struct NormalDistGenerator {
@@ -14,23 +14,32 @@ impl NormalDistGenerator {
 }
 
 impl KernelGenerator for NormalDistGenerator {
-    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn validate(&self) -> Result<(), KernelError> {
+        if self.diffusion <= 0.0 {
+            return Err(KernelError::NonPositiveDiffusion);
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         kernels
             .get_mut(0)
-            .ok_or(KernelGeneratorError::OneKernelRequired)?
+            .ok_or(KernelError::OneKernelRequired)?
             .initialize(self.size)?;
 
         Ok(())
     }
 
-    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         let kernel = kernels
             .get_mut(0)
-            .ok_or(KernelGeneratorError::OneKernelRequired)?;
+            .ok_or(KernelError::OneKernelRequired)?;
 
         let mean = vec![(self.size / 2) as f64, (self.size / 2) as f64];
         let cov = vec![self.diffusion, 0.0, 0.0, self.diffusion];
-        let distribution = MultivariateNormal::new(mean, cov).unwrap();
+        let distribution =
+            MultivariateNormal::new(mean, cov).map_err(|_| KernelError::NonPositiveDiffusion)?;
 
         for x in 0..self.size {
             for y in 0..self.size {
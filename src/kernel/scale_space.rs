@@ -0,0 +1,137 @@
+//! Provides the generation of kernel families at multiple spatial resolutions.
+//!
+//! A scale-space family consists of kernels generated from the same base generator at different
+//! spatial scales, e.g. a Gaussian at `sigma`, `2 * sigma` and `4 * sigma`. Such families can be
+//! used for multi-resolution dynamic programs, e.g. a coarse dynamic program for long walks and a
+//! fine dynamic program near the endpoints.
+//!
+//! Generators that support scaling implement [`ScalableGenerator`]. A family is created using
+//! [`Kernel::scale_space()`].
+//!
+//! ```
+//! # use randomwalks_lib::kernel::normal_dist::NormalDistGenerator;
+//! # use randomwalks_lib::kernel::scale_space::octaves;
+//! # use randomwalks_lib::kernel::Kernel;
+//! #
+//! let kernels = Kernel::scale_space(NormalDistGenerator::new(1.0, 5), &octaves(3)).unwrap();
+//!
+//! assert_eq!(kernels.len(), 3);
+//! assert_eq!(kernels[2].size(), 21);
+//! ```
+
+use crate::kernel::covariance::CovarianceGenerator;
+use crate::kernel::generator::KernelGenerator;
+use crate::kernel::normal_dist::NormalDistGenerator;
+use crate::kernel::{Kernel, KernelError};
+
+/// A [`KernelGenerator`] whose kernels can be generated at different spatial scales.
+pub trait ScalableGenerator: KernelGenerator + Sized {
+    /// Returns a generator that generates kernels spatially scaled by `factor`.
+    ///
+    /// Distances covered by the kernel are multiplied by `factor`. The size of the kernel is
+    /// increased accordingly.
+    fn scaled(&self, factor: f64) -> Self;
+}
+
+impl ScalableGenerator for NormalDistGenerator {
+    fn scaled(&self, factor: f64) -> Self {
+        // The diffusion is the variance of the distribution, thus it scales quadratically
+        Self {
+            diffusion: self.diffusion * factor.powi(2),
+            size: scaled_size(self.size, factor),
+        }
+    }
+}
+
+impl ScalableGenerator for CovarianceGenerator {
+    fn scaled(&self, factor: f64) -> Self {
+        let [[a, b], [c, d]] = self.covariance;
+        let f2 = factor.powi(2);
+
+        Self {
+            covariance: [[a * f2, b * f2], [c * f2, d * f2]],
+            drift: (self.drift.0 * factor, self.drift.1 * factor),
+            size: scaled_size(self.size, factor),
+        }
+    }
+}
+
+impl Kernel {
+    /// Creates a family of kernels from `generator` at each of the given scale `factors`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KernelError::InvalidScaleFactor`] if a factor is not strictly positive and
+    /// finite. Otherwise, errors of the generator are returned.
+    pub fn scale_space(
+        generator: impl ScalableGenerator,
+        factors: &[f64],
+    ) -> Result<Vec<Kernel>, KernelError> {
+        factors
+            .iter()
+            .map(|factor| {
+                if !factor.is_finite() || *factor <= 0.0 {
+                    return Err(KernelError::InvalidScaleFactor);
+                }
+
+                Kernel::from_generator(generator.scaled(*factor))
+            })
+            .collect()
+    }
+}
+
+/// Returns `n` scale factors which double from level to level, i.e. `1, 2, 4, ...`.
+pub fn octaves(n: usize) -> Vec<f64> {
+    (0..n).map(|i| 2f64.powi(i as i32)).collect()
+}
+
+/// Scales a kernel size by `factor` while keeping it odd.
+fn scaled_size(size: usize, factor: f64) -> usize {
+    let size = ((size as f64 * factor).ceil() as usize).max(1);
+
+    if size % 2 == 0 {
+        size + 1
+    } else {
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernel::normal_dist::NormalDistGenerator;
+    use crate::kernel::scale_space::{octaves, ScalableGenerator};
+    use crate::kernel::{Kernel, KernelError};
+
+    #[test]
+    fn test_scale_space() {
+        let generator = NormalDistGenerator::new(1.0, 5);
+        let kernels = Kernel::scale_space(generator, &octaves(3)).unwrap();
+
+        assert_eq!(
+            kernels.iter().map(|k| k.size()).collect::<Vec<_>>(),
+            vec![5, 11, 21]
+        );
+
+        // Twice the distance at twice the scale has approximately the same relative probability
+        let ratio1 = kernels[0].at(1, 0) / kernels[0].at(0, 0);
+        let ratio2 = kernels[1].at(2, 0) / kernels[1].at(0, 0);
+
+        assert!((ratio1 - ratio2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scaled() {
+        let generator = NormalDistGenerator::new(1.5, 3).scaled(2.0);
+
+        assert_eq!(generator.diffusion, 6.0);
+        assert_eq!(generator.size, 7);
+    }
+
+    #[test]
+    fn test_invalid_scale_factor() {
+        assert_eq!(
+            Kernel::scale_space(NormalDistGenerator::new(1.0, 5), &[1.0, 0.0]),
+            Err(KernelError::InvalidScaleFactor)
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Provides kernel schedules that switch between kernels over time.
+//!
+//! A [`KernelSchedule`] assigns kernels to ranges of time steps. Dynamic programs and walkers
+//! use the scheduled kernel for all time steps inside of a range, which allows modeling movement
+//! that changes its behavior over time, e.g. alternating resting and traveling phases.
+
+use crate::kernel::{Kernel, KernelError};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// A list of time ranges, each associated with a [`Kernel`].
+///
+/// Schedules are serialized as a list of pairs of time ranges and kernels and are validated when
+/// they are deserialized.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(
+    try_from = "Vec<(Range<usize>, Kernel)>",
+    into = "Vec<(Range<usize>, Kernel)>"
+)]
+pub struct KernelSchedule {
+    entries: Vec<(Range<usize>, Kernel)>,
+}
+
+impl KernelSchedule {
+    /// Creates a new kernel schedule from pairs of time ranges and kernels.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KernelError::OverlappingSchedule`] if any two time ranges overlap.
+    pub fn new(mut entries: Vec<(Range<usize>, Kernel)>) -> Result<Self, KernelError> {
+        entries.sort_by_key(|(range, _)| range.start);
+
+        for pair in entries.windows(2) {
+            if pair[0].0.end > pair[1].0.start {
+                return Err(KernelError::OverlappingSchedule);
+            }
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Returns the kernel scheduled for time step `t`, if any.
+    pub fn kernel_at(&self, t: usize) -> Option<&Kernel> {
+        self.entries
+            .iter()
+            .find(|(range, _)| range.contains(&t))
+            .map(|(_, kernel)| kernel)
+    }
+
+    /// Returns whether the schedule contains any entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over all time ranges and their kernels.
+    pub fn iter(&self) -> std::slice::Iter<'_, (Range<usize>, Kernel)> {
+        self.entries.iter()
+    }
+}
+
+impl TryFrom<Vec<(Range<usize>, Kernel)>> for KernelSchedule {
+    type Error = KernelError;
+
+    fn try_from(entries: Vec<(Range<usize>, Kernel)>) -> Result<Self, Self::Error> {
+        Self::new(entries)
+    }
+}
+
+impl From<KernelSchedule> for Vec<(Range<usize>, Kernel)> {
+    fn from(schedule: KernelSchedule) -> Self {
+        schedule.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernel::biased_rw::BiasedRwGenerator;
+    use crate::kernel::schedule::KernelSchedule;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::{Direction, Kernel, KernelError};
+
+    #[test]
+    fn test_kernel_schedule_kernel_at() {
+        let simple = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let biased = Kernel::from_generator(BiasedRwGenerator {
+            probability: 0.5,
+            direction: Direction::North,
+        })
+        .unwrap();
+
+        let schedule =
+            KernelSchedule::new(vec![(10..20, biased.clone()), (0..10, simple.clone())]).unwrap();
+
+        assert_eq!(schedule.kernel_at(0), Some(&simple));
+        assert_eq!(schedule.kernel_at(15), Some(&biased));
+        assert_eq!(schedule.kernel_at(20), None);
+    }
+
+    #[test]
+    fn test_kernel_schedule_overlapping() {
+        let simple = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let schedule = KernelSchedule::new(vec![(0..10, simple.clone()), (5..15, simple)]);
+
+        assert!(matches!(schedule, Err(KernelError::OverlappingSchedule)));
+    }
+}
@@ -1,29 +1,58 @@
-use crate::kernel::generator::{KernelGenerator, KernelGeneratorError};
-use crate::kernel::Kernel;
+use crate::kernel::generator::KernelGenerator;
+use crate::kernel::{Kernel, KernelError};
 
-pub struct SimpleRwGenerator;
+/// Generates a kernel for a simple random walk.
+///
+/// The walker stays at its current position with probability `stay_probability`. The remaining
+/// probability is split equally among the four possible moves. By default, all five options
+/// are equally likely.
+pub struct SimpleRwGenerator {
+    pub stay_probability: f64,
+}
+
+impl SimpleRwGenerator {
+    pub fn new(stay_probability: f64) -> Self {
+        Self { stay_probability }
+    }
+}
+
+impl Default for SimpleRwGenerator {
+    fn default() -> Self {
+        Self {
+            stay_probability: 0.2,
+        }
+    }
+}
 
 impl KernelGenerator for SimpleRwGenerator {
-    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn validate(&self) -> Result<(), KernelError> {
+        if !(0.0..=1.0).contains(&self.stay_probability) {
+            return Err(KernelError::ProbabilityOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         kernels
             .get_mut(0)
-            .ok_or(KernelGeneratorError::OneKernelRequired)?
-            .initialize(3)
-            .unwrap();
+            .ok_or(KernelError::OneKernelRequired)?
+            .initialize(3)?;
 
         Ok(())
     }
 
-    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelGeneratorError> {
+    fn generate(&self, kernels: &mut Vec<Kernel>) -> Result<(), KernelError> {
         let kernel = kernels
             .get_mut(0)
-            .ok_or(KernelGeneratorError::OneKernelRequired)?;
+            .ok_or(KernelError::OneKernelRequired)?;
+        let move_prob = (1.0 - self.stay_probability) / 4.0;
 
-        kernel.set(0, 0, 0.2);
-        kernel.set(0, -1, 0.2);
-        kernel.set(1, 0, 0.2);
-        kernel.set(0, 1, 0.2);
-        kernel.set(-1, 0, 0.2);
+        kernel.set(0, 0, self.stay_probability);
+        kernel.set(0, -1, move_prob);
+        kernel.set(1, 0, move_prob);
+        kernel.set(0, 1, move_prob);
+        kernel.set(-1, 0, move_prob);
 
         Ok(())
     }
@@ -36,3 +65,32 @@ impl KernelGenerator for SimpleRwGenerator {
         ("srw".into(), "Simple RW".into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::kernel;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::{Kernel, KernelError};
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_simple_rw_stay_probability() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::new(0.6));
+
+        let kernel_correct = kernel![
+            0.0, 0.1, 0.0,
+            0.1, 0.6, 0.1,
+            0.0, 0.1, 0.0
+        ];
+
+        assert!(kernel.is_ok());
+        assert_eq!(kernel.unwrap(), kernel_correct);
+    }
+
+    #[test]
+    fn test_simple_rw_invalid_stay_probability() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::new(-0.1));
+
+        assert!(matches!(kernel, Err(KernelError::ProbabilityOutOfRange)));
+    }
+}
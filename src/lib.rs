@@ -16,6 +16,8 @@
 //! : Combines the biased random walk with the correlated random walk
 //! - [Lévy walk](kernel::levy_walk::LevyWalkGenerator): Generates random walks which sometimes jump
 //! a few steps at once
+//! - [Anisotropic random walk](kernel::covariance::CovarianceGenerator): Generates random walks
+//! whose steps follow a normal distribution given by a covariance matrix and a drift vector
 //!
 //! Walk models are implemented in this library as so-called
 //! [`KernelGenerator`](kernel::generator::KernelGenerator)s. What they do is essentially to
@@ -51,6 +53,11 @@
 //! - [`MultiStepWalker`](walker::multi_step::MultiStepWalker): Like the `StandardWalker` but it
 //! allows multiple steps to be made at once, making use of dynamic programs that were generated
 //! with kernels larger than 3x3.
+//! - [`ForwardWalker`](walker::forward::ForwardWalker): Simulates walks forward in time from a
+//! start point using only a kernel, without requiring a dynamic program. Its walks are not
+//! conditioned on an end point and can serve as a null model.
+//! - [`SegmentedWalker`](walker::segmented::SegmentedWalker): Wraps another walker and splits walks
+//! exceeding the time limit of the dynamic program into segments that each fit within it.
 //!
 //! # Dataset Functionality
 //!
@@ -71,6 +78,13 @@
 //! - `plotting`: Allows generating plots of random walks and datasets and save them as images.
 //! - `polars_loading`: Allows loading `DataFrame`s from the
 //! [Polars](https://crates.io/crates/polars) crate.
+//! - `saving`: Allows saving and loading dynamic programs to and from files.
+//! - `projection`: Allows converting datasets between GCS and XY coordinates using the
+//! [proj](https://crates.io/crates/proj) crate.
+//! - `gpu`: Allows computing dynamic programs on the GPU.
+//!
+//! All features except `gpu` are enabled by default. Each feature can be disabled separately,
+//! e.g. for minimal server deployments.
 //!
 //! # Getting Started
 //!
@@ -112,11 +126,11 @@
 //! let mut dp = DynamicProgramBuilder::new()
 //!     .simple()
 //!     .time_limit(400)
-//!     .kernel(Kernel::from_generator(SimpleRwGenerator).unwrap())
+//!     .kernel(Kernel::from_generator(SimpleRwGenerator::default()).unwrap())
 //!     .build()
 //!     .unwrap();
 //!
-//! dp.compute();
+//! dp.compute().unwrap();
 //!
 //! let walker = StandardWalker;
 //! let walk = walker.generate_path(&dp, 100, 50, 400).unwrap();
@@ -150,20 +164,33 @@
 //!
 
 use pyo3::prelude::PyModule;
-use pyo3::{pymodule, PyResult, Python};
+use pyo3::{pymodule, wrap_pyfunction, PyResult, Python};
 
 pub mod dataset;
 pub mod dp;
 pub mod kernel;
 pub mod walk;
 pub mod walker;
+pub mod workspace;
 
 #[pymodule]
 fn randomwalks_lib(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<kernel::Kernel>()?;
-    m.add_class::<kernel::generator::KernelGeneratorError>()?;
+    m.add_class::<kernel::KernelError>()?;
     m.add_class::<kernel::Direction>()?;
     m.add_class::<walk::Walk>()?;
+    m.add_class::<walk::ensemble::WalkEnsemble>()?;
+    m.add_class::<walk::ensemble::ConfidenceInterval>()?;
+    m.add_class::<walk::report::Quantiles>()?;
+    m.add_class::<walk::report::ComparisonReport>()?;
+    #[cfg(feature = "plotting")]
+    m.add_class::<walk::plot::PlotOptions>()?;
+    #[cfg(feature = "plotting")]
+    m.add_class::<walk::plot::PlotBackend>()?;
+    #[cfg(feature = "plotting")]
+    m.add_class::<walk::animation::AnimationOptions>()?;
+    m.add_class::<workspace::Workspace>()?;
+    m.add_function(wrap_pyfunction!(workspace::workspace, m)?)?;
 
     add_module_dp(py, m)?;
     add_module_walker(py, m)?;
@@ -176,6 +203,13 @@ fn add_module_dp(py: Python<'_>, parent: &PyModule) -> PyResult<()> {
     let m = PyModule::new(py, "dp")?;
 
     m.add_class::<dp::simple::DynamicProgram>()?;
+    m.add_class::<dp::PyDynamicProgramPool>()?;
+    m.add_class::<dp::model_card::ModelCard>()?;
+    m.add_class::<dp::model_card::KernelCard>()?;
+    m.add_class::<dp::model_card::ScheduledKernelCard>()?;
+    m.add_class::<dp::compare::DpComparison>()?;
+    m.add_class::<dp::stats::ComputeStats>()?;
+    m.add_class::<dp::export::ExportFormat>()?;
 
     parent.add_submodule(m)?;
 
@@ -189,12 +223,24 @@ fn add_module_dp(py: Python<'_>, parent: &PyModule) -> PyResult<()> {
 fn add_module_walker(py: Python<'_>, parent: &PyModule) -> PyResult<()> {
     let m = PyModule::new(py, "walker")?;
 
-    m.add_class::<walker::WalkerError>()?;
+    m.add_class::<walker::WalkerErrorKind>()?;
     m.add_class::<walker::standard::StandardWalker>()?;
     m.add_class::<walker::correlated::CorrelatedWalker>()?;
     m.add_class::<walker::multi_step::MultiStepWalker>()?;
     m.add_class::<walker::land_cover::LandCoverWalker>()?;
     m.add_class::<walker::levy::LevyWalker>()?;
+    m.add_class::<walker::forward::ForwardWalker>()?;
+    m.add_class::<walker::scheduled::ScheduledWalker>()?;
+    m.add_class::<walker::attraction::AttractionWalker>()?;
+    m.add_class::<walker::budget::BudgetWalker>()?;
+    m.add_class::<walker::segmented::SegmentedWalker>()?;
+    m.add_class::<walker::diagnostics::FailureBucket>()?;
+    m.add_class::<walker::diagnostics::FailureReport>()?;
+    m.add_class::<walker::result::WalkResult>()?;
+    m.add_class::<walker::result::BatchResult>()?;
+    m.add_class::<walker::trace::StepTrace>()?;
+    m.add_class::<walker::trace::WalkTrace>()?;
+    m.add_class::<walker::mask::RegionMask>()?;
 
     parent.add_submodule(m)?;
 
@@ -210,9 +256,11 @@ fn add_module_dataset(py: Python<'_>, parent: &PyModule) -> PyResult<()> {
 
     m.add_class::<dataset::point::GCSPoint>()?;
     m.add_class::<dataset::point::XYPoint>()?;
+    m.add_class::<dataset::transform::Transform>()?;
     m.add_class::<dataset::Dataset>()?;
     m.add_class::<dataset::PyDatasetFilter>()?;
     m.add_class::<dataset::Datapoint>()?;
+    m.add_class::<dataset::example::ExampleKind>()?;
     m.add_class::<dataset::loader::DatasetLoaderError>()?;
     m.add_class::<dataset::loader::CoordinateType>()?;
     m.add_class::<dataset::loader::csv::CSVLoader>()?;
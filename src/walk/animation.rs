@@ -0,0 +1,190 @@
+//! Provides animations of walks.
+//!
+//! Using [`Walk::animate()`], a walk is rendered step by step, each frame showing the walk up to
+//! one more time step. Frames are either combined into an animated GIF or saved as separate PNG
+//! images in a directory, e.g. for creating videos with other tools. The probabilities of a
+//! dynamic program in the time step of each frame can be drawn below the walk as a heatmap,
+//! which helps understanding why a walker chose its steps.
+//!
+//! ```no_run
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::walk::animation::AnimationOptions;
+//! # use randomwalks_lib::walk::Walk;
+//! # use randomwalks_lib::xy;
+//! #
+//! let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1), xy!(2, 1)]);
+//! let options = AnimationOptions {
+//!     frame_delay: 250,
+//!     ..Default::default()
+//! };
+//!
+//! walk.animate("walk.gif", &options, None).unwrap();
+//! ```
+//!
+//! Requires the `plotting` feature.
+
+use crate::dp::DpAccess;
+use crate::walk::plot::{point_range, PlotOptions};
+use crate::walk::Walk;
+use crate::workspace;
+use anyhow::bail;
+use plotters::backend::{BitMapBackend, DrawingBackend};
+use plotters::chart::ChartBuilder;
+use plotters::coord::Shift;
+use plotters::drawing::{DrawingArea, IntoDrawingArea};
+use plotters::element::{Circle, EmptyElement, Rectangle, Text};
+use plotters::prelude::{IntoFont, LineSeries, PointSeries, RGBColor, BLACK};
+use plotters::style::Color;
+use pyo3::{pyclass, pymethods};
+use std::path::Path;
+
+/// Color of the heatmap cells with the highest probability.
+const HEATMAP_COLOR: RGBColor = RGBColor(30, 100, 220);
+
+/// Options for animating walks.
+#[pyclass(get_all, set_all)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationOptions {
+    /// Delay between two frames of a GIF in milliseconds.
+    pub frame_delay: u32,
+    /// Whether the frames are saved as separate PNG images in a directory instead of a GIF.
+    pub frames: bool,
+    /// Options for drawing each frame. The backend is ignored, as frames are always bitmaps.
+    pub plot: PlotOptions,
+}
+
+#[pymethods]
+impl AnimationOptions {
+    #[new]
+    #[pyo3(signature = (frame_delay=100, frames=false, plot=PlotOptions::default()))]
+    pub fn new(frame_delay: u32, frames: bool, plot: PlotOptions) -> Self {
+        Self {
+            frame_delay,
+            frames,
+            plot,
+        }
+    }
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self::new(100, false, PlotOptions::default())
+    }
+}
+
+/// Animates `walk` using `options` and saves the result to `path`, drawing the probabilities of
+/// `dp` as a heatmap if given.
+pub(crate) fn animate(
+    walk: &Walk,
+    path: String,
+    options: &AnimationOptions,
+    dp: Option<&dyn DpAccess>,
+) -> anyhow::Result<()> {
+    if walk.is_empty() {
+        bail!("Cannot animate empty walk");
+    }
+
+    let path = workspace::output_path(&path, "animation")?;
+    let size = (options.plot.width, options.plot.height);
+
+    if options.frames {
+        std::fs::create_dir_all(&path)?;
+
+        for t in 0..walk.len() {
+            let frame = Path::new(&path).join(format!("frame_{:05}.png", t));
+            let root = BitMapBackend::new(&frame, size).into_drawing_area();
+
+            draw_frame(&root, walk, t, &options.plot, dp)?;
+        }
+    } else {
+        let root = BitMapBackend::gif(&path, size, options.frame_delay)?.into_drawing_area();
+
+        for t in 0..walk.len() {
+            draw_frame(&root, walk, t, &options.plot, dp)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draws the frame of `walk` in time step `t` onto `root` and presents it.
+fn draw_frame<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    walk: &Walk,
+    t: usize,
+    options: &PlotOptions,
+    dp: Option<&dyn DpAccess>,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    // Initialize plot, keeping the plotted area fixed for all frames
+
+    let (fitted_x, fitted_y) = point_range(std::slice::from_ref(walk));
+    let coordinate_range_x = options.x_range.map_or(fitted_x, |(min, max)| min..max);
+    let coordinate_range_y = options.y_range.map_or(fitted_y, |(min, max)| max..min);
+
+    let (r, g, b) = options.background;
+    root.fill(&RGBColor(r, g, b))?;
+    let area = root.margin(10, 10, 10, 10);
+
+    let mut chart = ChartBuilder::on(&area)
+        .caption(format!("t = {}", t), ("sans-serif", 20))
+        .x_label_area_size(20)
+        .y_label_area_size(20)
+        .build_cartesian_2d(coordinate_range_x.clone(), coordinate_range_y.clone())?;
+
+    chart.configure_mesh().draw()?;
+
+    // Draw heatmap
+
+    if let Some(dp) = dp {
+        let (y_min, y_max) = (coordinate_range_y.end, coordinate_range_y.start);
+        let cells: Vec<(i64, i64, f64)> = coordinate_range_x
+            .flat_map(|x| (y_min..y_max).map(move |y| (x, y)))
+            .map(|(x, y)| (x, y, dp.at_or(x as isize, y as isize, t)))
+            .filter(|(_, _, p)| *p > 0.0)
+            .collect();
+        let max = cells.iter().map(|(_, _, p)| *p).fold(0.0, f64::max);
+
+        chart.draw_series(cells.iter().map(|&(x, y, p)| {
+            Rectangle::new(
+                [(x, y), (x + 1, y + 1)],
+                HEATMAP_COLOR.mix(p / max).filled(),
+            )
+        }))?;
+    }
+
+    // Draw walk up to time step t
+
+    let points: Vec<(i64, i64)> = walk.0[..=t].iter().map(|p| (p.x, p.y)).collect();
+    let color = options
+        .line_color
+        .map_or(BLACK, |(r, g, b)| RGBColor(r, g, b));
+
+    chart.draw_series(LineSeries::new(
+        points.clone(),
+        color.stroke_width(options.line_width),
+    ))?;
+
+    // Draw start and current point
+
+    chart.draw_series(PointSeries::of_element(
+        vec![points[0], points[t]],
+        5,
+        &BLACK,
+        &|c, s, st| {
+            let element = EmptyElement::at(c) + Circle::new((0, 0), s, st.filled());
+            let label = options
+                .point_labels
+                .then(|| format!("{:?}", c))
+                .unwrap_or_default();
+
+            element + Text::new(label, (10, 0), ("sans-serif", 10).into_font())
+        },
+    ))?;
+
+    root.present()?;
+
+    Ok(())
+}
@@ -0,0 +1,425 @@
+//! Provides [`WalkEnsemble`], a collection of random walks that are analyzed together.
+//!
+//! Ensembles are typically the result of generating many random walks between the same pair of
+//! points. Besides wrapping the walks themselves, they allow relating all walks to external
+//! raster data, e.g. land cover classes or elevation, using
+//! [`sample_raster()`](WalkEnsemble::sample_raster), and quantifying the uncertainty of ensemble
+//! summaries using [`bootstrap()`](WalkEnsemble::bootstrap).
+
+use crate::dataset::point::XYPoint;
+use crate::dataset::transform::Transform;
+use crate::walk::{self, report, Walk};
+use anyhow::bail;
+use pyo3::{pyclass, pymethods, PyObject, Python};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Confidence level of the intervals computed by [`WalkEnsemble::bootstrap()`].
+const CONFIDENCE_LEVEL: f64 = 0.95;
+
+/// A collection of random walks.
+#[pyclass]
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct WalkEnsemble(pub Vec<Walk>);
+
+#[pymethods]
+impl WalkEnsemble {
+    #[new]
+    pub fn new(walks: Vec<Walk>) -> Self {
+        Self(walks)
+    }
+
+    /// Returns the number of walks in the ensemble.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the ensemble contains any walks.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[getter]
+    pub fn walks(&self) -> Vec<Walk> {
+        self.0.clone()
+    }
+
+    /// Samples a categorical raster, e.g. land cover classes, under each walk point.
+    ///
+    /// Returns the per-walk sequences of raster values and the fraction of all sampled points
+    /// that fall into each raster value. See [`sample_raster()`](WalkEnsemble::sample_raster).
+    #[pyo3(name = "sample_raster")]
+    pub fn py_sample_raster(
+        &self,
+        grid: Vec<Vec<usize>>,
+        transform: Transform,
+    ) -> (Vec<Vec<Option<usize>>>, HashMap<usize, f64>) {
+        let sample = self.sample_raster(&grid, &transform);
+        let composition = sample.composition();
+
+        (sample.values, composition)
+    }
+
+    /// Computes a bootstrap confidence interval for the mean of a per-walk metric. See
+    /// [`bootstrap()`](WalkEnsemble::bootstrap).
+    #[pyo3(name = "bootstrap")]
+    pub fn py_bootstrap(
+        &self,
+        py: Python<'_>,
+        metric: PyObject,
+        n_resamples: usize,
+        seed: u64,
+    ) -> anyhow::Result<ConfidenceInterval> {
+        let values = self
+            .0
+            .iter()
+            .map(|walk| metric.call1(py, (walk.clone(),))?.extract(py))
+            .collect::<Result<Vec<f64>, _>>()?;
+
+        bootstrap(&values, n_resamples, seed)
+    }
+
+    /// Computes the mean squared displacement of all walks in the ensemble for each lag from `0`
+    /// up to `max_lag`. See [`msd()`](crate::walk::msd).
+    #[pyo3(signature = (max_lag=None, transform=None))]
+    pub fn msd(&self, max_lag: Option<usize>, transform: Option<Transform>) -> Vec<f64> {
+        walk::msd(&self.0, max_lag, transform.as_ref())
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("WalkEnsemble({} walks)", self.0.len())
+    }
+}
+
+impl WalkEnsemble {
+    pub fn iter(&self) -> std::slice::Iter<Walk> {
+        self.0.iter()
+    }
+
+    /// Extracts the raster value under each point of each walk in the ensemble.
+    ///
+    /// The raster `grid` is indexed as `grid[x][y]`. The `transform` maps the XY coordinates of
+    /// the walks to raster coordinates, i.e. [`Transform::to_metric()`] yields the (fractional)
+    /// raster cell a walk point falls into. Thus, `scale` is the number of XY units per raster
+    /// cell and `origin` is the raster position of the XY origin. Points that fall outside of the
+    /// raster are sampled as `None`.
+    ///
+    /// ```
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::dataset::transform::Transform;
+    /// # use randomwalks_lib::walk::ensemble::WalkEnsemble;
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let grid = vec![vec![1, 1], vec![2, 3]];
+    /// let ensemble = WalkEnsemble(vec![Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1), xy!(2, 1)])]);
+    ///
+    /// let sample = ensemble.sample_raster(&grid, &Transform::default());
+    ///
+    /// assert_eq!(sample.values, vec![vec![Some(1), Some(2), Some(3), None]]);
+    /// ```
+    pub fn sample_raster<T: Copy>(
+        &self,
+        grid: &[Vec<T>],
+        transform: &Transform,
+    ) -> RasterSample<T> {
+        let values = self
+            .0
+            .iter()
+            .map(|walk| {
+                walk.iter()
+                    .map(|point| raster_value(grid, transform, *point))
+                    .collect()
+            })
+            .collect();
+
+        RasterSample { values }
+    }
+
+    /// Computes a bootstrap confidence interval for the mean of a per-walk metric.
+    ///
+    /// The `metric`, e.g. the length or straightness of a walk, is evaluated for each walk. The
+    /// walks are then resampled with replacement `n_resamples` times, yielding the distribution
+    /// of the mean metric from which a 95% percentile interval is taken. Using the same `seed`
+    /// always results in the same interval.
+    ///
+    /// Returns an error if the ensemble is empty or `n_resamples` is zero.
+    ///
+    /// ```
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::walk::ensemble::WalkEnsemble;
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let ensemble = WalkEnsemble(vec![
+    ///     Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0)]),
+    ///     Walk(vec![xy!(0, 0), xy!(0, 1), xy!(1, 1), xy!(2, 0)]),
+    /// ]);
+    ///
+    /// let interval = ensemble.bootstrap(|walk| walk.len() as f64, 1000, 42).unwrap();
+    ///
+    /// assert_eq!(interval.estimate, 3.5);
+    /// assert!(interval.lower >= 3.0 && interval.upper <= 4.0);
+    /// ```
+    pub fn bootstrap<F>(
+        &self,
+        metric: F,
+        n_resamples: usize,
+        seed: u64,
+    ) -> anyhow::Result<ConfidenceInterval>
+    where
+        F: Fn(&Walk) -> f64,
+    {
+        let values: Vec<f64> = self.0.iter().map(metric).collect();
+
+        bootstrap(&values, n_resamples, seed)
+    }
+}
+
+/// A bootstrap confidence interval for the mean of a per-walk metric.
+///
+/// Created by [`WalkEnsemble::bootstrap()`].
+#[pyclass(get_all)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    /// Mean of the metric over all walks of the ensemble.
+    pub estimate: f64,
+    /// Lower bound of the interval.
+    pub lower: f64,
+    /// Upper bound of the interval.
+    pub upper: f64,
+    /// Standard deviation of the resampled means.
+    pub std_error: f64,
+    /// Confidence level of the interval.
+    pub level: f64,
+}
+
+#[pymethods]
+impl ConfidenceInterval {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "ConfidenceInterval(estimate={:.3}, lower={:.3}, upper={:.3})",
+            self.estimate, self.lower, self.upper
+        )
+    }
+}
+
+/// Raster values sampled under the points of a [`WalkEnsemble`].
+///
+/// Created by [`WalkEnsemble::sample_raster()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterSample<T> {
+    /// The raster value under each point of each walk, or `None` if the point lies outside of the
+    /// raster.
+    pub values: Vec<Vec<Option<T>>>,
+}
+
+impl<T> RasterSample<T> {
+    /// Returns the number of sampled points that lie outside of the raster.
+    pub fn outside(&self) -> usize {
+        self.values.iter().flatten().filter(|v| v.is_none()).count()
+    }
+}
+
+impl<T: Copy + Eq + Hash> RasterSample<T> {
+    /// Returns the fraction of sampled points inside the raster that fall into each raster value,
+    /// aggregated over all walks.
+    pub fn composition(&self) -> HashMap<T, f64> {
+        composition(self.values.iter().flatten())
+    }
+
+    /// Returns the fraction of sampled points inside the raster that fall into each raster value
+    /// for each walk separately.
+    pub fn walk_compositions(&self) -> Vec<HashMap<T, f64>> {
+        self.values
+            .iter()
+            .map(|walk| composition(walk.iter()))
+            .collect()
+    }
+}
+
+impl<T: Copy + Into<f64>> RasterSample<T> {
+    /// Returns the mean raster value, e.g. the mean elevation, under each walk.
+    ///
+    /// Walks without any point inside of the raster have a mean of `None`.
+    pub fn walk_means(&self) -> Vec<Option<f64>> {
+        self.values.iter().map(|walk| mean(walk.iter())).collect()
+    }
+
+    /// Returns the mean raster value under all points of all walks.
+    pub fn mean(&self) -> Option<f64> {
+        mean(self.values.iter().flatten())
+    }
+}
+
+impl From<Vec<Walk>> for WalkEnsemble {
+    fn from(value: Vec<Walk>) -> Self {
+        Self(value)
+    }
+}
+
+impl FromIterator<Walk> for WalkEnsemble {
+    fn from_iter<I: IntoIterator<Item = Walk>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+fn bootstrap(values: &[f64], n_resamples: usize, seed: u64) -> anyhow::Result<ConfidenceInterval> {
+    if values.is_empty() {
+        bail!("ensemble must not be empty");
+    }
+
+    if n_resamples == 0 {
+        bail!("number of resamples must be greater than zero");
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut means: Vec<f64> = (0..n_resamples)
+        .map(|_| {
+            let sum: f64 = (0..values.len())
+                .map(|_| values[rng.gen_range(0..values.len())])
+                .sum();
+
+            sum / values.len() as f64
+        })
+        .collect();
+
+    means.sort_by(|a, b| a.total_cmp(b));
+
+    let mean_of_means = means.iter().sum::<f64>() / n_resamples as f64;
+    let variance = means
+        .iter()
+        .map(|mean| (mean - mean_of_means).powi(2))
+        .sum::<f64>()
+        / n_resamples as f64;
+    let alpha = (1.0 - CONFIDENCE_LEVEL) / 2.0;
+
+    Ok(ConfidenceInterval {
+        estimate: values.iter().sum::<f64>() / values.len() as f64,
+        lower: report::quantile(&means, alpha),
+        upper: report::quantile(&means, 1.0 - alpha),
+        std_error: variance.sqrt(),
+        level: CONFIDENCE_LEVEL,
+    })
+}
+
+fn raster_value<T: Copy>(grid: &[Vec<T>], transform: &Transform, point: XYPoint) -> Option<T> {
+    let (x, y) = transform.to_metric(point);
+
+    if x < 0.0 || y < 0.0 {
+        return None;
+    }
+
+    grid.get(x.floor() as usize)?
+        .get(y.floor() as usize)
+        .copied()
+}
+
+fn composition<'a, T: Copy + Eq + Hash + 'a>(
+    values: impl Iterator<Item = &'a Option<T>>,
+) -> HashMap<T, f64> {
+    let mut counts = HashMap::new();
+    let mut total = 0;
+
+    for value in values.flatten() {
+        *counts.entry(*value).or_insert(0usize) += 1;
+        total += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|(value, count)| (value, count as f64 / total as f64))
+        .collect()
+}
+
+fn mean<'a, T: Copy + Into<f64> + 'a>(values: impl Iterator<Item = &'a Option<T>>) -> Option<f64> {
+    let (sum, count) = values.flatten().fold((0.0, 0usize), |(sum, count), v| {
+        (sum + (*v).into(), count + 1)
+    });
+
+    (count > 0).then_some(sum / count as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dataset::transform::Transform;
+    use crate::walk::ensemble::WalkEnsemble;
+    use crate::walk::Walk;
+    use crate::xy;
+
+    #[test]
+    fn test_sample_raster_composition() {
+        let grid = vec![vec![0, 0, 1], vec![0, 1, 1], vec![2, 2, 2]];
+        let ensemble = WalkEnsemble(vec![
+            Walk(vec![xy!(0, 0), xy!(1, 1), xy!(2, 2)]),
+            Walk(vec![xy!(0, 0), xy!(-1, 0), xy!(0, 1)]),
+        ]);
+
+        let sample = ensemble.sample_raster(&grid, &Transform::default());
+
+        assert_eq!(
+            sample.values,
+            vec![
+                vec![Some(0), Some(1), Some(2)],
+                vec![Some(0), None, Some(0)]
+            ]
+        );
+        assert_eq!(sample.outside(), 1);
+
+        let composition = sample.composition();
+
+        assert_eq!(composition[&0], 0.6);
+        assert_eq!(composition[&1], 0.2);
+        assert_eq!(composition[&2], 0.2);
+        assert_eq!(sample.walk_compositions()[1][&0], 1.0);
+    }
+
+    #[test]
+    fn test_sample_raster_transform() {
+        let grid = vec![vec![10.0, 20.0], vec![30.0, 40.0]];
+        let ensemble = WalkEnsemble(vec![Walk(vec![xy!(0, 0), xy!(3, 1), xy!(3, 3)])]);
+
+        // Two XY units per raster cell
+        let sample = ensemble.sample_raster(&grid, &Transform::new(2.0, (0.0, 0.0)).unwrap());
+
+        assert_eq!(
+            sample.values,
+            vec![vec![Some(10.0), Some(30.0), Some(40.0)]]
+        );
+        assert_eq!(sample.walk_means(), vec![Some(80.0 / 3.0)]);
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let ensemble: WalkEnsemble = (1..=10)
+            .map(|len| Walk((0..len).map(|x| xy!(x, 0)).collect()))
+            .collect();
+
+        let interval = ensemble
+            .bootstrap(|walk| walk.len() as f64, 500, 1)
+            .unwrap();
+
+        assert_eq!(interval.estimate, 5.5);
+        assert!(interval.lower < 5.5 && interval.upper > 5.5);
+        assert!(interval.lower >= 1.0 && interval.upper <= 10.0);
+        assert!(interval.std_error > 0.0);
+        assert_eq!(
+            interval,
+            ensemble
+                .bootstrap(|walk| walk.len() as f64, 500, 1)
+                .unwrap()
+        );
+        assert!(ensemble.bootstrap(|walk| walk.len() as f64, 0, 1).is_err());
+        assert!(WalkEnsemble::default()
+            .bootstrap(|walk| walk.len() as f64, 500, 1)
+            .is_err());
+    }
+}
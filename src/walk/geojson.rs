@@ -0,0 +1,143 @@
+//! Provides an export of walks to [GeoJSON](https://geojson.org/).
+//!
+//! Each walk is exported as a `Feature` with a `LineString` geometry, and multiple walks as a
+//! `FeatureCollection`, so that they can be opened directly in GIS tools like QGIS or kepler.gl.
+//! Additional [`Property`] values, e.g. the name of the walker or the number of time steps, can
+//! be attached to the features.
+//!
+//! ```
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::walk::geojson::{feature, Property};
+//! # use randomwalks_lib::walk::Walk;
+//! # use randomwalks_lib::xy;
+//! # use std::collections::HashMap;
+//! #
+//! let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1)]);
+//! let properties = HashMap::from([
+//!     ("walker".to_string(), Property::Str("Standard Walker".into())),
+//!     ("time_steps".to_string(), Property::Int(3)),
+//! ]);
+//!
+//! println!("{}", feature(&walk, &properties, None));
+//! ```
+//!
+//! GeoJSON expects GCS coordinates, while walks are given in XY coordinates. Using a
+//! [`Transform`], the points are converted to metric coordinates, which are Web Mercator
+//! coordinates (EPSG:3857) for walks between points of a dataset converted using
+//! [`Dataset::convert_gcs_to_xy()`](crate::dataset::Dataset::convert_gcs_to_xy). The CRS then has
+//! to be set accordingly when opening the file.
+
+use crate::dataset::transform::Transform;
+use crate::walk::Walk;
+use pyo3::FromPyObject;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// A value of a property attached to a feature.
+#[derive(Debug, Clone, PartialEq, FromPyObject, Serialize)]
+#[serde(untagged)]
+pub enum Property {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+/// Creates a GeoJSON `Feature` with the points of `walk` as a `LineString` geometry.
+///
+/// The coordinates are XY coordinates, or metric coordinates if a [`Transform`] is given.
+pub fn feature(
+    walk: &Walk,
+    properties: &HashMap<String, Property>,
+    transform: Option<&Transform>,
+) -> Value {
+    let coordinates: Vec<[f64; 2]> = walk
+        .iter()
+        .map(|point| {
+            let (x, y) = match transform {
+                Some(transform) => transform.to_metric(*point),
+                None => (point.x as f64, point.y as f64),
+            };
+
+            [x, y]
+        })
+        .collect();
+
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "LineString",
+            "coordinates": coordinates,
+        },
+        "properties": properties,
+    })
+}
+
+/// Creates a GeoJSON `FeatureCollection` containing a [`feature()`] for each of the `walks`.
+///
+/// All features share the same `properties`, and additionally have an `index` property holding
+/// the index of their walk in `walks`.
+pub fn feature_collection(
+    walks: &[Walk],
+    properties: &HashMap<String, Property>,
+    transform: Option<&Transform>,
+) -> Value {
+    let features: Vec<Value> = walks
+        .iter()
+        .enumerate()
+        .map(|(index, walk)| {
+            let mut properties = properties.clone();
+
+            properties.insert("index".into(), Property::Int(index as i64));
+
+            feature(walk, &properties, transform)
+        })
+        .collect();
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dataset::transform::Transform;
+    use crate::walk::geojson::{feature, feature_collection, Property};
+    use crate::walk::Walk;
+    use crate::xy;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_geojson() {
+        let walk = Walk(vec![xy!(0, 0), xy!(2, 0), xy!(2, 1)]);
+        let properties = HashMap::from([("walker".to_string(), Property::Str("swg".into()))]);
+
+        assert_eq!(
+            feature(&walk, &properties, None),
+            json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "LineString",
+                    "coordinates": [[0.0, 0.0], [2.0, 0.0], [2.0, 1.0]],
+                },
+                "properties": { "walker": "swg" },
+            })
+        );
+
+        let transform = Transform::new(2.0, (100.0, 50.0)).unwrap();
+        let collection = feature_collection(&[walk.clone(), walk], &properties, Some(&transform));
+
+        assert_eq!(collection["type"], "FeatureCollection");
+        assert_eq!(collection["features"].as_array().unwrap().len(), 2);
+        assert_eq!(collection["features"][1]["properties"]["index"], 1);
+        assert_eq!(collection["features"][1]["properties"]["walker"], "swg");
+        assert_eq!(
+            collection["features"][0]["geometry"]["coordinates"][2],
+            json!([101.0, 50.5])
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Provides an export of walks to [GPX](https://www.topografix.com/gpx.asp) tracks.
+//!
+//! GPX is supported by most tools working with GPS tracks. Each walk is written as a track with
+//! a single segment, and each of its points is given a timestamp, starting at a given time and
+//! advancing by a fixed time step length per point.
+//!
+//! As GPX requires GCS coordinates, the XY points of a walk are first converted to metric
+//! coordinates using a [`Transform`], which are then converted from Web Mercator (EPSG:3857) to
+//! WGS 84 (EPSG:4326). This reverses
+//! [`Dataset::convert_gcs_to_xy()`](crate::dataset::Dataset::convert_gcs_to_xy) if the transform
+//! uses the same scale.
+//!
+//! Requires the `projection` feature.
+
+use crate::dataset::projection::{ProjCache, GCS_CRS, XY_CRS};
+use crate::dataset::transform::Transform;
+use crate::walk::Walk;
+use crate::workspace;
+use std::fmt::Write;
+use time::macros::format_description;
+use time::{Duration, PrimitiveDateTime};
+
+/// Writes `walk` to the GPX file at `path`.
+///
+/// The first point is timestamped with `start`, which is interpreted as UTC, and each following
+/// point `time_step_len` after the previous one. If no [`Transform`] is given, XY coordinates
+/// are used as metric coordinates directly.
+pub fn write_gpx<S: Into<String>>(
+    walk: &Walk,
+    path: S,
+    start: PrimitiveDateTime,
+    time_step_len: Duration,
+    transform: Option<&Transform>,
+) -> anyhow::Result<()> {
+    let transform = transform.copied().unwrap_or_default();
+    let mut points: Vec<(f64, f64)> = walk.iter().map(|p| transform.to_metric(*p)).collect();
+
+    ProjCache::default().convert(XY_CRS, GCS_CRS, &mut points)?;
+
+    let path = workspace::output_path(&path.into(), "walk")?;
+
+    std::fs::write(path, gpx(&points, start, time_step_len)?)?;
+
+    Ok(())
+}
+
+/// Creates a GPX document containing a track through `points`, given as longitude and latitude.
+fn gpx(
+    points: &[(f64, f64)],
+    start: PrimitiveDateTime,
+    time_step_len: Duration,
+) -> anyhow::Result<String> {
+    let format = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z");
+    let mut gpx = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<gpx version=\"1.1\" creator=\"randomwalks-lib\" ",
+        "xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+        "  <trk>\n",
+        "    <trkseg>\n",
+    ));
+
+    for (i, (lon, lat)) in points.iter().enumerate() {
+        let time = (start + time_step_len * i as f64).format(&format)?;
+
+        writeln!(
+            gpx,
+            "      <trkpt lat=\"{lat:.7}\" lon=\"{lon:.7}\"><time>{time}</time></trkpt>"
+        )?;
+    }
+
+    gpx.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+
+    Ok(gpx)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::walk::gpx::gpx;
+    use time::macros::datetime;
+    use time::Duration;
+
+    #[test]
+    fn test_gpx() {
+        let gpx = gpx(
+            &[(7.0, 51.5), (7.25, 51.75)],
+            datetime!(2023-05-01 12:00),
+            Duration::seconds(90),
+        )
+        .unwrap();
+
+        assert!(gpx.starts_with("<?xml"));
+        assert!(gpx.contains(
+            "<trkpt lat=\"51.5000000\" lon=\"7.0000000\"><time>2023-05-01T12:00:00Z</time></trkpt>"
+        ));
+        assert!(gpx.contains(
+            "<trkpt lat=\"51.7500000\" lon=\"7.2500000\"><time>2023-05-01T12:01:30Z</time></trkpt>"
+        ));
+        assert!(gpx.ends_with("</gpx>\n"));
+    }
+}
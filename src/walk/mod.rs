@@ -0,0 +1,1726 @@
+//! Provides the basic data structure for a [`Walk`](Walk).
+//!
+//! A [`Walk`](Walk) can be created manually by specifying all of its points or generated using a
+//! [`DynamicProgram`](crate::dp::DynamicProgram). See the [`dp`](crate::dp) module for more
+//! information on how to generate random walks.
+//!
+//! The [`Walk`](Walk) structure also provides different useful functions for manipulating and
+//! reviewing walks. If the `plotting` feature is enabled, walks can also be plotted to an
+//! image file.
+
+#[cfg(feature = "plotting")]
+pub mod animation;
+pub mod ensemble;
+pub mod geojson;
+#[cfg(feature = "projection")]
+pub mod gpx;
+#[cfg(feature = "plotting")]
+pub mod plot;
+pub mod report;
+
+use crate::dataset::point::XYPoint;
+use crate::dataset::transform::Transform;
+use crate::walk::geojson::Property;
+use crate::workspace;
+use anyhow::bail;
+use geo::{
+    line_string, Area, ConvexHull, Coord, EuclideanDistance, FrechetDistance, Intersects, Line,
+    LineString, MultiPoint, Point, Polygon, Rect, Simplify,
+};
+use ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::exceptions::PyIndexError;
+use pyo3::types::PySlice;
+use pyo3::{
+    pyclass, pymethods, IntoPy, Py, PyAny, PyCell, PyObject, PyRef, PyRefMut, PyResult, Python,
+};
+use std::collections::HashMap;
+use std::f64::consts::{PI, TAU};
+use std::ops::Index;
+use std::os::raw::c_long;
+#[cfg(feature = "plotting")]
+use {
+    crate::dp::{simple::DynamicProgram, DpAccess},
+    crate::walk::{animation::AnimationOptions, plot::PlotOptions},
+};
+#[cfg(feature = "projection")]
+use {
+    time::macros::format_description,
+    time::{Duration, PrimitiveDateTime},
+};
+
+#[pyclass]
+pub struct WalkIterator {
+    inner: std::vec::IntoIter<XYPoint>,
+}
+
+#[pymethods]
+impl WalkIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> Option<XYPoint> {
+        self.inner.next()
+    }
+}
+
+/// A random walk consisting of multiple points.
+#[pyclass]
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Walk(pub Vec<XYPoint>);
+
+#[pymethods]
+impl Walk {
+    // Returns the number of steps in the walk.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    // Returns whether the walk contains any steps.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn __iter__(&self) -> WalkIterator {
+        WalkIterator {
+            inner: self.0.clone().into_iter(),
+        }
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the point at `index`, or a walk consisting of the selected points if `index` is a
+    /// slice. Like for Python lists, negative indices count from the end of the walk.
+    pub fn __getitem__(&self, py: Python<'_>, index: &PyAny) -> PyResult<PyObject> {
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let indices = slice.indices(self.0.len() as c_long)?;
+            let walk = self.select(indices.start, indices.step, indices.slicelength);
+
+            return Ok(walk.into_py(py));
+        }
+
+        match self.resolve_index(index.extract()?) {
+            Some(index) => Ok(self.0[index].into_py(py)),
+            None => Err(PyIndexError::new_err("walk index out of range")),
+        }
+    }
+
+    /// Appends `point` to the end of the walk.
+    ///
+    /// Like all editing methods, this does not check whether the walk remains valid, e.g. whether
+    /// consecutive points are still reachable from each other within a single time step.
+    pub fn push(&mut self, point: XYPoint) {
+        self.0.push(point);
+    }
+
+    /// Inserts `point` at `index`, shifting all following points back.
+    ///
+    /// Returns an error if `index` is greater than the length of the walk.
+    pub fn insert(&mut self, index: usize, point: XYPoint) -> anyhow::Result<()> {
+        if index > self.0.len() {
+            bail!("index out of range");
+        }
+
+        self.0.insert(index, point);
+
+        Ok(())
+    }
+
+    /// Shortens the walk to its first `len` points. Has no effect if the walk is shorter.
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    /// Reverses the order of the points, so that the walk leads from its end point to its start
+    /// point.
+    pub fn reverse(&mut self) {
+        self.0.reverse();
+    }
+
+    /// Removes the points with indices from `start` up to, but excluding, `end`.
+    ///
+    /// Returns an error if the range is out of bounds or `start` is greater than `end`.
+    pub fn remove_range(&mut self, start: usize, end: usize) -> anyhow::Result<()> {
+        if start > end || end > self.0.len() {
+            bail!("range out of bounds");
+        }
+
+        self.0.drain(start..end);
+
+        Ok(())
+    }
+
+    /// Keeps only the points for which `predicate` returns `True`. See
+    /// [`retain()`](Walk::retain).
+    #[pyo3(name = "retain")]
+    pub fn py_retain(&mut self, py: Python<'_>, predicate: PyObject) -> PyResult<()> {
+        let mut keep = Vec::with_capacity(self.0.len());
+
+        for point in self.0.iter() {
+            keep.push(predicate.call1(py, (*point,))?.is_true(py)?);
+        }
+
+        let mut keep = keep.into_iter();
+        self.0.retain(|_| keep.next().unwrap());
+
+        Ok(())
+    }
+
+    /// Returns the points of the walk as a NumPy array of shape `(len, 2)`, each row holding
+    /// the x and y coordinate of a point.
+    pub fn to_numpy<'py>(&self, py: Python<'py>) -> &'py PyArray2<i64> {
+        self.to_array().into_pyarray(py)
+    }
+
+    /// Computes the [Fréchet distance](https://en.wikipedia.org/wiki/Fr%C3%A9chet_distance) between
+    /// two random walks.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::Walk;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk1 = Walk(vec![xy!(0, 0), xy!(2, 2), xy!(5, 5)]);
+    /// let walk2 = Walk(vec![xy!(0, 0), xy!(3, 3), xy!(6, 6)]);
+    ///
+    /// let frechet = walk1.frechet_distance(&walk2);
+    /// ```
+    pub fn frechet_distance(&self, other: &Walk) -> f64 {
+        let self_line = LineString::from(self);
+        let other_line = LineString::from(other);
+
+        self_line.frechet_distance(&other_line)
+    }
+
+    /// Computes how much a random walk deviates from the straight line between the start and
+    /// end point.
+    pub fn directness_deviation(&self) -> f64 {
+        let self_line = LineString::from(self);
+        let other_line = line_string![
+            (x: self.0.first().unwrap().x as f64, y: self.0.first().unwrap().y as f64),
+            (x: self.0.last().unwrap().x as f64, y: self.0.last().unwrap().y as f64),
+        ];
+
+        self_line.frechet_distance(&other_line)
+    }
+
+    /// Approximates the [Fréchet distance](Walk::frechet_distance) between two random walks,
+    /// which is considerably faster for long walks.
+    ///
+    /// Both walks are first decimated by dropping all points within a distance of `tolerance`
+    /// of the previously kept point. The discrete Fréchet distance of the decimated walks is then
+    /// computed within a band around the diagonal, which is only widened if a coupling leaving it
+    /// could be shorter, so that similar walks take time linear in their length. The result
+    /// differs from the exact distance by at most `2 * tolerance`, and equals it for a `tolerance`
+    /// of `0.0`.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::Walk;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk1 = Walk(vec![xy!(0, 0), xy!(2, 2), xy!(5, 5)]);
+    /// let walk2 = Walk(vec![xy!(0, 0), xy!(3, 3), xy!(6, 6)]);
+    ///
+    /// let frechet = walk1.frechet_distance_approx(&walk2, 2.0);
+    ///
+    /// assert!((frechet - walk1.frechet_distance(&walk2)).abs() <= 4.0);
+    /// ```
+    pub fn frechet_distance_approx(&self, other: &Walk, tolerance: f64) -> f64 {
+        discrete_frechet(
+            &decimate(&self.0, tolerance),
+            &decimate(&other.0, tolerance),
+        )
+    }
+
+    /// Approximates the [directness deviation](Walk::directness_deviation) of a random walk
+    /// like [`frechet_distance_approx()`](Walk::frechet_distance_approx).
+    pub fn directness_deviation_approx(&self, tolerance: f64) -> f64 {
+        let (Some(first), Some(last)) = (self.0.first(), self.0.last()) else {
+            return 0.0;
+        };
+
+        discrete_frechet(
+            &decimate(&self.0, tolerance),
+            &[
+                (first.x as f64, first.y as f64),
+                (last.x as f64, last.y as f64),
+            ],
+        )
+    }
+
+    /// Computes the cumulative path length of the walk up to each of its points.
+    ///
+    /// The first entry is always `0.0`. Distances are measured in cell units, or in metric units
+    /// if a [`Transform`] is given.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 0), xy!(4, 4)]);
+    ///
+    /// assert_eq!(walk.cumulative_distance(None), vec![0.0, 1.0, 1.0, 6.0]);
+    /// ```
+    #[pyo3(signature = (transform=None))]
+    pub fn cumulative_distance(&self, transform: Option<Transform>) -> Vec<f64> {
+        let mut distances = Vec::with_capacity(self.0.len());
+        let mut total = 0.0;
+
+        for (i, point) in self.0.iter().enumerate() {
+            if i > 0 {
+                total += step_length(self.0[i - 1], *point, transform.as_ref());
+            }
+
+            distances.push(total);
+        }
+
+        distances
+    }
+
+    /// Computes the path length of the walk from its start up to the point at index `t`.
+    ///
+    /// Returns `None` if `t` is out of bounds. Distances are measured in cell units, or in metric
+    /// units if a [`Transform`] is given.
+    #[pyo3(signature = (t, transform=None))]
+    pub fn distance_at(&self, t: usize, transform: Option<Transform>) -> Option<f64> {
+        if t >= self.0.len() {
+            return None;
+        }
+
+        Some(
+            self.0[..=t]
+                .windows(2)
+                .map(|w| step_length(w[0], w[1], transform.as_ref()))
+                .sum(),
+        )
+    }
+
+    /// Resamples the walk such that consecutive points are spaced equally along the path.
+    ///
+    /// Points are placed every `spacing` units along the path, starting at the first point of
+    /// the walk, and rounded to the nearest cell. `spacing` is measured in cell units, or in
+    /// metric units if a [`Transform`] is given.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(0, 4), xy!(4, 4)]);
+    /// let resampled = walk.resample_by_distance(2.0, None).unwrap();
+    ///
+    /// assert_eq!(resampled, Walk(vec![xy!(0, 0), xy!(0, 2), xy!(0, 4), xy!(2, 4), xy!(4, 4)]));
+    /// ```
+    #[pyo3(signature = (spacing, transform=None))]
+    pub fn resample_by_distance(
+        &self,
+        spacing: f64,
+        transform: Option<Transform>,
+    ) -> anyhow::Result<Walk> {
+        if !spacing.is_finite() || spacing <= 0.0 {
+            bail!("spacing must be finite and greater than 0");
+        }
+
+        if self.0.is_empty() {
+            return Ok(Walk::default());
+        }
+
+        let distances = self.cumulative_distance(transform);
+        let total = *distances.last().unwrap();
+        let count = (total / spacing).floor() as usize;
+
+        Ok((0..=count)
+            .map(|i| self.point_at_distance(&distances, i as f64 * spacing))
+            .collect())
+    }
+
+    /// Computes the length of each step of the walk.
+    ///
+    /// The result has one entry less than the walk has points. Lengths are measured in cell units,
+    /// or in metric units if a [`Transform`] is given.
+    #[pyo3(signature = (transform=None))]
+    pub fn step_lengths(&self, transform: Option<Transform>) -> Vec<f64> {
+        self.0
+            .windows(2)
+            .map(|w| step_length(w[0], w[1], transform.as_ref()))
+            .collect()
+    }
+
+    /// Computes the turning angle between each pair of consecutive steps of the walk.
+    ///
+    /// Angles are given in radians in the range `(-π, π]`, where positive angles are turns to the
+    /// left. Steps in which the walk does not move have no direction and are skipped.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// # use std::f64::consts::FRAC_PI_2;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 0), xy!(1, 1), xy!(2, 1)]);
+    ///
+    /// assert_eq!(walk.turning_angles(), vec![FRAC_PI_2, -FRAC_PI_2]);
+    /// ```
+    pub fn turning_angles(&self) -> Vec<f64> {
+        let headings: Vec<f64> = self
+            .0
+            .windows(2)
+            .filter(|w| w[0] != w[1])
+            .map(|w| ((w[1].y - w[0].y) as f64).atan2((w[1].x - w[0].x) as f64))
+            .collect();
+
+        headings
+            .windows(2)
+            .map(|w| {
+                let angle = (w[1] - w[0]) % TAU;
+
+                if angle > PI {
+                    angle - TAU
+                } else if angle <= -PI {
+                    angle + TAU
+                } else {
+                    angle
+                }
+            })
+            .collect()
+    }
+
+    /// Counts the step lengths of the walk in `bins` bins of equal width spanning `[0, max]`.
+    ///
+    /// If `max` is not given, the length of the longest step is used. Longer steps are not
+    /// counted. Lengths are measured in cell units, or in metric units if a [`Transform`] is
+    /// given.
+    #[pyo3(signature = (bins, max=None, transform=None))]
+    pub fn step_length_histogram(
+        &self,
+        bins: usize,
+        max: Option<f64>,
+        transform: Option<Transform>,
+    ) -> anyhow::Result<Vec<usize>> {
+        let lengths = self.step_lengths(transform);
+        let max = max.unwrap_or_else(|| lengths.iter().copied().fold(0.0, f64::max));
+
+        histogram(&lengths, bins, 0.0, max)
+    }
+
+    /// Counts the turning angles of the walk in `bins` bins of equal width spanning `[-π, π]`.
+    pub fn turning_angle_histogram(&self, bins: usize) -> anyhow::Result<Vec<usize>> {
+        histogram(&self.turning_angles(), bins, -PI, PI)
+    }
+
+    /// Computes the radius of gyration of the walk, i.e. the root mean squared distance of its
+    /// points from their centroid.
+    ///
+    /// Distances are measured in cell units, or in metric units if a [`Transform`] is given.
+    /// Returns `0.0` for empty walks.
+    #[pyo3(signature = (transform=None))]
+    pub fn radius_of_gyration(&self, transform: Option<Transform>) -> f64 {
+        if self.0.is_empty() {
+            return 0.0;
+        }
+
+        let transform = transform.unwrap_or_default();
+        let points: Vec<(f64, f64)> = self.0.iter().map(|p| transform.to_metric(*p)).collect();
+        let n = points.len() as f64;
+        let (cx, cy) = points
+            .iter()
+            .fold((0.0, 0.0), |(cx, cy), (x, y)| (cx + x / n, cy + y / n));
+
+        (points
+            .iter()
+            .map(|(x, y)| (x - cx).powi(2) + (y - cy).powi(2))
+            .sum::<f64>()
+            / n)
+            .sqrt()
+    }
+
+    /// Computes the convex hull of all points of the walk.
+    ///
+    /// Returns the vertices of the hull in counter-clockwise order, without repeating the first
+    /// vertex at the end.
+    pub fn convex_hull(&self) -> Vec<XYPoint> {
+        let mut hull: Vec<XYPoint> = self
+            .hull()
+            .exterior()
+            .coords()
+            .map(|c| XYPoint {
+                x: c.x.round() as i64,
+                y: c.y.round() as i64,
+            })
+            .collect();
+
+        // The exterior of the hull is closed, i.e. it ends with its first vertex
+        hull.pop();
+
+        hull
+    }
+
+    /// Computes the area of the convex hull of all points of the walk.
+    ///
+    /// The area is measured in square cell units, or in square metric units if a [`Transform`]
+    /// is given.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(3, 0), xy!(1, 1), xy!(0, 2)]);
+    ///
+    /// assert_eq!(walk.hull_area(None), 3.0);
+    /// ```
+    #[pyo3(signature = (transform=None))]
+    pub fn hull_area(&self, transform: Option<Transform>) -> f64 {
+        let area = self.hull().unsigned_area();
+
+        match transform {
+            Some(transform) => area / transform.scale.powi(2),
+            None => area,
+        }
+    }
+
+    /// Computes the time-averaged mean squared displacement of the walk for each lag from `0` up
+    /// to `max_lag`.
+    ///
+    /// The entry at lag `k` is the mean squared distance between all pairs of points `k` time
+    /// steps apart. If `max_lag` is not given or too large, all lags up to the number of steps of
+    /// the walk are included. See [`msd()`] for computing it over multiple walks.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0), xy!(3, 0)]);
+    ///
+    /// assert_eq!(walk.msd(None, None), vec![0.0, 1.0, 4.0, 9.0]);
+    /// ```
+    #[pyo3(signature = (max_lag=None, transform=None))]
+    pub fn msd(&self, max_lag: Option<usize>, transform: Option<Transform>) -> Vec<f64> {
+        msd(std::slice::from_ref(self), max_lag, transform.as_ref())
+    }
+
+    /// Resamples the walk to `n_points` points spaced equally along the path.
+    ///
+    /// The first and last point of the walk are always kept, and all points are rounded to the
+    /// nearest cell. This allows comparing walks with different numbers of time steps point by
+    /// point. See [`resample_by_distance()`](Walk::resample_by_distance) for resampling with a
+    /// fixed spacing instead.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(0, 4), xy!(4, 4)]);
+    /// let resampled = walk.resample(3).unwrap();
+    ///
+    /// assert_eq!(resampled, Walk(vec![xy!(0, 0), xy!(0, 4), xy!(4, 4)]));
+    /// ```
+    pub fn resample(&self, n_points: usize) -> anyhow::Result<Walk> {
+        if n_points < 2 {
+            bail!("number of points must be at least 2");
+        }
+
+        if self.0.is_empty() {
+            return Ok(Walk::default());
+        }
+
+        let distances = self.cumulative_distance(None);
+        let total = *distances.last().unwrap();
+
+        Ok((0..n_points)
+            .map(|i| self.point_at_distance(&distances, total * i as f64 / (n_points - 1) as f64))
+            .collect())
+    }
+
+    /// Translates all points of a walk.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk1 = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]).translate(xy!(5, 1));
+    /// let walk2 = Walk(vec![xy!(5, 1), xy!(7, 4), xy!(12, 6)]);
+    ///
+    /// assert_eq!(walk1, walk2);
+    /// ```
+    pub fn translate(&self, by: XYPoint) -> Walk {
+        Walk(
+            self.0
+                .iter()
+                .map(|p| (p.x + by.x, p.y + by.y).into())
+                .collect(),
+        )
+    }
+
+    /// Scales all points of a walk.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk1 = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]).scale(xy!(2, 1));
+    /// let walk2 = Walk(vec![xy!(0, 0), xy!(4, 3), xy!(14, 5)]);
+    ///
+    /// assert_eq!(walk1, walk2);
+    /// ```
+    pub fn scale(&self, by: XYPoint) -> Walk {
+        Walk(
+            self.0
+                .iter()
+                .map(|p| (p.x * by.x, p.y * by.y).into())
+                .collect(),
+        )
+    }
+
+    /// Rotates all points of a walk around the origin.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk1 = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]).rotate(90.0);
+    /// let walk2 = Walk(vec![xy!(0, 0), xy!(-3, 2), xy!(-5, 7)]);
+    ///
+    /// assert_eq!(walk1, walk2);
+    /// ```
+    pub fn rotate(&self, degrees: f64) -> Walk {
+        let rad = degrees.to_radians();
+
+        Walk(
+            self.0
+                .iter()
+                .map(|p| {
+                    (
+                        (p.x as f64 * rad.cos() - p.y as f64 * rad.sin()) as i64,
+                        (p.y as f64 * rad.cos() + p.x as f64 * rad.sin()) as i64,
+                    )
+                        .into()
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the smallest rectangle containing all points of the walk as its minimum and
+    /// maximum corner, or `None` if the walk is empty.
+    pub fn bounding_box(&self) -> Option<(XYPoint, XYPoint)> {
+        let first = *self.0.first()?;
+
+        Some(self.0.iter().fold((first, first), |(min, max), p| {
+            (
+                XYPoint {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                XYPoint {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        }))
+    }
+
+    /// Returns whether the walk enters the rectangle spanned by the corners `from_point` and
+    /// `to_point`, including its boundary.
+    ///
+    /// Besides the points of the walk, the straight lines between consecutive points are
+    /// considered, so that walks jumping across the rectangle in a single step intersect it too.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(0, 5), xy!(5, 5)]);
+    ///
+    /// assert!(walk.intersects_rect(xy!(-1, 2), xy!(1, 3)));
+    /// assert!(!walk.intersects_rect(xy!(2, 1), xy!(4, 3)));
+    /// ```
+    pub fn intersects_rect(&self, from_point: XYPoint, to_point: XYPoint) -> bool {
+        let rect = Rect::new(
+            Coord::from((from_point.x as f64, from_point.y as f64)),
+            Coord::from((to_point.x as f64, to_point.y as f64)),
+        );
+
+        self.segments().any(|(a, b)| {
+            Line::new((a.x as f64, a.y as f64), (b.x as f64, b.y as f64)).intersects(&rect)
+        })
+    }
+
+    /// Returns whether the walk passes within a Euclidean distance of `tolerance` of `point`.
+    ///
+    /// Like in [`intersects_rect()`](Walk::intersects_rect), the straight lines between
+    /// consecutive points are considered as part of the walk.
+    pub fn passes_through(&self, point: XYPoint, tolerance: f64) -> bool {
+        let point = Point::new(point.x as f64, point.y as f64);
+
+        self.segments().any(|(a, b)| {
+            Line::new((a.x as f64, a.y as f64), (b.x as f64, b.y as f64)).euclidean_distance(&point)
+                <= tolerance
+        })
+    }
+
+    /// Finds all points at which the walk returns to a cell it has visited before.
+    ///
+    /// Each self-intersection is returned as a pair of the index of the point returning to a cell
+    /// and the index of the most recent earlier visit of that cell. Remaining in a cell for
+    /// multiple time steps is not counted as a self-intersection.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1), xy!(0, 1), xy!(0, 0), xy!(0, 0)]);
+    ///
+    /// assert_eq!(walk.self_intersections(), vec![(4, 0)]);
+    /// ```
+    pub fn self_intersections(&self) -> Vec<(usize, usize)> {
+        let mut last_visits: HashMap<XYPoint, usize> = HashMap::new();
+        let mut intersections = Vec::new();
+
+        for (i, point) in self.0.iter().enumerate() {
+            if let Some(&previous) = last_visits.get(point) {
+                if previous + 1 != i {
+                    intersections.push((i, previous));
+                }
+            }
+
+            last_visits.insert(*point, i);
+        }
+
+        intersections
+    }
+
+    /// Returns the number of [`self_intersections()`](Walk::self_intersections) of the walk.
+    pub fn self_intersection_count(&self) -> usize {
+        self.self_intersections().len()
+    }
+
+    /// Clips the walk to the rectangle from `min` to `max`, including its boundary.
+    ///
+    /// Each time the walk leaves the rectangle, it is split, so that each returned sub-walk is a
+    /// maximal sequence of consecutive points inside the rectangle.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0), xy!(1, 0), xy!(1, 1)]);
+    /// let clipped = walk.clip_to_bbox(xy!(0, 0), xy!(1, 1));
+    ///
+    /// assert_eq!(clipped, vec![Walk(vec![xy!(0, 0), xy!(1, 0)]), Walk(vec![xy!(1, 0), xy!(1, 1)])]);
+    /// ```
+    pub fn clip_to_bbox(&self, min: XYPoint, max: XYPoint) -> Vec<Walk> {
+        let mut walks = Vec::new();
+        let mut current = Vec::new();
+
+        for point in self.0.iter() {
+            if point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y {
+                current.push(*point);
+            } else if !current.is_empty() {
+                walks.push(Walk(std::mem::take(&mut current)));
+            }
+        }
+
+        if !current.is_empty() {
+            walks.push(Walk(current));
+        }
+
+        walks
+    }
+
+    /// Simplifies the walk using the
+    /// [Ramer-Douglas-Peucker algorithm](https://en.wikipedia.org/wiki/Ramer%E2%80%93Douglas%E2%80%93Peucker_algorithm).
+    ///
+    /// Points are removed as long as the simplified walk deviates from the original one by at
+    /// most `epsilon` cell units. The first and last point are always kept. As points are
+    /// removed, the simplified walk no longer has one point per time step, so it is meant for
+    /// exporting and plotting rather than analyzing walks.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0), xy!(3, 1), xy!(3, 2)]);
+    ///
+    /// assert_eq!(walk.simplify(0.5), Walk(vec![xy!(0, 0), xy!(2, 0), xy!(3, 2)]));
+    /// ```
+    pub fn simplify(&self, epsilon: f64) -> Walk {
+        LineString::from(self)
+            .simplify(&epsilon)
+            .coords()
+            .map(|c| XYPoint {
+                x: c.x.round() as i64,
+                y: c.y.round() as i64,
+            })
+            .collect()
+    }
+
+    /// Smooths the walk using a centered moving average over `window` points.
+    ///
+    /// Each point is replaced by the mean of the points around it. Near the start and end of the
+    /// walk, the window shrinks symmetrically, so that the first and last point are kept. As the
+    /// smoothed points no longer lie on the grid, they are returned as coordinates instead of a
+    /// walk, in cell units or in metric units if a [`Transform`] is given.
+    ///
+    /// Returns an error if `window` is not odd.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1), xy!(2, 1)]);
+    /// let smoothed = walk.smooth(3, None).unwrap();
+    ///
+    /// assert_eq!(smoothed, vec![(0.0, 0.0), (2.0 / 3.0, 1.0 / 3.0), (4.0 / 3.0, 2.0 / 3.0), (2.0, 1.0)]);
+    /// ```
+    #[pyo3(signature = (window, transform=None))]
+    pub fn smooth(
+        &self,
+        window: usize,
+        transform: Option<Transform>,
+    ) -> anyhow::Result<Vec<(f64, f64)>> {
+        if window % 2 == 0 {
+            bail!("window must be odd");
+        }
+
+        let transform = transform.unwrap_or_default();
+        let points: Vec<(f64, f64)> = self.0.iter().map(|p| transform.to_metric(*p)).collect();
+        let half = window / 2;
+
+        Ok((0..points.len())
+            .map(|i| {
+                let h = half.min(i).min(points.len() - 1 - i);
+                let neighbors = &points[i - h..=i + h];
+                let n = neighbors.len() as f64;
+                let (x, y) = neighbors
+                    .iter()
+                    .fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+
+                (x / n, y / n)
+            })
+            .collect())
+    }
+
+    /// Exports the walk as a GeoJSON `Feature` with the given `properties`. See
+    /// [`geojson::feature()`].
+    #[pyo3(signature = (properties=None, transform=None))]
+    pub fn to_geojson(
+        &self,
+        properties: Option<HashMap<String, Property>>,
+        transform: Option<Transform>,
+    ) -> String {
+        geojson::feature(self, &properties.unwrap_or_default(), transform.as_ref()).to_string()
+    }
+
+    /// Exports multiple walks as a GeoJSON `FeatureCollection` with the given `properties`. See
+    /// [`geojson::feature_collection()`].
+    #[staticmethod]
+    #[pyo3(signature = (walks, properties=None, transform=None))]
+    pub fn to_geojson_multiple(
+        walks: Vec<Walk>,
+        properties: Option<HashMap<String, Property>>,
+        transform: Option<Transform>,
+    ) -> String {
+        geojson::feature_collection(&walks, &properties.unwrap_or_default(), transform.as_ref())
+            .to_string()
+    }
+
+    /// Returns the walk as a WKT `LINESTRING`.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, -2)]);
+    ///
+    /// assert_eq!(walk.to_wkt(), "LINESTRING (0 0, 1 0, 1 -2)");
+    /// ```
+    pub fn to_wkt(&self) -> String {
+        format!("LINESTRING {}", self.wkt_coordinates())
+    }
+
+    /// Returns multiple walks as a WKT `MULTILINESTRING`.
+    #[staticmethod]
+    pub fn to_wkt_multiple(walks: Vec<Walk>) -> String {
+        if walks.is_empty() {
+            return String::from("MULTILINESTRING EMPTY");
+        }
+
+        let lines: Vec<String> = walks.iter().map(Walk::wkt_coordinates).collect();
+
+        format!("MULTILINESTRING ({})", lines.join(", "))
+    }
+
+    /// Writes the walk to a CSV file at `path` with the columns `index`, `x` and `y`, containing
+    /// one row per point.
+    pub fn to_csv(&self, path: String) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_path(workspace::output_path(&path, "walk")?)?;
+
+        writer.write_record(["index", "x", "y"])?;
+
+        for (i, point) in self.0.iter().enumerate() {
+            writer.serialize((i, point.x, point.y))?;
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Writes multiple walks to a CSV file at `path` with the columns `walk_id`, `index`, `x` and
+    /// `y`, containing one row per point. The `walk_id` of each walk is its index in `walks`.
+    #[staticmethod]
+    pub fn to_csv_multiple(walks: Vec<Walk>, path: String) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_path(workspace::output_path(&path, "walk")?)?;
+
+        writer.write_record(["walk_id", "index", "x", "y"])?;
+
+        for (walk_id, walk) in walks.iter().enumerate() {
+            for (i, point) in walk.0.iter().enumerate() {
+                writer.serialize((walk_id, i, point.x, point.y))?;
+            }
+        }
+
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Writes the walk to a GPX file. The `start_time` is given as `YYYY-MM-DD hh:mm:ss` and
+    /// `time_step_len` in seconds. See [`gpx::write_gpx()`].
+    #[cfg(feature = "projection")]
+    #[pyo3(name = "to_gpx", signature = (path, start_time, time_step_len, transform=None))]
+    pub fn py_to_gpx(
+        &self,
+        path: String,
+        start_time: String,
+        time_step_len: f64,
+        transform: Option<Transform>,
+    ) -> anyhow::Result<()> {
+        let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+        let start = PrimitiveDateTime::parse(&start_time, &format)?;
+
+        gpx::write_gpx(
+            self,
+            path,
+            start,
+            Duration::seconds_f64(time_step_len),
+            transform.as_ref(),
+        )
+    }
+
+    #[cfg(feature = "plotting")]
+    #[pyo3(name = "plot", signature = (filename, options=None))]
+    pub fn py_plot(&self, filename: String, options: Option<PlotOptions>) -> anyhow::Result<()> {
+        self.plot_with_options(filename, &options.unwrap_or_default())
+    }
+
+    #[cfg(feature = "plotting")]
+    #[staticmethod]
+    #[pyo3(name = "plot_multiple", signature = (walks, filename, options=None))]
+    pub fn py_plot_multiple(
+        walks: Vec<Walk>,
+        filename: String,
+        options: Option<PlotOptions>,
+    ) -> anyhow::Result<()> {
+        Walk::plot_multiple_with_options(&walks, filename, &options.unwrap_or_default())
+    }
+
+    #[cfg(feature = "plotting")]
+    #[pyo3(name = "animate", signature = (path, options=None, dp=None))]
+    pub fn py_animate(
+        &self,
+        path: String,
+        options: Option<AnimationOptions>,
+        dp: Option<DynamicProgram>,
+    ) -> anyhow::Result<()> {
+        self.animate(
+            path,
+            &options.unwrap_or_default(),
+            dp.as_ref().map(|dp| dp as &dyn DpAccess),
+        )
+    }
+
+    pub fn __repr__(slf: &PyCell<Self>) -> PyResult<String> {
+        let class_name: &str = slf.get_type().name()?;
+
+        Ok(format!("{}({})", class_name, slf.borrow().len()))
+    }
+}
+
+impl Walk {
+    pub fn iter(&self) -> std::slice::Iter<XYPoint> {
+        self.0.iter()
+    }
+
+    /// Returns the points of the walk as an array of shape `(len, 2)`, each row holding the x and
+    /// y coordinate of a point.
+    pub fn to_array(&self) -> Array2<i64> {
+        Array2::from_shape_fn((self.0.len(), 2), |(i, j)| match j {
+            0 => self.0[i].x,
+            _ => self.0[i].y,
+        })
+    }
+
+    /// Returns the index of the point at `index`, counting from the end of the walk if `index` is
+    /// negative, or `None` if it is out of range.
+    fn resolve_index(&self, index: isize) -> Option<usize> {
+        let len = self.0.len() as isize;
+        let index = if index < 0 { index + len } else { index };
+
+        (0..len).contains(&index).then_some(index as usize)
+    }
+
+    /// Returns the walk consisting of `len` points, starting at `start` and advancing by `step`,
+    /// i.e. the points selected by a Python slice with the given indices.
+    fn select(&self, start: isize, step: isize, len: isize) -> Walk {
+        (0..len)
+            .map(|i| self.0[(start + i * step) as usize])
+            .collect()
+    }
+
+    /// Keeps only the points for which `predicate` returns `true`, preserving their order.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walk::Walk;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let mut walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1), xy!(2, 1)]);
+    ///
+    /// walk.retain(|point| point.y == 1);
+    ///
+    /// assert_eq!(walk, Walk(vec![xy!(1, 1), xy!(2, 1)]));
+    /// ```
+    pub fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&XYPoint) -> bool,
+    {
+        self.0.retain(predicate);
+    }
+
+    /// Returns an iterator over all steps of the walk as pairs of consecutive points.
+    ///
+    /// Walks consisting of a single point yield it as a single step of length zero.
+    fn segments(&self) -> impl Iterator<Item = (XYPoint, XYPoint)> + '_ {
+        let single = (self.0.len() == 1).then(|| (self.0[0], self.0[0]));
+
+        self.0.windows(2).map(|w| (w[0], w[1])).chain(single)
+    }
+
+    /// Returns the coordinates of the walk in WKT notation, e.g. `(0 0, 1 0)`.
+    fn wkt_coordinates(&self) -> String {
+        if self.0.is_empty() {
+            return String::from("EMPTY");
+        }
+
+        let points: Vec<String> = self.0.iter().map(|p| format!("{} {}", p.x, p.y)).collect();
+
+        format!("({})", points.join(", "))
+    }
+
+    /// Computes the convex hull of all points of the walk as a polygon.
+    fn hull(&self) -> Polygon<f64> {
+        MultiPoint::from(
+            self.0
+                .iter()
+                .map(|p| (p.x as f64, p.y as f64))
+                .collect::<Vec<_>>(),
+        )
+        .convex_hull()
+    }
+
+    /// Interpolates the point that lies `distance` units along the path, given the cumulative
+    /// distances of the walk as computed by [`cumulative_distance()`](Walk::cumulative_distance).
+    fn point_at_distance(&self, distances: &[f64], distance: f64) -> XYPoint {
+        let i = distances.partition_point(|d| *d <= distance);
+
+        if i == 0 {
+            return self.0[0];
+        } else if i >= self.0.len() {
+            return self.0[self.0.len() - 1];
+        }
+
+        let (from, to) = (self.0[i - 1], self.0[i]);
+        let frac = (distance - distances[i - 1]) / (distances[i] - distances[i - 1]);
+
+        XYPoint {
+            x: (from.x as f64 + frac * (to.x - from.x) as f64).round() as i64,
+            y: (from.y as f64 + frac * (to.y - from.y) as f64).round() as i64,
+        }
+    }
+
+    /// Plots a walk and saves the resulting image to a .png file.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::Walk;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]);
+    ///
+    /// walk.plot("walk.png")?;
+    /// ```
+    #[cfg(feature = "plotting")]
+    pub fn plot<S: Into<String>>(&self, filename: S) -> anyhow::Result<()> {
+        self.plot_with_options(filename, &PlotOptions::default())
+    }
+
+    /// Plots a walk using the given [`PlotOptions`] and saves the resulting image to a file.
+    #[cfg(feature = "plotting")]
+    pub fn plot_with_options<S: Into<String>>(
+        &self,
+        filename: S,
+        options: &PlotOptions,
+    ) -> anyhow::Result<()> {
+        plot::plot(std::slice::from_ref(self), filename.into(), options)
+    }
+
+    /// Animates the walk step by step and saves the result as a GIF or a directory of frames.
+    ///
+    /// If a dynamic program is given, its probabilities in the time step of each frame are drawn
+    /// as a heatmap. See the [`animation`] module for more information.
+    #[cfg(feature = "plotting")]
+    pub fn animate<S: Into<String>>(
+        &self,
+        path: S,
+        options: &AnimationOptions,
+        dp: Option<&dyn DpAccess>,
+    ) -> anyhow::Result<()> {
+        animation::animate(self, path.into(), options, dp)
+    }
+
+    /// Plots multiple walks together and saves the resulting image to a .png file.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::Walk;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let walk1 = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]);
+    /// let walk2 = Walk(vec![xy!(0, 0), xy!(5, 5), xy!(7, 8)]);
+    /// let walks = vec![walk1, walk2];
+    ///
+    /// Walk::plot_multiple(&walks, "walks.png")?;
+    /// ```
+    #[cfg(feature = "plotting")]
+    pub fn plot_multiple<S: Into<String>>(walks: &[Walk], filename: S) -> anyhow::Result<()> {
+        Walk::plot_multiple_with_options(walks, filename, &PlotOptions::default())
+    }
+
+    /// Plots multiple walks together using the given [`PlotOptions`] and saves the resulting
+    /// image to a file.
+    #[cfg(feature = "plotting")]
+    pub fn plot_multiple_with_options<S: Into<String>>(
+        walks: &[Walk],
+        filename: S,
+        options: &PlotOptions,
+    ) -> anyhow::Result<()> {
+        plot::plot(walks, filename.into(), options)
+    }
+}
+
+/// Counts `values` in `bins` bins of equal width spanning `[min, max]`.
+///
+/// Values outside of the range are not counted. If the range is empty, all values equal to `min`
+/// are counted in the first bin.
+fn histogram(values: &[f64], bins: usize, min: f64, max: f64) -> anyhow::Result<Vec<usize>> {
+    if bins == 0 {
+        bail!("number of bins must be greater than 0");
+    }
+
+    if max.is_nan() || max < min {
+        bail!("histogram range must not be empty");
+    }
+
+    let mut counts = vec![0; bins];
+    let width = (max - min) / bins as f64;
+
+    for &value in values.iter().filter(|&&value| min <= value && value <= max) {
+        let bin = if width > 0.0 {
+            ((value - min) / width) as usize
+        } else {
+            0
+        };
+
+        // Values equal to max belong to the last bin
+        counts[bin.min(bins - 1)] += 1;
+    }
+
+    Ok(counts)
+}
+
+/// Computes the mean squared displacement of `walks` for each lag from `0` up to `max_lag`.
+///
+/// The squared displacements of all pairs of points `k` time steps apart are pooled over all
+/// walks, so that longer walks contribute more pairs. Comparing how the result grows with the lag
+/// shows whether walks are diffusive, i.e. growing linearly, or super-diffusive, i.e. growing
+/// faster. If `max_lag` is not given or too large, all lags up to the number of steps of the
+/// longest walk are included. Displacements are measured in cell units, or in metric units if a
+/// [`Transform`] is given.
+pub fn msd(walks: &[Walk], max_lag: Option<usize>, transform: Option<&Transform>) -> Vec<f64> {
+    let longest = walks.iter().map(Walk::len).max().unwrap_or(0);
+    let lags = max_lag.map_or(longest, |max_lag| longest.min(max_lag + 1));
+
+    (0..lags)
+        .map(|lag| {
+            let (sum, count) = walks
+                .iter()
+                .filter(|walk| walk.len() > lag)
+                .flat_map(|walk| walk.0.iter().zip(walk.0[lag..].iter()))
+                .fold((0.0, 0usize), |(sum, count), (&from, &to)| {
+                    let squared = match transform {
+                        Some(transform) => transform.distance(from, to).powi(2),
+                        None => ((to.x - from.x).pow(2) + (to.y - from.y).pow(2)) as f64,
+                    };
+
+                    (sum + squared, count + 1)
+                });
+
+            sum / count as f64
+        })
+        .collect()
+}
+
+/// Drops all points within a distance of `tolerance` of the previously kept point, always
+/// keeping the first and last point.
+///
+/// Each dropped point is within `tolerance` of a kept point preceding it, so the discrete Fréchet
+/// distance between the original and the decimated points is at most `tolerance`.
+fn decimate(points: &[XYPoint], tolerance: f64) -> Vec<(f64, f64)> {
+    let mut kept: Vec<(f64, f64)> = Vec::new();
+
+    for (i, point) in points.iter().enumerate() {
+        let point = (point.x as f64, point.y as f64);
+        let is_last = i == points.len() - 1;
+
+        match kept.last() {
+            Some(&(x, y)) if !is_last && (point.0 - x).hypot(point.1 - y) <= tolerance => {}
+            _ => kept.push(point),
+        }
+    }
+
+    kept
+}
+
+/// Computes the discrete Fréchet distance between `a` and `b`.
+///
+/// Starting with a narrow band around the diagonal of the dynamic program, the band is doubled
+/// in width until [`banded_frechet()`] confirms that it contains an optimal coupling. Returns
+/// `0.0` if either is empty.
+fn discrete_frechet(a: &[(f64, f64)], b: &[(f64, f64)]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // The distance is symmetric, so the band only has to follow diagonals of slope at most 1
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut width = 16;
+
+    loop {
+        let (distance, exact) = banded_frechet(a, b, width);
+
+        if exact {
+            return distance;
+        }
+
+        width *= 2;
+    }
+}
+
+/// Computes the discrete Fréchet distance between `a` and `b` considering only couplings within
+/// `width` cells of the diagonal, keeping only a single row of the band in memory.
+///
+/// Also returns whether the result is exact, i.e. whether every coupling leaving the band passes
+/// a pair of points at least as far apart as the result. Requires `a` to be at least as long as
+/// `b` and both to be non-empty.
+fn banded_frechet(a: &[(f64, f64)], b: &[(f64, f64)], width: usize) -> (f64, bool) {
+    let (n, m) = (a.len(), b.len());
+    let dist = |i: usize, j: usize| (a[i].0 - b[j].0).hypot(a[i].1 - b[j].1);
+    let bounds = |i: usize| {
+        if n == 1 {
+            return (0, m - 1);
+        }
+
+        let floor = i * (m - 1) / (n - 1);
+        let ceil = (i * (m - 1) + n - 2) / (n - 1);
+
+        (floor.saturating_sub(width), (ceil + width).min(m - 1))
+    };
+
+    let mut prev: Vec<f64> = Vec::new();
+    let mut prev_lo = 0;
+    // Smallest distance of the pairs a coupling passes first when leaving the band
+    let mut outside = f64::INFINITY;
+
+    for i in 0..n {
+        let (lo, hi) = bounds(i);
+        let above = |j: usize| match j.checked_sub(prev_lo) {
+            Some(k) if k < prev.len() => prev[k],
+            _ => f64::INFINITY,
+        };
+        let mut row: Vec<f64> = Vec::with_capacity(hi - lo + 1);
+
+        for j in lo..=hi {
+            let coupling = match (i, j) {
+                (0, 0) => 0.0,
+                _ => {
+                    let left = row.last().copied().unwrap_or(f64::INFINITY);
+                    let diagonal = if j > 0 { above(j - 1) } else { f64::INFINITY };
+
+                    left.min(above(j)).min(diagonal)
+                }
+            };
+
+            row.push(coupling.max(dist(i, j)));
+        }
+
+        if i > 0 {
+            for j in prev_lo..lo {
+                outside = outside.min(dist(i, j));
+            }
+        }
+
+        if hi + 1 < m {
+            outside = outside.min(dist(i, hi + 1));
+        }
+
+        prev = row;
+        prev_lo = lo;
+    }
+
+    let distance = prev[prev.len() - 1];
+
+    (distance, outside >= distance)
+}
+
+/// Computes the length of a single step, either in cell units or in metric units.
+fn step_length(from: XYPoint, to: XYPoint, transform: Option<&Transform>) -> f64 {
+    match transform {
+        Some(transform) => transform.distance(from, to),
+        None => ((to.x - from.x) as f64).hypot((to.y - from.y) as f64),
+    }
+}
+
+impl From<Vec<XYPoint>> for Walk {
+    fn from(value: Vec<XYPoint>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Walk> for Vec<XYPoint> {
+    fn from(value: Walk) -> Self {
+        value.0
+    }
+}
+
+impl From<&Walk> for LineString<f64> {
+    fn from(value: &Walk) -> Self {
+        Self(
+            value
+                .0
+                .iter()
+                .map(|p| (p.x as f64, p.y as f64))
+                .map(Coord::from)
+                .collect(),
+        )
+    }
+}
+
+impl FromIterator<XYPoint> for Walk {
+    fn from_iter<T: IntoIterator<Item = XYPoint>>(iter: T) -> Self {
+        let mut c = Vec::new();
+
+        for i in iter {
+            c.push(i);
+        }
+
+        Self(c)
+    }
+}
+
+impl Index<usize> for Walk {
+    type Output = XYPoint;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dataset::transform::Transform;
+    use crate::walk::{banded_frechet, discrete_frechet, msd, Walk};
+    use crate::xy;
+    use ndarray::Array2;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_walk_translate() {
+        let walk1 = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]).translate(xy!(5, 1));
+        let walk2 = Walk(vec![xy!(5, 1), xy!(7, 4), xy!(12, 6)]);
+
+        assert_eq!(walk1, walk2);
+    }
+
+    #[test]
+    fn test_walk_scale() {
+        let walk1 = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]).scale(xy!(2, 1));
+        let walk2 = Walk(vec![xy!(0, 0), xy!(4, 3), xy!(14, 5)]);
+
+        assert_eq!(walk1, walk2);
+    }
+
+    #[test]
+    fn test_walk_clip_to_bbox() {
+        let walk = Walk(vec![
+            xy!(-1, 0),
+            xy!(0, 0),
+            xy!(1, 1),
+            xy!(5, 1),
+            xy!(2, 2),
+            xy!(2, 3),
+        ]);
+        let clipped = walk.clip_to_bbox(xy!(0, 0), xy!(2, 2));
+
+        assert_eq!(
+            clipped,
+            vec![Walk(vec![xy!(0, 0), xy!(1, 1)]), Walk(vec![xy!(2, 2)])]
+        );
+        assert!(walk.clip_to_bbox(xy!(10, 10), xy!(20, 20)).is_empty());
+    }
+
+    #[test]
+    fn test_walk_rotate() {
+        let walk1 = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]).rotate(90.0);
+        let walk2 = Walk(vec![xy!(0, 0), xy!(-3, 2), xy!(-5, 7)]);
+
+        assert_eq!(walk1, walk2);
+    }
+
+    #[test]
+    fn test_walk_cumulative_distance() {
+        let walk = Walk(vec![xy!(0, 0), xy!(0, 1), xy!(3, 5), xy!(3, 6)]);
+
+        assert_eq!(walk.cumulative_distance(None), vec![0.0, 1.0, 6.0, 7.0]);
+        assert_eq!(
+            walk.cumulative_distance(Some(Transform::new(0.5, (0.0, 0.0)).unwrap())),
+            vec![0.0, 2.0, 12.0, 14.0]
+        );
+        assert_eq!(walk.distance_at(2, None), Some(6.0));
+        assert_eq!(walk.distance_at(4, None), None);
+    }
+
+    #[test]
+    fn test_walk_resample_by_distance() {
+        let walk = Walk(vec![xy!(0, 0), xy!(0, 1), xy!(0, 2), xy!(3, 6)]);
+        let resampled = walk.resample_by_distance(3.5, None).unwrap();
+
+        assert_eq!(resampled, Walk(vec![xy!(0, 0), xy!(1, 3), xy!(3, 6)]));
+        assert!(walk.resample_by_distance(0.0, None).is_err());
+        assert!(walk.resample_by_distance(f64::NAN, None).is_err());
+        assert!(walk.resample_by_distance(f64::INFINITY, None).is_err());
+    }
+
+    #[test]
+    fn test_walk_resample() {
+        let walk = Walk(vec![xy!(0, 0), xy!(0, 1), xy!(0, 2), xy!(3, 6)]);
+
+        assert_eq!(
+            walk.resample(3).unwrap(),
+            Walk(vec![xy!(0, 0), xy!(1, 3), xy!(3, 6)])
+        );
+        assert_eq!(walk.resample(8).unwrap().len(), 8);
+        assert_eq!(walk.resample(2).unwrap(), Walk(vec![xy!(0, 0), xy!(3, 6)]));
+        assert!(walk.resample(1).is_err());
+    }
+
+    #[test]
+    fn test_walk_step_statistics() {
+        let walk = Walk(vec![
+            xy!(0, 0),
+            xy!(2, 0),
+            xy!(2, 0),
+            xy!(2, 1),
+            xy!(1, 1),
+            xy!(1, 0),
+            xy!(-2, -4),
+        ]);
+
+        assert_eq!(walk.step_lengths(None), vec![2.0, 0.0, 1.0, 1.0, 1.0, 5.0]);
+        assert_eq!(
+            walk.step_length_histogram(5, None, None).unwrap(),
+            vec![1, 3, 1, 0, 1]
+        );
+        assert_eq!(
+            walk.step_length_histogram(2, Some(2.0), None).unwrap(),
+            vec![1, 4]
+        );
+        assert!(walk.step_length_histogram(0, None, None).is_err());
+
+        let angles = walk.turning_angles();
+        let expected = [FRAC_PI_2, FRAC_PI_2, FRAC_PI_2, -(0.75f64).atan()];
+
+        assert_eq!(angles.len(), expected.len());
+        assert!(angles
+            .iter()
+            .zip(expected.iter())
+            .all(|(angle, expected)| (angle - expected).abs() < 1e-9));
+        assert_eq!(walk.turning_angle_histogram(3).unwrap(), vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn test_walk_spatial_predicates() {
+        let walk = Walk(vec![xy!(0, 0), xy!(4, 0), xy!(4, 3), xy!(-2, 3)]);
+
+        assert_eq!(walk.bounding_box(), Some((xy!(-2, 0), xy!(4, 3))));
+        assert_eq!(Walk::default().bounding_box(), None);
+
+        // The step from (0, 0) to (4, 0) crosses the rectangle without a point inside of it
+        assert!(walk.intersects_rect(xy!(2, -1), xy!(3, 1)));
+        assert!(walk.intersects_rect(xy!(3, 1), xy!(2, -1)));
+        assert!(!walk.intersects_rect(xy!(1, 1), xy!(2, 2)));
+        assert!(Walk(vec![xy!(1, 1)]).intersects_rect(xy!(0, 0), xy!(2, 2)));
+
+        assert!(walk.passes_through(xy!(2, 1), 1.0));
+        assert!(!walk.passes_through(xy!(2, 1), 0.5));
+        assert!(walk.passes_through(xy!(-2, 3), 0.0));
+    }
+
+    #[test]
+    fn test_walk_self_intersections() {
+        let walk = Walk(vec![
+            xy!(0, 0),
+            xy!(0, 0),
+            xy!(1, 0),
+            xy!(1, 1),
+            xy!(0, 1),
+            xy!(0, 0),
+            xy!(1, 0),
+            xy!(2, 0),
+            xy!(1, 0),
+        ]);
+
+        assert_eq!(walk.self_intersections(), vec![(5, 1), (6, 2), (8, 6)]);
+        assert_eq!(walk.self_intersection_count(), 3);
+        assert_eq!(
+            Walk(vec![xy!(0, 0), xy!(0, 0)]).self_intersection_count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_walk_editing() {
+        let mut walk = Walk(vec![xy!(0, 0), xy!(1, 0)]);
+
+        walk.push(xy!(1, 1));
+        walk.insert(1, xy!(0, 1)).unwrap();
+
+        assert_eq!(walk, Walk(vec![xy!(0, 0), xy!(0, 1), xy!(1, 0), xy!(1, 1)]));
+        assert!(walk.insert(5, xy!(2, 2)).is_err());
+
+        walk.reverse();
+        walk.remove_range(1, 3).unwrap();
+
+        assert_eq!(walk, Walk(vec![xy!(1, 1), xy!(0, 0)]));
+        assert!(walk.remove_range(1, 3).is_err());
+        assert!(walk.remove_range(2, 1).is_err());
+
+        walk.truncate(1);
+
+        assert_eq!(walk, Walk(vec![xy!(1, 1)]));
+    }
+
+    #[test]
+    fn test_walk_getitem() {
+        let walk = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1)]);
+
+        assert_eq!(walk.resolve_index(0), Some(0));
+        assert_eq!(walk.resolve_index(2), Some(2));
+        assert_eq!(walk.resolve_index(-1), Some(2));
+        assert_eq!(walk.resolve_index(-3), Some(0));
+        assert_eq!(walk.resolve_index(3), None);
+        assert_eq!(walk.resolve_index(-4), None);
+        assert_eq!(Walk::default().resolve_index(0), None);
+
+        // Indices as computed by Python for `walk[:]`, `walk[::-1]`, `walk[::2]` and `walk[5:]`
+        assert_eq!(walk.select(0, 1, 3), walk);
+        assert_eq!(
+            walk.select(2, -1, 3),
+            Walk(vec![xy!(1, 1), xy!(1, 0), xy!(0, 0)])
+        );
+        assert_eq!(walk.select(0, 2, 2), Walk(vec![xy!(0, 0), xy!(1, 1)]));
+        assert_eq!(walk.select(3, 1, 0), Walk::default());
+    }
+
+    #[test]
+    fn test_walk_to_array() {
+        let array: Array2<i64> = Walk(vec![xy!(0, 0), xy!(1, -2), xy!(3, 4)]).to_array();
+
+        assert_eq!(array.shape(), &[3, 2]);
+        assert_eq!(array, ndarray::arr2(&[[0, 0], [1, -2], [3, 4]]));
+        assert_eq!(Walk::default().to_array().shape(), &[0, 2]);
+    }
+
+    #[test]
+    fn test_walk_frechet_distance_approx() {
+        let walk1: Walk = (0..60).map(|i| xy!(i / 2, (i % 7) - 3)).collect();
+        let walk2: Walk = (0..45).map(|i| xy!(i * 2 / 3, 4 - (i % 5))).collect();
+        let exact = walk1.frechet_distance(&walk2);
+
+        assert!((walk1.frechet_distance_approx(&walk2, 0.0) - exact).abs() < 1e-9);
+        assert!((walk1.frechet_distance_approx(&walk2, 2.0) - exact).abs() <= 4.0);
+        assert!(
+            (walk1.directness_deviation_approx(0.0) - walk1.directness_deviation()).abs() < 1e-9
+        );
+        assert_eq!(Walk::default().frechet_distance_approx(&walk2, 1.0), 0.0);
+
+        // Walks whose optimal coupling is far from the diagonal require widening the band
+        let walk3: Walk = (0..400).map(|i| xy!(i % 40, i / 40)).collect();
+        let walk4: Walk = (0..150).rev().map(|i| xy!(i / 10, i % 10)).collect();
+
+        for (a, b) in [(&walk3, &walk4), (&walk4, &walk3), (&walk3, &walk1)] {
+            assert!((a.frechet_distance_approx(b, 0.0) - a.frechet_distance(b)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_banded_frechet() {
+        let a: Vec<(f64, f64)> = (0..100).map(|i| (i as f64, 0.0)).collect();
+        let b: Vec<(f64, f64)> = (0..100).map(|i| (i as f64, 1.0)).collect();
+        let (distance, exact) = banded_frechet(&a, &b, 1);
+
+        assert_eq!(distance, 1.0);
+        assert!(exact);
+
+        let reversed: Vec<(f64, f64)> = b.iter().rev().copied().collect();
+        let (distance, exact) = banded_frechet(&a, &reversed, 1);
+
+        assert!(!exact);
+        assert!(distance >= discrete_frechet(&a, &reversed));
+        assert_eq!(
+            banded_frechet(&a, &reversed, 100),
+            (discrete_frechet(&a, &reversed), true)
+        );
+    }
+
+    #[test]
+    fn test_walk_simplify() {
+        let walk: Walk = (0..=10)
+            .map(|x| xy!(x, 0))
+            .chain((1..=10).map(|y| xy!(10, y)))
+            .collect();
+
+        assert_eq!(
+            walk.simplify(0.5),
+            Walk(vec![xy!(0, 0), xy!(10, 0), xy!(10, 10)])
+        );
+        assert_eq!(walk.simplify(10.0), Walk(vec![xy!(0, 0), xy!(10, 10)]));
+    }
+
+    #[test]
+    fn test_walk_smooth() {
+        let walk = Walk(vec![xy!(0, 0), xy!(2, 0), xy!(2, 2), xy!(4, 2), xy!(4, 4)]);
+
+        assert_eq!(
+            walk.smooth(1, None).unwrap(),
+            vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (4.0, 2.0), (4.0, 4.0)]
+        );
+        assert_eq!(
+            walk.smooth(5, None).unwrap(),
+            vec![
+                (0.0, 0.0),
+                (4.0 / 3.0, 2.0 / 3.0),
+                (2.4, 1.6),
+                (10.0 / 3.0, 8.0 / 3.0),
+                (4.0, 4.0)
+            ]
+        );
+        assert_eq!(
+            walk.smooth(3, Some(Transform::new(2.0, (10.0, 0.0)).unwrap()))
+                .unwrap()[0],
+            (10.0, 0.0)
+        );
+        assert!(walk.smooth(2, None).is_err());
+    }
+
+    #[test]
+    fn test_walk_wkt_csv() {
+        let walk1 = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1)]);
+        let walk2 = Walk(vec![xy!(-3, 2), xy!(-3, 3)]);
+
+        assert_eq!(walk1.to_wkt(), "LINESTRING (0 0, 1 0, 1 1)");
+        assert_eq!(Walk::default().to_wkt(), "LINESTRING EMPTY");
+        assert_eq!(
+            Walk::to_wkt_multiple(vec![walk1.clone(), walk2.clone()]),
+            "MULTILINESTRING ((0 0, 1 0, 1 1), (-3 2, -3 3))"
+        );
+
+        let path = std::env::temp_dir().join("randomwalks_test_walks.csv");
+
+        Walk::to_csv_multiple(vec![walk1, walk2], path.to_str().unwrap().into()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "walk_id,index,x,y\n0,0,0,0\n0,1,1,0\n0,2,1,1\n1,0,-3,2\n1,1,-3,3\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_walk_space_use() {
+        let walk = Walk(vec![
+            xy!(0, 0),
+            xy!(1, 0),
+            xy!(2, 0),
+            xy!(1, 1),
+            xy!(2, 2),
+            xy!(0, 2),
+        ]);
+        let hull = walk.convex_hull();
+
+        assert_eq!(hull.len(), 4);
+        assert!([xy!(0, 0), xy!(2, 0), xy!(2, 2), xy!(0, 2)]
+            .iter()
+            .all(|corner| hull.contains(corner)));
+        assert_eq!(walk.hull_area(None), 4.0);
+        assert_eq!(
+            walk.hull_area(Some(Transform::new(2.0, (10.0, 10.0)).unwrap())),
+            1.0
+        );
+
+        let walk = Walk(vec![xy!(0, 0), xy!(2, 0), xy!(2, 2), xy!(0, 2)]);
+        let transform = Transform::new(2.0, (0.0, 0.0)).unwrap();
+
+        assert!((walk.radius_of_gyration(None) - 2f64.sqrt()).abs() < 1e-9);
+        assert!((walk.radius_of_gyration(Some(transform)) - 0.5f64.sqrt()).abs() < 1e-9);
+        assert_eq!(Walk::default().radius_of_gyration(None), 0.0);
+    }
+
+    #[test]
+    fn test_msd() {
+        let walk1 = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(1, 1), xy!(0, 1)]);
+        let walk2 = Walk(vec![xy!(0, 0), xy!(0, 2)]);
+
+        assert_eq!(walk1.msd(None, None), vec![0.0, 1.0, 2.0, 1.0]);
+        assert_eq!(walk1.msd(Some(1), None), vec![0.0, 1.0]);
+        assert_eq!(
+            msd(&[walk1, walk2], None, None),
+            vec![0.0, 7.0 / 4.0, 2.0, 1.0]
+        );
+        assert!(msd(&[], None, None).is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+//! Provides configurable plots of walks.
+//!
+//! [`Walk::plot()`] and [`Walk::plot_multiple()`] draw walks using the default [`PlotOptions`],
+//! i.e. a 1000x1000 bitmap with thin lines. For figures in publications, the size of the image,
+//! the lines, the labels of the start and end points, the background and the plotted area can
+//! be changed using [`Walk::plot_with_options()`] and [`Walk::plot_multiple_with_options()`].
+//! Plots can also be saved as vector graphics using [`PlotBackend::Svg`].
+//!
+//! ```no_run
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::walk::plot::{PlotBackend, PlotOptions};
+//! # use randomwalks_lib::walk::Walk;
+//! # use randomwalks_lib::xy;
+//! #
+//! let walk = Walk(vec![xy!(0, 0), xy!(2, 3), xy!(7, 5)]);
+//! let options = PlotOptions {
+//!     width: 600,
+//!     height: 400,
+//!     line_color: Some((200, 30, 30)),
+//!     line_width: 3,
+//!     point_labels: false,
+//!     backend: PlotBackend::Svg,
+//!     ..Default::default()
+//! };
+//!
+//! walk.plot_with_options("walk.svg", &options).unwrap();
+//! ```
+//!
+//! Requires the `plotting` feature.
+
+use crate::walk::Walk;
+use crate::workspace;
+use anyhow::bail;
+use plotters::backend::{BitMapBackend, DrawingBackend, SVGBackend};
+use plotters::chart::ChartBuilder;
+use plotters::coord::Shift;
+use plotters::drawing::{DrawingArea, IntoDrawingArea};
+use plotters::element::{Circle, EmptyElement, Text};
+use plotters::prelude::{IntoFont, LineSeries, PointSeries, RGBColor, BLACK};
+use plotters::style::Color;
+use pyo3::{pyclass, pymethods};
+use rand::Rng;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// A backend plots can be drawn with.
+#[pyclass]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum PlotBackend {
+    /// Bitmap image, saved in the format given by the file extension, e.g. `.png`.
+    #[default]
+    Bitmap,
+
+    /// SVG vector graphic.
+    Svg,
+}
+
+/// Options for plotting walks.
+#[pyclass(get_all, set_all)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotOptions {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// RGB color of all walks, or `None` to draw single walks in black and multiple walks in
+    /// random colors.
+    pub line_color: Option<(u8, u8, u8)>,
+    /// Width of the lines in pixels.
+    pub line_width: u32,
+    /// Whether the start and end points are labeled with their coordinates.
+    pub point_labels: bool,
+    /// RGB color of the background.
+    pub background: (u8, u8, u8),
+    /// Plotted range `(min, max)` in x direction, or `None` to fit all walks.
+    pub x_range: Option<(i64, i64)>,
+    /// Plotted range `(min, max)` in y direction, or `None` to fit all walks.
+    pub y_range: Option<(i64, i64)>,
+    pub backend: PlotBackend,
+}
+
+#[pymethods]
+impl PlotOptions {
+    #[new]
+    #[pyo3(signature = (
+        width=1000,
+        height=1000,
+        line_color=None,
+        line_width=1,
+        point_labels=true,
+        background=(255, 255, 255),
+        x_range=None,
+        y_range=None,
+        backend=PlotBackend::Bitmap,
+    ))]
+    pub fn new(
+        width: u32,
+        height: u32,
+        line_color: Option<(u8, u8, u8)>,
+        line_width: u32,
+        point_labels: bool,
+        background: (u8, u8, u8),
+        x_range: Option<(i64, i64)>,
+        y_range: Option<(i64, i64)>,
+        backend: PlotBackend,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            line_color,
+            line_width,
+            point_labels,
+            background,
+            x_range,
+            y_range,
+            backend,
+        }
+    }
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self::new(
+            1000,
+            1000,
+            None,
+            1,
+            true,
+            (255, 255, 255),
+            None,
+            None,
+            PlotBackend::Bitmap,
+        )
+    }
+}
+
+/// Plots `walks` using `options` and saves the resulting image to `filename`.
+pub(crate) fn plot(walks: &[Walk], filename: String, options: &PlotOptions) -> anyhow::Result<()> {
+    if walks.is_empty() || walks.iter().any(Walk::is_empty) {
+        bail!("Cannot plot empty walk");
+    }
+
+    let filename = workspace::output_path(&filename, "plot")?;
+    let size = (options.width, options.height);
+
+    match options.backend {
+        PlotBackend::Bitmap => draw(
+            BitMapBackend::new(&filename, size).into_drawing_area(),
+            walks,
+            options,
+        ),
+        PlotBackend::Svg => draw(
+            SVGBackend::new(&filename, size).into_drawing_area(),
+            walks,
+            options,
+        ),
+    }
+}
+
+/// Draws `walks` onto `root` using `options`.
+fn draw<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    walks: &[Walk],
+    options: &PlotOptions,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    // Initialize plot
+
+    let (fitted_x, fitted_y) = point_range(walks);
+    let coordinate_range_x = options.x_range.map_or(fitted_x, |(min, max)| min..max);
+    let coordinate_range_y = options.y_range.map_or(fitted_y, |(min, max)| max..min);
+
+    let (r, g, b) = options.background;
+    root.fill(&RGBColor(r, g, b))?;
+    let root = root.margin(10, 10, 10, 10);
+
+    let mut chart = ChartBuilder::on(&root)
+        .x_label_area_size(20)
+        .y_label_area_size(20)
+        .build_cartesian_2d(coordinate_range_x, coordinate_range_y)?;
+
+    chart.configure_mesh().draw()?;
+
+    // Draw walks
+
+    let walks: Vec<Vec<(i64, i64)>> = walks
+        .iter()
+        .map(|w| w.iter().map(|p| (p.x, p.y)).collect())
+        .collect();
+
+    let mut rng = rand::thread_rng();
+
+    for walk in walks.iter() {
+        let color = match options.line_color {
+            Some((r, g, b)) => RGBColor(r, g, b),
+            None if walks.len() == 1 => BLACK,
+            None => RGBColor(
+                rng.gen_range(30..220),
+                rng.gen_range(30..220),
+                rng.gen_range(30..220),
+            ),
+        };
+
+        chart.draw_series(LineSeries::new(
+            walk.clone(),
+            color.stroke_width(options.line_width),
+        ))?;
+    }
+
+    // Find unique start and end points
+
+    let mut se_points = HashSet::new();
+
+    for walk in walks.iter() {
+        se_points.insert((
+            walk.first().copied().unwrap(),
+            walk.last().copied().unwrap(),
+        ));
+    }
+
+    // Draw start and end points
+
+    for (start, end) in se_points {
+        chart.draw_series(PointSeries::of_element(
+            vec![start, end],
+            5,
+            &BLACK,
+            &|c, s, st| {
+                let element = EmptyElement::at(c) + Circle::new((0, 0), s, st.filled());
+                let label = options
+                    .point_labels
+                    .then(|| format!("{:?}", c))
+                    .unwrap_or_default();
+
+                element + Text::new(label, (10, 0), ("sans-serif", 10).into_font())
+            },
+        ))?;
+    }
+
+    root.present()?;
+
+    Ok(())
+}
+
+/// Computes the area spanned by all `walks` with a margin of 5 cells, the y range being reversed.
+pub(crate) fn point_range(walks: &[Walk]) -> (Range<i64>, Range<i64>) {
+    // Compute size of plotting area
+
+    let points: Vec<_> = walks.iter().flat_map(|x| &x.0).copied().collect();
+
+    let xs: Vec<i64> = points.iter().map(|p| p.x).collect();
+    let ys: Vec<i64> = points.iter().map(|p| p.y).collect();
+
+    let x_range = (*xs.iter().min().unwrap(), *xs.iter().max().unwrap());
+    let y_range = (*ys.iter().min().unwrap(), *ys.iter().max().unwrap());
+
+    let coordinate_range_x = x_range.0 - 5..x_range.1 + 5;
+    let coordinate_range_y = y_range.1 + 5..y_range.0 - 5;
+
+    (coordinate_range_x, coordinate_range_y)
+}
@@ -0,0 +1,231 @@
+//! Provides reports comparing a real walk with simulated random walks.
+//!
+//! A [`ComparisonReport`] is created using [`compare()`] and summarizes how well an ensemble of
+//! simulated walks matches an observed walk. Reports are serializable and can be rendered as
+//! Markdown or JSON.
+//!
+//! ```
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::walk::ensemble::WalkEnsemble;
+//! # use randomwalks_lib::walk::report::compare;
+//! # use randomwalks_lib::walk::Walk;
+//! # use randomwalks_lib::xy;
+//! #
+//! let real = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0)]);
+//! let simulated = WalkEnsemble(vec![
+//!     Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0)]),
+//!     Walk(vec![xy!(0, 0), xy!(1, 1), xy!(2, 1)]),
+//! ]);
+//!
+//! let report = compare(&real, &simulated).unwrap();
+//!
+//! println!("{}", report.to_markdown());
+//! ```
+
+use crate::dataset::point::XYPoint;
+use crate::walk::ensemble::WalkEnsemble;
+use crate::walk::Walk;
+use anyhow::bail;
+use pyo3::{pyclass, pymethods};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Summary statistics of a set of values.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quantiles {
+    pub min: f64,
+    pub q25: f64,
+    pub median: f64,
+    pub q75: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Quantiles {
+    /// Computes the quantiles of `values` using linear interpolation.
+    ///
+    /// Returns `None` if `values` is empty.
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        Some(Self {
+            min: sorted[0],
+            q25: quantile(&sorted, 0.25),
+            median: quantile(&sorted, 0.5),
+            q75: quantile(&sorted, 0.75),
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        })
+    }
+}
+
+/// Returns the `q`-quantile of the non-empty, `sorted` values using linear interpolation.
+pub(crate) fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (pos.floor() as usize, pos.ceil() as usize);
+
+    sorted[lower] + (pos - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+/// A report comparing a real walk with an ensemble of simulated walks.
+///
+/// Created using [`compare()`].
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    /// Number of simulated walks.
+    pub walks: usize,
+    /// Fréchet distances between the real walk and each simulated walk.
+    pub frechet_distance: Quantiles,
+    /// Euclidean distances between the endpoint of the real walk and the endpoint of each
+    /// simulated walk.
+    pub endpoint_error: Quantiles,
+    /// Fraction of points of the real walk that are visited by at least one simulated walk.
+    pub corridor_coverage: f64,
+}
+
+#[pymethods]
+impl ComparisonReport {
+    #[staticmethod]
+    #[pyo3(name = "compare")]
+    pub fn py_compare(real: Walk, simulated: WalkEnsemble) -> anyhow::Result<Self> {
+        compare(&real, &simulated)
+    }
+
+    /// Renders the report as a Markdown table.
+    pub fn to_markdown(&self) -> String {
+        let mut res = String::new();
+
+        res += &format!("Comparison with {} simulated walks\n\n", self.walks);
+        res += "| Metric | Min | Q25 | Median | Q75 | Max | Mean |\n";
+        res += "|---|---|---|---|---|---|---|\n";
+
+        for (name, q) in [
+            ("Fréchet distance", &self.frechet_distance),
+            ("Endpoint error", &self.endpoint_error),
+        ] {
+            res += &format!(
+                "| {} | {:.3} | {:.3} | {:.3} | {:.3} | {:.3} | {:.3} |\n",
+                name, q.min, q.q25, q.median, q.q75, q.max, q.mean
+            );
+        }
+
+        res += &format!(
+            "\nCorridor coverage: {:.1}%\n",
+            self.corridor_coverage * 100.0
+        );
+
+        res
+    }
+
+    /// Renders the report as JSON.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "ComparisonReport(walks={}, median_frechet={:.3}, corridor_coverage={:.3})",
+            self.walks, self.frechet_distance.median, self.corridor_coverage
+        )
+    }
+}
+
+/// Compares a real walk with an ensemble of simulated walks.
+///
+/// # Errors
+///
+/// Returns an error if the real walk or the ensemble is empty, or if the ensemble contains an
+/// empty walk.
+pub fn compare(real: &Walk, simulated: &WalkEnsemble) -> anyhow::Result<ComparisonReport> {
+    if real.is_empty() {
+        bail!("real walk must not be empty");
+    }
+
+    if simulated.is_empty() {
+        bail!("ensemble of simulated walks must not be empty");
+    }
+
+    if simulated.iter().any(|walk| walk.is_empty()) {
+        bail!("simulated walks must not be empty");
+    }
+
+    let frechet: Vec<f64> = simulated
+        .iter()
+        .map(|walk| real.frechet_distance(walk))
+        .collect();
+
+    let real_end = real[real.len() - 1];
+    let endpoint: Vec<f64> = simulated
+        .iter()
+        .map(|walk| euclidean(real_end, walk[walk.len() - 1]))
+        .collect();
+
+    let visited: HashSet<XYPoint> = simulated
+        .iter()
+        .flat_map(|walk| walk.iter())
+        .copied()
+        .collect();
+    let covered = real.iter().filter(|p| visited.contains(p)).count();
+
+    Ok(ComparisonReport {
+        walks: simulated.len(),
+        frechet_distance: Quantiles::from_values(&frechet).unwrap(),
+        endpoint_error: Quantiles::from_values(&endpoint).unwrap(),
+        corridor_coverage: covered as f64 / real.len() as f64,
+    })
+}
+
+fn euclidean(from: XYPoint, to: XYPoint) -> f64 {
+    (((to.x - from.x).pow(2) + (to.y - from.y).pow(2)) as f64).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::walk::ensemble::WalkEnsemble;
+    use crate::walk::report::{compare, Quantiles};
+    use crate::walk::Walk;
+    use crate::xy;
+
+    #[test]
+    fn test_quantiles() {
+        let q = Quantiles::from_values(&[4.0, 1.0, 3.0, 2.0, 5.0]).unwrap();
+
+        assert_eq!(q.min, 1.0);
+        assert_eq!(q.q25, 2.0);
+        assert_eq!(q.median, 3.0);
+        assert_eq!(q.q75, 4.0);
+        assert_eq!(q.max, 5.0);
+        assert_eq!(q.mean, 3.0);
+        assert!(Quantiles::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn test_compare() {
+        let real = Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0), xy!(3, 0)]);
+        let simulated = WalkEnsemble(vec![
+            Walk(vec![xy!(0, 0), xy!(1, 0), xy!(2, 0), xy!(3, 0)]),
+            Walk(vec![xy!(0, 0), xy!(0, 1), xy!(1, 1), xy!(3, 4)]),
+        ]);
+
+        let report = compare(&real, &simulated).unwrap();
+
+        assert_eq!(report.walks, 2);
+        assert_eq!(report.endpoint_error.min, 0.0);
+        assert_eq!(report.endpoint_error.max, 4.0);
+        assert_eq!(report.frechet_distance.min, 0.0);
+        assert_eq!(report.corridor_coverage, 1.0);
+        assert!(report
+            .to_json()
+            .unwrap()
+            .contains("\"corridor_coverage\": 1.0"));
+        assert!(compare(&real, &WalkEnsemble::default()).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+//! Provides a walker that is attracted towards a fixed point.
+//!
+//! Animals returning to a nest or den, i.e. central-place foragers, move towards it more
+//! strongly the further away they are. The [`AttractionWalker`] models this by recomputing the
+//! probabilities of each step depending on its position: the probabilities of its base kernel are
+//! blended with an attraction towards the `attractor`, which favors all moves pointing towards it
+//! by how well they are aligned with the direction to it.
+//!
+//! The weight of the attraction grows linearly with the distance to the attractor, from zero at
+//! the attractor itself to `attraction` at a distance of `range` or more. Only moves the base
+//! kernel allows are favored, so walks generated by this walker are consistent with dynamic
+//! programs computed using the base kernel.
+//!
+//! ```
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::attraction::AttractionWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! # use randomwalks_lib::xy;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let walker = AttractionWalker::new(kernel, xy!(20, 20), 0.5, 10.0);
+//! let walk = walker.generate_path(&dp, 10, -5, 100).unwrap();
+//! ```
+
+use crate::dataset::point::XYPoint;
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::kernel::Kernel;
+use crate::walker::{log_weight, sample_log_weights, seeded_rng, Walk, Walker, WalkerError};
+use pyo3::{pyclass, pymethods};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A walker blending the probabilities of its `kernel` with an attraction towards `attractor`.
+#[pyclass]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AttractionWalker {
+    pub kernel: Kernel,
+    /// Point the walker is attracted towards, in the coordinates of the dynamic program.
+    pub attractor: XYPoint,
+    /// Weight of the attraction at a distance of `range` or more, between 0 and 1.
+    pub attraction: f64,
+    /// Distance from which on the full `attraction` is used.
+    pub range: f64,
+}
+
+#[pymethods]
+impl AttractionWalker {
+    #[new]
+    pub fn new(kernel: Kernel, attractor: XYPoint, attraction: f64, range: f64) -> Self {
+        Self {
+            kernel,
+            attractor,
+            attraction,
+            range,
+        }
+    }
+
+    // Trait function wrappers for Python
+
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_with_rng(self, &dp, to_x, to_y, time_steps, &mut *seeded_rng(seed))
+    }
+
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
+        &self,
+        dp: DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        Walker::generate_paths_with_rng(
+            self,
+            &dp,
+            qty,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_scored", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path_scored(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<(Walk, f64), WalkerError> {
+        Walker::generate_path_scored_with_rng(
+            self,
+            &dp,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_through", signature = (dp, from_point, waypoints, seed=None))]
+    pub fn py_generate_path_through(
+        &self,
+        dp: DynamicProgramPool,
+        from_point: XYPoint,
+        waypoints: Vec<(XYPoint, usize)>,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_through_with_rng(
+            self,
+            &dp,
+            from_point,
+            &waypoints,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(
+        name = "generate_path_between",
+        signature = (dp, from_x, from_y, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path_between(
+        &self,
+        dp: DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_between_with_rng(
+            self,
+            &dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    pub fn name(&self, short: bool) -> String {
+        Walker::name(self, short)
+    }
+}
+
+impl AttractionWalker {
+    /// Returns the probability of moving by `(dx, dy)` from `(x, y)`.
+    fn step_prob(&self, (x, y): (isize, isize), (dx, dy): (isize, isize)) -> f64 {
+        let base = self.kernel.at(dx, dy);
+        let (vx, vy) = (
+            (self.attractor.x - x as i64) as f64,
+            (self.attractor.y - y as i64) as f64,
+        );
+        let distance = vx.hypot(vy);
+        let weight = self.attraction * (distance / self.range).min(1.0);
+
+        if weight == 0.0 {
+            return base;
+        }
+
+        // Cosine of the angle between a move and the direction to the attractor
+        let alignment = |dx: isize, dy: isize| {
+            if (dx, dy) == (0, 0) || self.kernel.at(dx, dy) == 0.0 {
+                return 0.0;
+            }
+
+            let (dx, dy) = (dx as f64, dy as f64);
+
+            ((dx * vx + dy * vy) / (dx.hypot(dy) * distance)).max(0.0)
+        };
+
+        let ks = (self.kernel.size() / 2) as isize;
+        let total: f64 = (-ks..=ks)
+            .flat_map(|i| (-ks..=ks).map(move |j| (i, j)))
+            .map(|(i, j)| alignment(i, j))
+            .sum();
+
+        if total == 0.0 {
+            return base;
+        }
+
+        (1.0 - weight) * base + weight * alignment(dx, dy) / total
+    }
+}
+
+impl Walker for AttractionWalker {
+    fn generate_path_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let (DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _)) = dp else {
+            return Err(WalkerError::RequiresSingleDynamicProgram);
+        };
+
+        self.generate_path_from_with_rng(dp, to_x, to_y, time_steps, rng)
+    }
+
+    fn generate_path_from_with_rng(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        if !(0.0..=1.0).contains(&self.attraction) || self.range <= 0.0 {
+            return Err(WalkerError::InvalidConfiguration);
+        }
+
+        let ks = (self.kernel.size() / 2) as isize;
+        let mut path = Vec::new();
+        let (mut x, mut y) = (to_x, to_y);
+
+        // Check if any path exists leading to the given end point
+        if dp.is_zero(to_x, to_y, time_steps) {
+            return Err(WalkerError::NoPathExists);
+        }
+
+        for t in (1..time_steps).rev() {
+            path.push((x as i64, y as i64).into());
+
+            let mut prev_probs = Vec::new();
+            let mut movements = Vec::new();
+
+            // The attraction depends on the position a step starts at
+            for i in x - ks..=x + ks {
+                for j in y - ks..=y + ks {
+                    let p_a_b = self.step_prob((i, j), (x - i, y - j));
+
+                    prev_probs.push(log_weight(dp, (i, j), (x, y), t, p_a_b));
+                    movements.push((i, j));
+                }
+            }
+
+            (x, y) = movements[sample_log_weights(&prev_probs, rng)?];
+        }
+
+        path.reverse();
+        path.insert(0, (x as i64, y as i64).into());
+
+        Ok(path.into())
+    }
+
+    fn name(&self, short: bool) -> String {
+        if short {
+            String::from("atw")
+        } else {
+            String::from("Attraction Walker")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::attraction::AttractionWalker;
+    use crate::walker::{Walker, WalkerError};
+    use crate::xy;
+
+    #[test]
+    fn test_attraction_walker() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let walker = AttractionWalker::new(kernel.clone(), xy!(10, 0), 0.5, 5.0);
+        let moves = [(0, 0), (-1, 0), (0, -1), (1, 0), (0, 1)];
+        let probs = |from| moves.map(|mov| walker.step_prob(from, mov));
+
+        // Far from the attractor, half of the probability moves towards it
+        let far = probs((-10, 0));
+
+        assert!((far.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((far[3] - 0.6).abs() < 1e-12);
+        assert!((far[1] - 0.1).abs() < 1e-12);
+
+        // The attraction is weaker closer to the attractor and vanishes at it
+        let near = probs((8, 0));
+
+        assert!((near[3] - 0.36).abs() < 1e-12);
+        assert_eq!(probs((10, 0)), moves.map(|(dx, dy)| kernel.at(dx, dy)));
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walk: Vec<XYPoint> = walker.generate_path(&dp, 3, -2, 20).unwrap().into();
+
+        assert_eq!(*walk.last().unwrap(), xy!(3, -2));
+        assert_eq!(
+            AttractionWalker::new(kernel, xy!(10, 0), 1.5, 5.0)
+                .generate_path(&dp, 3, -2, 20)
+                .unwrap_err(),
+            WalkerError::InvalidConfiguration
+        );
+    }
+}
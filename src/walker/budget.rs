@@ -0,0 +1,300 @@
+//! Provides a walker limiting the total length of its walks.
+//!
+//! Many animals are known to travel at most a certain distance per day, but walks conditioned
+//! only on their start and end point often wind around considerably and overshoot it. The
+//! [`BudgetWalker`] keeps track of the Euclidean length of all steps taken so far and rejects
+//! every step after which the walk could no longer return to the start point within its
+//! `budget`, as the remaining distance is at least the straight-line distance to it.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::budget::BudgetWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let walker = BudgetWalker::new(kernel, 40.0);
+//! let walk = walker.generate_path(&dp, 20, -10, 100).unwrap();
+//! ```
+//!
+//! Rejecting steps only looks ahead by the straight-line distance, so walks may still run out of
+//! budget when the kernel cannot move straight towards the start point. Such walks fail with
+//! [`InconsistentPath`](WalkerError::InconsistentPath) and can be retried.
+
+use crate::dataset::point::XYPoint;
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::kernel::Kernel;
+use crate::walker::{log_weight, sample_log_weights, seeded_rng, Walk, Walker, WalkerError};
+use pyo3::{pyclass, pymethods};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A walker using `kernel` whose walks have a total Euclidean length of at most `budget`.
+#[pyclass]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BudgetWalker {
+    pub kernel: Kernel,
+    /// Maximum total length of all steps of a walk.
+    pub budget: f64,
+}
+
+#[pymethods]
+impl BudgetWalker {
+    #[new]
+    pub fn new(kernel: Kernel, budget: f64) -> Self {
+        Self { kernel, budget }
+    }
+
+    // Trait function wrappers for Python
+
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_with_rng(self, &dp, to_x, to_y, time_steps, &mut *seeded_rng(seed))
+    }
+
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
+        &self,
+        dp: DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        Walker::generate_paths_with_rng(
+            self,
+            &dp,
+            qty,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_scored", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path_scored(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<(Walk, f64), WalkerError> {
+        Walker::generate_path_scored_with_rng(
+            self,
+            &dp,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_through", signature = (dp, from_point, waypoints, seed=None))]
+    pub fn py_generate_path_through(
+        &self,
+        dp: DynamicProgramPool,
+        from_point: XYPoint,
+        waypoints: Vec<(XYPoint, usize)>,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_through_with_rng(
+            self,
+            &dp,
+            from_point,
+            &waypoints,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(
+        name = "generate_path_between",
+        signature = (dp, from_x, from_y, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path_between(
+        &self,
+        dp: DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_between_with_rng(
+            self,
+            &dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    pub fn name(&self, short: bool) -> String {
+        Walker::name(self, short)
+    }
+}
+
+impl Walker for BudgetWalker {
+    fn generate_path_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let (DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _)) = dp else {
+            return Err(WalkerError::RequiresSingleDynamicProgram);
+        };
+
+        self.generate_path_from_with_rng(dp, to_x, to_y, time_steps, rng)
+    }
+
+    fn generate_path_from_with_rng(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        if self.budget.is_nan() || self.budget < 0.0 {
+            return Err(WalkerError::InvalidConfiguration);
+        }
+
+        let distance = |x: isize, y: isize| (x as f64).hypot(y as f64);
+
+        // Check if any path exists leading to the given end point within the budget
+        if dp.is_zero(to_x, to_y, time_steps) || distance(to_x, to_y) > self.budget {
+            return Err(WalkerError::NoPathExists);
+        }
+
+        let ks = (self.kernel.size() / 2) as isize;
+        let mut path = Vec::new();
+        let (mut x, mut y) = (to_x, to_y);
+        let mut used = 0.0;
+
+        for t in (1..time_steps).rev() {
+            path.push((x as i64, y as i64).into());
+
+            let mut prev_probs = Vec::new();
+            let mut movements = Vec::new();
+
+            for i in x - ks..=x + ks {
+                for j in y - ks..=y + ks {
+                    let length = distance(x - i, y - j);
+
+                    // The walk still has to reach the start point from (i, j)
+                    if used + length + distance(i, j) > self.budget {
+                        prev_probs.push(f64::NEG_INFINITY);
+                    } else {
+                        let p_a_b = self.kernel.at(x - i, y - j);
+
+                        prev_probs.push(log_weight(dp, (i, j), (x, y), t, p_a_b));
+                    }
+
+                    movements.push((i, j));
+                }
+            }
+
+            let (i, j) = movements[sample_log_weights(&prev_probs, rng)?];
+
+            used += distance(x - i, y - j);
+            (x, y) = (i, j);
+        }
+
+        path.reverse();
+        path.insert(0, (x as i64, y as i64).into());
+
+        Ok(path.into())
+    }
+
+    fn name(&self, short: bool) -> String {
+        if short {
+            String::from("bgw")
+        } else {
+            String::from("Budget Walker")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::budget::BudgetWalker;
+    use crate::walker::{Walker, WalkerError};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_budget_walker() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        // The shortest walks to (3, -2) have a length of 5, so at most two detours are allowed
+        let walker = BudgetWalker::new(kernel.clone(), 9.0);
+        let walks: Vec<Vec<XYPoint>> = (0..20)
+            .filter_map(|seed| {
+                walker
+                    .generate_path_with_rng(&dp, 3, -2, 20, &mut StdRng::seed_from_u64(seed))
+                    .ok()
+            })
+            .map(|walk| walk.into())
+            .collect();
+
+        assert!(!walks.is_empty());
+
+        for walk in walks.iter() {
+            let length: i64 = walk
+                .windows(2)
+                .map(|w| (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs())
+                .sum();
+
+            assert!(length <= 9);
+        }
+
+        assert_eq!(
+            BudgetWalker::new(kernel.clone(), 3.0).generate_path(&dp, 3, -2, 20),
+            Err(WalkerError::NoPathExists)
+        );
+        assert_eq!(
+            BudgetWalker::new(kernel, -1.0).generate_path(&dp, 3, -2, 20),
+            Err(WalkerError::InvalidConfiguration)
+        );
+    }
+}
@@ -1,15 +1,15 @@
-use crate::dp::simple::DynamicProgram;
 use crate::dp::DynamicProgramPool;
-use crate::walker::{Walk, Walker, WalkerError};
-use num::Zero;
+use crate::walker::{log_weight, sample_log_weights, seeded_rng, Walk, Walker, WalkerError};
 use pyo3::{pyclass, pymethods};
-use rand::distributions::{WeightedError, WeightedIndex};
-use rand::prelude::Distribution;
-use rand::Rng;
-use crate::kernel::Kernel;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use crate::kernel::{Direction, Kernel};
+
+/// Moves from a point to its possible predecessors, in the order stay, west, north, east, south.
+const NEIGHBORS: [(isize, isize); 5] = [(0, 0), (-1, 0), (0, -1), (1, 0), (0, 1)];
 
 #[pyclass]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CorrelatedWalker {
     kernels: Vec<Kernel>,
 }
@@ -25,37 +25,69 @@ impl CorrelatedWalker {
 
     // Trait function wrappers for Python
 
-    pub fn generate_path(
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
         &self,
-        dp: Vec<DynamicProgram>,
+        dp: DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Walk, WalkerError> {
-        Walker::generate_path(
+        Walker::generate_path_with_rng(
             self,
-            &DynamicProgramPool::Multiple(dp),
+            &dp,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
-    pub fn generate_paths(
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
         &self,
-        dp: Vec<DynamicProgram>,
+        dp: DynamicProgramPool,
         qty: usize,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Vec<Walk>, WalkerError> {
-        Walker::generate_paths(
+        Walker::generate_paths_with_rng(
             self,
-            &DynamicProgramPool::Multiple(dp),
+            &dp,
             qty,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(
+        name = "generate_path_between",
+        signature = (dp, from_x, from_y, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path_between(
+        &self,
+        dp: DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_between_with_rng(
+            self,
+            &dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
@@ -65,12 +97,13 @@ impl CorrelatedWalker {
 }
 
 impl Walker for CorrelatedWalker {
-    fn generate_path(
+    fn generate_path_with_rng(
         &self,
         dp: &DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        rng: &mut dyn RngCore,
     ) -> Result<Walk, WalkerError> {
         let DynamicProgramPool::Multiple(dp) = dp else {
             return Err(WalkerError::RequiresMultipleDynamicPrograms);
@@ -78,76 +111,40 @@ impl Walker for CorrelatedWalker {
 
         let mut path = Vec::new();
         let (mut x, mut y) = (to_x, to_y);
-        let mut rng = rand::thread_rng();
 
         // Check if any path exists leading to the given end point for each variant
         for variant in 0..dp.len() {
-            if dp[variant].at(to_x, to_y, time_steps).is_zero() {
+            if dp[variant].is_zero(to_x, to_y, time_steps) {
                 return Err(WalkerError::NoPathExists);
             }
         }
 
-        path.push((x as i64, y as i64).into());
-
-        // Compute first (= last, because reconstructing backwards) step manually
-        let direction: usize = rng.gen_range(0..4);
+        // Direction of the step following the current point, unknown for the end point
+        let mut next_variant = None;
 
-        match direction {
-            1 => x -= 1,
-            2 => y -= 1,
-            3 => x += 1,
-            4 => y += 1,
-            _ => (),
-        }
-
-        let mut last_direction = direction;
-
-        for t in (1..time_steps - 1).rev() {
+        for t in (1..time_steps).rev() {
             path.push((x as i64, y as i64).into());
 
-            let variant: usize = match last_direction {
-                0 => 4,
-                1 => 1,
-                2 => 0,
-                3 => 3,
-                4 => 2,
-                _ => panic!("Invalid last direction. This should not happen."),
-            };
-
-            let neighbors = [
-                (0, 0),
-                (-1, 0),
-                (0, -1),
-                (1, 0),
-                (0, 1),
-            ];
-            let mut prev_probs = Vec::new();
-
-            for (mov_x, mov_y) in neighbors.iter() {
+            let prev_probs = NEIGHBORS.map(|(mov_x, mov_y)| {
                 let (i, j) = (x + mov_x, y + mov_y);
+                let log_weight_of = |variant: usize| {
+                    let p_a_b = self.kernels[variant].at(x - i, y - j);
 
-                let p_b = dp[variant].at_or(i, j, t - 1, 0.0);
-                let p_a = dp[variant].at_or(x, y, t, 0.0);
-                let p_a_b = self.kernels[variant].at(i - x, j - y);
-
-                prev_probs.push((p_a_b * p_b) / p_a);
-            }
+                    log_weight(&dp[variant], (i, j), (x, y), t, p_a_b)
+                };
 
-            let direction = match WeightedIndex::new(prev_probs) {
-                Ok(dist) => dist.sample(&mut rng),
-                Err(WeightedError::AllWeightsZero) => return Err(WalkerError::InconsistentPath),
-                _ => return Err(WalkerError::RandomDistributionError),
-            };
+                match next_variant {
+                    Some(variant) => log_weight_of(variant),
+                    // Without a following step, all variants are considered equally likely
+                    None => log_sum_exp((0..dp.len()).map(log_weight_of)),
+                }
+            });
 
-            last_direction = direction;
+            let (mov_x, mov_y) = NEIGHBORS[sample_log_weights(&prev_probs, rng)?];
 
-            match direction {
-                1 => x -= 1,
-                2 => y -= 1,
-                3 => x += 1,
-                4 => y += 1,
-                _ => (),
-            }
+            next_variant = Some(variant_of((-mov_x, -mov_y)));
+            x += mov_x;
+            y += mov_y;
         }
 
         path.reverse();
@@ -164,3 +161,89 @@ impl Walker for CorrelatedWalker {
         }
     }
 }
+
+/// Returns the variant of the kernels and dynamic programs used for the step preceding a step
+/// by `mov`, which is the index of the direction of `mov` in [`Direction`].
+///
+/// Correlated walks tend to keep their direction, so the kernel of each variant favors steps in
+/// its direction. Panics if `mov` is not a move to a direct neighbor or staying in place.
+fn variant_of(mov: (isize, isize)) -> usize {
+    Direction::try_from(mov).expect("steps only move to direct neighbors") as usize
+}
+
+/// Returns the natural logarithm of the sum of the exponentials of `log_weights`.
+fn log_sum_exp(log_weights: impl Iterator<Item = f64> + Clone) -> f64 {
+    let max = log_weights.clone().fold(f64::NEG_INFINITY, f64::max);
+
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+
+    let sum: f64 = log_weights.map(|weight| (weight - max).exp()).sum();
+
+    max + sum.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::{DynamicProgramPool, DynamicPrograms};
+    use crate::kernel::correlated_rw::CorrelatedRwGenerator;
+    use crate::kernel::{Direction, Kernel};
+    use crate::walker::correlated::{variant_of, CorrelatedWalker, NEIGHBORS};
+    use crate::walker::Walker;
+    use crate::xy;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_variant() {
+        let kernels =
+            Kernel::multiple_from_generator(CorrelatedRwGenerator { persistence: 0.6 }).unwrap();
+
+        // The kernel of each variant favors steps continuing in the same direction
+        for mov in NEIGHBORS {
+            assert_eq!(kernels[variant_of(mov)].at(mov.0, mov.1), 0.6);
+        }
+
+        for (i, direction) in Direction::iter().enumerate() {
+            assert_eq!(variant_of(direction.into()), i);
+        }
+    }
+
+    #[test]
+    fn test_correlated_walker() {
+        let kernels =
+            Kernel::multiple_from_generator(CorrelatedRwGenerator { persistence: 0.6 }).unwrap();
+        let dps = kernels
+            .iter()
+            .map(|kernel| {
+                let mut dp = DynamicProgramBuilder::new()
+                    .simple()
+                    .time_limit(20)
+                    .kernel(kernel.clone())
+                    .build()
+                    .unwrap();
+
+                dp.compute().unwrap();
+
+                let DynamicProgramPool::Single(dp) = dp else {
+                    unreachable!();
+                };
+
+                dp
+            })
+            .collect();
+
+        let walk: Vec<XYPoint> = CorrelatedWalker::new(kernels)
+            .generate_path(&DynamicProgramPool::Multiple(dps), 4, -3, 20)
+            .unwrap()
+            .into();
+
+        assert_eq!(walk.len(), 20);
+        assert_eq!(walk[19], xy!(4, -3));
+        assert!(walk
+            .windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs() <= 1));
+    }
+}
@@ -0,0 +1,197 @@
+//! Provides support for walkers implemented in Python.
+//!
+//! Walkers accepted by Python functions like `Dataset.rw_between()` and
+//! `Dataset.generate_walks()` are given as a [`WalkerType`](crate::walker::WalkerType). Besides
+//! the walkers of this crate, it accepts any Python object exposing the same interface as them,
+//! i.e. the methods
+//!
+//! - `generate_path(dp, to_x, to_y, time_steps, seed)` returning a `Walk`, where `dp` is a
+//!   `DynamicProgram`, or a list of them for pools of multiple dynamic programs, and
+//! - `name(short)` returning the name of the walker.
+//!
+//! Such objects are wrapped in a [`CustomWalker`], which implements [`Walker`] by calling them.
+//! This allows adding walkers without changing this crate:
+//!
+//! ```python
+//! class StraightWalker:
+//!     def generate_path(self, dp, to_x, to_y, time_steps, seed=None):
+//!         ...
+//!
+//!     def name(self, short):
+//!         return "stw" if short else "Straight Walker"
+//!
+//! walks = dataset.generate_walks(dp, StraightWalker(), time_steps=100)
+//! ```
+//!
+//! The dynamic program is copied into Python once for each call of `generate_path()` or
+//! `generate_paths()`, so custom walkers are slower than the walkers of this crate, especially
+//! when generating single walks. Exceptions raised by the Python object are passed on as
+//! [`WalkerError::PythonException`] together with their traceback, unless their message is the
+//! one of another [`WalkerError`].
+
+use crate::dp::DynamicProgramPool;
+use crate::walk::Walk;
+use crate::walker::{Walker, WalkerError};
+use pyo3::exceptions::PyTypeError;
+use pyo3::{FromPyObject, IntoPy, PyAny, PyErr, PyObject, PyResult, Python};
+use rand::RngCore;
+
+/// A walker implemented by a Python object.
+#[derive(Clone)]
+pub struct CustomWalker {
+    walker: PyObject,
+}
+
+impl<'source> FromPyObject<'source> for CustomWalker {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if !ob.hasattr("generate_path")? || !ob.hasattr("name")? {
+            return Err(PyTypeError::new_err(
+                "walkers must provide generate_path() and name() methods",
+            ));
+        }
+
+        Ok(Self { walker: ob.into() })
+    }
+}
+
+impl CustomWalker {
+    /// Calls `generate_path()` of the Python object with `dp`, which was already converted using
+    /// [`dp_into_py()`].
+    fn call_generate_path(
+        &self,
+        py: Python,
+        dp: &PyObject,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: u64,
+    ) -> Result<Walk, WalkerError> {
+        self.walker
+            .call_method1(
+                py,
+                "generate_path",
+                (dp.clone_ref(py), to_x, to_y, time_steps, seed),
+            )
+            .and_then(|walk| walk.extract::<Walk>(py))
+            .map_err(|err| error_from_py(py, err))
+    }
+}
+
+impl Walker for CustomWalker {
+    /// Generates a random walk by calling `generate_path()` of the Python object.
+    ///
+    /// The seed passed to it is drawn from `rng`, so that walks are reproducible if the Python
+    /// object uses it. See the [`custom`](crate::walker::custom) module for how errors raised by
+    /// the Python object are converted.
+    fn generate_path_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let seed = rng.next_u64();
+
+        Python::with_gil(|py| {
+            let dp = dp_into_py(py, dp);
+
+            self.call_generate_path(py, &dp, to_x, to_y, time_steps, seed)
+        })
+    }
+
+    /// Generates `qty` random walks by calling `generate_path()` of the Python object, converting
+    /// the dynamic program to Python only once.
+    fn generate_paths_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        Python::with_gil(|py| {
+            let dp = dp_into_py(py, dp);
+
+            (0..qty)
+                .map(|_| {
+                    let seed = rng.next_u64();
+
+                    self.call_generate_path(py, &dp, to_x, to_y, time_steps, seed)
+                })
+                .collect()
+        })
+    }
+
+    fn name(&self, short: bool) -> String {
+        Python::with_gil(|py| {
+            self.walker
+                .call_method1(py, "name", (short,))
+                .and_then(|name| name.extract::<String>(py))
+                .unwrap_or_else(|_| String::from(if short { "cw" } else { "Custom Walker" }))
+        })
+    }
+}
+
+/// Copies `dp` into Python, i.e. a single dynamic program for single and bridge pools and a list
+/// of dynamic programs otherwise.
+fn dp_into_py(py: Python, dp: &DynamicProgramPool) -> PyObject {
+    match dp {
+        DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _) => {
+            dp.clone().into_py(py)
+        }
+        DynamicProgramPool::Multiple(dps) => dps.clone().into_py(py),
+    }
+}
+
+/// Converts an exception raised by the Python object into a [`WalkerError`], keeping its type,
+/// message and traceback. See [`error_from_message()`].
+fn error_from_py(py: Python, err: PyErr) -> WalkerError {
+    let traceback = err
+        .traceback(py)
+        .and_then(|traceback| traceback.format().ok())
+        .unwrap_or_default();
+
+    error_from_message(&err.value(py).to_string(), format!("{traceback}{err}"))
+}
+
+/// Returns the [`WalkerError`] whose message is `message`, or [`WalkerError::PythonException`]
+/// holding `details` if there is none.
+fn error_from_message(message: &str, details: String) -> WalkerError {
+    [
+        WalkerError::RequiresSingleDynamicProgram,
+        WalkerError::RequiresMultipleDynamicPrograms,
+        WalkerError::NoPathExists,
+        WalkerError::InconsistentPath,
+        WalkerError::RandomDistributionError,
+    ]
+    .into_iter()
+    .find(|error| error.to_string() == message)
+    .unwrap_or(WalkerError::PythonException(details))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::walker::custom::error_from_message;
+    use crate::walker::WalkerError;
+
+    #[test]
+    fn test_error_from_message() {
+        let details = String::from("ZeroDivisionError: division by zero");
+
+        assert_eq!(
+            error_from_message(&WalkerError::NoPathExists.to_string(), details.clone()),
+            WalkerError::NoPathExists
+        );
+        assert_eq!(
+            error_from_message(&WalkerError::InconsistentPath.to_string(), details.clone()),
+            WalkerError::InconsistentPath
+        );
+
+        let error = error_from_message("division by zero", details.clone());
+
+        assert_eq!(error, WalkerError::PythonException(details.clone()));
+        assert_eq!(error.to_string(), details);
+    }
+}
@@ -0,0 +1,272 @@
+//! Provides diagnostics for checking whether a walker and dynamic program fit together.
+//!
+//! Before generating walks for a whole dataset, [`failure_rate()`] can be used to find out how
+//! often generating a walk fails for end points at different distances from the origin. High
+//! rates of [`InconsistentPath`](WalkerError::InconsistentPath) usually mean that the walker does
+//! not match the kernel of the dynamic program, while high rates of
+//! [`NoPathExists`](WalkerError::NoPathExists) mean that the time limit is too small for the
+//! distances involved.
+//!
+//! ```
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::diagnostics::failure_rate;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::xy;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(50)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let walker = StandardWalker::new(kernel);
+//! let report = failure_rate(&dp, &walker, &[xy!(5, 5), xy!(20, 10), xy!(60, 0)], 10).unwrap();
+//!
+//! for bucket in report.buckets.iter() {
+//!     println!("{:?}: {:.2}", bucket.min_distance..bucket.max_distance, bucket.failure_rate());
+//! }
+//! ```
+
+use crate::dataset::point::XYPoint;
+use crate::dp::DynamicProgramPool;
+use crate::walker::{Walker, WalkerError, WalkerType};
+use anyhow::{bail, Context};
+use pyo3::{pyclass, pymethods};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Number of distance buckets the time limit is divided into.
+const BUCKETS_PER_TIME_LIMIT: usize = 10;
+
+/// Outcomes of generating walks to all end points within a range of distances from the origin.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureBucket {
+    /// Smallest Euclidean distance covered by the bucket, inclusive.
+    pub min_distance: f64,
+    /// Largest Euclidean distance covered by the bucket, exclusive.
+    pub max_distance: f64,
+    /// Number of end points within the bucket.
+    pub targets: usize,
+    /// Number of walks that were attempted.
+    pub samples: usize,
+    /// Number of attempts that failed with [`WalkerError::InconsistentPath`].
+    pub inconsistent_path: usize,
+    /// Number of attempts that failed with [`WalkerError::NoPathExists`].
+    pub no_path_exists: usize,
+    /// Number of attempts that failed with any other error.
+    pub other: usize,
+}
+
+#[pymethods]
+impl FailureBucket {
+    /// Returns the fraction of attempts that failed for any reason.
+    pub fn failure_rate(&self) -> f64 {
+        self.rate(self.inconsistent_path + self.no_path_exists + self.other)
+    }
+
+    pub fn inconsistent_path_rate(&self) -> f64 {
+        self.rate(self.inconsistent_path)
+    }
+
+    pub fn no_path_exists_rate(&self) -> f64 {
+        self.rate(self.no_path_exists)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "FailureBucket(distance={}..{}, samples={}, failure_rate={:.3})",
+            self.min_distance,
+            self.max_distance,
+            self.samples,
+            self.failure_rate()
+        )
+    }
+}
+
+impl FailureBucket {
+    fn rate(&self, count: usize) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+
+        count as f64 / self.samples as f64
+    }
+}
+
+/// Failure rates of a walker for end points at different distances from the origin.
+///
+/// Created using [`failure_rate()`].
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureReport {
+    /// Number of time steps of each attempted walk, i.e. the time limit of the dynamic program.
+    pub time_steps: usize,
+    /// Width of each distance bucket.
+    pub bucket_width: f64,
+    /// All buckets containing at least one end point, ordered by distance.
+    pub buckets: Vec<FailureBucket>,
+}
+
+#[pymethods]
+impl FailureReport {
+    #[staticmethod]
+    #[pyo3(name = "measure")]
+    pub fn py_measure(
+        dp: DynamicProgramPool,
+        walker: WalkerType,
+        targets: Vec<XYPoint>,
+        n: usize,
+    ) -> anyhow::Result<Self> {
+        let walker: Box<dyn Walker> = walker.into();
+
+        failure_rate(&dp, walker.as_ref(), &targets, n)
+    }
+
+    /// Returns the fraction of all attempts that failed for any reason.
+    pub fn failure_rate(&self) -> f64 {
+        let samples: usize = self.buckets.iter().map(|bucket| bucket.samples).sum();
+        let failures: f64 = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.failure_rate() * bucket.samples as f64)
+            .sum();
+
+        if samples == 0 {
+            return 0.0;
+        }
+
+        failures / samples as f64
+    }
+
+    /// Renders the report as JSON.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "FailureReport(time_steps={}, buckets={}, failure_rate={:.3})",
+            self.time_steps,
+            self.buckets.len(),
+            self.failure_rate()
+        )
+    }
+}
+
+/// Generates `n` walks to each of the `targets` and reports how often generation fails.
+///
+/// Each walk has as many time steps as the time limit of `dp`. End points are grouped into buckets
+/// by their Euclidean distance from the origin, each spanning a tenth of the time limit. End
+/// points outside of the dynamic program's table count as [`WalkerError::NoPathExists`].
+///
+/// # Errors
+///
+/// Returns an error if `targets` is empty, `n` is zero or `dp` contains no dynamic program.
+pub fn failure_rate(
+    dp: &DynamicProgramPool,
+    walker: &dyn Walker,
+    targets: &[XYPoint],
+    n: usize,
+) -> anyhow::Result<FailureReport> {
+    if targets.is_empty() {
+        bail!("at least one target is required");
+    }
+
+    if n == 0 {
+        bail!("at least one sample per target is required");
+    }
+
+    let time_steps = dp
+        .programs()
+        .first()
+        .context("no dynamic program given")?
+        .time_limit;
+    let bucket_width = (time_steps as f64 / BUCKETS_PER_TIME_LIMIT as f64).max(1.0);
+    let mut buckets: BTreeMap<usize, FailureBucket> = BTreeMap::new();
+
+    for target in targets {
+        let distance = ((target.x.pow(2) + target.y.pow(2)) as f64).sqrt();
+        let index = (distance / bucket_width) as usize;
+        let bucket = buckets.entry(index).or_insert_with(|| FailureBucket {
+            min_distance: index as f64 * bucket_width,
+            max_distance: (index + 1) as f64 * bucket_width,
+            targets: 0,
+            samples: 0,
+            inconsistent_path: 0,
+            no_path_exists: 0,
+            other: 0,
+        });
+        let (x, y) = (target.x as isize, target.y as isize);
+
+        bucket.targets += 1;
+        bucket.samples += n;
+
+        if !dp.contains(x, y) {
+            bucket.no_path_exists += n;
+            continue;
+        }
+
+        for _ in 0..n {
+            match walker.generate_path(dp, x, y, time_steps) {
+                Ok(_) => (),
+                Err(WalkerError::InconsistentPath) => bucket.inconsistent_path += 1,
+                Err(WalkerError::NoPathExists) => bucket.no_path_exists += 1,
+                Err(_) => bucket.other += 1,
+            }
+        }
+    }
+
+    Ok(FailureReport {
+        time_steps,
+        bucket_width,
+        buckets: buckets.into_values().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::diagnostics::failure_rate;
+    use crate::walker::standard::StandardWalker;
+    use crate::xy;
+
+    #[test]
+    fn test_failure_rate() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+        let report = failure_rate(&dp, &walker, &[xy!(1, 1), xy!(15, 10), xy!(30, 0)], 5).unwrap();
+
+        assert_eq!(report.time_steps, 20);
+        assert_eq!(report.bucket_width, 2.0);
+        assert_eq!(report.buckets.len(), 3);
+        assert_eq!(report.buckets[0].min_distance, 0.0);
+        assert_eq!(report.buckets[0].failure_rate(), 0.0);
+        assert_eq!(report.buckets[1].no_path_exists_rate(), 1.0);
+        assert_eq!(report.buckets[2].samples, 5);
+        assert_eq!(report.buckets[2].no_path_exists, 5);
+        assert!(failure_rate(&dp, &walker, &[], 5).is_err());
+        assert!(failure_rate(&dp, &walker, &[xy!(0, 0)], 0).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Provides a walker simulating unconditioned random walks forward in time.
+//!
+//! Unlike all other walkers, the [`ForwardWalker`] does not require a dynamic program, as its
+//! walks are not conditioned on an end point. Starting at a given point, each step is drawn
+//! directly from the probabilities of its kernel. Such walks can be used as a null model, e.g. to
+//! compare walks interpolated between two observed points with walks moving freely.
+//!
+//! ```
+//! # use randomwalks_lib::dataset::point::XYPoint;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::forward::ForwardWalker;
+//! # use randomwalks_lib::xy;
+//! #
+//! let walker = ForwardWalker::new(Kernel::from_generator(SimpleRwGenerator::default()).unwrap());
+//! let walk = walker.generate_path(xy!(100, 50), 400).unwrap();
+//!
+//! assert_eq!(walk.len(), 401);
+//! ```
+
+use crate::dataset::point::XYPoint;
+use crate::kernel::Kernel;
+use crate::walk::Walk;
+use crate::walker::{sample_log_weights, seeded_rng, WalkerError};
+use pyo3::{pyclass, pymethods};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// A walker simulating random walks forward in time using only a kernel.
+#[pyclass]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ForwardWalker {
+    pub kernel: Kernel,
+}
+
+#[pymethods]
+impl ForwardWalker {
+    #[new]
+    pub fn new(kernel: Kernel) -> Self {
+        Self { kernel }
+    }
+
+    #[pyo3(name = "generate_path", signature = (from_point, time_steps, seed=None))]
+    pub fn py_generate_path(
+        &self,
+        from_point: XYPoint,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        self.generate_path_with_rng(from_point, time_steps, &mut *seeded_rng(seed))
+    }
+
+    #[pyo3(name = "generate_paths", signature = (from_point, qty, time_steps, seed=None))]
+    pub fn py_generate_paths(
+        &self,
+        from_point: XYPoint,
+        qty: usize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        self.generate_paths_with_rng(from_point, qty, time_steps, &mut *seeded_rng(seed))
+    }
+
+    pub fn name(&self, short: bool) -> String {
+        if short {
+            String::from("fwg")
+        } else {
+            String::from("Forward Walker")
+        }
+    }
+}
+
+impl ForwardWalker {
+    /// Simulates a random walk starting at `from` for `time_steps` steps. The walk contains one
+    /// point for each time step from 0 to `time_steps`.
+    ///
+    /// Returns [`WalkerError::InvalidConfiguration`] if the kernel contains no probabilities.
+    pub fn generate_path(&self, from: XYPoint, time_steps: usize) -> Result<Walk, WalkerError> {
+        self.generate_path_with_rng(from, time_steps, &mut rand::thread_rng())
+    }
+
+    /// Simulates a random walk like [`generate_path()`](ForwardWalker::generate_path), but draws
+    /// all random numbers from `rng`.
+    pub fn generate_path_with_rng(
+        &self,
+        from: XYPoint,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let (moves, log_probs) = self.moves()?;
+        let mut path = vec![from];
+        let mut point = from;
+
+        for _ in 0..time_steps {
+            let (mov_x, mov_y) = moves[sample_log_weights(&log_probs, rng)?];
+
+            point = point + (mov_x as i64, mov_y as i64).into();
+            path.push(point);
+        }
+
+        Ok(path.into())
+    }
+
+    /// Simulates `qty` random walks like [`generate_path()`](ForwardWalker::generate_path).
+    pub fn generate_paths(
+        &self,
+        from: XYPoint,
+        qty: usize,
+        time_steps: usize,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        self.generate_paths_with_rng(from, qty, time_steps, &mut rand::thread_rng())
+    }
+
+    /// Simulates `qty` random walks like [`generate_paths()`](ForwardWalker::generate_paths), but
+    /// draws all random numbers from `rng`.
+    pub fn generate_paths_with_rng(
+        &self,
+        from: XYPoint,
+        qty: usize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        (0..qty)
+            .map(|_| self.generate_path_with_rng(from, time_steps, rng))
+            .collect()
+    }
+
+    /// Returns all moves of the kernel together with the log-probabilities of taking them.
+    fn moves(&self) -> Result<(Vec<(isize, isize)>, Vec<f64>), WalkerError> {
+        if self.kernel.sum() <= 0.0 {
+            return Err(WalkerError::InvalidConfiguration);
+        }
+
+        let ks = (self.kernel.size() / 2) as isize;
+
+        Ok((-ks..=ks)
+            .flat_map(|x| (-ks..=ks).map(move |y| (x, y)))
+            .map(|(x, y)| ((x, y), self.kernel.at(x, y).ln()))
+            .unzip())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::kernel::biased_rw::BiasedRwGenerator;
+    use crate::kernel::{Direction, Kernel};
+    use crate::walker::forward::ForwardWalker;
+    use crate::walker::WalkerError;
+    use crate::xy;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_forward_walker() {
+        let walker = ForwardWalker::new(
+            Kernel::from_generator(BiasedRwGenerator {
+                probability: 0.5,
+                direction: Direction::East,
+            })
+            .unwrap(),
+        );
+
+        let walks = walker.generate_paths(xy!(10, -5), 20, 50).unwrap();
+
+        for walk in walks.iter() {
+            let walk: Vec<XYPoint> = walk.clone().into();
+
+            assert_eq!(walk.len(), 51);
+            assert_eq!(walk[0], xy!(10, -5));
+            assert!(walk
+                .windows(2)
+                .all(|w| (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs() <= 1));
+        }
+
+        // Walks drift into the direction of the bias on average
+        let mean_x = walks.iter().map(|walk| walk.0[50].x).sum::<i64>() as f64 / 20.0;
+
+        assert!(mean_x > 10.0);
+
+        let generate = |seed| {
+            walker
+                .generate_path_with_rng(xy!(0, 0), 30, &mut StdRng::seed_from_u64(seed))
+                .unwrap()
+        };
+
+        assert_eq!(generate(7), generate(7));
+        assert_eq!(
+            walker.generate_path(xy!(0, 0), 0).unwrap().0,
+            vec![xy!(0, 0)]
+        );
+
+        let empty = ForwardWalker::new(Kernel::try_new(3, ("Empty".into(), "e".into())).unwrap());
+
+        assert_eq!(
+            empty.generate_path(xy!(0, 0), 5).unwrap_err(),
+            WalkerError::InvalidConfiguration
+        );
+    }
+}
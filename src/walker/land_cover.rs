@@ -1,15 +1,14 @@
-use crate::dp::simple::DynamicProgram;
-use crate::dp::DynamicProgramPool;
-use crate::walker::{Walk, Walker, WalkerError};
-use num::Zero;
+use crate::dataset::point::XYPoint;
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::walker::{log_weight, sample_log_weights, seeded_rng, Walk, Walker, WalkerError};
 use pyo3::{pyclass, pymethods};
-use rand::distributions::{WeightedError, WeightedIndex};
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::kernel::Kernel;
 
 #[pyclass]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LandCoverWalker {
     pub max_step_sizes: HashMap<usize, usize>,
     pub land_cover: Vec<Vec<usize>>,
@@ -29,37 +28,105 @@ impl LandCoverWalker {
 
     // Trait function wrappers for Python
 
-    pub fn generate_path(
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
         &self,
-        dp: DynamicProgram,
+        dp: DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Walk, WalkerError> {
-        Walker::generate_path(
+        Walker::generate_path_with_rng(
             self,
-            &DynamicProgramPool::Single(dp),
+            &dp,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
-    pub fn generate_paths(
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
         &self,
-        dp: DynamicProgram,
+        dp: DynamicProgramPool,
         qty: usize,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Vec<Walk>, WalkerError> {
-        Walker::generate_paths(
+        Walker::generate_paths_with_rng(
             self,
-            &DynamicProgramPool::Single(dp),
+            &dp,
             qty,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_scored", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path_scored(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<(Walk, f64), WalkerError> {
+        Walker::generate_path_scored_with_rng(
+            self,
+            &dp,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_through", signature = (dp, from_point, waypoints, seed=None))]
+    pub fn py_generate_path_through(
+        &self,
+        dp: DynamicProgramPool,
+        from_point: XYPoint,
+        waypoints: Vec<(XYPoint, usize)>,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_through_with_rng(
+            self,
+            &dp,
+            from_point,
+            &waypoints,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(
+        name = "generate_path_between",
+        signature = (dp, from_x, from_y, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path_between(
+        &self,
+        dp: DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_between_with_rng(
+            self,
+            &dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
@@ -69,24 +136,35 @@ impl LandCoverWalker {
 }
 
 impl Walker for LandCoverWalker {
-    fn generate_path(
+    fn generate_path_with_rng(
         &self,
         dp: &DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        rng: &mut dyn RngCore,
     ) -> Result<Walk, WalkerError> {
-        let DynamicProgramPool::Single(dp) = dp else {
+        let (DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _)) = dp else {
             return Err(WalkerError::RequiresSingleDynamicProgram);
         };
 
+        self.generate_path_from_with_rng(dp, to_x, to_y, time_steps, rng)
+    }
+
+    fn generate_path_from_with_rng(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
         let mut path = Vec::new();
         let time_limit = (self.land_cover.len() / 2) as isize;
         let (mut x, mut y) = (to_x, to_y);
-        let mut rng = rand::thread_rng();
 
         // Check if any path exists leading to the given end point
-        if dp.at(to_x, to_y, time_steps).is_zero() {
+        if dp.is_zero(to_x, to_y, time_steps) {
             return Err(WalkerError::NoPathExists);
         }
 
@@ -102,20 +180,14 @@ impl Walker for LandCoverWalker {
 
             for i in x - max_step_size..=x + max_step_size {
                 for j in y - max_step_size..=y + max_step_size {
-                    let p_b = dp.at_or(i, j, t - 1, 0.0);
-                    let p_a = dp.at_or(x, y, t, 0.0);
                     let p_a_b = self.kernel.at(x - i, y - j);
 
-                    prev_probs.push((p_a_b * p_b) / p_a);
+                    prev_probs.push(log_weight(dp, (i, j), (x, y), t, p_a_b));
                     movements.push((i - x, j - y));
                 }
             }
 
-            let direction = match WeightedIndex::new(prev_probs) {
-                Ok(dist) => dist.sample(&mut rng),
-                Err(WeightedError::AllWeightsZero) => return Err(WalkerError::InconsistentPath),
-                _ => return Err(WalkerError::RandomDistributionError),
-            };
+            let direction = sample_log_weights(&prev_probs, rng)?;
             let (dx, dy) = movements[direction];
 
             x += dx;
@@ -0,0 +1,231 @@
+//! Provides region masks keeping walks out of forbidden cells.
+//!
+//! Barriers have to be known when computing a dynamic program, which is expensive for large time
+//! limits. A [`RegionMask`] instead excludes cells only when generating walks, so that a single
+//! dynamic program computed without barriers can be reused with several different exclusion
+//! zones. Using [`generate_path_masked()`](Walker::generate_path_masked), all forbidden cells
+//! are treated as unreachable, so that walkers never step onto them.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::mask::RegionMask;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(50)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! // Forbid a lake of 5x5 cells in the upper left
+//! let mut allowed = vec![vec![true; 101]; 101];
+//!
+//! for column in allowed[20..25].iter_mut() {
+//!     column[20..25].fill(false);
+//! }
+//!
+//! let mask = RegionMask::new(allowed);
+//! let walk = StandardWalker::new(kernel).generate_path_masked(&dp, &mask, 10, 5, 50);
+//! ```
+//!
+//! The mask only restricts each step to the allowed cells, while the probabilities of the
+//! dynamic program still include walks crossing forbidden cells. Walks running into a dead end,
+//! i.e. a cell from which all steps lead into forbidden cells, fail with
+//! [`InconsistentPath`](WalkerError::InconsistentPath) and can be retried.
+
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::walk::Walk;
+use crate::walker::{seeded_rng, Walker, WalkerError, WalkerType};
+use pyo3::{pyclass, pymethods};
+
+/// Cells walks are allowed to enter, indexed as `allowed[x][y]`.
+///
+/// Like the field types of a dynamic program, the outer vector spans the mask in x direction and
+/// each inner vector spans it in y direction. The mask is centered at the origin, so it matches
+/// the field types and field probabilities of dynamic programs without a bounding box. Cells
+/// outside of the mask are allowed.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegionMask {
+    x_min: isize,
+    y_min: isize,
+    allowed: Vec<Vec<bool>>,
+}
+
+#[pymethods]
+impl RegionMask {
+    #[new]
+    pub fn new(allowed: Vec<Vec<bool>>) -> Self {
+        let x_min = -((allowed.len() / 2) as isize);
+        let y_min = -((allowed.first().map_or(0, Vec::len) / 2) as isize);
+
+        Self {
+            x_min,
+            y_min,
+            allowed,
+        }
+    }
+
+    /// Creates a mask allowing all cells with a positive probability, indexed like the field
+    /// probabilities of a [`DynamicProgramBuilder`](crate::dp::builder::DynamicProgramBuilder).
+    #[staticmethod]
+    pub fn from_probabilities(probabilities: Vec<Vec<f64>>) -> Self {
+        Self::new(
+            probabilities
+                .iter()
+                .map(|column| column.iter().map(|&p| p > 0.0).collect())
+                .collect(),
+        )
+    }
+
+    /// Returns whether walks may enter `(x, y)`.
+    pub fn allows(&self, x: isize, y: isize) -> bool {
+        let (x, y) = (x - self.x_min, y - self.y_min);
+
+        if x < 0 || y < 0 {
+            return true;
+        }
+
+        self.allowed
+            .get(x as usize)
+            .and_then(|column| column.get(y as usize))
+            .copied()
+            .unwrap_or(true)
+    }
+
+    #[pyo3(
+        name = "generate_path",
+        signature = (walker, dp, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path(
+        &self,
+        walker: WalkerType,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        let walker: Box<dyn Walker> = walker.into();
+
+        walker.generate_path_masked_with_rng(
+            &dp,
+            self,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+}
+
+impl RegionMask {
+    /// Returns a view of `dp` in which all forbidden cells are unreachable.
+    pub fn apply<'a, D: DpAccess + ?Sized>(&'a self, dp: &'a D) -> Masked<'a, D> {
+        Masked { dp, mask: self }
+    }
+}
+
+/// A dynamic program in which all cells forbidden by a [`RegionMask`] are unreachable.
+pub struct Masked<'a, D: DpAccess + ?Sized> {
+    dp: &'a D,
+    mask: &'a RegionMask,
+}
+
+impl<D: DpAccess + ?Sized> DpAccess for Masked<'_, D> {
+    fn log_at_or(&self, x: isize, y: isize, t: usize) -> f64 {
+        if !self.mask.allows(x, y) {
+            return f64::NEG_INFINITY;
+        }
+
+        self.dp.log_at_or(x, y, t)
+    }
+
+    fn is_zero(&self, x: isize, y: isize, t: usize) -> bool {
+        !self.mask.allows(x, y) || self.dp.is_zero(x, y, t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::{DpAccess, DynamicProgramPool, DynamicPrograms};
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::mask::RegionMask;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::{Walker, WalkerError};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_region_mask() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(30)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        // Forbid all cells with 2 <= x <= 3 and -1 <= y <= 1
+        let mut probabilities = vec![vec![1.0; 61]; 61];
+
+        for column in probabilities[32..34].iter_mut() {
+            column[29..32].fill(0.0);
+        }
+
+        let mask = RegionMask::from_probabilities(probabilities);
+        let forbidden = |x: i64, y: i64| (2..=3).contains(&x) && (-1..=1).contains(&y);
+
+        assert!(!mask.allows(2, -1));
+        assert!(mask.allows(4, 0));
+        assert!(mask.allows(100, 100));
+
+        let DynamicProgramPool::Single(single) = &dp else {
+            unreachable!();
+        };
+
+        assert!(single.log_at_or(3, 0, 29) > f64::NEG_INFINITY);
+        assert_eq!(mask.apply(single).log_at_or(3, 0, 29), f64::NEG_INFINITY);
+
+        let walker = StandardWalker::new(kernel);
+        let walks: Vec<Vec<XYPoint>> = (0..20)
+            .filter_map(|seed| {
+                walker
+                    .generate_path_masked_with_rng(
+                        &dp,
+                        &mask,
+                        6,
+                        0,
+                        30,
+                        &mut StdRng::seed_from_u64(seed),
+                    )
+                    .ok()
+            })
+            .map(|walk| walk.into())
+            .collect();
+
+        assert!(!walks.is_empty());
+
+        for walk in walks.iter() {
+            assert!(walk.iter().all(|point| !forbidden(point.x, point.y)));
+        }
+
+        assert_eq!(
+            walker.generate_path_masked(&dp, &mask, 3, 0, 30),
+            Err(WalkerError::NoPathExists)
+        );
+    }
+}
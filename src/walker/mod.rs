@@ -1,30 +1,199 @@
 //! Provides walkers used to generate random walks by using a dynamic program.
 
+pub mod attraction;
+pub mod budget;
 pub mod correlated;
+pub mod custom;
+pub mod diagnostics;
+pub mod forward;
 pub mod land_cover;
 pub mod levy;
+pub mod mask;
 pub mod multi_step;
+pub mod result;
+pub mod scheduled;
+pub mod segmented;
 pub mod standard;
+pub mod trace;
 
-use crate::dp::DynamicProgramPool;
+use crate::dataset::point::XYPoint;
+use crate::dp::backward::{kernel_prob, BackwardTable};
+use crate::dp::simple::DynamicProgram;
+use crate::dp::{DpAccess, DynamicProgramPool};
 use crate::walk::Walk;
+use crate::walker::attraction::AttractionWalker;
+use crate::walker::budget::BudgetWalker;
 use crate::walker::correlated::CorrelatedWalker;
+use crate::walker::custom::CustomWalker;
+use crate::walker::land_cover::LandCoverWalker;
 use crate::walker::levy::LevyWalker;
+use crate::walker::mask::RegionMask;
 use crate::walker::multi_step::MultiStepWalker;
+use crate::walker::result::{BatchResult, RetryPolicy, WalkResult};
+use crate::walker::scheduled::ScheduledWalker;
 use crate::walker::standard::StandardWalker;
+use crate::walker::trace::WalkTrace;
+use dyn_clone::DynClone;
 use pyo3::exceptions::PyValueError;
-use pyo3::{pyclass, FromPyObject, PyErr};
+use pyo3::{pyclass, FromPyObject, IntoPy, PyErr, PyObject, Python};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use serde::de::DeserializeOwned;
+use std::time::Instant;
 use thiserror::Error;
 
-pub trait Walker {
+/// A walker that generates random walks using a computed dynamic program.
+///
+/// Walkers can be cloned even as trait objects, i.e. a `Box<dyn Walker>` can be duplicated, e.g.
+/// to use a separate walker in each thread.
+pub trait Walker: DynClone {
+    /// Creates a walker from a JSON configuration, e.g. read from a configuration file.
+    ///
+    /// The configuration must be an object containing the fields of the walker. Kernels are given
+    /// by their `probabilities`.
+    ///
+    /// ```
+    /// # use randomwalks_lib::walker::standard::StandardWalker;
+    /// # use randomwalks_lib::walker::Walker;
+    /// # use serde_json::json;
+    /// #
+    /// let walker = StandardWalker::configure(&json!({
+    ///     "kernel": {
+    ///         "probabilities": [[0.0, 0.2, 0.0], [0.2, 0.2, 0.2], [0.0, 0.2, 0.0]]
+    ///     }
+    /// }))
+    /// .unwrap();
+    /// ```
+    fn configure(params: &serde_json::Value) -> Result<Self, WalkerError>
+    where
+        Self: Sized + DeserializeOwned,
+    {
+        serde_json::from_value(params.clone()).map_err(|_| WalkerError::InvalidConfiguration)
+    }
+
     fn generate_path(
         &self,
         dp: &DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+    ) -> Result<Walk, WalkerError> {
+        self.generate_path_with_rng(dp, to_x, to_y, time_steps, &mut rand::thread_rng())
+    }
+
+    /// Generates a random walk like [`generate_path()`](Walker::generate_path), but draws all
+    /// random numbers from `rng`.
+    ///
+    /// Using a seeded random number generator, walks can be reproduced exactly.
+    ///
+    /// ```
+    /// # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+    /// # use randomwalks_lib::dp::DynamicPrograms;
+    /// # use randomwalks_lib::kernel::Kernel;
+    /// # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+    /// # use randomwalks_lib::walker::standard::StandardWalker;
+    /// # use randomwalks_lib::walker::Walker;
+    /// # use rand::rngs::StdRng;
+    /// # use rand::SeedableRng;
+    /// #
+    /// let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+    /// let mut dp = DynamicProgramBuilder::new()
+    ///     .simple()
+    ///     .time_limit(20)
+    ///     .kernel(kernel.clone())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// dp.compute().unwrap();
+    ///
+    /// let walker = StandardWalker::new(kernel);
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// let walk = walker
+    ///     .generate_path_with_rng(&dp, 5, 5, 20, &mut rng)
+    ///     .unwrap();
+    /// ```
+    fn generate_path_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
     ) -> Result<Walk, WalkerError>;
 
+    /// Generates a random walk like [`generate_path()`](Walker::generate_path), but reads the
+    /// probabilities from any [`DpAccess`] instead of a [`DynamicProgramPool`].
+    ///
+    /// This allows generating walks from dynamic programs independent of where their table is
+    /// stored, e.g. from a [`LazyDynamicProgram`](crate::dp::lazy::LazyDynamicProgram) reading a
+    /// saved file. Walkers requiring multiple dynamic programs return
+    /// [`WalkerError::RequiresMultipleDynamicPrograms`].
+    fn generate_path_from(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> Result<Walk, WalkerError> {
+        self.generate_path_from_with_rng(dp, to_x, to_y, time_steps, &mut rand::thread_rng())
+    }
+
+    /// Generates a random walk like [`generate_path_from()`](Walker::generate_path_from), but
+    /// draws all random numbers from `rng`.
+    fn generate_path_from_with_rng(
+        &self,
+        _dp: &dyn DpAccess,
+        _to_x: isize,
+        _to_y: isize,
+        _time_steps: usize,
+        _rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        Err(WalkerError::RequiresMultipleDynamicPrograms)
+    }
+
+    /// Generates a random walk like [`generate_path()`](Walker::generate_path) that never enters
+    /// a cell forbidden by `mask`.
+    ///
+    /// The walk is generated using [`generate_path_from()`](Walker::generate_path_from) on the
+    /// dynamic program with all forbidden cells made unreachable. Returns
+    /// [`WalkerError::RequiresSingleDynamicProgram`] for pools of multiple dynamic programs.
+    fn generate_path_masked(
+        &self,
+        dp: &DynamicProgramPool,
+        mask: &RegionMask,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> Result<Walk, WalkerError> {
+        self.generate_path_masked_with_rng(
+            dp,
+            mask,
+            to_x,
+            to_y,
+            time_steps,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Generates a random walk like [`generate_path_masked()`](Walker::generate_path_masked), but
+    /// draws all random numbers from `rng`.
+    fn generate_path_masked_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        mask: &RegionMask,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let (DynamicProgramPool::Single(single) | DynamicProgramPool::Bridge(single, _)) = dp
+        else {
+            return Err(WalkerError::RequiresSingleDynamicProgram);
+        };
+
+        self.generate_path_from_with_rng(&mask.apply(single), to_x, to_y, time_steps, rng)
+    }
+
     fn generate_paths(
         &self,
         dp: &DynamicProgramPool,
@@ -32,20 +201,554 @@ pub trait Walker {
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        self.generate_paths_with_rng(dp, qty, to_x, to_y, time_steps, &mut rand::thread_rng())
+    }
+
+    /// Generates `qty` random walks like [`generate_paths()`](Walker::generate_paths), but draws
+    /// all random numbers from `rng`.
+    fn generate_paths_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
     ) -> Result<Vec<Walk>, WalkerError> {
         let mut paths = Vec::new();
 
         for _ in 0..qty {
-            paths.push(self.generate_path(dp, to_x, to_y, time_steps)?);
+            paths.push(self.generate_path_with_rng(dp, to_x, to_y, time_steps, rng)?);
         }
 
         Ok(paths)
     }
 
+    /// Generates a random walk from `(from_x, from_y)` to `(to_x, to_y)`.
+    ///
+    /// The walks of dynamic programs start at the origin, so the end point is translated relative
+    /// to the start point before generating the walk, and the walk is translated back afterwards.
+    /// This requires the initial distribution of `dp` to be the origin.
+    ///
+    /// ```
+    /// # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+    /// # use randomwalks_lib::dp::DynamicPrograms;
+    /// # use randomwalks_lib::kernel::Kernel;
+    /// # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+    /// # use randomwalks_lib::walker::standard::StandardWalker;
+    /// # use randomwalks_lib::walker::Walker;
+    /// #
+    /// let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+    /// let mut dp = DynamicProgramBuilder::new()
+    ///     .simple()
+    ///     .time_limit(50)
+    ///     .kernel(kernel.clone())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// dp.compute().unwrap();
+    ///
+    /// let walk = StandardWalker::new(kernel)
+    ///     .generate_path_between(&dp, 1000, 500, 1010, 480, 40)
+    ///     .unwrap();
+    /// ```
+    fn generate_path_between(
+        &self,
+        dp: &DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> Result<Walk, WalkerError> {
+        self.generate_path_between_with_rng(
+            dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Generates a random walk like [`generate_path_between()`](Walker::generate_path_between),
+    /// but draws all random numbers from `rng`.
+    fn generate_path_between_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let walk =
+            self.generate_path_with_rng(dp, to_x - from_x, to_y - from_y, time_steps, rng)?;
+        let from = XYPoint::from((from_x as i64, from_y as i64));
+
+        Ok(walk.iter().map(|&point| point + from).collect())
+    }
+
+    /// Generates a random walk like [`generate_path()`](Walker::generate_path) and returns it
+    /// together with its log-likelihood under the model of the dynamic program.
+    ///
+    /// The log-likelihood is computed using [`DynamicProgram::log_likelihood()`], which allows
+    /// weighting walks in importance sampling or comparing walks generated by different walkers.
+    /// Returns [`WalkerError::RequiresSingleDynamicProgram`] for pools of multiple dynamic
+    /// programs.
+    fn generate_path_scored(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> Result<(Walk, f64), WalkerError> {
+        self.generate_path_scored_with_rng(dp, to_x, to_y, time_steps, &mut rand::thread_rng())
+    }
+
+    /// Generates a random walk like [`generate_path_scored()`](Walker::generate_path_scored), but
+    /// draws all random numbers from `rng`.
+    fn generate_path_scored_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Walk, f64), WalkerError> {
+        let (DynamicProgramPool::Single(single) | DynamicProgramPool::Bridge(single, _)) = dp
+        else {
+            return Err(WalkerError::RequiresSingleDynamicProgram);
+        };
+
+        let walk = self.generate_path_with_rng(dp, to_x, to_y, time_steps, rng)?;
+        let log_likelihood = single.log_likelihood(&walk);
+
+        Ok((walk, log_likelihood))
+    }
+
+    /// Generates a random walk starting at `from` that passes through each of the given
+    /// `waypoints` in order, each given together with the number of time steps available to reach
+    /// it from the previous one.
+    ///
+    /// The walk is chained from one conditioned segment per waypoint, each generated using
+    /// [`generate_path()`](Walker::generate_path) relative to the end of the previous segment.
+    /// This requires the walks of `dp` to start at the origin. Points shared by two consecutive
+    /// segments are only contained once. Returns the first error occurring in any segment, e.g.
+    /// [`WalkerError::NoPathExists`] if a waypoint cannot be reached within its time steps.
+    ///
+    /// ```
+    /// # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+    /// # use randomwalks_lib::dp::DynamicPrograms;
+    /// # use randomwalks_lib::dataset::point::XYPoint;
+    /// # use randomwalks_lib::kernel::Kernel;
+    /// # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+    /// # use randomwalks_lib::walker::standard::StandardWalker;
+    /// # use randomwalks_lib::walker::Walker;
+    /// # use randomwalks_lib::xy;
+    /// #
+    /// let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+    /// let mut dp = DynamicProgramBuilder::new()
+    ///     .simple()
+    ///     .time_limit(50)
+    ///     .kernel(kernel.clone())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// dp.compute().unwrap();
+    ///
+    /// let walk = StandardWalker::new(kernel)
+    ///     .generate_path_through(&dp, xy!(100, 100), &[(xy!(110, 105), 30), (xy!(90, 95), 50)])
+    ///     .unwrap();
+    /// ```
+    fn generate_path_through(
+        &self,
+        dp: &DynamicProgramPool,
+        from: XYPoint,
+        waypoints: &[(XYPoint, usize)],
+    ) -> Result<Walk, WalkerError> {
+        self.generate_path_through_with_rng(dp, from, waypoints, &mut rand::thread_rng())
+    }
+
+    /// Generates a random walk like [`generate_path_through()`](Walker::generate_path_through),
+    /// but draws all random numbers from `rng`.
+    fn generate_path_through_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        from: XYPoint,
+        waypoints: &[(XYPoint, usize)],
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let mut path = vec![from];
+
+        for &(waypoint, time_steps) in waypoints {
+            let start = *path.last().unwrap();
+            let segment = self.generate_path_between_with_rng(
+                dp,
+                start.x as isize,
+                start.y as isize,
+                waypoint.x as isize,
+                waypoint.y as isize,
+                time_steps,
+                rng,
+            )?;
+
+            path.extend(segment.iter().skip(1).copied());
+        }
+
+        Ok(path.into())
+    }
+
+    /// Generates a random walk like [`generate_path()`](Walker::generate_path), but falls back to
+    /// the nearest reachable end point if `(to_x, to_y)` cannot be reached in `time_steps`.
+    ///
+    /// The fallback end point is searched within a Euclidean distance of `tolerance` around
+    /// `(to_x, to_y)` using [`nearest_reachable()`]. Besides the walk, the substituted end point
+    /// is returned, or `None` if the original end point could be reached. This allows generating
+    /// walks for end points that lie slightly outside of the reachable area, e.g. due to rounding
+    /// when scaling a dataset.
+    fn generate_path_or_nearest(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        tolerance: usize,
+    ) -> Result<(Walk, Option<XYPoint>), WalkerError> {
+        match self.generate_path(dp, to_x, to_y, time_steps) {
+            Err(WalkerError::NoPathExists) if tolerance > 0 => {
+                let Some((x, y)) = nearest_reachable(dp, to_x, to_y, time_steps, tolerance) else {
+                    return Err(WalkerError::NoPathExists);
+                };
+
+                let walk = self.generate_path(dp, x, y, time_steps)?;
+
+                Ok((walk, Some((x as i64, y as i64).into())))
+            }
+            res => res.map(|walk| (walk, None)),
+        }
+    }
+
+    /// Generates a random walk like [`generate_path()`](Walker::generate_path), but returns a
+    /// [`WalkResult`] instead of failing.
+    ///
+    /// Attempts failing with [`WalkerError::InconsistentPath`] or
+    /// [`WalkerError::RandomDistributionError`] are retried up to `max_retries` times. Other
+    /// errors do not depend on chance and are returned immediately.
+    fn try_generate_path(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        max_retries: usize,
+    ) -> WalkResult {
+        self.try_generate_path_with_policy(
+            dp,
+            to_x,
+            to_y,
+            time_steps,
+            RetryPolicy::new(max_retries, 0),
+        )
+    }
+
+    /// Generates a random walk like [`try_generate_path()`](Walker::try_generate_path), but
+    /// retries failed attempts as specified by `policy`.
+    ///
+    /// If the policy adds time steps with each retry, the walk of a successful retry is longer
+    /// than `time_steps`. Retrying stops once the next attempt would exceed the smallest time
+    /// limit of the dynamic programs in `dp`.
+    fn try_generate_path_with_policy(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        policy: RetryPolicy,
+    ) -> WalkResult {
+        let start = Instant::now();
+        let time_limit = dp.time_limit();
+        let mut retries = 0;
+
+        loop {
+            let res = self.generate_path(dp, to_x, to_y, policy.time_steps(time_steps, retries));
+            let retry = match &res {
+                Ok(_) => false,
+                Err(error) => policy.retries_within(error, retries, time_steps, time_limit),
+            };
+
+            if retry {
+                retries += 1;
+                continue;
+            }
+
+            let (walk, error) = match res {
+                Ok(walk) => (Some(walk), None),
+                Err(error) => (None, Some(error)),
+            };
+
+            return WalkResult {
+                walk,
+                error,
+                retries,
+                duration: start.elapsed().as_secs_f64(),
+            };
+        }
+    }
+
+    /// Generates `qty` random walks using [`try_generate_path()`](Walker::try_generate_path).
+    ///
+    /// In contrast to [`generate_paths()`](Walker::generate_paths), failing walks do not abort
+    /// the batch.
+    fn try_generate_paths(
+        &self,
+        dp: &DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        max_retries: usize,
+    ) -> BatchResult {
+        let start = Instant::now();
+        let results = (0..qty)
+            .map(|_| self.try_generate_path(dp, to_x, to_y, time_steps, max_retries))
+            .collect();
+
+        BatchResult {
+            results,
+            duration: start.elapsed().as_secs_f64(),
+        }
+    }
+
+    /// Generates a random walk like [`generate_path()`](Walker::generate_path) and records the
+    /// probabilities of all candidates in each step together with the chosen one.
+    ///
+    /// The returned [`WalkTrace`] contains the walk or the error, so that the step leading to
+    /// an error can be inspected. Errors occurring before the first step, e.g.
+    /// [`WalkerError::NoPathExists`], result in a trace without any steps.
+    fn generate_path_traced(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+    ) -> WalkTrace {
+        self.generate_path_traced_with_rng(dp, to_x, to_y, time_steps, &mut rand::thread_rng())
+    }
+
+    /// Generates a random walk like [`generate_path_traced()`](Walker::generate_path_traced), but
+    /// draws all random numbers from `rng`.
+    fn generate_path_traced_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> WalkTrace {
+        WalkTrace::record(|| self.generate_path_with_rng(dp, to_x, to_y, time_steps, rng))
+    }
+
     fn name(&self, short: bool) -> String;
 }
 
-#[derive(FromPyObject)]
+/// Returns the point nearest to `(x, y)` that can be reached in time step `t`, i.e. has a non-zero
+/// probability in all given dynamic programs.
+///
+/// Only points within a Euclidean distance of `tolerance` are considered. If multiple points have
+/// the same distance, the one with the highest probability is chosen. Returns `None` if no such
+/// point exists.
+pub fn nearest_reachable(
+    dp: &DynamicProgramPool,
+    x: isize,
+    y: isize,
+    t: usize,
+    tolerance: usize,
+) -> Option<(isize, isize)> {
+    let radius = tolerance as isize;
+    let mut nearest = None;
+    let mut nearest_key = (usize::MAX, f64::INFINITY);
+
+    for i in x - radius..=x + radius {
+        for j in y - radius..=y + radius {
+            let dist = ((i - x).pow(2) + (j - y).pow(2)) as usize;
+
+            if dist > tolerance.pow(2) {
+                continue;
+            }
+
+            if dp.is_zero(i, j, t) {
+                continue;
+            }
+
+            let log_prob = dp.min_log_at(i, j, t);
+
+            if (dist, -log_prob) < nearest_key {
+                nearest = Some((i, j));
+                nearest_key = (dist, -log_prob);
+            }
+        }
+    }
+
+    nearest
+}
+
+dyn_clone::clone_trait_object!(Walker);
+
+/// Returns a random number generator seeded with `seed`, or the thread-local random number
+/// generator if no seed is given.
+pub(crate) fn seeded_rng(seed: Option<u64>) -> Box<dyn RngCore> {
+    match seed {
+        Some(seed) => Box::new(StdRng::seed_from_u64(seed)),
+        None => Box::new(rand::thread_rng()),
+    }
+}
+
+/// Returns the log-weight of stepping back from `(x, y)` in time step `t` to `(i, j)` in time
+/// step `t - 1`, where `kernel_prob` is the kernel probability of the step.
+///
+/// Weights are computed from log-probabilities, so that walkers also work with dynamic programs
+/// computed in log space, whose probabilities cannot be represented otherwise.
+pub(crate) fn log_weight<D: DpAccess + ?Sized>(
+    dp: &D,
+    (i, j): (isize, isize),
+    (x, y): (isize, isize),
+    t: usize,
+    kernel_prob: f64,
+) -> f64 {
+    dp.log_at_or(i, j, t - 1) + kernel_prob.ln() - dp.log_at_or(x, y, t)
+}
+
+/// Samples an index with probabilities proportional to the exponentials of `log_weights`.
+///
+/// Walkers only choose between a handful of candidates per step, so instead of constructing a
+/// [`WeightedIndex`](rand::distributions::WeightedIndex) each time, the index is found by
+/// counting the cumulative weights not exceeding a uniformly drawn target. This avoids any
+/// allocation and branching on the weights.
+///
+/// If a [trace](trace) is being recorded, the probabilities and the sampled index are added to it.
+pub(crate) fn sample_log_weights<R: Rng + ?Sized>(
+    log_weights: &[f64],
+    rng: &mut R,
+) -> Result<usize, WalkerError> {
+    let res = sample_index(log_weights, rng);
+
+    trace::record_step(log_weights, res.as_ref().ok().copied());
+
+    res
+}
+
+/// Samples an index like [`sample_log_weights()`] without recording it.
+fn sample_index<R: Rng + ?Sized>(log_weights: &[f64], rng: &mut R) -> Result<usize, WalkerError> {
+    let max = log_weights
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    if max == f64::NEG_INFINITY {
+        return Err(WalkerError::InconsistentPath);
+    }
+
+    // Shifting by the maximum keeps the largest weight at one and thus avoids underflow
+    let weights = log_weights.iter().map(|weight| (weight - max).exp());
+    let total: f64 = weights.clone().sum();
+
+    if !total.is_finite() {
+        return Err(WalkerError::RandomDistributionError);
+    }
+
+    let target = rng.gen::<f64>() * total;
+    let mut cumulative = 0.0;
+
+    // Candidates with a weight of zero are never chosen, as their cumulative weight equals the
+    // one of their predecessor
+    let index: usize = weights
+        .map(|weight| {
+            cumulative += weight;
+            (cumulative <= target) as usize
+        })
+        .sum();
+
+    if index < log_weights.len() {
+        return Ok(index);
+    }
+
+    // Rounding may let the target reach the total weight, so fall back to the last candidate
+    // with a positive weight
+    log_weights
+        .iter()
+        .rposition(|&weight| weight > f64::NEG_INFINITY)
+        .ok_or(WalkerError::RandomDistributionError)
+}
+
+/// Samples a walk from the initial distribution of `dp` to the end point of `backward`, containing
+/// one point for each time step up to and including the one the end point is reached in.
+///
+/// In contrast to walkers stepping back from the end point, the walk is sampled forward in time.
+/// The start point is chosen proportional to its initial probability and each following point
+/// proportional to the kernel probability of the step times the probability of reaching the end
+/// point from there, as given by `backward`. This yields an exact sample of all walks between
+/// both points, even for biased kernels.
+pub(crate) fn sample_bridge(
+    dp: &DynamicProgram,
+    backward: &BackwardTable,
+    rng: &mut dyn RngCore,
+) -> Result<Walk, WalkerError> {
+    let start_weights = dp
+        .initial
+        .iter()
+        .map(|&((x, y), weight)| weight.ln() + backward.log_at_or(x, y, 0))
+        .collect::<Vec<_>>();
+
+    // Check if any path exists leading to the end point
+    if start_weights
+        .iter()
+        .all(|&weight| weight == f64::NEG_INFINITY)
+    {
+        return Err(WalkerError::NoPathExists);
+    }
+
+    let ((mut x, mut y), _) = dp.initial[sample_log_weights(&start_weights, rng)?];
+    let mut path = vec![(x as i64, y as i64).into()];
+
+    for t in 1..=backward.time_steps {
+        // Walks reaching an absorbing cell stay there
+        if !dp.absorbing.contains(&(x, y)) {
+            let kernels = dp.kernels_at(t);
+            let radius = kernels.iter().map(|k| k.size() / 2).max().unwrap_or(0) as isize;
+            let diffusivity = dp.diffusivity_at(x, y);
+            let mut log_weights = Vec::new();
+            let mut movements = Vec::new();
+
+            for i in x - radius..=x + radius {
+                for j in y - radius..=y + radius {
+                    if !dp.domain.contains(i, j) {
+                        continue;
+                    }
+
+                    let (ii, ji) = dp.domain.index(i, j);
+                    let kernel = &kernels[dp.field_types[ii][ji]];
+                    let p_a_b = kernel_prob(kernel, i - x, j - y, diffusivity)
+                        * dp.field_probability_at(i, j);
+
+                    log_weights.push(p_a_b.ln() + backward.log_at_or(i, j, t));
+                    movements.push((i, j));
+                }
+            }
+
+            (x, y) = movements[sample_log_weights(&log_weights, rng)?];
+        }
+
+        path.push((x as i64, y as i64).into());
+    }
+
+    Ok(path.into())
+}
+
+#[derive(FromPyObject, Clone)]
 pub enum WalkerType {
     #[pyo3(transparent)]
     Standard(StandardWalker),
@@ -55,10 +758,52 @@ pub enum WalkerType {
     MultiStep(MultiStepWalker),
     #[pyo3(transparent)]
     Levy(LevyWalker),
+    #[pyo3(transparent)]
+    LandCover(LandCoverWalker),
+    #[pyo3(transparent)]
+    Scheduled(ScheduledWalker),
+    #[pyo3(transparent)]
+    Attraction(AttractionWalker),
+    #[pyo3(transparent)]
+    Budget(BudgetWalker),
+    #[pyo3(transparent)]
+    Custom(CustomWalker),
+}
+
+impl WalkerType {
+    /// Returns the walker held by this variant.
+    pub fn as_walker(&self) -> &dyn Walker {
+        match self {
+            WalkerType::Standard(walker) => walker,
+            WalkerType::Correlated(walker) => walker,
+            WalkerType::MultiStep(walker) => walker,
+            WalkerType::Levy(walker) => walker,
+            WalkerType::LandCover(walker) => walker,
+            WalkerType::Scheduled(walker) => walker,
+            WalkerType::Attraction(walker) => walker,
+            WalkerType::Budget(walker) => walker,
+            WalkerType::Custom(walker) => walker,
+        }
+    }
+}
+
+impl From<WalkerType> for Box<dyn Walker> {
+    fn from(value: WalkerType) -> Self {
+        match value {
+            WalkerType::Standard(walker) => Box::new(walker),
+            WalkerType::Correlated(walker) => Box::new(walker),
+            WalkerType::MultiStep(walker) => Box::new(walker),
+            WalkerType::Levy(walker) => Box::new(walker),
+            WalkerType::LandCover(walker) => Box::new(walker),
+            WalkerType::Scheduled(walker) => Box::new(walker),
+            WalkerType::Attraction(walker) => Box::new(walker),
+            WalkerType::Budget(walker) => Box::new(walker),
+            WalkerType::Custom(walker) => Box::new(walker),
+        }
+    }
 }
 
-#[pyclass]
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum WalkerError {
     #[error("the walker requires a single dynamic program but multiple were given")]
     RequiresSingleDynamicProgram,
@@ -74,6 +819,13 @@ pub enum WalkerError {
 
     #[error("error while computing random distribution")]
     RandomDistributionError,
+
+    #[error("invalid walker configuration")]
+    InvalidConfiguration,
+
+    /// A [`CustomWalker`] raised a Python exception, given by its traceback, type and message.
+    #[error("{0}")]
+    PythonException(String),
 }
 
 impl From<WalkerError> for PyErr {
@@ -81,3 +833,390 @@ impl From<WalkerError> for PyErr {
         PyValueError::new_err(value.to_string())
     }
 }
+
+/// The kind of a [`WalkerError`], exposed to Python as `WalkerError`.
+///
+/// Python enums cannot hold values, so the message of [`WalkerError::PythonException`] is only
+/// available from Python using `error_message()` of the corresponding result.
+#[pyclass(name = "WalkerError")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalkerErrorKind {
+    RequiresSingleDynamicProgram,
+    RequiresMultipleDynamicPrograms,
+    NoPathExists,
+    InconsistentPath,
+    RandomDistributionError,
+    InvalidConfiguration,
+    PythonException,
+}
+
+impl From<&WalkerError> for WalkerErrorKind {
+    fn from(value: &WalkerError) -> Self {
+        match value {
+            WalkerError::RequiresSingleDynamicProgram => Self::RequiresSingleDynamicProgram,
+            WalkerError::RequiresMultipleDynamicPrograms => Self::RequiresMultipleDynamicPrograms,
+            WalkerError::NoPathExists => Self::NoPathExists,
+            WalkerError::InconsistentPath => Self::InconsistentPath,
+            WalkerError::RandomDistributionError => Self::RandomDistributionError,
+            WalkerError::InvalidConfiguration => Self::InvalidConfiguration,
+            WalkerError::PythonException(_) => Self::PythonException,
+        }
+    }
+}
+
+impl IntoPy<PyObject> for WalkerError {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        WalkerErrorKind::from(&self).into_py(py)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::{DynamicProgramPool, DynamicPrograms};
+    use crate::kernel::biased_rw::BiasedRwGenerator;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::{Direction, Kernel};
+    use crate::walker::levy::LevyWalker;
+    use crate::walker::scheduled::ScheduledWalker;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::{sample_log_weights, Walker, WalkerError};
+    use crate::xy;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use serde_json::json;
+
+    #[test]
+    fn test_sample_log_weights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let log_weights = [f64::NEG_INFINITY, 0.0, 2.0_f64.ln(), f64::NEG_INFINITY];
+        let mut counts = [0; 4];
+
+        for _ in 0..3000 {
+            counts[sample_log_weights(&log_weights, &mut rng).unwrap()] += 1;
+        }
+
+        assert_eq!(counts[0], 0);
+        assert_eq!(counts[3], 0);
+        assert!((counts[2] as f64 / counts[1] as f64 - 2.0).abs() < 0.3);
+        assert_eq!(
+            sample_log_weights(&[f64::NEG_INFINITY; 5], &mut rng),
+            Err(WalkerError::InconsistentPath)
+        );
+    }
+
+    #[test]
+    fn test_generate_path_with_rng() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(30)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+        let generate = |seed| {
+            walker
+                .generate_paths_with_rng(&dp, 5, 4, -3, 30, &mut StdRng::seed_from_u64(seed))
+                .unwrap()
+                .into_iter()
+                .map(Vec::<XYPoint>::from)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(generate(1), generate(1));
+        assert_ne!(generate(1), generate(2));
+
+        // A single walk uses the same random numbers as the first walk of a batch
+        let walk = walker
+            .generate_path_with_rng(&dp, 4, -3, 30, &mut StdRng::seed_from_u64(1))
+            .unwrap();
+
+        assert_eq!(Vec::<XYPoint>::from(walk), generate(1)[0]);
+    }
+
+    #[test]
+    fn test_generate_path_diagonal() {
+        let mut kernel = Kernel::try_new(3, ("Diagonal".into(), "diag".into())).unwrap();
+
+        for (x, y) in [(-1, -1), (1, -1), (1, 1), (-1, 1)] {
+            kernel.set(x, y, 0.25);
+        }
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walk: Vec<XYPoint> = StandardWalker::new(kernel)
+            .generate_path(&dp, 4, 2, 10)
+            .unwrap()
+            .into();
+
+        assert_eq!(walk.len(), 10);
+        assert_eq!(*walk.last().unwrap(), xy!(4, 2));
+        assert!(walk
+            .windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() == 1 && (w[1].y - w[0].y).abs() == 1));
+    }
+
+    #[test]
+    fn test_scheduled_walker() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut diagonal = Kernel::try_new(3, ("Diagonal".into(), "diag".into())).unwrap();
+
+        for (x, y) in [(-1, -1), (1, -1), (1, 1), (-1, 1)] {
+            diagonal.set(x, y, 0.25);
+        }
+
+        let schedule = vec![(0..10, diagonal)];
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(30)
+            .kernel(kernel.clone())
+            .kernel_schedule(schedule.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walk: Vec<XYPoint> = ScheduledWalker::new(kernel.clone(), schedule)
+            .unwrap()
+            .generate_path(&dp, 5, 3, 30)
+            .unwrap()
+            .into();
+
+        // Steps ending in the first 10 time steps are diagonal, all later ones are not
+        assert_eq!(*walk.last().unwrap(), xy!(5, 3));
+        assert!(walk[..10]
+            .windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() == 1 && (w[1].y - w[0].y).abs() == 1));
+        assert!(walk[9..]
+            .windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs() <= 1));
+
+        // Walkers ignoring the schedule cannot step back through the diagonal phase
+        assert_eq!(
+            StandardWalker::new(kernel.clone())
+                .generate_path(&dp, 5, 3, 30)
+                .unwrap_err(),
+            WalkerError::InconsistentPath
+        );
+        assert!(
+            ScheduledWalker::new(kernel.clone(), vec![(0..5, kernel.clone()), (3..8, kernel)])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_generate_path_between() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+        let walk: Vec<XYPoint> = walker
+            .generate_path_between(&dp, 100, -50, 103, -52, 20)
+            .unwrap()
+            .into();
+
+        assert_eq!(walk.len(), 20);
+        assert_eq!(walk[0], xy!(100, -50));
+        assert_eq!(walk[19], xy!(103, -52));
+        assert_eq!(
+            walker
+                .generate_path_between(&dp, 100, -50, 130, -50, 20)
+                .unwrap_err(),
+            WalkerError::NoPathExists
+        );
+    }
+
+    #[test]
+    fn test_generate_path_through() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+        let waypoints = [(xy!(55, 48), 15), (xy!(45, 45), 20), (xy!(45, 45), 5)];
+        let walk: Vec<XYPoint> = walker
+            .generate_path_through(&dp, xy!(50, 50), &waypoints)
+            .unwrap()
+            .into();
+
+        assert_eq!(walk[0], xy!(50, 50));
+        assert_eq!(*walk.last().unwrap(), xy!(45, 45));
+
+        // The walk passes through the waypoints in order without jumps
+        let first = walk.iter().position(|&p| p == xy!(55, 48)).unwrap();
+
+        assert!(walk[first..].contains(&xy!(45, 45)));
+        assert!(walk
+            .windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs() <= 1));
+
+        assert_eq!(
+            walker
+                .generate_path_through(&dp, xy!(0, 0), &[(xy!(5, 0), 10), (xy!(15, 0), 5)])
+                .unwrap_err(),
+            WalkerError::NoPathExists
+        );
+        assert_eq!(
+            Vec::<XYPoint>::from(walker.generate_path_through(&dp, xy!(1, 2), &[]).unwrap()),
+            vec![xy!(1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_generate_path_scored() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+        let (walk, log_likelihood) = walker.generate_path_scored(&dp, 3, -2, 20).unwrap();
+        let DynamicProgramPool::Single(single) = &dp else {
+            unreachable!();
+        };
+
+        // Every step of the simple random walk has a probability of 0.2
+        assert!((log_likelihood - (walk.len() - 1) as f64 * 0.2_f64.ln()).abs() < 1e-9);
+        assert_eq!(log_likelihood, single.log_likelihood(&walk));
+        assert_eq!(
+            walker
+                .generate_path_scored(&DynamicProgramPool::Multiple(Vec::new()), 3, -2, 20)
+                .unwrap_err(),
+            WalkerError::RequiresSingleDynamicProgram
+        );
+    }
+
+    #[test]
+    fn test_sample_bridge() {
+        let kernel = Kernel::from_generator(BiasedRwGenerator {
+            probability: 0.5,
+            direction: Direction::East,
+        })
+        .unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let DynamicProgramPool::Single(dp) = dp else {
+            unreachable!();
+        };
+
+        let dp = DynamicProgramPool::bridge(dp, -2, 1, 6).unwrap();
+        let backward = dp.backward().unwrap();
+        let walker = StandardWalker::new(kernel.clone());
+        let mut west = 0;
+
+        for _ in 0..2000 {
+            let walk: Vec<XYPoint> = walker.generate_path(&dp, -2, 1, 6).unwrap().into();
+
+            assert_eq!(walk.len(), 7);
+            assert_eq!(walk[0], xy!(0, 0));
+            assert_eq!(walk[6], xy!(-2, 1));
+
+            if walk[1] == xy!(-1, 0) {
+                west += 1;
+            }
+        }
+
+        // The first step follows the exact conditional distribution of the bridge
+        let expected = kernel.at(-1, 0) * backward.at_or(-1, 0, 1) / backward.at_or(0, 0, 0);
+
+        assert!((west as f64 / 2000.0 - expected).abs() < 0.05);
+
+        // Walks to other end points are generated by stepping back from them
+        assert_eq!(
+            walker.generate_path(&dp, 8, 0, 6).unwrap_err(),
+            WalkerError::NoPathExists
+        );
+    }
+
+    #[test]
+    fn test_walker_clone() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker: Box<dyn Walker> = Box::new(LevyWalker::new(0.2, 3, kernel));
+        let cloned = walker.clone();
+        let generate = |walker: &dyn Walker| {
+            walker
+                .generate_path_with_rng(&dp, 3, -2, 20, &mut StdRng::seed_from_u64(3))
+                .unwrap()
+        };
+
+        assert_eq!(cloned.name(false), walker.name(false));
+        assert_eq!(generate(cloned.as_ref()), generate(walker.as_ref()));
+    }
+
+    #[test]
+    fn test_walker_configure() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let walker = LevyWalker::new(0.2, 3, kernel.clone());
+        let configured = LevyWalker::configure(&serde_json::to_value(&walker).unwrap()).unwrap();
+
+        assert_eq!(configured.jump_probability, 0.2);
+        assert_eq!(configured.jump_distance, 3);
+        assert_eq!(configured.kernel, kernel);
+
+        let configure = |probabilities| {
+            StandardWalker::configure(&json!({ "kernel": { "probabilities": probabilities } }))
+        };
+
+        assert!(configure(json!([[0.0, 0.2, 0.0], [0.2, 0.2, 0.2], [0.0, 0.2, 0.0]])).is_ok());
+
+        // Negative, unnormalized and non-square kernels and kernels of even size are rejected
+        for probabilities in [
+            json!([[0.0, -0.2, 0.0], [0.2, 0.6, 0.2], [0.0, 0.2, 0.0]]),
+            json!([[0.0, 0.5, 0.0], [0.2, 0.2, 0.2], [0.0, 0.2, 0.0]]),
+            json!([[0.0, 0.2, 0.0], [0.2, 0.2, 0.2], [0.2, 0.2]]),
+            json!([[0.25, 0.25], [0.25, 0.25]]),
+            json!([[null, 0.2, 0.0], [0.2, 0.2, 0.2], [0.0, 0.2, 0.0]]),
+        ] {
+            assert_eq!(
+                configure(probabilities).err(),
+                Some(WalkerError::InvalidConfiguration)
+            );
+        }
+    }
+}
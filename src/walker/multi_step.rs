@@ -1,62 +1,178 @@
-use crate::dp::simple::DynamicProgram;
-use crate::dp::DynamicProgramPool;
-use crate::walker::{Walk, Walker, WalkerError};
-use num::Zero;
+use crate::dataset::point::XYPoint;
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::walker::{log_weight, sample_log_weights, seeded_rng, Walk, Walker, WalkerError};
 use pyo3::{pyclass, pymethods};
-use rand::distributions::{WeightedError, WeightedIndex};
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::kernel::Kernel;
 
+/// A penalty on the length of steps, applied on top of the kernel probabilities.
+///
+/// Long jumps can be discouraged this way without recomputing the dynamic program with a new
+/// kernel. The probability of each step is multiplied by a factor depending on its Euclidean
+/// length `d` and all probabilities of a time step are normalized afterwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum JumpPenalty {
+    /// All steps keep their kernel probabilities.
+    #[default]
+    None,
+    /// Multiplies the probability of each step by `exp(-rate * d)`.
+    Exponential(f64),
+    /// Multiplies the probability of each step by `(1 + d)^-exponent`.
+    Power(f64),
+}
+
+impl JumpPenalty {
+    /// Returns the factor the probability of moving by `(dx, dy)` is multiplied by.
+    pub fn factor(&self, dx: isize, dy: isize) -> f64 {
+        let length = (dx as f64).hypot(dy as f64);
+
+        match self {
+            JumpPenalty::None => 1.0,
+            JumpPenalty::Exponential(rate) => (-rate * length).exp(),
+            JumpPenalty::Power(exponent) => (1.0 + length).powf(-exponent),
+        }
+    }
+
+    /// Returns whether the penalty never favors longer steps over shorter ones.
+    fn is_valid(&self) -> bool {
+        match self {
+            JumpPenalty::None => true,
+            JumpPenalty::Exponential(strength) | JumpPenalty::Power(strength) => *strength >= 0.0,
+        }
+    }
+}
+
 #[pyclass]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MultiStepWalker {
     pub max_step_size: usize,
     pub kernel: Kernel,
+    #[serde(default)]
+    pub jump_penalty: JumpPenalty,
 }
 
 #[pymethods]
 impl MultiStepWalker {
     #[new]
-    pub fn new(max_step_size: usize, kernel: Kernel) -> Self {
-        Self {
-            max_step_size,
-            kernel,
-        }
+    #[pyo3(signature = (max_step_size, kernel, jump_penalty=None, penalty_strength=1.0))]
+    pub fn py_new(
+        max_step_size: usize,
+        kernel: Kernel,
+        jump_penalty: Option<&str>,
+        penalty_strength: f64,
+    ) -> Result<Self, WalkerError> {
+        let jump_penalty = match jump_penalty {
+            None => JumpPenalty::None,
+            Some("exponential") => JumpPenalty::Exponential(penalty_strength),
+            Some("power") => JumpPenalty::Power(penalty_strength),
+            Some(_) => return Err(WalkerError::InvalidConfiguration),
+        };
+
+        Ok(Self::new(max_step_size, kernel).with_jump_penalty(jump_penalty))
     }
 
     // Trait function wrappers for Python
 
-    pub fn generate_path(
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
         &self,
-        dp: DynamicProgram,
+        dp: DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Walk, WalkerError> {
-        Walker::generate_path(
+        Walker::generate_path_with_rng(
             self,
-            &DynamicProgramPool::Single(dp),
+            &dp,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
-    pub fn generate_paths(
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
         &self,
-        dp: DynamicProgram,
+        dp: DynamicProgramPool,
         qty: usize,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Vec<Walk>, WalkerError> {
-        Walker::generate_paths(
+        Walker::generate_paths_with_rng(
             self,
-            &DynamicProgramPool::Single(dp),
+            &dp,
             qty,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_scored", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path_scored(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<(Walk, f64), WalkerError> {
+        Walker::generate_path_scored_with_rng(
+            self,
+            &dp,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_through", signature = (dp, from_point, waypoints, seed=None))]
+    pub fn py_generate_path_through(
+        &self,
+        dp: DynamicProgramPool,
+        from_point: XYPoint,
+        waypoints: Vec<(XYPoint, usize)>,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_through_with_rng(
+            self,
+            &dp,
+            from_point,
+            &waypoints,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(
+        name = "generate_path_between",
+        signature = (dp, from_x, from_y, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path_between(
+        &self,
+        dp: DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_between_with_rng(
+            self,
+            &dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
@@ -65,25 +181,58 @@ impl MultiStepWalker {
     }
 }
 
+impl MultiStepWalker {
+    pub fn new(max_step_size: usize, kernel: Kernel) -> Self {
+        Self {
+            max_step_size,
+            kernel,
+            jump_penalty: JumpPenalty::None,
+        }
+    }
+
+    /// Sets the penalty applied to the length of each step.
+    pub fn with_jump_penalty(mut self, jump_penalty: JumpPenalty) -> Self {
+        self.jump_penalty = jump_penalty;
+
+        self
+    }
+}
+
 impl Walker for MultiStepWalker {
-    fn generate_path(
+    fn generate_path_with_rng(
         &self,
         dp: &DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        rng: &mut dyn RngCore,
     ) -> Result<Walk, WalkerError> {
-        let DynamicProgramPool::Single(dp) = dp else {
+        let (DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _)) = dp else {
             return Err(WalkerError::RequiresSingleDynamicProgram);
         };
+
+        self.generate_path_from_with_rng(dp, to_x, to_y, time_steps, rng)
+    }
+
+    fn generate_path_from_with_rng(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
         let max_step_size = self.max_step_size as isize;
 
+        if !self.jump_penalty.is_valid() {
+            return Err(WalkerError::InvalidConfiguration);
+        }
+
         let mut path = Vec::new();
         let (mut x, mut y) = (to_x, to_y);
-        let mut rng = rand::thread_rng();
 
         // Check if any path exists leading to the given end point
-        if dp.at(to_x, to_y, time_steps).is_zero() {
+        if dp.is_zero(to_x, to_y, time_steps) {
             return Err(WalkerError::NoPathExists);
         }
 
@@ -95,22 +244,20 @@ impl Walker for MultiStepWalker {
 
             for i in x - max_step_size..=x + max_step_size {
                 for j in y - max_step_size..=y + max_step_size {
-                    let p_b = dp.at_or(i, j, t - 1, 0.0);
-                    let p_a = dp.at_or(x, y, t, 0.0);
-                    let p_a_b = self.kernel.at(i - x, j - y);
+                    let (dx, dy) = (i - x, j - y);
+                    let p_a_b = self.kernel.at(dx, dy) * self.jump_penalty.factor(dx, dy);
 
-                    prev_probs.push((p_a_b * p_b) / p_a);
+                    prev_probs.push(log_weight(dp, (i, j), (x, y), t, p_a_b));
                     movements.push((i - x, j - y));
                 }
             }
 
-            let direction = match WeightedIndex::new(prev_probs) {
-                Ok(dist) => dist.sample(&mut rng),
-                Err(WeightedError::AllWeightsZero) => {
+            let direction = match sample_log_weights(&prev_probs, rng) {
+                Err(WalkerError::InconsistentPath) => {
                     eprintln!("time step: {t}, x: {x}, y: {y}");
                     return Err(WalkerError::InconsistentPath)
                 },
-                _ => return Err(WalkerError::RandomDistributionError),
+                res => res?,
             };
             let (dx, dy) = movements[direction];
 
@@ -132,3 +279,68 @@ impl Walker for MultiStepWalker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::Kernel;
+    use crate::walker::multi_step::{JumpPenalty, MultiStepWalker};
+    use crate::walker::Walker;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_jump_penalty() {
+        assert_eq!(JumpPenalty::None.factor(3, 4), 1.0);
+        assert!((JumpPenalty::Exponential(0.5).factor(3, 4) - (-2.5_f64).exp()).abs() < 1e-12);
+        assert!((JumpPenalty::Power(2.0).factor(3, 4) - 1.0 / 36.0).abs() < 1e-12);
+
+        let mut kernel = Kernel::try_new(5, ("Uniform".into(), "u".into())).unwrap();
+
+        for x in -2..=2 {
+            for y in -2..=2 {
+                kernel.set(x, y, 1.0 / 25.0);
+            }
+        }
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        // Strongly penalized walkers make shorter steps on average
+        let mean_step_length = |walker: MultiStepWalker| {
+            let lengths: Vec<f64> = (0..10)
+                .flat_map(|seed| {
+                    let walk: Vec<XYPoint> = walker
+                        .generate_path_with_rng(&dp, 10, 5, 20, &mut StdRng::seed_from_u64(seed))
+                        .unwrap()
+                        .into();
+
+                    walk.windows(2)
+                        .map(|w| ((w[1].x - w[0].x) as f64).hypot((w[1].y - w[0].y) as f64))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            lengths.iter().sum::<f64>() / lengths.len() as f64
+        };
+
+        let walker = MultiStepWalker::new(2, kernel);
+        let penalized = walker
+            .clone()
+            .with_jump_penalty(JumpPenalty::Exponential(3.0));
+
+        assert!(mean_step_length(penalized) < mean_step_length(walker.clone()));
+        assert!(walker
+            .with_jump_penalty(JumpPenalty::Power(-1.0))
+            .generate_path(&dp, 10, 5, 20)
+            .is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! Provides result types describing the outcome of generating walks.
+//!
+//! [`generate_path()`](Walker::generate_path) and [`generate_paths()`](Walker::generate_paths)
+//! return on the first error, which in Python raises an exception and discards all walks
+//! generated so far. Using [`try_generate_path()`](Walker::try_generate_path) and
+//! [`try_generate_paths()`](Walker::try_generate_paths) instead, each attempt results in a
+//! [`WalkResult`] containing either the walk or the error, together with the time it took and the
+//! number of retries. Multiple results are collected in a [`BatchResult`], so that partial
+//! successes can be analyzed.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let walker = StandardWalker::new(kernel);
+//! let batch = walker.try_generate_paths(&dp, 10, 20, 30, 100, 2);
+//!
+//! println!("{} of {} walks generated", batch.walks().len(), batch.results.len());
+//! ```
+//!
+//! How failed attempts are retried can be configured in more detail using a [`RetryPolicy`],
+//! e.g. to allow more time steps with each retry. Policies can also be set on a
+//! [`DatasetWalksBuilder`](crate::dataset::walks_builder::DatasetWalksBuilder).
+
+use crate::dp::DynamicProgramPool;
+use crate::walk::Walk;
+use crate::walker::{Walker, WalkerError, WalkerType};
+use pyo3::{pyclass, pymethods};
+
+/// Specifies how often and how generating a walk is retried after it failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt.
+    pub max_retries: usize,
+    /// Number of time steps added to the walk with each retry.
+    pub extra_steps: usize,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: usize, extra_steps: usize) -> Self {
+        Self {
+            max_retries,
+            extra_steps,
+        }
+    }
+
+    /// Returns whether an attempt failing with `error` after `retries` retries is retried.
+    ///
+    /// [`WalkerError::InconsistentPath`] and [`WalkerError::RandomDistributionError`] depend on
+    /// chance and are always retried. [`WalkerError::NoPathExists`] is only retried if time steps
+    /// are added with each retry, as the end point may be reachable with more time steps. Other
+    /// errors are never retried.
+    pub fn retries_on(&self, error: &WalkerError, retries: usize) -> bool {
+        retries < self.max_retries
+            && match error {
+                WalkerError::InconsistentPath | WalkerError::RandomDistributionError => true,
+                WalkerError::NoPathExists => self.extra_steps > 0,
+                _ => false,
+            }
+    }
+
+    /// Returns whether an attempt of a walk with `time_steps` time steps failing with `error` after
+    /// `retries` retries is retried like [`retries_on()`](RetryPolicy::retries_on), and the next
+    /// attempt does not exceed `time_limit` time steps.
+    pub fn retries_within(
+        &self,
+        error: &WalkerError,
+        retries: usize,
+        time_steps: usize,
+        time_limit: usize,
+    ) -> bool {
+        self.retries_on(error, retries) && self.time_steps(time_steps, retries + 1) <= time_limit
+    }
+
+    /// Returns the number of time steps of the attempt after `retries` retries.
+    pub fn time_steps(&self, time_steps: usize, retries: usize) -> usize {
+        time_steps + retries * self.extra_steps
+    }
+}
+
+/// The outcome of generating a single walk.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalkResult {
+    /// The generated walk, or `None` if generation failed.
+    pub walk: Option<Walk>,
+    /// The error of the last attempt, or `None` if generation succeeded.
+    pub error: Option<WalkerError>,
+    /// Number of failed attempts before the last attempt.
+    pub retries: usize,
+    /// Time spent on all attempts in seconds.
+    pub duration: f64,
+}
+
+#[pymethods]
+impl WalkResult {
+    #[staticmethod]
+    #[pyo3(name = "generate", signature = (walker, dp, to_x, to_y, time_steps, max_retries=0))]
+    pub fn py_generate(
+        walker: WalkerType,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        max_retries: usize,
+    ) -> Self {
+        let walker: Box<dyn Walker> = walker.into();
+
+        walker.try_generate_path(&dp, to_x, to_y, time_steps, max_retries)
+    }
+
+    /// Returns whether the walk was generated successfully.
+    pub fn is_ok(&self) -> bool {
+        self.walk.is_some()
+    }
+
+    /// Returns a description of the error, or `None` if generation succeeded.
+    pub fn error_message(&self) -> Option<String> {
+        self.error.as_ref().map(|error| error.to_string())
+    }
+
+    pub fn __repr__(&self) -> String {
+        match &self.error {
+            Some(error) => format!("WalkResult(error={:?}, retries={})", error, self.retries),
+            None => format!(
+                "WalkResult(len={}, retries={})",
+                self.walk.as_ref().map_or(0, |walk| walk.len()),
+                self.retries
+            ),
+        }
+    }
+}
+
+impl WalkResult {
+    /// Converts the result into a [`Result`], discarding timing and retry information.
+    pub fn into_result(self) -> Result<Walk, WalkerError> {
+        match (self.walk, self.error) {
+            (Some(walk), _) => Ok(walk),
+            (None, Some(error)) => Err(error),
+            (None, None) => Err(WalkerError::NoPathExists),
+        }
+    }
+}
+
+/// The outcomes of generating multiple walks.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    /// Results of all walks in the order they were generated.
+    pub results: Vec<WalkResult>,
+    /// Time spent on the whole batch in seconds.
+    pub duration: f64,
+}
+
+#[pymethods]
+impl BatchResult {
+    #[staticmethod]
+    #[pyo3(
+        name = "generate",
+        signature = (walker, dp, qty, to_x, to_y, time_steps, max_retries=0)
+    )]
+    pub fn py_generate(
+        walker: WalkerType,
+        dp: DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        max_retries: usize,
+    ) -> Self {
+        let walker: Box<dyn Walker> = walker.into();
+
+        walker.try_generate_paths(&dp, qty, to_x, to_y, time_steps, max_retries)
+    }
+
+    /// Returns all successfully generated walks.
+    pub fn walks(&self) -> Vec<Walk> {
+        self.results
+            .iter()
+            .filter_map(|result| result.walk.clone())
+            .collect()
+    }
+
+    /// Returns the errors of all failed walks together with their index in the batch.
+    pub fn errors(&self) -> Vec<(usize, WalkerError)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, result)| result.error.clone().map(|error| (i, error)))
+            .collect()
+    }
+
+    /// Returns the fraction of walks that were generated successfully.
+    pub fn success_rate(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+
+        self.walks().len() as f64 / self.results.len() as f64
+    }
+
+    /// Returns the total number of retries of all walks.
+    pub fn retries(&self) -> usize {
+        self.results.iter().map(|result| result.retries).sum()
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "BatchResult(len={}, success_rate={:.3})",
+            self.results.len(),
+            self.success_rate()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::result::RetryPolicy;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::{Walker, WalkerError};
+
+    #[test]
+    fn test_batch_result() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+        let batch = walker.try_generate_paths(&dp, 3, 2, 3, 10, 1);
+
+        assert_eq!(batch.__len__(), 3);
+        assert_eq!(batch.success_rate(), 1.0);
+        assert_eq!(batch.walks().len(), 3);
+        assert_eq!(batch.retries(), 0);
+        assert!(batch.errors().is_empty());
+
+        let result = walker.try_generate_path(&dp, 8, 8, 10, 5);
+
+        assert!(!result.is_ok());
+        assert_eq!(result.error, Some(WalkerError::NoPathExists));
+        // Unreachable end points are not retried
+        assert_eq!(result.retries, 0);
+        assert!(result.into_result().is_err());
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(10)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+
+        // The end point can only be reached with additional time steps
+        let result = walker.try_generate_path_with_policy(&dp, 5, 0, 4, RetryPolicy::new(3, 1));
+
+        assert_eq!(result.retries, 2);
+        assert_eq!(result.walk.unwrap().len(), 6);
+
+        let result = walker.try_generate_path_with_policy(&dp, 8, 8, 4, RetryPolicy::new(3, 2));
+
+        assert_eq!(result.retries, 3);
+        assert_eq!(result.error, Some(WalkerError::NoPathExists));
+
+        // Retries stop before exceeding the time limit of the dynamic program
+        let result = walker.try_generate_path_with_policy(&dp, 10, 10, 8, RetryPolicy::new(3, 2));
+
+        assert_eq!(result.retries, 1);
+        assert_eq!(result.error, Some(WalkerError::NoPathExists));
+        assert!(!RetryPolicy::new(3, 0).retries_on(&WalkerError::NoPathExists, 0));
+        assert!(!RetryPolicy::new(3, 0).retries_on(&WalkerError::InconsistentPath, 3));
+    }
+}
@@ -0,0 +1,262 @@
+//! Provides a walker switching between kernels over time.
+//!
+//! Dynamic programs computed with a [kernel schedule](crate::kernel::schedule) use different
+//! kernels in different time ranges, e.g. to model distinct behavioral phases like resting and
+//! traveling. The [`ScheduledWalker`] uses the same schedule when stepping back through the
+//! dynamic program, so that the probability of each step is computed with the kernel that was
+//! used to compute its time step.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::biased_rw::BiasedRwGenerator;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::kernel::Direction;
+//! # use randomwalks_lib::walker::scheduled::ScheduledWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let schedule = vec![(
+//!     20..50,
+//!     Kernel::from_generator(BiasedRwGenerator {
+//!         probability: 0.5,
+//!         direction: Direction::East,
+//!     })
+//!     .unwrap(),
+//! )];
+//!
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(100)
+//!     .kernel(kernel.clone())
+//!     .kernel_schedule(schedule.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let walker = ScheduledWalker::new(kernel, schedule).unwrap();
+//! let walk = walker.generate_path(&dp, 20, 5, 100).unwrap();
+//! ```
+
+use crate::dataset::point::XYPoint;
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::kernel::schedule::KernelSchedule;
+use crate::kernel::{Kernel, KernelError};
+use crate::walker::{log_weight, sample_log_weights, seeded_rng, Walk, Walker, WalkerError};
+use pyo3::{pyclass, pymethods};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// A walker using the kernel scheduled for each time step, falling back to `kernel` for all time
+/// steps without a scheduled kernel.
+#[pyclass]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScheduledWalker {
+    pub kernel: Kernel,
+    pub schedule: KernelSchedule,
+}
+
+#[pymethods]
+impl ScheduledWalker {
+    #[new]
+    #[pyo3(signature = (kernel, schedule=Vec::new()))]
+    pub fn py_new(
+        kernel: Kernel,
+        schedule: Vec<(usize, usize, Kernel)>,
+    ) -> Result<Self, KernelError> {
+        Self::new(
+            kernel,
+            schedule
+                .into_iter()
+                .map(|(start, end, kernel)| (start..end, kernel))
+                .collect(),
+        )
+    }
+
+    // Trait function wrappers for Python
+
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_with_rng(self, &dp, to_x, to_y, time_steps, &mut *seeded_rng(seed))
+    }
+
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
+        &self,
+        dp: DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        Walker::generate_paths_with_rng(
+            self,
+            &dp,
+            qty,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_scored", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path_scored(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<(Walk, f64), WalkerError> {
+        Walker::generate_path_scored_with_rng(
+            self,
+            &dp,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_through", signature = (dp, from_point, waypoints, seed=None))]
+    pub fn py_generate_path_through(
+        &self,
+        dp: DynamicProgramPool,
+        from_point: XYPoint,
+        waypoints: Vec<(XYPoint, usize)>,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_through_with_rng(
+            self,
+            &dp,
+            from_point,
+            &waypoints,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(
+        name = "generate_path_between",
+        signature = (dp, from_x, from_y, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path_between(
+        &self,
+        dp: DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_between_with_rng(
+            self,
+            &dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    pub fn name(&self, short: bool) -> String {
+        Walker::name(self, short)
+    }
+}
+
+impl ScheduledWalker {
+    /// Creates a walker using the kernels of `schedule` in their time ranges and `kernel` in all
+    /// other time steps. The time ranges must not overlap.
+    pub fn new(kernel: Kernel, schedule: Vec<(Range<usize>, Kernel)>) -> Result<Self, KernelError> {
+        Ok(Self {
+            kernel,
+            schedule: KernelSchedule::new(schedule)?,
+        })
+    }
+
+    /// Returns the kernel used for steps ending in time step `t`.
+    fn kernel_at(&self, t: usize) -> &Kernel {
+        self.schedule.kernel_at(t).unwrap_or(&self.kernel)
+    }
+}
+
+impl Walker for ScheduledWalker {
+    fn generate_path_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let (DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _)) = dp else {
+            return Err(WalkerError::RequiresSingleDynamicProgram);
+        };
+
+        self.generate_path_from_with_rng(dp, to_x, to_y, time_steps, rng)
+    }
+
+    fn generate_path_from_with_rng(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let mut path = Vec::new();
+        let (mut x, mut y) = (to_x, to_y);
+
+        // Check if any path exists leading to the given end point
+        if dp.is_zero(to_x, to_y, time_steps) {
+            return Err(WalkerError::NoPathExists);
+        }
+
+        for t in (1..time_steps).rev() {
+            path.push((x as i64, y as i64).into());
+
+            let kernel = self.kernel_at(t);
+            let ks = (kernel.size() / 2) as isize;
+            let mut prev_probs = Vec::new();
+            let mut movements = Vec::new();
+
+            for i in x - ks..=x + ks {
+                for j in y - ks..=y + ks {
+                    let p_a_b = kernel.at(x - i, y - j);
+
+                    prev_probs.push(log_weight(dp, (i, j), (x, y), t, p_a_b));
+                    movements.push((i, j));
+                }
+            }
+
+            (x, y) = movements[sample_log_weights(&prev_probs, rng)?];
+        }
+
+        path.reverse();
+        path.insert(0, (x as i64, y as i64).into());
+
+        Ok(path.into())
+    }
+
+    fn name(&self, short: bool) -> String {
+        if short {
+            String::from("scw")
+        } else {
+            String::from("Scheduled Walker")
+        }
+    }
+}
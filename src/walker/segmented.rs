@@ -0,0 +1,248 @@
+//! Provides a walker generating walks longer than the time limit of the dynamic program.
+//!
+//! A dynamic program only covers walks of up to its time limit, so walks with more time steps
+//! cannot be generated directly, while computing a dynamic program with a larger time limit
+//! quickly becomes expensive. The [`SegmentedWalker`] wraps another walker and splits such walks
+//! into segments that each fit within the time limit. The end points of the segments, called
+//! anchors, are placed evenly along the straight line from the origin to the end point, and the
+//! segments are generated and chained using
+//! [`generate_path_through()`](Walker::generate_path_through).
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::segmented::SegmentedWalker;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::walker::{Walker, WalkerType};
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(50)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let walker = SegmentedWalker::new(WalkerType::Standard(StandardWalker::new(kernel)));
+//! let walk = walker.generate_path(&dp, 80, -40, 400).unwrap();
+//! ```
+//!
+//! As the anchors are fixed, the walks are not sampled exactly from all walks between the origin
+//! and the end point, but from those passing through the anchors at the end of each segment.
+
+use crate::dataset::point::XYPoint;
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::walk::Walk;
+use crate::walker::{seeded_rng, Walker, WalkerError, WalkerType};
+use pyo3::{pyclass, pymethods};
+use rand::RngCore;
+
+/// A walker splitting walks exceeding the time limit of the dynamic program into segments, each
+/// generated using `walker`.
+#[pyclass]
+#[derive(Clone)]
+pub struct SegmentedWalker {
+    pub walker: WalkerType,
+}
+
+#[pymethods]
+impl SegmentedWalker {
+    #[new]
+    pub fn new(walker: WalkerType) -> Self {
+        Self { walker }
+    }
+
+    // Trait function wrappers for Python
+
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_with_rng(self, &dp, to_x, to_y, time_steps, &mut *seeded_rng(seed))
+    }
+
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
+        &self,
+        dp: DynamicProgramPool,
+        qty: usize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Vec<Walk>, WalkerError> {
+        Walker::generate_paths_with_rng(
+            self,
+            &dp,
+            qty,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_scored", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path_scored(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<(Walk, f64), WalkerError> {
+        Walker::generate_path_scored_with_rng(
+            self,
+            &dp,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    pub fn name(&self, short: bool) -> String {
+        Walker::name(self, short)
+    }
+}
+
+/// Returns the anchors of a walk to `(to_x, to_y)` split into segments of at most `time_limit`
+/// time steps, each together with the number of time steps of its segment.
+fn anchors(
+    to_x: isize,
+    to_y: isize,
+    time_steps: usize,
+    time_limit: usize,
+) -> Vec<(XYPoint, usize)> {
+    let segments = time_steps.div_ceil(time_limit);
+
+    (1..=segments)
+        .map(|k| {
+            let fraction = k as f64 / segments as f64;
+            let anchor = XYPoint {
+                x: (to_x as f64 * fraction).round() as i64,
+                y: (to_y as f64 * fraction).round() as i64,
+            };
+
+            // Distribute the remaining time steps over the first segments
+            let steps = time_steps / segments + usize::from(k <= time_steps % segments);
+
+            (anchor, steps)
+        })
+        .collect()
+}
+
+impl Walker for SegmentedWalker {
+    /// Generates a random walk to `(to_x, to_y)`, split into segments if `time_steps` exceeds the
+    /// smallest time limit of the dynamic programs in `dp`.
+    ///
+    /// Walks fitting within the time limit are generated by the wrapped walker directly.
+    fn generate_path_with_rng(
+        &self,
+        dp: &DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        let walker = self.walker.as_walker();
+        let time_limit = dp
+            .programs()
+            .iter()
+            .map(|dp| dp.time_limit)
+            .min()
+            .unwrap_or(0);
+
+        if time_limit == 0 {
+            return Err(WalkerError::InvalidConfiguration);
+        }
+
+        if time_steps <= time_limit {
+            return walker.generate_path_with_rng(dp, to_x, to_y, time_steps, rng);
+        }
+
+        walker.generate_path_through_with_rng(
+            dp,
+            XYPoint { x: 0, y: 0 },
+            &anchors(to_x, to_y, time_steps, time_limit),
+            rng,
+        )
+    }
+
+    /// Generates a random walk using the wrapped walker without splitting it, as the time limit
+    /// of `dp` is not known.
+    fn generate_path_from_with_rng(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
+        self.walker
+            .as_walker()
+            .generate_path_from_with_rng(dp, to_x, to_y, time_steps, rng)
+    }
+
+    fn name(&self, short: bool) -> String {
+        let name = self.walker.as_walker().name(short);
+
+        if short {
+            format!("sg-{}", name)
+        } else {
+            format!("Segmented {}", name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dataset::point::XYPoint;
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::segmented::{anchors, SegmentedWalker};
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::{Walker, WalkerType};
+    use crate::xy;
+
+    #[test]
+    fn test_segmented_walker() {
+        assert_eq!(
+            anchors(30, -10, 50, 20),
+            vec![(xy!(10, -3), 17), (xy!(20, -7), 17), (xy!(30, -10), 16)]
+        );
+
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(20)
+            .kernel(kernel.clone())
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = SegmentedWalker::new(WalkerType::Standard(StandardWalker::new(kernel)));
+        let walk: Vec<XYPoint> = walker.generate_path(&dp, 30, 10, 60).unwrap().into();
+
+        assert_eq!(walk[0], xy!(0, 0));
+        assert_eq!(*walk.last().unwrap(), xy!(30, 10));
+        assert!(walk
+            .windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() + (w[1].y - w[0].y).abs() <= 1));
+
+        // Walks within the time limit are not split
+        assert_eq!(walker.generate_path(&dp, 3, 2, 20).unwrap().len(), 20);
+    }
+}
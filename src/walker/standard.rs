@@ -1,14 +1,32 @@
-use crate::dp::simple::DynamicProgram;
-use crate::dp::DynamicProgramPool;
-use crate::walker::{Walk, Walker, WalkerError};
-use num::Zero;
+use crate::dataset::point::XYPoint;
+use crate::dp::{DpAccess, DynamicProgramPool};
+use crate::walker::{
+    log_weight, sample_bridge, sample_log_weights, seeded_rng, Walk, Walker, WalkerError,
+};
 use pyo3::{pyclass, pymethods, PyAny};
-use rand::distributions::{WeightedError, WeightedIndex};
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::kernel::Kernel;
 
+/// Moves from a point to its possible predecessors, in the order stay, west, north, east, south,
+/// followed by the diagonal moves north-west, north-east, south-east and south-west.
+///
+/// Moves the kernel assigns no probability to are never chosen, so kernels without diagonal
+/// probabilities only ever step along the axes.
+const NEIGHBORS: [(isize, isize); 9] = [
+    (0, 0),
+    (-1, 0),
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, -1),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+];
+
 #[pyclass]
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct StandardWalker {
     pub kernel: Kernel,
 }
@@ -24,37 +42,105 @@ impl StandardWalker {
 
     // Trait function wrappers for Python
 
-    pub fn generate_path(
+    #[pyo3(name = "generate_path", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path(
         &self,
-        dp: DynamicProgram,
+        dp: DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Walk, WalkerError> {
-        Walker::generate_path(
+        Walker::generate_path_with_rng(
             self,
-            &DynamicProgramPool::Single(dp),
+            &dp,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
-    pub fn generate_paths(
+    #[pyo3(name = "generate_paths", signature = (dp, qty, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_paths(
         &self,
-        dp: DynamicProgram,
+        dp: DynamicProgramPool,
         qty: usize,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        seed: Option<u64>,
     ) -> Result<Vec<Walk>, WalkerError> {
-        Walker::generate_paths(
+        Walker::generate_paths_with_rng(
             self,
-            &DynamicProgramPool::Single(dp),
+            &dp,
             qty,
             to_x,
             to_y,
             time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_scored", signature = (dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate_path_scored(
+        &self,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<(Walk, f64), WalkerError> {
+        Walker::generate_path_scored_with_rng(
+            self,
+            &dp,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(name = "generate_path_through", signature = (dp, from_point, waypoints, seed=None))]
+    pub fn py_generate_path_through(
+        &self,
+        dp: DynamicProgramPool,
+        from_point: XYPoint,
+        waypoints: Vec<(XYPoint, usize)>,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_through_with_rng(
+            self,
+            &dp,
+            from_point,
+            &waypoints,
+            &mut *seeded_rng(seed),
+        )
+    }
+
+    #[pyo3(
+        name = "generate_path_between",
+        signature = (dp, from_x, from_y, to_x, to_y, time_steps, seed=None)
+    )]
+    pub fn py_generate_path_between(
+        &self,
+        dp: DynamicProgramPool,
+        from_x: isize,
+        from_y: isize,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Result<Walk, WalkerError> {
+        Walker::generate_path_between_with_rng(
+            self,
+            &dp,
+            from_x,
+            from_y,
+            to_x,
+            to_y,
+            time_steps,
+            &mut *seeded_rng(seed),
         )
     }
 
@@ -64,62 +150,65 @@ impl StandardWalker {
 }
 
 impl Walker for StandardWalker {
-    fn generate_path(
+    /// Generates a random walk to `(to_x, to_y)` by stepping back from it.
+    ///
+    /// If `dp` holds a [`BackwardTable`](crate::dp::backward::BackwardTable) for the same end
+    /// point and number of time steps, an exact bridge is sampled forward in time instead, using
+    /// the kernels of the dynamic program. Such walks contain one point for each time step from
+    /// 0 to `time_steps`.
+    fn generate_path_with_rng(
         &self,
         dp: &DynamicProgramPool,
         to_x: isize,
         to_y: isize,
         time_steps: usize,
+        rng: &mut dyn RngCore,
     ) -> Result<Walk, WalkerError> {
-        let DynamicProgramPool::Single(dp) = dp else {
-            return Err(WalkerError::RequiresSingleDynamicProgram);
-        };
+        match dp {
+            DynamicProgramPool::Bridge(dp, backward)
+                if backward.matches(to_x, to_y, time_steps) =>
+            {
+                sample_bridge(dp, backward, rng)
+            }
+            DynamicProgramPool::Single(dp) | DynamicProgramPool::Bridge(dp, _) => {
+                self.generate_path_from_with_rng(dp, to_x, to_y, time_steps, rng)
+            }
+            DynamicProgramPool::Multiple(_) => Err(WalkerError::RequiresSingleDynamicProgram),
+        }
+    }
 
+    fn generate_path_from_with_rng(
+        &self,
+        dp: &dyn DpAccess,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        rng: &mut dyn RngCore,
+    ) -> Result<Walk, WalkerError> {
         let mut path = Vec::new();
         let (mut x, mut y) = (to_x, to_y);
-        let mut rng = rand::thread_rng();
 
         // Check if any path exists leading to the given end point
-        if dp.at(to_x, to_y, time_steps).is_zero() {
+        if dp.is_zero(to_x, to_y, time_steps) {
             return Err(WalkerError::NoPathExists);
         }
 
         for t in (1..time_steps).rev() {
             path.push((x as i64, y as i64).into());
 
-            let neighbors = [
-                (0, 0),
-                (-1, 0),
-                (0, -1),
-                (1, 0),
-                (0, 1),
-            ];
-            let mut prev_probs = Vec::new();
-
-            for (mov_x, mov_y) in neighbors.iter() {
+            // Keep the weights on the stack to avoid an allocation per step
+            let prev_probs = NEIGHBORS.map(|(mov_x, mov_y)| {
                 let (i, j) = (x + mov_x, y + mov_y);
 
-                let p_b = dp.at_or(i, j, t - 1, 0.0);
-                let p_a = dp.at_or(x, y, t, 0.0);
                 let p_a_b = self.kernel.at(i - x, j - y);
 
-                prev_probs.push((p_a_b * p_b) / p_a);
-            }
+                log_weight(dp, (i, j), (x, y), t, p_a_b)
+            });
 
-            let direction = match WeightedIndex::new(prev_probs) {
-                Ok(dist) => dist.sample(&mut rng),
-                Err(WeightedError::AllWeightsZero) => return Err(WalkerError::InconsistentPath),
-                _ => return Err(WalkerError::RandomDistributionError),
-            };
-
-            match direction {
-                0 => (),     // Stay
-                1 => x -= 1, // West
-                2 => y -= 1, // North
-                3 => x += 1, // East
-                4 => y += 1, // South
-                _ => unreachable!("Other directions should not be chosen from the distribution"),
-            }
+            let (mov_x, mov_y) = NEIGHBORS[sample_log_weights(&prev_probs, rng)?];
+
+            x += mov_x;
+            y += mov_y;
         }
 
         path.reverse();
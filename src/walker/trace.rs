@@ -0,0 +1,223 @@
+//! Provides per-step traces of the probabilities walkers sample their steps from.
+//!
+//! When a walker produces [`InconsistentPath`](WalkerError::InconsistentPath) errors or
+//! degenerate walks, e.g. near barriers, it is often unclear which step went wrong. Using
+//! [`generate_path_traced()`](Walker::generate_path_traced), each random choice a walker makes
+//! is recorded as a [`StepTrace`] holding the normalized probabilities of all candidates and the
+//! index of the chosen one. The steps are returned in a [`WalkTrace`] alongside the walk or the
+//! error.
+//!
+//! ```
+//! # use randomwalks_lib::dp::builder::DynamicProgramBuilder;
+//! # use randomwalks_lib::dp::DynamicPrograms;
+//! # use randomwalks_lib::kernel::Kernel;
+//! # use randomwalks_lib::kernel::simple_rw::SimpleRwGenerator;
+//! # use randomwalks_lib::walker::standard::StandardWalker;
+//! # use randomwalks_lib::walker::Walker;
+//! #
+//! let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+//! let mut dp = DynamicProgramBuilder::new()
+//!     .simple()
+//!     .time_limit(50)
+//!     .kernel(kernel.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! dp.compute().unwrap();
+//!
+//! let trace = StandardWalker::new(kernel).generate_path_traced(&dp, 10, 5, 50);
+//!
+//! for step in trace.steps.iter() {
+//!     println!("{:?} -> {:?}", step.probabilities, step.chosen);
+//! }
+//! ```
+//!
+//! Steps are recorded in the order they are sampled. For walkers stepping back from the end
+//! point, the first step is thus the one leading to the end point.
+
+use crate::dp::DynamicProgramPool;
+use crate::walk::Walk;
+use crate::walker::{seeded_rng, Walker, WalkerError, WalkerType};
+use pyo3::{pyclass, pymethods};
+use std::cell::RefCell;
+
+thread_local! {
+    /// Steps recorded on the current thread, or `None` if no trace is being recorded.
+    static RECORDER: RefCell<Option<Vec<StepTrace>>> = RefCell::new(None);
+}
+
+/// A single random choice made by a walker.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepTrace {
+    /// Probabilities of all candidates, summing up to 1 unless all candidates are impossible.
+    pub probabilities: Vec<f64>,
+    /// Index of the chosen candidate, or `None` if no candidate could be chosen.
+    pub chosen: Option<usize>,
+}
+
+#[pymethods]
+impl StepTrace {
+    /// Returns the probability of the chosen candidate, or `None` if none was chosen.
+    pub fn chosen_probability(&self) -> Option<f64> {
+        self.chosen.map(|index| self.probabilities[index])
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "StepTrace(candidates={}, chosen={:?})",
+            self.probabilities.len(),
+            self.chosen
+        )
+    }
+}
+
+/// The outcome of generating a single walk together with all random choices made.
+#[pyclass(get_all)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalkTrace {
+    /// The generated walk, or `None` if generation failed.
+    pub walk: Option<Walk>,
+    /// The error of the walker, or `None` if generation succeeded.
+    pub error: Option<WalkerError>,
+    /// All random choices in the order they were made.
+    pub steps: Vec<StepTrace>,
+}
+
+#[pymethods]
+impl WalkTrace {
+    #[staticmethod]
+    #[pyo3(name = "generate", signature = (walker, dp, to_x, to_y, time_steps, seed=None))]
+    pub fn py_generate(
+        walker: WalkerType,
+        dp: DynamicProgramPool,
+        to_x: isize,
+        to_y: isize,
+        time_steps: usize,
+        seed: Option<u64>,
+    ) -> Self {
+        let walker: Box<dyn Walker> = walker.into();
+
+        walker.generate_path_traced_with_rng(&dp, to_x, to_y, time_steps, &mut *seeded_rng(seed))
+    }
+
+    /// Returns the index of the step in which no candidate could be chosen, if any.
+    pub fn failed_step(&self) -> Option<usize> {
+        self.steps.iter().position(|step| step.chosen.is_none())
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn __repr__(&self) -> String {
+        match &self.error {
+            Some(error) => format!("WalkTrace(error={:?}, steps={})", error, self.steps.len()),
+            None => format!("WalkTrace(steps={})", self.steps.len()),
+        }
+    }
+}
+
+impl WalkTrace {
+    /// Calls `generate` while recording all steps sampled on the current thread.
+    ///
+    /// Traces can be nested, in which case the steps are only recorded by the innermost one.
+    pub(crate) fn record<F>(generate: F) -> Self
+    where
+        F: FnOnce() -> Result<Walk, WalkerError>,
+    {
+        let outer = RECORDER.with(|recorder| recorder.replace(Some(Vec::new())));
+        let res = generate();
+        let steps = RECORDER.with(|recorder| recorder.replace(outer).unwrap_or_default());
+
+        let (walk, error) = match res {
+            Ok(walk) => (Some(walk), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        Self { walk, error, steps }
+    }
+}
+
+/// Records a step sampled from `log_weights` if a trace is being recorded on the current thread.
+pub(crate) fn record_step(log_weights: &[f64], chosen: Option<usize>) {
+    RECORDER.with(|recorder| {
+        if let Some(steps) = recorder.borrow_mut().as_mut() {
+            steps.push(StepTrace {
+                probabilities: normalize(log_weights),
+                chosen,
+            });
+        }
+    });
+}
+
+/// Converts `log_weights` into probabilities summing up to 1, or to zeros if all weights are zero.
+fn normalize(log_weights: &[f64]) -> Vec<f64> {
+    let max = log_weights
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    if !max.is_finite() {
+        return vec![0.0; log_weights.len()];
+    }
+
+    let weights: Vec<f64> = log_weights
+        .iter()
+        .map(|weight| (weight - max).exp())
+        .collect();
+    let total: f64 = weights.iter().sum();
+
+    weights.iter().map(|weight| weight / total).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::builder::DynamicProgramBuilder;
+    use crate::dp::DynamicPrograms;
+    use crate::kernel::simple_rw::SimpleRwGenerator;
+    use crate::kernel::Kernel;
+    use crate::walker::standard::StandardWalker;
+    use crate::walker::{Walker, WalkerError};
+
+    #[test]
+    fn test_walk_trace() {
+        let kernel = Kernel::from_generator(SimpleRwGenerator::default()).unwrap();
+        let mut diagonal = Kernel::try_new(3, ("Diagonal".into(), "diag".into())).unwrap();
+
+        for (x, y) in [(-1, -1), (1, -1), (1, 1), (-1, 1)] {
+            diagonal.set(x, y, 0.25);
+        }
+
+        let mut dp = DynamicProgramBuilder::new()
+            .simple()
+            .time_limit(30)
+            .kernel(kernel.clone())
+            .kernel_schedule(vec![(0..10, diagonal)])
+            .build()
+            .unwrap();
+
+        dp.compute().unwrap();
+
+        let walker = StandardWalker::new(kernel);
+
+        // Stepping back from the end point works until the diagonal phase is reached
+        let trace = walker.generate_path_traced(&dp, 5, 3, 30);
+        let failed = trace.failed_step().unwrap();
+
+        assert_eq!(trace.walk, None);
+        assert_eq!(trace.error, Some(WalkerError::InconsistentPath));
+        assert_eq!(failed, trace.steps.len() - 1);
+        assert!(trace.steps[failed].probabilities.iter().all(|&p| p == 0.0));
+
+        for step in trace.steps[..failed].iter() {
+            assert!((step.probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(step.chosen_probability().unwrap() > 0.0);
+        }
+
+        let trace = walker.generate_path_traced(&dp, 20, 15, 30);
+
+        assert_eq!(trace.error, Some(WalkerError::NoPathExists));
+        assert!(trace.steps.is_empty());
+    }
+}
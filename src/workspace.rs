@@ -0,0 +1,224 @@
+//! Provides workspaces which collect all outputs of a session in a single directory.
+//!
+//! While a [`Workspace`] is active, relative paths passed to functions that write plots or
+//! dynamic programs, e.g. [`Walk::plot()`](crate::walk::Walk::plot) or
+//! [`save()`](crate::dp::DynamicPrograms::save), are resolved relative to the workspace directory
+//! instead of the current working directory. Absolute paths are left untouched. Every file
+//! written this way is recorded, and when the workspace is exited, a `manifest.json` listing all
+//! produced artifacts is written to the workspace directory.
+//!
+//! Only one workspace can be active at a time. Paths of files that are read, e.g. by
+//! [`DynamicProgram::load()`](crate::dp::simple::DynamicProgram::load), are not affected.
+//!
+//! In Python, workspaces are used as context managers:
+//!
+//! ```python
+//! with randomwalks_lib.workspace("experiments/run1"):
+//!     dp.save("dp.zst")
+//!     walk.plot("walk.png")
+//! ```
+
+use anyhow::{bail, Context};
+use pyo3::{pyclass, pyfunction, pymethods, PyAny, PyRef};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the manifest file written to the workspace directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+static ACTIVE: Mutex<Option<Manifest>> = Mutex::new(None);
+
+/// A file produced while a workspace was active.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Artifact {
+    /// Path of the file relative to the workspace directory.
+    pub path: String,
+    /// What the file contains, e.g. `plot` or `dynamic_program`.
+    pub kind: String,
+    /// Time the file was created at, in seconds since the Unix epoch.
+    pub created: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Manifest {
+    library_version: String,
+    #[serde(skip)]
+    directory: PathBuf,
+    started: u64,
+    finished: Option<u64>,
+    artifacts: Vec<Artifact>,
+}
+
+/// A directory collecting all outputs written while it is active.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Workspace {
+    path: String,
+}
+
+impl Workspace {
+    pub fn new<S: Into<String>>(path: S) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Activates the workspace, creating its directory if it does not exist.
+    ///
+    /// Returns an error if another workspace is already active.
+    pub fn enter(&self) -> anyhow::Result<()> {
+        let mut active = ACTIVE.lock().unwrap();
+
+        if active.is_some() {
+            bail!("another workspace is already active");
+        }
+
+        fs::create_dir_all(&self.path).context("could not create workspace directory")?;
+
+        *active = Some(Manifest {
+            library_version: env!("CARGO_PKG_VERSION").into(),
+            directory: PathBuf::from(&self.path),
+            started: now(),
+            finished: None,
+            artifacts: Vec::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Deactivates the workspace and writes its manifest.
+    ///
+    /// Returns the artifacts produced while the workspace was active.
+    pub fn exit(&self) -> anyhow::Result<Vec<Artifact>> {
+        let Some(mut manifest) = ACTIVE.lock().unwrap().take() else {
+            bail!("workspace is not active");
+        };
+
+        manifest.finished = Some(now());
+
+        fs::write(
+            manifest.directory.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&manifest)?,
+        )
+        .context("could not write workspace manifest")?;
+
+        Ok(manifest.artifacts)
+    }
+}
+
+#[pymethods]
+impl Workspace {
+    #[new]
+    pub fn py_new(path: String) -> Self {
+        Self::new(path)
+    }
+
+    #[getter]
+    pub fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    pub fn __enter__(slf: PyRef<'_, Self>) -> anyhow::Result<PyRef<'_, Self>> {
+        slf.enter()?;
+
+        Ok(slf)
+    }
+
+    pub fn __exit__(
+        &self,
+        _exc_type: Option<&PyAny>,
+        _exc_value: Option<&PyAny>,
+        _traceback: Option<&PyAny>,
+    ) -> anyhow::Result<bool> {
+        self.exit()?;
+
+        Ok(false)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!("Workspace({:?})", self.path)
+    }
+}
+
+/// Creates a workspace at `path`, to be used as a context manager.
+#[pyfunction]
+pub fn workspace(path: String) -> Workspace {
+    Workspace::new(path)
+}
+
+/// Resolves the path of an output file.
+///
+/// If a workspace is active and `path` is relative, the file is placed inside the workspace
+/// directory, missing parent directories are created and the file is recorded in the manifest as
+/// an artifact of type `kind`. Otherwise, `path` is returned unchanged.
+pub(crate) fn output_path(path: &str, kind: &str) -> anyhow::Result<String> {
+    let mut active = ACTIVE.lock().unwrap();
+
+    let Some(manifest) = active.as_mut() else {
+        return Ok(path.into());
+    };
+
+    if Path::new(path).is_absolute() {
+        return Ok(path.into());
+    }
+
+    let resolved = manifest.directory.join(path);
+
+    if let Some(parent) = resolved.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    manifest.artifacts.push(Artifact {
+        path: path.into(),
+        kind: kind.into(),
+        created: now(),
+    });
+
+    resolved
+        .to_str()
+        .map(String::from)
+        .context("workspace path is not valid UTF-8")
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::workspace::{output_path, Workspace, MANIFEST_FILE};
+
+    #[test]
+    fn test_workspace() {
+        let dir = std::env::temp_dir().join("randomwalks_test_workspace");
+        let workspace = Workspace::new(dir.to_str().unwrap());
+
+        assert!(workspace.exit().is_err());
+
+        workspace.enter().unwrap();
+
+        assert!(workspace.enter().is_err());
+
+        let path = output_path("plots/walk.png", "plot").unwrap();
+
+        assert!(path.starts_with(dir.to_str().unwrap()));
+        assert!(dir.join("plots").is_dir());
+        assert_eq!(
+            output_path("/tmp/walk.png", "plot").unwrap(),
+            "/tmp/walk.png"
+        );
+
+        let artifacts = workspace.exit().unwrap();
+
+        assert!(artifacts.iter().any(|a| a.path == "plots/walk.png"));
+        assert!(std::fs::read_to_string(dir.join(MANIFEST_FILE))
+            .unwrap()
+            .contains("plots/walk.png"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}